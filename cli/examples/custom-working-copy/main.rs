@@ -34,6 +34,7 @@ use jj_lib::repo::ReadonlyRepo;
 use jj_lib::repo_path::RepoPathBuf;
 use jj_lib::settings::UserSettings;
 use jj_lib::signing::Signer;
+use jj_lib::sparse_profile::SparseProfile;
 use jj_lib::store::Store;
 use jj_lib::working_copy::CheckoutError;
 use jj_lib::working_copy::CheckoutOptions;
@@ -170,6 +171,10 @@ impl WorkingCopy for ConflictsWorkingCopy {
         self.inner.sparse_patterns()
     }
 
+    fn sparse_profile(&self) -> Result<Option<&SparseProfile>, WorkingCopyStateError> {
+        self.inner.sparse_profile()
+    }
+
     fn start_mutation(&self) -> Result<Box<dyn LockedWorkingCopy>, WorkingCopyStateError> {
         let inner = self.inner.start_mutation()?;
         Ok(Box::new(LockedConflictsWorkingCopy {
@@ -288,6 +293,18 @@ impl LockedWorkingCopy for LockedConflictsWorkingCopy {
         self.inner.set_sparse_patterns(new_sparse_patterns, options)
     }
 
+    fn sparse_profile(&self) -> Result<Option<&SparseProfile>, WorkingCopyStateError> {
+        self.inner.sparse_profile()
+    }
+
+    fn set_sparse_profile(
+        &mut self,
+        new_sparse_profile: Option<SparseProfile>,
+        options: &CheckoutOptions,
+    ) -> Result<CheckoutStats, CheckoutError> {
+        self.inner.set_sparse_profile(new_sparse_profile, options)
+    }
+
     fn finish(
         self: Box<Self>,
         operation_id: OperationId,
@@ -65,13 +65,17 @@ use jj_lib::config::ConfigNamePathBuf;
 use jj_lib::config::ConfigSource;
 use jj_lib::config::StackedConfig;
 use jj_lib::conflicts::ConflictMarkerStyle;
+use jj_lib::conflicts::ConflictMarkerStyleOverrides;
 use jj_lib::fileset;
 use jj_lib::fileset::FilesetDiagnostics;
 use jj_lib::fileset::FilesetExpression;
+use jj_lib::fileset::FilesetValuePredicateError;
+use jj_lib::fsmonitor::FsmonitorSettings;
 use jj_lib::gitignore::GitIgnoreError;
 use jj_lib::gitignore::GitIgnoreFile;
 use jj_lib::id_prefix::IdPrefixContext;
 use jj_lib::matchers::Matcher;
+use jj_lib::matchers::NothingMatcher;
 use jj_lib::merge::MergedTreeValue;
 use jj_lib::merged_tree::MergedTree;
 use jj_lib::object_id::ObjectId as _;
@@ -80,7 +84,6 @@ use jj_lib::op_store::OpStoreError;
 use jj_lib::op_store::OperationId;
 use jj_lib::op_store::RefTarget;
 use jj_lib::op_walk;
-use jj_lib::op_walk::OpsetEvaluationError;
 use jj_lib::operation::Operation;
 use jj_lib::ref_name::RefName;
 use jj_lib::ref_name::RefNameBuf;
@@ -116,6 +119,8 @@ use jj_lib::revset::UserRevsetExpression;
 use jj_lib::rewrite::restore_tree;
 use jj_lib::settings::HumanByteSize;
 use jj_lib::settings::UserSettings;
+use jj_lib::sparse_profile::SparseProfile;
+use jj_lib::sparse_profile::SparseProfileError;
 use jj_lib::str_util::StringPattern;
 use jj_lib::transaction::Transaction;
 use jj_lib::view::View;
@@ -639,7 +644,10 @@ impl CommandHelper {
         repo_loader: &RepoLoader,
     ) -> Result<Operation, CommandError> {
         if let Some(op_str) = &self.data.global_args.at_operation {
-            Ok(op_walk::resolve_op_for_load(repo_loader, op_str)?)
+            let mut diagnostics = RevsetDiagnostics::new();
+            let op = op_walk::resolve_op_for_load(&mut diagnostics, repo_loader, op_str)?;
+            print_parse_diagnostics(ui, "In operation expression", &diagnostics)?;
+            Ok(op)
         } else {
             op_heads_store::resolve_op_heads(
                 repo_loader.op_heads_store().as_ref(),
@@ -788,6 +796,7 @@ pub struct WorkspaceCommandEnvironment {
     immutable_heads_expression: Rc<UserRevsetExpression>,
     short_prefixes_expression: Option<Rc<UserRevsetExpression>>,
     conflict_marker_style: ConflictMarkerStyle,
+    conflict_marker_style_overrides: ConflictMarkerStyleOverrides,
 }
 
 impl WorkspaceCommandEnvironment {
@@ -810,9 +819,11 @@ impl WorkspaceCommandEnvironment {
             immutable_heads_expression: RevsetExpression::root(),
             short_prefixes_expression: None,
             conflict_marker_style: settings.get("ui.conflict-marker-style")?,
+            conflict_marker_style_overrides: ConflictMarkerStyleOverrides::default(),
         };
         env.immutable_heads_expression = env.load_immutable_heads_expression(ui)?;
         env.short_prefixes_expression = env.load_short_prefixes_expression(ui)?;
+        env.conflict_marker_style_overrides = env.load_conflict_marker_style_overrides(ui)?;
         Ok(env)
     }
 
@@ -873,6 +884,13 @@ impl WorkspaceCommandEnvironment {
         self.conflict_marker_style
     }
 
+    /// User-configured conflict marker styles for files matching some
+    /// filesets, which take precedence over `conflict_marker_style()` in the
+    /// working copy
+    pub fn conflict_marker_style_overrides(&self) -> &ConflictMarkerStyleOverrides {
+        &self.conflict_marker_style_overrides
+    }
+
     fn load_immutable_heads_expression(
         &self,
         ui: &Ui,
@@ -912,6 +930,38 @@ impl WorkspaceCommandEnvironment {
         }
     }
 
+    fn load_conflict_marker_style_overrides(
+        &self,
+        ui: &Ui,
+    ) -> Result<ConflictMarkerStyleOverrides, CommandError> {
+        let path_converter = RepoPathUiConverter::Fs {
+            cwd: "".into(),
+            base: "".into(),
+        };
+        let mut diagnostics = FilesetDiagnostics::new();
+        let overrides = self
+            .settings
+            .table_keys("ui.conflict-marker-style-overrides")
+            // Sort keys early so the precedence and errors are deterministic.
+            .sorted()
+            .map(|pattern| -> Result<_, CommandError> {
+                let expression = fileset::parse(&mut diagnostics, pattern, &path_converter)?;
+                if expression.has_value_predicates() {
+                    return Err(config_error_with_message(
+                        "Invalid `ui.conflict-marker-style-overrides`",
+                        FilesetValuePredicateError,
+                    ));
+                }
+                let style =
+                    self.settings
+                        .get(["ui", "conflict-marker-style-overrides", pattern])?;
+                Ok((expression, style))
+            })
+            .try_collect()?;
+        print_parse_diagnostics(ui, "In `ui.conflict-marker-style-overrides`", &diagnostics)?;
+        Ok(ConflictMarkerStyleOverrides::new(overrides))
+    }
+
     /// Returns first immutable commit + lower and upper bounds on number of
     /// immutable commits.
     fn find_immutable_commit<'a>(
@@ -1012,6 +1062,13 @@ impl WorkspaceCommandEnvironment {
     }
 }
 
+/// Rules from the `snapshot` settings for leaving new files untracked.
+pub struct SnapshotPolicy {
+    file_size_limits: Vec<(Box<dyn Matcher>, u64)>,
+    ignore_by_default_matcher: Box<dyn Matcher>,
+    untrack_binaries: bool,
+}
+
 /// Provides utilities for writing a command that works on a [`Workspace`]
 /// (which most commands do).
 pub struct WorkspaceCommandHelper {
@@ -1046,6 +1103,21 @@ where
     SnapshotWorkingCopyError::Command(err.into())
 }
 
+/// Command that runs the inotify daemon in the foreground, i.e. `jj debug
+/// inotify run` using the current executable.
+fn inotify_daemon_command() -> Vec<OsString> {
+    match std::env::current_exe() {
+        Ok(exe) => vec![exe.into(), "debug".into(), "inotify".into(), "run".into()],
+        Err(err) => {
+            tracing::warn!(
+                ?err,
+                "Failed to find the executable to run the inotify daemon"
+            );
+            vec![]
+        }
+    }
+}
+
 impl WorkspaceCommandHelper {
     #[instrument(skip_all)]
     fn new(
@@ -1255,6 +1327,7 @@ impl WorkspaceCommandHelper {
     pub fn checkout_options(&self) -> CheckoutOptions {
         CheckoutOptions {
             conflict_marker_style: self.env.conflict_marker_style(),
+            conflict_marker_style_overrides: self.env.conflict_marker_style_overrides().clone(),
         }
     }
 
@@ -1386,15 +1459,68 @@ to the current parents may contain changes from multiple commits.
             },
         )?;
         print_parse_diagnostics(ui, "In `snapshot.auto-track`", &diagnostics)?;
-        Ok(expression.to_matcher())
+        expression
+            .to_path_matcher()
+            .map_err(|err| config_error_with_message("Invalid `snapshot.auto-track`", err))
+    }
+
+    /// Parses the `snapshot` settings that decide which new files are left
+    /// untracked.
+    pub fn snapshot_policy(&self, ui: &Ui) -> Result<SnapshotPolicy, CommandError> {
+        let settings = self.settings();
+        let path_converter = RepoPathUiConverter::Fs {
+            cwd: "".into(),
+            base: "".into(),
+        };
+        let mut diagnostics = FilesetDiagnostics::new();
+        let file_size_limits = settings
+            .table_keys("snapshot.file-size-limits")
+            // Sort keys early so errors are deterministic.
+            .sorted()
+            .map(|pattern| -> Result<_, CommandError> {
+                let expression = fileset::parse(&mut diagnostics, pattern, &path_converter)?;
+                let HumanByteSize(max_size) = settings
+                    .get_value_with(["snapshot", "file-size-limits", pattern], TryInto::try_into)?;
+                let max_size = if max_size == 0 { u64::MAX } else { max_size };
+                let matcher = expression.to_path_matcher().map_err(|err| {
+                    config_error_with_message("Invalid `snapshot.file-size-limits`", err)
+                })?;
+                Ok((matcher, max_size))
+            })
+            .try_collect()?;
+        print_parse_diagnostics(ui, "In `snapshot.file-size-limits`", &diagnostics)?;
+        let mut diagnostics = FilesetDiagnostics::new();
+        let ignore_by_default_expression = FilesetExpression::union_all(
+            settings
+                .get::<Vec<String>>("snapshot.ignore-by-default")?
+                .iter()
+                .map(|pattern| fileset::parse(&mut diagnostics, pattern, &path_converter))
+                .try_collect()?,
+        );
+        print_parse_diagnostics(ui, "In `snapshot.ignore-by-default`", &diagnostics)?;
+        let ignore_by_default_matcher =
+            ignore_by_default_expression
+                .to_path_matcher()
+                .map_err(|err| {
+                    config_error_with_message("Invalid `snapshot.ignore-by-default`", err)
+                })?;
+        Ok(SnapshotPolicy {
+            file_size_limits,
+            ignore_by_default_matcher,
+            untrack_binaries: settings.get_bool("snapshot.untrack-binaries")?,
+        })
     }
 
     pub fn snapshot_options_with_start_tracking_matcher<'a>(
         &self,
         start_tracking_matcher: &'a dyn Matcher,
+        policy: &'a SnapshotPolicy,
     ) -> Result<SnapshotOptions<'a>, CommandError> {
         let base_ignores = self.base_ignores()?;
-        let fsmonitor_settings = self.settings().fsmonitor_settings()?;
+        let mut fsmonitor_settings = self.settings().fsmonitor_settings()?;
+        if let FsmonitorSettings::Inotify(config) = &mut fsmonitor_settings {
+            config.daemon_command = inotify_daemon_command();
+        }
         let HumanByteSize(mut max_new_file_size) = self
             .settings()
             .get_value_with("snapshot.max-new-file-size", TryInto::try_into)?;
@@ -1408,7 +1534,12 @@ to the current parents may contain changes from multiple commits.
             progress: None,
             start_tracking_matcher,
             max_new_file_size,
+            new_file_size_limits: &policy.file_size_limits,
+            ignore_by_default_matcher: policy.ignore_by_default_matcher.as_ref(),
+            untrack_binaries: policy.untrack_binaries,
+            force_tracking_matcher: &NothingMatcher,
             conflict_marker_style,
+            conflict_marker_style_overrides: self.env.conflict_marker_style_overrides().clone(),
         })
     }
 
@@ -1505,12 +1636,14 @@ to the current parents may contain changes from multiple commits.
     ) -> Result<DiffEditor, CommandError> {
         let base_ignores = self.base_ignores()?;
         let conflict_marker_style = self.env.conflict_marker_style();
+        let conflict_marker_style_overrides = self.env.conflict_marker_style_overrides().clone();
         if let Some(name) = tool_name {
             Ok(DiffEditor::with_name(
                 name,
                 self.settings(),
                 base_ignores,
                 conflict_marker_style,
+                conflict_marker_style_overrides,
             )?)
         } else {
             Ok(DiffEditor::from_settings(
@@ -1518,6 +1651,7 @@ to the current parents may contain changes from multiple commits.
                 self.settings(),
                 base_ignores,
                 conflict_marker_style,
+                conflict_marker_style_overrides,
             )?)
         }
     }
@@ -1569,8 +1703,11 @@ to the current parents may contain changes from multiple commits.
         TextEditor::from_settings(self.settings())
     }
 
-    pub fn resolve_single_op(&self, op_str: &str) -> Result<Operation, OpsetEvaluationError> {
-        op_walk::resolve_op_with_repo(self.repo(), op_str)
+    pub fn resolve_single_op(&self, ui: &Ui, op_str: &str) -> Result<Operation, CommandError> {
+        let mut diagnostics = RevsetDiagnostics::new();
+        let op = op_walk::resolve_op_with_repo(&mut diagnostics, self.repo(), op_str)?;
+        print_parse_diagnostics(ui, "In operation expression", &diagnostics)?;
+        Ok(op)
     }
 
     /// Resolve a revset to a single revision. Return an error if the revset is
@@ -1885,8 +2022,9 @@ to the current parents may contain changes from multiple commits.
         let auto_tracking_matcher = self
             .auto_tracking_matcher(ui)
             .map_err(snapshot_command_error)?;
+        let snapshot_policy = self.snapshot_policy(ui).map_err(snapshot_command_error)?;
         let options = self
-            .snapshot_options_with_start_tracking_matcher(&auto_tracking_matcher)
+            .snapshot_options_with_start_tracking_matcher(&auto_tracking_matcher, &snapshot_policy)
             .map_err(snapshot_command_error)?;
 
         // Compare working-copy tree and operation with repo's, and reload as needed.
@@ -2017,16 +2155,111 @@ See https://jj-vcs.github.io/jj/latest/working-copy/#stale-working-copy \
     ) -> Result<(), CommandError> {
         assert!(self.may_update_working_copy);
         let checkout_options = self.checkout_options();
-        let stats = update_working_copy(
+        let mut stats = update_working_copy(
             &self.user_repo.repo,
             &mut self.workspace,
             maybe_old_commit,
             new_commit,
             &checkout_options,
         )?;
+        if let Some(profile_stats) =
+            self.update_sparse_profile(ui, new_commit, &checkout_options)?
+        {
+            stats.added_files += profile_stats.added_files;
+            stats.removed_files += profile_stats.removed_files;
+        }
+        #[cfg(feature = "git")]
+        self.update_git_submodules(ui, maybe_old_commit, new_commit)?;
         self.print_updated_working_copy_stats(ui, maybe_old_commit, new_commit, &stats)
     }
 
+    /// Reads the sparse profile again from the parents of the new working-copy
+    /// commit, and updates the working copy if the profile file has changed.
+    fn update_sparse_profile(
+        &mut self,
+        ui: &Ui,
+        new_commit: &Commit,
+        options: &CheckoutOptions,
+    ) -> Result<Option<CheckoutStats>, CommandError> {
+        use std::error::Error as _;
+        let Some(old_profile) = self.working_copy().sparse_profile()?.cloned() else {
+            return Ok(None);
+        };
+        let parent_tree = new_commit.parent_tree(self.repo().as_ref())?;
+        let new_profile = match SparseProfile::resolve(&parent_tree, &old_profile.name) {
+            Ok(profile) => profile,
+            Err(SparseProfileError::Backend(err)) => return Err(err.into()),
+            Err(err) => {
+                writeln!(
+                    ui.warning_default(),
+                    "Failed to update the working copy to the new sparse profile: {err}"
+                )?;
+                crate::command_error::print_error_sources(ui, err.source())?;
+                writeln!(
+                    ui.hint_default(),
+                    "The working copy still uses the previous version of the profile."
+                )?;
+                return Ok(None);
+            }
+        };
+        if new_profile == old_profile {
+            return Ok(None);
+        }
+        let mut locked_ws = self.workspace.start_working_copy_mutation()?;
+        let stats = locked_ws
+            .locked_wc()
+            .set_sparse_profile(Some(new_profile), options)
+            .map_err(|err| {
+                internal_error_with_message("Failed to update working copy paths", err)
+            })?;
+        let operation_id = locked_ws.locked_wc().old_operation_id().clone();
+        locked_ws.finish(operation_id)?;
+        Ok(Some(stats))
+    }
+
+    /// Checks out the Git submodules that changed between the old and new
+    /// working-copy commits if `git.submodules.auto-update` is enabled.
+    /// Failures are reported as warnings since the working copy itself has
+    /// been updated.
+    #[cfg(feature = "git")]
+    fn update_git_submodules(
+        &self,
+        ui: &Ui,
+        maybe_old_commit: Option<&Commit>,
+        new_commit: &Commit,
+    ) -> Result<(), CommandError> {
+        use std::error::Error as _;
+        let git_settings = self.settings().git_settings()?;
+        if !git_settings.submodule_auto_update {
+            return Ok(());
+        }
+        let repo = self.repo().as_ref();
+        let old_tree = match maybe_old_commit {
+            Some(commit) => commit.tree()?,
+            None => repo.store().root_commit().tree()?,
+        };
+        let new_tree = new_commit.tree()?;
+        let matcher = jj_lib::sparse_profile::sparse_matcher(
+            self.working_copy().sparse_patterns()?,
+            self.working_copy().sparse_profile()?,
+        )?;
+        if let Err(err) = jj_lib::git::update_submodules(
+            repo,
+            &git_settings,
+            self.workspace_root(),
+            &old_tree,
+            &new_tree,
+            matcher.as_ref(),
+        ) {
+            writeln!(
+                ui.warning_default(),
+                "Failed to update Git submodules: {err}"
+            )?;
+            crate::command_error::print_error_sources(ui, err.source())?;
+        }
+        Ok(())
+    }
+
     fn print_updated_working_copy_stats(
         &self,
         ui: &Ui,
@@ -2426,6 +2659,11 @@ impl WorkspaceCommandTransaction<'_> {
         self.tx.repo_mut()
     }
 
+    /// Sets a tag on the operation to be committed by this transaction.
+    pub fn set_tag(&mut self, key: String, value: String) {
+        self.tx.set_tag(key, value);
+    }
+
     pub fn check_out(&mut self, commit: &Commit) -> Result<Commit, CheckOutCommitError> {
         let name = self.helper.workspace_name().to_owned();
         self.id_prefix_context.take(); // invalidate
@@ -2745,10 +2983,12 @@ fn build_untracked_reason_message(reason: &UntrackedReason) -> Option<String> {
                  ({max_size} bytes)",
             ))
         }
-        // Paths with UntrackedReason::FileNotAutoTracked shouldn't be warned about
-        // every time we make a snapshot. These paths will be printed by
+        // Paths left untracked by the snapshot settings shouldn't be warned
+        // about every time we make a snapshot. These paths will be printed by
         // "jj status" instead.
-        UntrackedReason::FileNotAutoTracked => None,
+        UntrackedReason::FileNotAutoTracked
+        | UntrackedReason::GeneratedBinary { .. }
+        | UntrackedReason::IgnoredByDefault => None,
     }
 }
 
@@ -2787,7 +3027,9 @@ pub fn print_snapshot_stats(
         .values()
         .filter_map(|reason| match reason {
             UntrackedReason::FileTooLarge { size, .. } => Some(size),
-            UntrackedReason::FileNotAutoTracked => None,
+            UntrackedReason::FileNotAutoTracked
+            | UntrackedReason::GeneratedBinary { .. }
+            | UntrackedReason::IgnoredByDefault => None,
         });
     if let Some(size) = large_files_sizes.max() {
         writedoc!(
@@ -24,6 +24,7 @@ use std::sync::Arc;
 use itertools::Itertools as _;
 use jj_lib::absorb::AbsorbError;
 use jj_lib::backend::BackendError;
+use jj_lib::bisect::BisectError;
 use jj_lib::config::ConfigFileSaveError;
 use jj_lib::config::ConfigGetError;
 use jj_lib::config::ConfigLoadError;
@@ -50,6 +51,7 @@ use jj_lib::revset::RevsetEvaluationError;
 use jj_lib::revset::RevsetParseError;
 use jj_lib::revset::RevsetParseErrorKind;
 use jj_lib::revset::RevsetResolutionError;
+use jj_lib::sparse_profile::SparseProfileError;
 use jj_lib::str_util::StringPatternParseError;
 use jj_lib::trailer::TrailerParseError;
 use jj_lib::transaction::TransactionCommitError;
@@ -59,6 +61,7 @@ use jj_lib::working_copy::ResetError;
 use jj_lib::working_copy::SnapshotError;
 use jj_lib::working_copy::WorkingCopyStateError;
 use jj_lib::workspace::WorkspaceInitError;
+use jj_lib::workspace_store::WorkspaceStoreError;
 use thiserror::Error;
 
 use crate::cli_util::short_operation_hash;
@@ -76,6 +79,7 @@ use crate::revset_util::BookmarkNameParseError;
 use crate::revset_util::UserRevsetEvaluationError;
 use crate::template_parser::TemplateParseError;
 use crate::template_parser::TemplateParseErrorKind;
+use crate::templater::TemplatePropertyError;
 use crate::ui::Ui;
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -357,10 +361,17 @@ impl From<WorkspaceInitError> for CommandError {
             }
             WorkspaceInitError::SignInit(err) => user_error(err),
             WorkspaceInitError::TransactionCommit(err) => err.into(),
+            WorkspaceInitError::WorkspaceStore(err) => err.into(),
         }
     }
 }
 
+impl From<WorkspaceStoreError> for CommandError {
+    fn from(err: WorkspaceStoreError) -> Self {
+        internal_error_with_message("Failed to access the workspace store", err)
+    }
+}
+
 impl From<OpHeadResolutionError> for CommandError {
     fn from(err: OpHeadResolutionError) -> Self {
         match err {
@@ -383,6 +394,15 @@ impl From<OpsetEvaluationError> for CommandError {
             OpsetEvaluationError::OpHeadResolution(err) => err.into(),
             OpsetEvaluationError::OpHeadsStore(err) => err.into(),
             OpsetEvaluationError::OpStore(err) => err.into(),
+            OpsetEvaluationError::Parse(err) => {
+                let hint = revset_parse_error_hint(&err);
+                let mut cmd_err = user_error_with_message(
+                    format!("Failed to parse operation expression: {}", err.kind()),
+                    err,
+                );
+                cmd_err.extend_hints(hint);
+                cmd_err
+            }
         }
     }
 }
@@ -514,6 +534,7 @@ mod git {
     use jj_lib::git::GitRemoteManagementError;
     use jj_lib::git::GitResetHeadError;
     use jj_lib::git::UnexpectedGitBackendError;
+    use jj_lib::git_lfs::GitLfsError;
 
     use super::*;
 
@@ -595,6 +616,18 @@ jj currently does not support partial clones. To use jj with this repository, tr
         }
     }
 
+    impl From<GitLfsError> for CommandError {
+        fn from(err: GitLfsError) -> Self {
+            match err {
+                GitLfsError::Backend(err) => err.into(),
+                GitLfsError::RevsetEvaluation(err) => err.into(),
+                GitLfsError::UnexpectedBackend(_) | GitLfsError::CopyObject { .. } => {
+                    user_error_with_message("Failed to transfer Git LFS objects", err)
+                }
+            }
+        }
+    }
+
     impl From<GitRemoteManagementError> for CommandError {
         fn from(err: GitRemoteManagementError) -> Self {
             user_error(err)
@@ -700,6 +733,12 @@ impl From<TemplateParseError> for CommandError {
     }
 }
 
+impl From<TemplatePropertyError> for CommandError {
+    fn from(err: TemplatePropertyError) -> Self {
+        internal_error(err.0)
+    }
+}
+
 impl From<UiPathParseError> for CommandError {
     fn from(err: UiPathParseError) -> Self {
         user_error(err)
@@ -742,6 +781,15 @@ impl From<AbsorbError> for CommandError {
     }
 }
 
+impl From<BisectError> for CommandError {
+    fn from(err: BisectError) -> Self {
+        match err {
+            BisectError::NoBadCommits | BisectError::EmptyRange => user_error(err),
+            BisectError::Evaluation(err) => err.into(),
+        }
+    }
+}
+
 impl From<FixError> for CommandError {
     fn from(err: FixError) -> Self {
         match err {
@@ -756,6 +804,15 @@ impl From<FixError> for CommandError {
     }
 }
 
+impl From<SparseProfileError> for CommandError {
+    fn from(err: SparseProfileError) -> Self {
+        match err {
+            SparseProfileError::Backend(err) => err.into(),
+            err => user_error(err),
+        }
+    }
+}
+
 fn find_source_parse_error_hint(err: &dyn error::Error) -> Option<String> {
     let source = err.source()?;
     if let Some(source) = source.downcast_ref() {
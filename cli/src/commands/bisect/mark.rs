@@ -0,0 +1,117 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use clap_complete::ArgValueCandidates;
+use itertools::Itertools as _;
+use jj_lib::bisect::mark;
+use jj_lib::bisect::next_step;
+use jj_lib::bisect::BisectionStep;
+use jj_lib::bisect::Evaluation;
+
+use super::current_bisect_state;
+use super::evaluation_name;
+use super::update_bisection;
+use crate::cli_util::short_commit_hash;
+use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::command_error::user_error;
+use crate::command_error::CommandError;
+use crate::complete;
+use crate::ui::Ui;
+
+/// Mark revisions as bad, and check out the next revision to test
+#[derive(clap::Args, Clone, Debug)]
+pub(crate) struct BisectBadArgs {
+    /// The revisions to mark (default: the revision being tested)
+    #[arg(value_name = "REVSETS", add = ArgValueCandidates::new(complete::all_revisions))]
+    revisions: Vec<RevisionArg>,
+}
+
+/// Mark revisions as good, and check out the next revision to test
+#[derive(clap::Args, Clone, Debug)]
+pub(crate) struct BisectGoodArgs {
+    /// The revisions to mark (default: the revision being tested)
+    #[arg(value_name = "REVSETS", add = ArgValueCandidates::new(complete::all_revisions))]
+    revisions: Vec<RevisionArg>,
+}
+
+/// Mark revisions as untestable, and check out the next revision to test
+///
+/// Skipped revisions are not checked out again. If the first bad revision
+/// can't be determined without testing them, all the candidates are listed.
+#[derive(clap::Args, Clone, Debug)]
+pub(crate) struct BisectSkipArgs {
+    /// The revisions to mark (default: the revision being tested)
+    #[arg(value_name = "REVSETS", add = ArgValueCandidates::new(complete::all_revisions))]
+    revisions: Vec<RevisionArg>,
+}
+
+pub(crate) fn cmd_bisect_bad(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &BisectBadArgs,
+) -> Result<(), CommandError> {
+    mark_revisions(ui, command, &args.revisions, Evaluation::Bad)
+}
+
+pub(crate) fn cmd_bisect_good(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &BisectGoodArgs,
+) -> Result<(), CommandError> {
+    mark_revisions(ui, command, &args.revisions, Evaluation::Good)
+}
+
+pub(crate) fn cmd_bisect_skip(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &BisectSkipArgs,
+) -> Result<(), CommandError> {
+    mark_revisions(ui, command, &args.revisions, Evaluation::Skip)
+}
+
+fn mark_revisions(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    revisions: &[RevisionArg],
+    evaluation: Evaluation,
+) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui)?;
+    let mut state = current_bisect_state(&workspace_command)?;
+    let commit_ids: Vec<_> = if revisions.is_empty() {
+        match next_step(workspace_command.repo().as_ref(), &state)? {
+            BisectionStep::Test(commit_id) => vec![commit_id],
+            BisectionStep::Found(_) | BisectionStep::Ambiguous(_) => {
+                return Err(user_error(
+                    "The bisection is finished. Specify the revisions to mark explicitly.",
+                ));
+            }
+        }
+    } else {
+        workspace_command
+            .parse_union_revsets(ui, revisions)?
+            .evaluate_to_commit_ids()?
+            .try_collect()?
+    };
+    for commit_id in &commit_ids {
+        mark(&mut state, commit_id, evaluation);
+    }
+    let description = format!(
+        "bisect: mark {} as {}",
+        commit_ids.iter().map(short_commit_hash).join(", "),
+        evaluation_name(evaluation)
+    );
+    update_bisection(ui, &mut workspace_command, state, description)?;
+    Ok(())
+}
@@ -0,0 +1,163 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod mark;
+mod reset;
+mod run;
+mod start;
+
+use std::io::Write as _;
+
+use clap::Subcommand;
+use jj_lib::bisect::next_step;
+use jj_lib::bisect::BisectionStep;
+use jj_lib::bisect::Evaluation;
+use jj_lib::op_store::BisectState;
+use jj_lib::repo::Repo as _;
+
+use self::mark::cmd_bisect_bad;
+use self::mark::cmd_bisect_good;
+use self::mark::cmd_bisect_skip;
+use self::mark::BisectBadArgs;
+use self::mark::BisectGoodArgs;
+use self::mark::BisectSkipArgs;
+use self::reset::cmd_bisect_reset;
+use self::reset::BisectResetArgs;
+use self::run::cmd_bisect_run;
+use self::run::BisectRunArgs;
+use self::start::cmd_bisect_start;
+use self::start::BisectStartArgs;
+use crate::cli_util::CommandHelper;
+use crate::cli_util::WorkspaceCommandHelper;
+use crate::command_error::user_error_with_hint;
+use crate::command_error::CommandError;
+use crate::ui::Ui;
+
+/// Find the revision that introduced a bug by binary search
+///
+/// Start with `jj bisect start --good <REVSETS> --bad <REVSETS>`. Jujutsu
+/// then repeatedly checks out a revision between the good and the bad ones,
+/// which you mark with `jj bisect good`, `jj bisect bad`, or `jj bisect skip`
+/// until the first bad revision is found. Use `jj bisect run` to test the
+/// revisions with a command instead.
+///
+/// The bisection state is recorded in the operation log, so `jj undo` takes
+/// back the last decision.
+#[derive(Subcommand, Clone, Debug)]
+pub(crate) enum BisectCommand {
+    Bad(BisectBadArgs),
+    Good(BisectGoodArgs),
+    Reset(BisectResetArgs),
+    Run(BisectRunArgs),
+    Skip(BisectSkipArgs),
+    Start(BisectStartArgs),
+}
+
+pub(crate) fn cmd_bisect(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    subcommand: &BisectCommand,
+) -> Result<(), CommandError> {
+    match subcommand {
+        BisectCommand::Bad(args) => cmd_bisect_bad(ui, command, args),
+        BisectCommand::Good(args) => cmd_bisect_good(ui, command, args),
+        BisectCommand::Reset(args) => cmd_bisect_reset(ui, command, args),
+        BisectCommand::Run(args) => cmd_bisect_run(ui, command, args),
+        BisectCommand::Skip(args) => cmd_bisect_skip(ui, command, args),
+        BisectCommand::Start(args) => cmd_bisect_start(ui, command, args),
+    }
+}
+
+fn evaluation_name(evaluation: Evaluation) -> &'static str {
+    match evaluation {
+        Evaluation::Good => "good",
+        Evaluation::Bad => "bad",
+        Evaluation::Skip => "skipped",
+    }
+}
+
+fn current_bisect_state(
+    workspace_command: &WorkspaceCommandHelper,
+) -> Result<BisectState, CommandError> {
+    workspace_command
+        .repo()
+        .view()
+        .bisect_state()
+        .cloned()
+        .ok_or_else(|| {
+            user_error_with_hint(
+                "No bisection in progress",
+                "Use `jj bisect start` to start one.",
+            )
+        })
+}
+
+/// Records the new bisection `state` and checks out the next revision to
+/// test, in a single operation.
+fn update_bisection(
+    ui: &mut Ui,
+    workspace_command: &mut WorkspaceCommandHelper,
+    state: BisectState,
+    description: String,
+) -> Result<BisectionStep, CommandError> {
+    let step = next_step(workspace_command.repo().as_ref(), &state)?;
+    let mut tx = workspace_command.start_transaction();
+    tx.repo_mut().set_bisect_state(Some(state));
+    if let BisectionStep::Test(commit_id) = &step {
+        let commit = tx.repo().store().get_commit(commit_id)?;
+        tx.check_out(&commit)?;
+    }
+    tx.finish(ui, description)?;
+    print_bisection_step(ui, workspace_command, &step)?;
+    Ok(step)
+}
+
+fn print_bisection_step(
+    ui: &Ui,
+    workspace_command: &WorkspaceCommandHelper,
+    step: &BisectionStep,
+) -> Result<(), CommandError> {
+    let store = workspace_command.repo().store();
+    match step {
+        BisectionStep::Test(commit_id) => {
+            if let Some(mut formatter) = ui.status_formatter() {
+                let commit = store.get_commit(commit_id)?;
+                write!(formatter, "Now testing: ")?;
+                workspace_command.write_commit_summary(formatter.as_mut(), &commit)?;
+                writeln!(formatter)?;
+            }
+        }
+        BisectionStep::Found(commit_id) => {
+            let mut formatter = ui.stdout_formatter();
+            let commit = store.get_commit(commit_id)?;
+            write!(formatter, "The first bad revision is: ")?;
+            workspace_command.write_commit_summary(formatter.as_mut(), &commit)?;
+            writeln!(formatter)?;
+        }
+        BisectionStep::Ambiguous(commit_ids) => {
+            let mut formatter = ui.stdout_formatter();
+            writeln!(
+                formatter,
+                "The first bad revision could be any of these, because some were skipped:"
+            )?;
+            for commit_id in commit_ids {
+                let commit = store.get_commit(commit_id)?;
+                write!(formatter, "  ")?;
+                workspace_command.write_commit_summary(formatter.as_mut(), &commit)?;
+                writeln!(formatter)?;
+            }
+        }
+    }
+    Ok(())
+}
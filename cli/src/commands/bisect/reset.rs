@@ -0,0 +1,40 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::Write as _;
+
+use super::current_bisect_state;
+use crate::cli_util::CommandHelper;
+use crate::command_error::CommandError;
+use crate::ui::Ui;
+
+/// Stop bisecting
+///
+/// The working copy is left at the revision that was checked out last.
+#[derive(clap::Args, Clone, Debug)]
+pub(crate) struct BisectResetArgs {}
+
+pub(crate) fn cmd_bisect_reset(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    _args: &BisectResetArgs,
+) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui)?;
+    current_bisect_state(&workspace_command)?;
+    let mut tx = workspace_command.start_transaction();
+    tx.repo_mut().set_bisect_state(None);
+    tx.finish(ui, "bisect reset")?;
+    writeln!(ui.status(), "Stopped bisecting.")?;
+    Ok(())
+}
@@ -0,0 +1,89 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use jj_lib::bisect::mark;
+use jj_lib::bisect::BisectionStep;
+use jj_lib::bisect::Evaluation;
+
+use super::current_bisect_state;
+use super::evaluation_name;
+use super::update_bisection;
+use crate::cli_util::short_commit_hash;
+use crate::cli_util::CommandHelper;
+use crate::cli_util::WorkspaceCommandHelper;
+use crate::command_error::user_error;
+use crate::command_error::CommandError;
+use crate::commands::run::shell_command_builder;
+use crate::ui::Ui;
+
+/// Test revisions with a command until the first bad revision is found
+///
+/// The command is run in the workspace root with the revision to test checked
+/// out. Exit status 0 marks the revision as good, 125 marks it as skipped, and
+/// any other status from 1 to 127 marks it as bad. Any other exit status, or
+/// termination by a signal, aborts the bisection.
+#[derive(clap::Args, Clone, Debug)]
+pub(crate) struct BisectRunArgs {
+    /// Shell command to run for each revision
+    #[arg(long, value_name = "COMMAND")]
+    command: String,
+}
+
+pub(crate) fn cmd_bisect_run(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &BisectRunArgs,
+) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui)?;
+    let state = current_bisect_state(&workspace_command)?;
+    // Record the unchanged state so that the revision to test gets checked out
+    // even if the user moved the working copy in the meantime.
+    let step = update_bisection(ui, &mut workspace_command, state, "bisect run".to_owned())?;
+    run_bisection(ui, &mut workspace_command, &args.command, step)
+}
+
+pub(super) fn run_bisection(
+    ui: &mut Ui,
+    workspace_command: &mut WorkspaceCommandHelper,
+    shell_command: &str,
+    mut step: BisectionStep,
+) -> Result<(), CommandError> {
+    while let BisectionStep::Test(commit_id) = step {
+        let status = shell_command_builder(shell_command)
+            .current_dir(workspace_command.workspace_root())
+            .status()
+            .map_err(|err| user_error(format!("Failed to run '{shell_command}': {err}")))?;
+        let evaluation = match status.code() {
+            Some(0) => Evaluation::Good,
+            Some(125) => Evaluation::Skip,
+            Some(1..=127) => Evaluation::Bad,
+            _ => {
+                return Err(user_error(format!(
+                    "Command '{shell_command}' failed with {status}, aborting bisection"
+                )));
+            }
+        };
+        // The command may have modified the working copy
+        workspace_command.maybe_snapshot(ui)?;
+        let mut state = current_bisect_state(workspace_command)?;
+        mark(&mut state, &commit_id, evaluation);
+        let description = format!(
+            "bisect: mark {} as {}",
+            short_commit_hash(&commit_id),
+            evaluation_name(evaluation)
+        );
+        step = update_bisection(ui, workspace_command, state, description)?;
+    }
+    Ok(())
+}
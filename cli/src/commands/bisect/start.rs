@@ -0,0 +1,89 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use clap_complete::ArgValueCandidates;
+use itertools::Itertools as _;
+use jj_lib::bisect::mark;
+use jj_lib::bisect::Evaluation;
+use jj_lib::op_store::BisectState;
+
+use super::run::run_bisection;
+use super::update_bisection;
+use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::command_error::user_error_with_hint;
+use crate::command_error::CommandError;
+use crate::complete;
+use crate::ui::Ui;
+
+/// Start a bisection
+///
+/// The first revision to test is checked out in the working copy.
+#[derive(clap::Args, Clone, Debug)]
+pub(crate) struct BisectStartArgs {
+    /// Revisions known to be good
+    #[arg(
+        long,
+        short,
+        value_name = "REVSETS",
+        add = ArgValueCandidates::new(complete::all_revisions),
+    )]
+    good: Vec<RevisionArg>,
+    /// Revisions known to be bad
+    #[arg(
+        long,
+        short,
+        value_name = "REVSETS",
+        default_value = "@",
+        add = ArgValueCandidates::new(complete::all_revisions),
+    )]
+    bad: Vec<RevisionArg>,
+    /// Test the revisions with this shell command, as in `jj bisect run`
+    #[arg(long, value_name = "COMMAND")]
+    command: Option<String>,
+}
+
+pub(crate) fn cmd_bisect_start(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &BisectStartArgs,
+) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui)?;
+    if workspace_command.repo().view().bisect_state().is_some() {
+        return Err(user_error_with_hint(
+            "A bisection is already in progress",
+            "Use `jj bisect reset` to stop it.",
+        ));
+    }
+    let good_ids: Vec<_> = workspace_command
+        .parse_union_revsets(ui, &args.good)?
+        .evaluate_to_commit_ids()?
+        .try_collect()?;
+    let bad_ids: Vec<_> = workspace_command
+        .parse_union_revsets(ui, &args.bad)?
+        .evaluate_to_commit_ids()?
+        .try_collect()?;
+    let mut state = BisectState::default();
+    for commit_id in &good_ids {
+        mark(&mut state, commit_id, Evaluation::Good);
+    }
+    for commit_id in &bad_ids {
+        mark(&mut state, commit_id, Evaluation::Bad);
+    }
+    let step = update_bisection(ui, &mut workspace_command, state, "bisect start".to_owned())?;
+    if let Some(shell_command) = &args.command {
+        run_bisection(ui, &mut workspace_command, shell_command, step)?;
+    }
+    Ok(())
+}
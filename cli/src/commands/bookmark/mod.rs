@@ -174,7 +174,11 @@ fn find_remote_bookmarks<'a>(
     }
 }
 
-fn is_fast_forward(repo: &dyn Repo, old_target: &RefTarget, new_target_id: &CommitId) -> bool {
+pub(crate) fn is_fast_forward(
+    repo: &dyn Repo,
+    old_target: &RefTarget,
+    new_target_id: &CommitId,
+) -> bool {
     if old_target.is_present() {
         // Strictly speaking, "all" old targets should be ancestors, but we allow
         // conflict resolution by setting bookmark to "any" of the old target
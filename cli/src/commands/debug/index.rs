@@ -59,6 +59,13 @@ pub fn cmd_debug_index(
             writeln!(ui.stdout(), "    Number of commits: {}", level.num_commits)?;
             writeln!(ui.stdout(), "    Name: {}", level.name.as_ref().unwrap())?;
         }
+        if let Some(changed_paths) = default_index.changed_paths() {
+            writeln!(
+                ui.stdout(),
+                "Number of commits with changed paths: {}",
+                changed_paths.num_commits()
+            )?;
+        }
     } else {
         return Err(user_error(format!(
             "Cannot get stats for indexes of type '{}'",
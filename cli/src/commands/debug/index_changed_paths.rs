@@ -0,0 +1,65 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::Debug;
+use std::io::Write as _;
+
+use jj_lib::default_index::DefaultIndexStore;
+use jj_lib::default_index::DefaultReadonlyIndex;
+
+use crate::cli_util::CommandHelper;
+use crate::command_error::internal_error;
+use crate::command_error::user_error;
+use crate::command_error::CommandError;
+use crate::ui::Ui;
+
+/// Build changed-path index
+///
+/// Once the changed-path index is built, it is updated automatically, and used
+/// to speed up evaluation of `files()` and `diff_contains()` revsets.
+#[derive(clap::Args, Clone, Debug)]
+pub struct DebugIndexChangedPathsArgs {}
+
+pub fn cmd_debug_index_changed_paths(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    _args: &DebugIndexChangedPathsArgs,
+) -> Result<(), CommandError> {
+    let workspace = command.load_workspace()?;
+    let repo_loader = workspace.repo_loader();
+    let op = command.resolve_operation(ui, repo_loader)?;
+    let index_store = repo_loader.index_store();
+    let Some(default_index_store) = index_store.as_any().downcast_ref::<DefaultIndexStore>() else {
+        return Err(user_error(format!(
+            "Cannot build changed-path index of indexes of type '{}'",
+            index_store.name()
+        )));
+    };
+    let index = index_store
+        .get_index_at_op(&op, repo_loader.store())
+        .map_err(internal_error)?;
+    let default_index = index
+        .as_any()
+        .downcast_ref::<DefaultReadonlyIndex>()
+        .expect("default index store should return default index");
+    let changed_paths = default_index_store
+        .build_changed_path_index_at_operation(&op, repo_loader.store(), default_index)
+        .map_err(internal_error)?;
+    writeln!(
+        ui.status(),
+        "Finished indexing changed paths of {} commits.",
+        changed_paths.num_commits()
+    )?;
+    Ok(())
+}
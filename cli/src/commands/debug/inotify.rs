@@ -0,0 +1,130 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::Debug;
+#[cfg(target_os = "linux")]
+use std::io::Write as _;
+use std::path::PathBuf;
+
+use clap::Subcommand;
+#[cfg(target_os = "linux")]
+use jj_lib::fsmonitor::inotify;
+#[cfg(target_os = "linux")]
+use jj_lib::fsmonitor::FsmonitorSettings;
+
+#[cfg(target_os = "linux")]
+use super::check_local_disk_wc;
+use crate::cli_util::CommandHelper;
+#[cfg(target_os = "linux")]
+use crate::command_error::internal_error_with_message;
+#[cfg(not(target_os = "linux"))]
+use crate::command_error::user_error;
+use crate::command_error::CommandError;
+use crate::ui::Ui;
+
+#[derive(Subcommand, Clone, Debug)]
+pub enum DebugInotifyCommand {
+    /// Check whether the inotify daemon is enabled and running
+    Status,
+    /// Run the inotify daemon in the foreground
+    ///
+    /// This is how the daemon is started in the background when a command
+    /// needs it.
+    Run {
+        /// Root of the working copy to watch
+        #[arg(long)]
+        working_copy: PathBuf,
+        /// Directory where the working-copy state is stored
+        #[arg(long)]
+        state_dir: PathBuf,
+    },
+    /// Stop the inotify daemon of the workspace
+    Stop,
+}
+
+#[cfg(target_os = "linux")]
+pub fn cmd_debug_inotify(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    subcommand: &DebugInotifyCommand,
+) -> Result<(), CommandError> {
+    match subcommand {
+        DebugInotifyCommand::Status => {
+            let workspace_command = command.workspace_helper_no_snapshot(ui)?;
+            if matches!(
+                workspace_command.settings().fsmonitor_settings()?,
+                FsmonitorSettings::Inotify(_)
+            ) {
+                writeln!(
+                    ui.stdout(),
+                    "The inotify daemon is enabled via `core.fsmonitor`."
+                )?;
+            } else {
+                writeln!(
+                    ui.stdout(),
+                    r#"The inotify daemon is disabled. Set `core.fsmonitor="inotify"` to enable."#
+                )?;
+            }
+            let wc = check_local_disk_wc(workspace_command.working_copy().as_any())?;
+            let fsmonitor = inotify::Fsmonitor::new(wc.state_path());
+            match fsmonitor.query_changed_files(None) {
+                Ok((clock, _changed_files)) => {
+                    writeln!(ui.stdout(), "The inotify daemon is running.")?;
+                    writeln!(ui.stdout(), "Clock: {}", clock.as_str())?;
+                }
+                Err(_) if fsmonitor.is_daemon_running() => {
+                    writeln!(ui.stdout(), "The inotify daemon is starting.")?;
+                }
+                Err(_) => {
+                    writeln!(ui.stdout(), "The inotify daemon is not running.")?;
+                }
+            }
+        }
+        DebugInotifyCommand::Run {
+            working_copy,
+            state_dir,
+        } => {
+            inotify::run_daemon(working_copy, state_dir)
+                .map_err(|err| internal_error_with_message("The inotify daemon failed", err))?;
+        }
+        DebugInotifyCommand::Stop => {
+            let workspace_command = command.workspace_helper_no_snapshot(ui)?;
+            let wc = check_local_disk_wc(workspace_command.working_copy().as_any())?;
+            match inotify::Fsmonitor::new(wc.state_path()).stop() {
+                Ok(()) => writeln!(ui.status(), "Stopped the inotify daemon")?,
+                Err(inotify::Error::NotRunning(_)) => {
+                    writeln!(ui.status(), "The inotify daemon is not running")?;
+                }
+                Err(err) => {
+                    return Err(internal_error_with_message(
+                        "Failed to stop the inotify daemon",
+                        err,
+                    ));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn cmd_debug_inotify(
+    _ui: &mut Ui,
+    _command: &CommandHelper,
+    _subcommand: &DebugInotifyCommand,
+) -> Result<(), CommandError> {
+    Err(user_error(
+        "The inotify filesystem monitor is only supported on Linux",
+    ))
+}
@@ -15,7 +15,9 @@
 mod copy_detection;
 mod fileset;
 mod index;
+mod index_changed_paths;
 mod init_simple;
+mod inotify;
 mod local_working_copy;
 mod operation;
 mod reindex;
@@ -38,8 +40,12 @@ use self::fileset::cmd_debug_fileset;
 use self::fileset::DebugFilesetArgs;
 use self::index::cmd_debug_index;
 use self::index::DebugIndexArgs;
+use self::index_changed_paths::cmd_debug_index_changed_paths;
+use self::index_changed_paths::DebugIndexChangedPathsArgs;
 use self::init_simple::cmd_debug_init_simple;
 use self::init_simple::DebugInitSimpleArgs;
+use self::inotify::cmd_debug_inotify;
+use self::inotify::DebugInotifyCommand;
 use self::local_working_copy::cmd_debug_local_working_copy;
 use self::local_working_copy::DebugLocalWorkingCopyArgs;
 use self::operation::cmd_debug_operation;
@@ -70,7 +76,10 @@ pub enum DebugCommand {
     CopyDetection(CopyDetectionArgs),
     Fileset(DebugFilesetArgs),
     Index(DebugIndexArgs),
+    IndexChangedPaths(DebugIndexChangedPathsArgs),
     InitSimple(DebugInitSimpleArgs),
+    #[command(subcommand)]
+    Inotify(DebugInotifyCommand),
     LocalWorkingCopy(DebugLocalWorkingCopyArgs),
     #[command(visible_alias = "view")]
     Operation(DebugOperationArgs),
@@ -93,7 +102,9 @@ pub fn cmd_debug(
         DebugCommand::CopyDetection(args) => cmd_debug_copy_detection(ui, command, args),
         DebugCommand::Fileset(args) => cmd_debug_fileset(ui, command, args),
         DebugCommand::Index(args) => cmd_debug_index(ui, command, args),
+        DebugCommand::IndexChangedPaths(args) => cmd_debug_index_changed_paths(ui, command, args),
         DebugCommand::InitSimple(args) => cmd_debug_init_simple(ui, command, args),
+        DebugCommand::Inotify(args) => cmd_debug_inotify(ui, command, args),
         DebugCommand::LocalWorkingCopy(args) => cmd_debug_local_working_copy(ui, command, args),
         DebugCommand::Operation(args) => cmd_debug_operation(ui, command, args),
         DebugCommand::Reindex(args) => cmd_debug_reindex(ui, command, args),
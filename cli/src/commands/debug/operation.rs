@@ -18,8 +18,10 @@ use std::io::Write as _;
 use clap_complete::ArgValueCandidates;
 use jj_lib::object_id::ObjectId as _;
 use jj_lib::op_walk;
+use jj_lib::revset::RevsetDiagnostics;
 
 use crate::cli_util::CommandHelper;
+use crate::command_error::print_parse_diagnostics;
 use crate::command_error::CommandError;
 use crate::complete;
 use crate::ui::Ui;
@@ -54,7 +56,9 @@ pub fn cmd_debug_operation(
     // even if e.g. the view object is broken.
     let workspace = command.load_workspace()?;
     let repo_loader = workspace.repo_loader();
-    let op = op_walk::resolve_op_for_load(repo_loader, &args.operation)?;
+    let mut diagnostics = RevsetDiagnostics::new();
+    let op = op_walk::resolve_op_for_load(&mut diagnostics, repo_loader, &args.operation)?;
+    print_parse_diagnostics(ui, "In operation expression", &diagnostics)?;
     if args.display == OperationDisplay::Id {
         writeln!(ui.stdout(), "{}", op.id().hex())?;
         return Ok(());
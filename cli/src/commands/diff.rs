@@ -118,7 +118,7 @@ pub(crate) fn cmd_diff(
         from_tree = from.tree()?;
         to_tree = to.tree()?;
 
-        let records = get_copy_records(repo.store(), from.id(), to.id(), &matcher)?;
+        let records = get_copy_records(repo.store(), from.id(), to.id(), &to_tree, &matcher)?;
         copy_records.add_records(records)?;
     } else {
         let revision_args = args
@@ -156,7 +156,7 @@ pub(crate) fn cmd_diff(
 
         for p in &parents {
             for to in &heads {
-                let records = get_copy_records(repo.store(), p.id(), to.id(), &matcher)?;
+                let records = get_copy_records(repo.store(), p.id(), to.id(), &to_tree, &matcher)?;
                 copy_records.add_records(records)?;
             }
         }
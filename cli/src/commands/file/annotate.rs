@@ -12,11 +12,14 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::ops::Range;
+
 use clap_complete::ArgValueCandidates;
 use clap_complete::ArgValueCompleter;
 use jj_lib::annotate::FileAnnotation;
 use jj_lib::annotate::FileAnnotator;
 use jj_lib::repo::Repo;
+use jj_lib::repo_path::RepoPath;
 use jj_lib::revset::RevsetExpression;
 use tracing::instrument;
 
@@ -34,6 +37,13 @@ use crate::ui::Ui;
 ///
 /// Annotates a revision line by line. Each line includes the source change that
 /// introduced the associated line. A path to the desired file must be provided.
+///
+/// Renames and copies of the file are followed, so lines which were moved from
+/// another file are attributed to the change that introduced them there.
+///
+/// Revisions such as mass reformatting commits can be skipped with `--ignore`
+/// or the `revsets.annotate-ignore` setting. The lines changed by them are
+/// attributed to the lines at the same position in their parents.
 #[derive(clap::Args, Clone, Debug)]
 pub(crate) struct FileAnnotateArgs {
     /// the file to annotate
@@ -50,6 +60,19 @@ pub(crate) struct FileAnnotateArgs {
         add = ArgValueCandidates::new(complete::all_revisions)
     )]
     revision: Option<RevisionArg>,
+    /// Revisions to skip, in addition to the `revsets.annotate-ignore` setting
+    #[arg(
+        long,
+        value_name = "REVSETS",
+        add = ArgValueCandidates::new(complete::all_revisions)
+    )]
+    ignore: Vec<RevisionArg>,
+    /// Only annotate the lines in the given range
+    ///
+    /// The range is given as `START,END`, where both line numbers are 1-based
+    /// and inclusive.
+    #[arg(short = 'L', long = "lines", value_name = "START,END", value_parser = parse_line_range)]
+    line_range: Option<Range<usize>>,
     /// Render each line using the given template
     ///
     /// All 0-argument methods of the [`AnnotationLine` type] are available as
@@ -108,36 +131,88 @@ pub(crate) fn cmd_file_annotate(
     // Note that this is probably different from "--skip REVS", which won't
     // exclude the revisions, but will ignore diffs in those revisions as if
     // ancestor revisions had new content.
+    let ignored_revisions = {
+        let revset_string = workspace_command
+            .settings()
+            .get_string("revsets.annotate-ignore")?;
+        let mut expression = workspace_command
+            .parse_revset(ui, &RevisionArg::from(revset_string))?
+            .expression()
+            .clone();
+        if !args.ignore.is_empty() {
+            let ignore = workspace_command.parse_union_revsets(ui, &args.ignore)?;
+            expression = expression.union(ignore.expression());
+        }
+        workspace_command
+            .attach_revset_evaluator(expression)
+            .resolve()?
+    };
     let mut annotator = FileAnnotator::from_commit(&starting_commit, &file_path)?;
+    annotator.set_follow_copies(true);
+    annotator.set_ignored_revisions(ignored_revisions);
+    if let Some(line_range) = &args.line_range {
+        annotator.restrict_to_lines(line_range.clone());
+    }
     annotator.compute(repo.as_ref(), &RevsetExpression::all())?;
     let annotation = annotator.to_annotation();
 
-    render_file_annotation(repo.as_ref(), ui, &template, &annotation)?;
+    let line_range = args.line_range.clone().unwrap_or(0..usize::MAX);
+    render_file_annotation(
+        repo.as_ref(),
+        ui,
+        &template,
+        &file_path,
+        &annotation,
+        line_range,
+    )?;
     Ok(())
 }
 
+/// Parses 1-based inclusive `START,END` into 0-based line range.
+fn parse_line_range(s: &str) -> Result<Range<usize>, String> {
+    let (start, end) = s
+        .split_once(',')
+        .ok_or("Line range must be in the form START,END")?;
+    let parse = |n: &str| match n.trim().parse() {
+        Ok(n) if n > 0 => Ok(n),
+        _ => Err(format!("Invalid line number: {n}")),
+    };
+    let (start, end): (usize, usize) = (parse(start)?, parse(end)?);
+    if start > end {
+        return Err(format!("Line range start {start} is after end {end}"));
+    }
+    Ok(start - 1..end)
+}
+
 fn render_file_annotation(
     repo: &dyn Repo,
     ui: &mut Ui,
     template_render: &TemplateRenderer<AnnotationLine>,
+    file_path: &RepoPath,
     annotation: &FileAnnotation,
+    line_range: Range<usize>,
 ) -> Result<(), CommandError> {
     ui.request_pager();
     let mut formatter = ui.stdout_formatter();
     let mut last_id = None;
     let default_id = repo.store().root_commit_id();
-    for (line_number, (commit_id, content)) in annotation.lines().enumerate() {
+    let lines = annotation.lines().enumerate();
+    for (line_number, (line_origin, content)) in lines.take(line_range.end).skip(line_range.start) {
         /* At least in cases where the repository was jj-initialized shallowly,
         then unshallow'd with git, some changes will not have a commit id
         because jj does not import the unshallow'd commits. So we default
         to the root commit id for now. */
-        let commit_id = commit_id.unwrap_or(default_id);
+        let (commit_id, path) = match line_origin {
+            Ok(origin) => (&origin.commit_id, origin.path.as_ref()),
+            Err(origin) => (default_id, origin.path.as_ref()),
+        };
         let commit = repo.store().get_commit(commit_id)?;
         let first_line_in_hunk = last_id != Some(commit_id);
         let annotation_line = AnnotationLine {
             commit,
             content: content.to_owned(),
             line_number: line_number + 1,
+            original_path: (path != file_path).then(|| path.to_owned()),
             first_line_in_hunk,
         };
         template_render.format(&annotation_line, formatter.as_mut())?;
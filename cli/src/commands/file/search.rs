@@ -0,0 +1,198 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::Read as _;
+use std::io::Write as _;
+
+use bstr::ByteSlice as _;
+use clap_complete::ArgValueCandidates;
+use clap_complete::ArgValueCompleter;
+use jj_lib::backend::BackendError;
+use jj_lib::backend::BackendResult;
+use jj_lib::backend::TreeValue;
+use jj_lib::files;
+use jj_lib::merge::MergedTreeValue;
+use jj_lib::repo::Repo as _;
+use jj_lib::repo_path::RepoPathBuf;
+use jj_lib::store::Store;
+use rayon::iter::IntoParallelIterator as _;
+use rayon::iter::ParallelIterator as _;
+use regex::bytes::Regex;
+use tracing::instrument;
+
+use crate::cli_util::print_unmatched_explicit_paths;
+use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::command_error::CommandError;
+use crate::complete;
+use crate::ui::Ui;
+
+/// Search for a pattern in the contents of files in a revision
+///
+/// Each matching line is printed as `path:line:text`. Lines in conflicted
+/// files are searched on every side and base of the conflict, and the term
+/// the line came from is printed after the line number, e.g.
+/// `path:line:(side #2):text`. Binary files are skipped.
+#[derive(clap::Args, Clone, Debug)]
+pub(crate) struct FileSearchArgs {
+    /// The revision to search files in
+    #[arg(
+        long, short,
+        default_value = "@",
+        value_name = "REVSET",
+        add = ArgValueCandidates::new(complete::all_revisions),
+    )]
+    revision: RevisionArg,
+    /// Regular expression to search for
+    #[arg(value_parser = Regex::new)]
+    pattern: Regex,
+    /// Paths to search in
+    #[arg(
+        value_name = "FILESETS",
+        value_hint = clap::ValueHint::AnyPath,
+        add = ArgValueCompleter::new(complete::all_revision_files),
+    )]
+    paths: Vec<String>,
+}
+
+/// A line matching the search pattern.
+struct LineMatch {
+    /// Label of the conflict term the line was found in, if the file is
+    /// conflicted.
+    term: Option<String>,
+    line_number: usize,
+    line: Vec<u8>,
+}
+
+#[instrument(skip_all)]
+pub(crate) fn cmd_file_search(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &FileSearchArgs,
+) -> Result<(), CommandError> {
+    let workspace_command = command.workspace_helper(ui)?;
+    let commit = workspace_command.resolve_single_rev(ui, &args.revision)?;
+    let tree = commit.tree()?;
+    let fileset_expression = workspace_command.parse_file_patterns(ui, &args.paths)?;
+    let matcher = fileset_expression.to_matcher();
+    let store = workspace_command.repo().store();
+
+    let entries: Vec<(RepoPathBuf, MergedTreeValue)> = tree
+        .entries_matching(matcher.as_ref())
+        .map(|(path, value)| Ok((path, value?)))
+        .collect::<BackendResult<_>>()?;
+    let results: Vec<(RepoPathBuf, Vec<LineMatch>)> = entries
+        .into_par_iter()
+        .map(|(path, value)| {
+            let matches = search_tree_value(store, &path, &value, &args.pattern)?;
+            Ok((path, matches))
+        })
+        .collect::<BackendResult<_>>()?;
+
+    ui.request_pager();
+    let mut formatter = ui.stdout_formatter();
+    for (path, matches) in &results {
+        let ui_path = workspace_command.format_file_path(path);
+        for line_match in matches {
+            write!(formatter.labeled("file_search path"), "{ui_path}")?;
+            write!(formatter, ":")?;
+            write!(
+                formatter.labeled("file_search line_number"),
+                "{}",
+                line_match.line_number
+            )?;
+            write!(formatter, ":")?;
+            if let Some(term) = &line_match.term {
+                write!(formatter.labeled("file_search term"), "({term})")?;
+                write!(formatter, ":")?;
+            }
+            formatter.write_all(&line_match.line)?;
+            writeln!(formatter)?;
+        }
+    }
+    drop(formatter);
+    print_unmatched_explicit_paths(ui, &workspace_command, &fileset_expression, [&tree])?;
+    Ok(())
+}
+
+fn search_tree_value(
+    store: &Store,
+    path: &RepoPathBuf,
+    value: &MergedTreeValue,
+    pattern: &Regex,
+) -> BackendResult<Vec<LineMatch>> {
+    if let Some(value) = value.as_resolved() {
+        return search_term(store, path, value.as_ref(), None, pattern);
+    }
+    // Label the terms the same way as in materialized conflicts.
+    let num_bases = value.removes().len();
+    let labeled_terms = value
+        .adds()
+        .enumerate()
+        .map(|(i, term)| (format!("side #{}", i + 1), term))
+        .chain(value.removes().enumerate().map(|(i, term)| {
+            let label = if num_bases == 1 {
+                "base".to_owned()
+            } else {
+                format!("base #{}", i + 1)
+            };
+            (label, term)
+        }));
+    let mut matches = vec![];
+    for (label, term) in labeled_terms {
+        matches.extend(search_term(
+            store,
+            path,
+            term.as_ref(),
+            Some(label),
+            pattern,
+        )?);
+    }
+    Ok(matches)
+}
+
+fn search_term(
+    store: &Store,
+    path: &RepoPathBuf,
+    term: Option<&TreeValue>,
+    label: Option<String>,
+    pattern: &Regex,
+) -> BackendResult<Vec<LineMatch>> {
+    let Some(TreeValue::File { id, .. }) = term else {
+        return Ok(vec![]);
+    };
+    let mut content = vec![];
+    store
+        .read_file(path, id)?
+        .read_to_end(&mut content)
+        .map_err(|err| BackendError::ReadFile {
+            path: path.clone(),
+            id: id.clone(),
+            source: err.into(),
+        })?;
+    if files::is_binary_content(&content) {
+        return Ok(vec![]);
+    }
+    let matches = content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| pattern.is_match(line))
+        .map(|(i, line)| LineMatch {
+            term: label.clone(),
+            line_number: i + 1,
+            line: line.to_vec(),
+        })
+        .collect();
+    Ok(matches)
+}
@@ -24,6 +24,7 @@ use tracing::instrument;
 
 use crate::cli_util::print_untracked_files;
 use crate::cli_util::CommandHelper;
+use crate::command_error::user_error;
 use crate::command_error::CommandError;
 use crate::ui::Ui;
 
@@ -36,11 +37,23 @@ use crate::ui::Ui;
 /// `snapshot.auto-track` (e.g. to `"none()"` or `"glob:**/*.rs"`). Files that
 /// don't match the pattern can be manually tracked using this command. The
 /// default pattern is `all()` and this command has no effect.
+///
+/// New files that are too large, that look like generated binaries (with
+/// `snapshot.untrack-binaries`), or that match `snapshot.ignore-by-default`
+/// are left untracked even if specified. Use `--force` to track them anyway.
 #[derive(clap::Args, Clone, Debug)]
 pub(crate) struct FileTrackArgs {
     /// Paths to track
     #[arg(required = true, value_name = "FILESETS", value_hint = clap::ValueHint::AnyPath)]
     paths: Vec<String>,
+    /// Track the paths even if the snapshot settings would leave them
+    /// untracked
+    ///
+    /// The paths are remembered by the working copy, so they're tracked again
+    /// if they're deleted and re-created. Use `jj file untrack` to forget
+    /// them.
+    #[arg(long)]
+    force: bool,
 }
 
 #[instrument(skip_all)]
@@ -52,8 +65,14 @@ pub(crate) fn cmd_file_track(
     let (mut workspace_command, auto_stats) = command.workspace_helper_with_stats(ui)?;
     let matcher = workspace_command
         .parse_file_patterns(ui, &args.paths)?
-        .to_matcher();
-    let options = workspace_command.snapshot_options_with_start_tracking_matcher(&matcher)?;
+        .to_path_matcher()
+        .map_err(user_error)?;
+    let snapshot_policy = workspace_command.snapshot_policy(ui)?;
+    let mut options = workspace_command
+        .snapshot_options_with_start_tracking_matcher(&matcher, &snapshot_policy)?;
+    if args.force {
+        options.force_tracking_matcher = &matcher;
+    }
 
     let mut tx = workspace_command.start_transaction().into_inner();
     let (mut locked_ws, _wc_commit) = workspace_command.start_working_copy_mutation()?;
@@ -80,6 +99,21 @@ pub fn print_track_snapshot_stats(
     path_converter: &RepoPathUiConverter,
 ) -> io::Result<()> {
     let mut merged_untracked_paths = auto_stats.untracked_paths;
+    // forget about files that are now tracked, e.g. with `file track --force`
+    merged_untracked_paths.retain(|path, _| track_stats.untracked_paths.contains_key(path));
+    let policy_files = track_stats
+        .untracked_paths
+        .iter()
+        .filter_map(|(path, reason)| match reason {
+            UntrackedReason::GeneratedBinary { format } => {
+                Some((path.clone(), format!("generated binary ({format})")))
+            }
+            UntrackedReason::IgnoredByDefault => {
+                Some((path.clone(), "ignored by default".to_owned()))
+            }
+            UntrackedReason::FileTooLarge { .. } | UntrackedReason::FileNotAutoTracked => None,
+        })
+        .collect_vec();
     for (path, reason) in track_stats
         .untracked_paths
         .into_iter()
@@ -93,11 +127,31 @@ pub fn print_track_snapshot_stats(
 
     print_untracked_files(ui, &merged_untracked_paths, path_converter)?;
 
+    if !policy_files.is_empty() {
+        writeln!(ui.warning_default(), "Refused to track some files:")?;
+        let mut formatter = ui.stderr_formatter();
+        for (path, message) in &policy_files {
+            let ui_path = path_converter.format_file_path(path);
+            writeln!(formatter, "  {ui_path}: {message}")?;
+        }
+        drop(formatter);
+        let files_list = policy_files
+            .iter()
+            .map(|(path, _)| path_converter.format_file_path(path))
+            .join(" ");
+        writeln!(
+            ui.hint_default(),
+            "Run `jj file track --force {files_list}` to track them anyway."
+        )?;
+    }
+
     let (large_files, sizes): (Vec<_>, Vec<_>) = merged_untracked_paths
         .iter()
         .filter_map(|(path, reason)| match reason {
             UntrackedReason::FileTooLarge { size, .. } => Some((path, *size)),
-            UntrackedReason::FileNotAutoTracked => None,
+            UntrackedReason::FileNotAutoTracked
+            | UntrackedReason::GeneratedBinary { .. }
+            | UntrackedReason::IgnoredByDefault => None,
         })
         .unzip();
     if let Some(size) = sizes.iter().max() {
@@ -114,6 +168,8 @@ pub fn print_track_snapshot_stats(
                 This will increase the maximum file size allowed for new files, in this repository only.
               - Run `jj --config snapshot.max-new-file-size={size} file track {large_files_list}`
                 This will increase the maximum file size allowed for new files, for this command only.
+              - Run `jj file track --force {large_files_list}`
+                This will track the files regardless of their size.
             "
         )?;
     }
@@ -56,8 +56,9 @@ pub(crate) fn cmd_file_untrack(
         .parse_file_patterns(ui, &args.paths)?
         .to_matcher();
     let auto_tracking_matcher = workspace_command.auto_tracking_matcher(ui)?;
-    let options =
-        workspace_command.snapshot_options_with_start_tracking_matcher(&auto_tracking_matcher)?;
+    let snapshot_policy = workspace_command.snapshot_policy(ui)?;
+    let options = workspace_command
+        .snapshot_options_with_start_tracking_matcher(&auto_tracking_matcher, &snapshot_policy)?;
 
     let mut tx = workspace_command.start_transaction().into_inner();
     let (mut locked_ws, wc_commit) = workspace_command.start_working_copy_mutation()?;
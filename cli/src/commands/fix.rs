@@ -38,6 +38,7 @@ use tracing::instrument;
 use crate::cli_util::CommandHelper;
 use crate::cli_util::RevisionArg;
 use crate::command_error::config_error;
+use crate::command_error::config_error_with_message;
 use crate::command_error::print_parse_diagnostics;
 use crate::command_error::CommandError;
 use crate::complete;
@@ -321,9 +322,12 @@ fn get_tools_config(ui: &mut Ui, settings: &UserSettings) -> Result<ToolsConfig,
                     .try_collect()?,
             );
             print_parse_diagnostics(ui, &format!("In `fix.tools.{name}`"), &diagnostics)?;
+            let matcher = expression.to_path_matcher().map_err(|err| {
+                config_error_with_message(format!("Invalid `fix.tools.{name}.patterns`"), err)
+            })?;
             Ok(ToolConfig {
                 command: tool.command,
-                matcher: expression.to_matcher(),
+                matcher,
                 enabled: tool.enabled,
             })
         })
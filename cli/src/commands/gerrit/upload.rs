@@ -0,0 +1,294 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::io::Write as _;
+
+use clap_complete::ArgValueCandidates;
+use itertools::Itertools as _;
+use jj_lib::backend::ChangeId;
+use jj_lib::backend::CommitId;
+use jj_lib::commit::Commit;
+use jj_lib::git;
+use jj_lib::git::GitRefUpdate;
+use jj_lib::object_id::ObjectId as _;
+use jj_lib::ref_name::RemoteNameBuf;
+use jj_lib::revset::RevsetExpression;
+use jj_lib::trailer::parse_description_trailers;
+
+use crate::cli_util::short_commit_hash;
+use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::command_error::user_error;
+use crate::command_error::CommandError;
+use crate::commands::git::push::get_default_push_remote;
+use crate::complete;
+use crate::git_util::with_remote_git_callbacks;
+use crate::ui::Ui;
+
+/// Upload changes to Gerrit for review
+///
+/// The specified revisions and their ancestors that are neither immutable nor
+/// on the remote are pushed to `refs/for/<BRANCH>`, which makes Gerrit create
+/// or update a change for each of them.
+///
+/// Gerrit identifies changes by the `Change-Id:` trailer in the description.
+/// If a commit doesn't have one, a trailer derived from its change ID is added
+/// to the uploaded commit. The local commits are left unchanged. Since the
+/// trailer only depends on the change ID, uploading a rewritten commit again
+/// creates a new patch set of the same Gerrit change.
+#[derive(clap::Args, Clone, Debug)]
+pub struct GerritUploadArgs {
+    /// The revisions to upload
+    #[arg(
+        long,
+        short,
+        required = true,
+        value_name = "REVSETS",
+        add = ArgValueCandidates::new(complete::mutable_revisions)
+    )]
+    revisions: Vec<RevisionArg>,
+    /// The remote to push to
+    ///
+    /// This defaults to the same remote as `jj git push`.
+    #[arg(long, add = ArgValueCandidates::new(complete::git_remotes))]
+    remote: Option<RemoteNameBuf>,
+    /// The branch the changes are intended to be merged into
+    #[arg(
+        long = "for",
+        value_name = "BRANCH",
+        add = ArgValueCandidates::new(complete::bookmarks)
+    )]
+    for_branch: String,
+    /// Only display what would be uploaded
+    #[arg(long)]
+    dry_run: bool,
+}
+
+pub fn cmd_gerrit_upload(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &GerritUploadArgs,
+) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui)?;
+    let remote = match &args.remote {
+        Some(remote) => remote.clone(),
+        None => get_default_push_remote(ui, &workspace_command)?,
+    };
+    let heads = workspace_command
+        .parse_union_revsets(ui, &args.revisions)?
+        .expression()
+        .clone();
+    let repo = workspace_command.repo().clone();
+    let remote_heads = repo
+        .view()
+        .remote_bookmarks(&remote)
+        .flat_map(|(_, remote_ref)| remote_ref.target.added_ids())
+        .cloned()
+        .collect_vec();
+    let commits_to_upload: Vec<Commit> = workspace_command
+        .attach_revset_evaluator(
+            RevsetExpression::commits(remote_heads)
+                .union(workspace_command.env().immutable_heads_expression())
+                .range(&heads),
+        )
+        .evaluate_to_commits()?
+        .try_collect()?;
+    if commits_to_upload.is_empty() {
+        writeln!(ui.status(), "No changes to upload.")?;
+        return Ok(());
+    }
+    for commit in &commits_to_upload {
+        let reason = if commit.description().is_empty() {
+            "it has no description"
+        } else if commit.has_conflict()? {
+            "it has conflicts"
+        } else {
+            continue;
+        };
+        let mut error = user_error(format!(
+            "Won't upload commit {} since {reason}",
+            short_commit_hash(commit.id()),
+        ));
+        error.add_formatted_hint_with(|formatter| {
+            write!(formatter, "Rejected commit: ")?;
+            workspace_command.write_commit_summary(formatter, commit)?;
+            Ok(())
+        });
+        return Err(error);
+    }
+
+    if let Some(mut formatter) = ui.status_formatter() {
+        writeln!(
+            formatter,
+            "Commits to upload to {remote} for review on {branch}:",
+            remote = remote.as_symbol(),
+            branch = args.for_branch,
+        )?;
+        for commit in &commits_to_upload {
+            write!(formatter, "  ")?;
+            workspace_command.write_commit_summary(formatter.as_mut(), commit)?;
+            writeln!(formatter)?;
+        }
+    }
+    if args.dry_run {
+        writeln!(ui.status(), "Dry-run requested, not uploading.")?;
+        return Ok(());
+    }
+
+    // Write the commits with Change-Id trailers without making them visible.
+    // The commits are processed parents first so the rewritten parents can be
+    // substituted.
+    let mut tx = workspace_command.start_transaction();
+    let mut uploaded_ids: HashMap<CommitId, CommitId> = HashMap::new();
+    for commit in commits_to_upload.iter().rev() {
+        let new_parents = commit
+            .parent_ids()
+            .iter()
+            .map(|id| uploaded_ids.get(id).unwrap_or(id).clone())
+            .collect_vec();
+        let new_description = add_change_id_trailer(commit.description(), commit.change_id());
+        let new_id = if new_parents == commit.parent_ids() && new_description.is_none() {
+            commit.id().clone()
+        } else {
+            tx.repo_mut()
+                .rewrite_commit(commit)
+                .set_parents(new_parents)
+                .set_description(new_description.as_deref().unwrap_or(commit.description()))
+                // Keep the committer timestamp so uploading an unchanged
+                // commit again doesn't create a new patch set.
+                .set_committer(commit.committer().clone())
+                .detach()
+                .write_hidden()?
+                .id()
+                .clone()
+        };
+        uploaded_ids.insert(commit.id().clone(), new_id);
+    }
+
+    // Pushing a head uploads all its ancestors, but each head has to be pushed
+    // separately since they are pushed to the same ref.
+    let upload_heads: Vec<CommitId> = tx
+        .base_workspace_helper()
+        .attach_revset_evaluator(
+            RevsetExpression::commits(uploaded_ids.keys().cloned().collect()).heads(),
+        )
+        .evaluate_to_commit_ids()?
+        .try_collect()?;
+    let git_settings = tx.settings().git_settings()?;
+    let qualified_name = format!("refs/for/{}", args.for_branch);
+    for head in &upload_heads {
+        let update = GitRefUpdate {
+            qualified_name: qualified_name.clone().into(),
+            // Gerrit doesn't store refs/for/*, so the ref never exists.
+            expected_current_target: None,
+            new_target: Some(uploaded_ids[head].clone()),
+        };
+        let push_stats = with_remote_git_callbacks(ui, |cb| {
+            git::push_updates(tx.repo(), &git_settings, &remote, &[update], cb)
+        })?;
+        if let Some((_, reason)) = push_stats
+            .rejected
+            .iter()
+            .chain(&push_stats.remote_rejected)
+            .next()
+        {
+            let mut error = user_error(format!(
+                "Failed to upload commit {}",
+                short_commit_hash(head)
+            ));
+            if let Some(reason) = reason {
+                error.add_hint(format!("The remote rejected the upload: {reason}"));
+            }
+            return Err(error);
+        }
+    }
+    Ok(())
+}
+
+/// Returns the description with a `Change-Id:` trailer derived from the
+/// `change_id` added, or `None` if it already has one.
+fn add_change_id_trailer(description: &str, change_id: &ChangeId) -> Option<String> {
+    let trailers = parse_description_trailers(description);
+    if trailers
+        .iter()
+        .any(|trailer| trailer.key.eq_ignore_ascii_case("Change-Id"))
+    {
+        return None;
+    }
+    let mut description = description.to_owned();
+    if !description.is_empty() && !description.ends_with('\n') {
+        description.push('\n');
+    }
+    if trailers.is_empty() {
+        if description.is_empty() {
+            // a first empty line where the user will edit the commit summary
+            description.push('\n');
+        }
+        // create a new paragraph for the trailer
+        description.push('\n');
+    }
+    // Gerrit expects 40 hex digits, so pad the change ID with "jjid" in hex.
+    description.push_str(&format!("Change-Id: I{}6a6a6964\n", change_id.hex()));
+    Some(description)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add_change_id_trailer() {
+        let change_id = ChangeId::from_hex("ffdaa62087a280bddc5e3d3ff933b8ae");
+        let add = |description: &str| add_change_id_trailer(description, &change_id);
+
+        // A new paragraph is created for the trailer
+        insta::assert_snapshot!(add("summary\n").unwrap(), @r"
+        summary
+
+        Change-Id: Iffdaa62087a280bddc5e3d3ff933b8ae6a6a6964
+        ");
+        insta::assert_snapshot!(add("summary").unwrap(), @r"
+        summary
+
+        Change-Id: Iffdaa62087a280bddc5e3d3ff933b8ae6a6a6964
+        ");
+        insta::assert_snapshot!(add("summary\n\nSome body text.\n").unwrap(), @r"
+        summary
+
+        Some body text.
+
+        Change-Id: Iffdaa62087a280bddc5e3d3ff933b8ae6a6a6964
+        ");
+
+        // The trailer is appended to the existing trailers
+        let signed_off = "summary\n\nSigned-off-by: Alice <alice@example.com>";
+        insta::assert_snapshot!(add(&format!("{signed_off}\n")).unwrap(), @r"
+        summary
+
+        Signed-off-by: Alice <alice@example.com>
+        Change-Id: Iffdaa62087a280bddc5e3d3ff933b8ae6a6a6964
+        ");
+        insta::assert_snapshot!(add(signed_off).unwrap(), @r"
+        summary
+
+        Signed-off-by: Alice <alice@example.com>
+        Change-Id: Iffdaa62087a280bddc5e3d3ff933b8ae6a6a6964
+        ");
+
+        // Existing Change-Id trailers are kept regardless of the case
+        assert_eq!(add("summary\n\nChange-Id: I0123456789abcdef\n"), None);
+        assert_eq!(add("summary\n\nchange-id: I0123456789abcdef\n"), None);
+    }
+}
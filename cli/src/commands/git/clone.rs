@@ -36,6 +36,7 @@ use crate::command_error::user_error_with_message;
 use crate::command_error::CommandError;
 use crate::commands::git::maybe_add_gitignore;
 use crate::git_util::absolute_git_url;
+use crate::git_util::fetch_lfs_objects;
 #[cfg(feature = "git2")]
 use crate::git_util::print_git2_deprecation_warning;
 use crate::git_util::print_git_import_stats;
@@ -230,6 +231,7 @@ fn fetch_new_remote(
         with_remote_git_callbacks(ui, |cb| git_fetch.get_default_branch(remote_name, cb))?;
     let import_stats = git_fetch.import_refs()?;
     print_git_import_stats(ui, fetch_tx.repo(), &import_stats, true)?;
+    fetch_lfs_objects(ui, fetch_tx.base_repo(), fetch_tx.repo(), &[remote_name])?;
     fetch_tx.finish(ui, "fetch from git remote into empty repo")?;
     Ok(default_branch)
 }
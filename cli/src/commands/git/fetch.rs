@@ -31,6 +31,7 @@ use crate::command_error::user_error;
 use crate::command_error::CommandError;
 use crate::commands::git::get_single_remote;
 use crate::complete;
+use crate::git_util::fetch_lfs_objects;
 #[cfg(feature = "git2")]
 use crate::git_util::print_git2_deprecation_warning;
 use crate::git_util::print_git_import_stats;
@@ -185,6 +186,7 @@ fn do_git_fetch(
     }
     let import_stats = git_fetch.import_refs()?;
     print_git_import_stats(ui, tx.repo(), &import_stats, true)?;
+    fetch_lfs_objects(ui, tx.base_repo(), tx.repo(), remotes)?;
     warn_if_branches_not_found(ui, tx, branch_names, remotes)
 }
 
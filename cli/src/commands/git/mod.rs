@@ -17,7 +17,7 @@ mod export;
 mod fetch;
 mod import;
 mod init;
-mod push;
+pub mod push;
 mod remote;
 mod root;
 
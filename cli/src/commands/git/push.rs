@@ -64,6 +64,7 @@ use crate::complete;
 use crate::formatter::Formatter;
 #[cfg(feature = "git2")]
 use crate::git_util::print_git2_deprecation_warning;
+use crate::git_util::push_lfs_objects;
 use crate::git_util::with_remote_git_callbacks;
 use crate::revset_util::parse_bookmark_name;
 use crate::ui::Ui;
@@ -400,7 +401,9 @@ pub fn cmd_git_push(
         return Ok(());
     }
 
-    let sign_behavior = if tx.settings().get_bool("git.sign-on-push")? {
+    let sign_behavior = if tx.settings().sign_settings().behavior == SignBehavior::OnPush {
+        Some(SignBehavior::Force)
+    } else if tx.settings().get_bool("git.sign-on-push")? {
         Some(SignBehavior::Own)
     } else {
         None
@@ -446,6 +449,20 @@ pub fn cmd_git_push(
         return Ok(());
     }
 
+    // Like Git LFS, upload the objects before the pointers to them are pushed.
+    let lfs_heads = bookmark_updates
+        .iter()
+        .filter_map(|(_, update)| update.new_target.clone())
+        .collect_vec();
+    let lfs_bases = tx
+        .repo()
+        .view()
+        .remote_bookmarks(remote)
+        .flat_map(|(_, remote_ref)| remote_ref.target.added_ids())
+        .cloned()
+        .collect_vec();
+    push_lfs_objects(ui, tx.repo(), remote, &lfs_heads, &lfs_bases)?;
+
     let targets = GitBranchPushTargets {
         branch_updates: bookmark_updates,
     };
@@ -713,7 +730,7 @@ fn print_commits_ready_to_push(
     Ok(())
 }
 
-fn get_default_push_remote(
+pub fn get_default_push_remote(
     ui: &Ui,
     workspace_command: &WorkspaceCommandHelper,
 ) -> Result<RemoteNameBuf, CommandError> {
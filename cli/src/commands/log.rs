@@ -21,8 +21,11 @@ use jj_lib::config::ConfigGetResultExt as _;
 use jj_lib::graph::reverse_graph;
 use jj_lib::graph::GraphEdge;
 use jj_lib::graph::GraphEdgeType;
+use jj_lib::graph::GraphNode;
 use jj_lib::graph::TopoGroupedGraphIterator;
+use jj_lib::object_id::ObjectId as _;
 use jj_lib::repo::Repo as _;
+use jj_lib::revset::Revset;
 use jj_lib::revset::RevsetEvaluationError;
 use jj_lib::revset::RevsetExpression;
 use jj_lib::revset::RevsetFilterPredicate;
@@ -35,11 +38,16 @@ use crate::cli_util::CommandHelper;
 use crate::cli_util::LogContentFormat;
 use crate::cli_util::RevisionArg;
 use crate::command_error::CommandError;
+use crate::commit_templater::CommitJson;
 use crate::commit_templater::CommitTemplateLanguage;
 use crate::complete;
 use crate::diff_util::DiffFormatArgs;
 use crate::graphlog::get_graphlog;
+use crate::graphlog::GraphEdgeJson;
 use crate::graphlog::GraphStyle;
+use crate::json_util::JsonRecordWriter;
+use crate::json_util::OutputFormat;
+use crate::revset_util::RevsetExpressionEvaluator;
 use crate::ui::Ui;
 
 /// Show revision history
@@ -118,6 +126,26 @@ pub(crate) struct LogArgs {
     patch: bool,
     #[command(flatten)]
     diff_format: DiffFormatArgs,
+    /// Print the revisions in a machine-readable format
+    ///
+    /// Each revision is printed as a JSON record with the values of all
+    /// commit keywords. Unless `--no-graph` is specified, the record also
+    /// contains the edges of the node in the graph. See [JSON output] for
+    /// the schema.
+    ///
+    /// [JSON output]:
+    ///     https://jj-vcs.github.io/jj/latest/templates/#json-output
+    #[arg(
+        long,
+        value_name = "FORMAT",
+        conflicts_with_all = ["template", "patch", "DiffFormatArgs"],
+    )]
+    output: Option<OutputFormat>,
+    /// Verify the signatures of signed commits in the JSON output
+    ///
+    /// This runs the signing backend for each signed commit, so it's slow.
+    #[arg(long, requires = "output")]
+    verify_signatures: bool,
 }
 
 #[instrument(skip_all)]
@@ -189,7 +217,38 @@ pub(crate) fn cmd_log(
             .labeled("node");
     }
 
-    {
+    if let Some(output_format) = args.output {
+        ui.request_pager();
+        let language = workspace_command.commit_template_language();
+        let serializer = language
+            .json_serializer()?
+            .verify_signatures(args.verify_signatures);
+        let mut writer = JsonRecordWriter::new(ui.stdout(), output_format);
+        if !args.no_graph {
+            for node in log_graph_iter(revset.as_ref(), &prio_revset, args)? {
+                let (commit_id, edges) = node?;
+                let commit = store.get_commit(&commit_id)?;
+                writer.write(&LogEntryJson {
+                    commit: serializer.to_json(&commit)?,
+                    edges: Some(
+                        edges
+                            .iter()
+                            .map(|edge| GraphEdgeJson::new(edge, |id| id.hex()))
+                            .collect(),
+                    ),
+                })?;
+            }
+        } else {
+            for commit_or_error in log_flat_iter(revset.as_ref(), args)?.commits(store) {
+                let commit = commit_or_error?;
+                writer.write(&LogEntryJson {
+                    commit: serializer.to_json(&commit)?,
+                    edges: None,
+                })?;
+            }
+        }
+        writer.finish()?;
+    } else {
         ui.request_pager();
         let mut formatter = ui.stdout_formatter();
         let formatter = formatter.as_mut();
@@ -197,28 +256,7 @@ pub(crate) fn cmd_log(
         if !args.no_graph {
             let mut raw_output = formatter.raw()?;
             let mut graph = get_graphlog(graph_style, raw_output.as_mut());
-            let iter: Box<dyn Iterator<Item = _>> = {
-                let mut forward_iter = TopoGroupedGraphIterator::new(revset.iter_graph());
-
-                let has_commit = revset.containing_fn();
-
-                for prio in prio_revset.evaluate_to_commit_ids()? {
-                    let prio = prio?;
-                    if has_commit(&prio)? {
-                        forward_iter.prioritize_branch(prio);
-                    }
-                }
-
-                // The input to TopoGroupedGraphIterator shouldn't be truncated
-                // because the prioritized commit must exist in the input set.
-                let forward_iter = forward_iter.take(args.limit.unwrap_or(usize::MAX));
-                if args.reversed {
-                    Box::new(reverse_graph(forward_iter, |id| id)?.into_iter().map(Ok))
-                } else {
-                    Box::new(forward_iter)
-                }
-            };
-            for node in iter {
+            for node in log_graph_iter(revset.as_ref(), &prio_revset, args)? {
                 let (commit_id, edges) = node?;
 
                 // The graph is keyed by (CommitId, is_synthetic)
@@ -298,16 +336,7 @@ pub(crate) fn cmd_log(
                 }
             }
         } else {
-            let iter: Box<dyn Iterator<Item = Result<CommitId, RevsetEvaluationError>>> = {
-                let forward_iter = revset.iter().take(args.limit.unwrap_or(usize::MAX));
-                if args.reversed {
-                    let entries: Vec<_> = forward_iter.try_collect()?;
-                    Box::new(entries.into_iter().rev().map(Ok))
-                } else {
-                    Box::new(forward_iter)
-                }
-            };
-            for commit_or_error in iter.commits(store) {
+            for commit_or_error in log_flat_iter(revset.as_ref(), args)?.commits(store) {
                 let commit = commit_or_error?;
                 with_content_format
                     .write(formatter, |formatter| template.format(&commit, formatter))?;
@@ -346,6 +375,61 @@ pub(crate) fn cmd_log(
     Ok(())
 }
 
+/// Log entry of JSON output.
+#[derive(serde::Serialize)]
+struct LogEntryJson {
+    #[serde(flatten)]
+    commit: CommitJson,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    edges: Option<Vec<GraphEdgeJson>>,
+}
+
+type BoxedGraphIterator<'a> =
+    Box<dyn Iterator<Item = Result<GraphNode<CommitId>, RevsetEvaluationError>> + 'a>;
+
+/// Iterates over the graph nodes in the order they are shown.
+fn log_graph_iter<'a>(
+    revset: &'a dyn Revset,
+    prio_revset: &RevsetExpressionEvaluator,
+    args: &LogArgs,
+) -> Result<BoxedGraphIterator<'a>, CommandError> {
+    let mut forward_iter = TopoGroupedGraphIterator::new(revset.iter_graph());
+
+    let has_commit = revset.containing_fn();
+
+    for prio in prio_revset.evaluate_to_commit_ids()? {
+        let prio = prio?;
+        if has_commit(&prio)? {
+            forward_iter.prioritize_branch(prio);
+        }
+    }
+
+    // The input to TopoGroupedGraphIterator shouldn't be truncated
+    // because the prioritized commit must exist in the input set.
+    let forward_iter = forward_iter.take(args.limit.unwrap_or(usize::MAX));
+    if args.reversed {
+        Ok(Box::new(
+            reverse_graph(forward_iter, |id| id)?.into_iter().map(Ok),
+        ))
+    } else {
+        Ok(Box::new(forward_iter))
+    }
+}
+
+/// Iterates over the commit ids in the order they are shown without graph.
+fn log_flat_iter<'a>(
+    revset: &'a dyn Revset,
+    args: &LogArgs,
+) -> Result<Box<dyn Iterator<Item = Result<CommitId, RevsetEvaluationError>> + 'a>, CommandError> {
+    let forward_iter = revset.iter().take(args.limit.unwrap_or(usize::MAX));
+    if args.reversed {
+        let entries: Vec<_> = forward_iter.try_collect()?;
+        Ok(Box::new(entries.into_iter().rev().map(Ok)))
+    } else {
+        Ok(Box::new(forward_iter))
+    }
+}
+
 pub fn get_node_template(
     style: GraphStyle,
     settings: &UserSettings,
@@ -17,6 +17,7 @@ mod absorb;
 mod backout;
 #[cfg(feature = "bench")]
 mod bench;
+mod bisect;
 mod bookmark;
 mod commit;
 mod config;
@@ -30,6 +31,8 @@ mod evolog;
 mod file;
 mod fix;
 #[cfg(feature = "git")]
+mod gerrit;
+#[cfg(feature = "git")]
 mod git;
 mod help;
 mod interdiff;
@@ -38,6 +41,7 @@ mod new;
 mod next;
 mod operation;
 mod parallelize;
+mod patch;
 mod prev;
 mod rebase;
 mod resolve;
@@ -94,6 +98,8 @@ enum Command {
     #[command(subcommand)]
     Bench(bench::BenchCommand),
     #[command(subcommand)]
+    Bisect(bisect::BisectCommand),
+    #[command(subcommand)]
     Bookmark(bookmark::BookmarkCommand),
     // TODO: Remove in jj 0.28+
     #[command(subcommand, hide = true)]
@@ -115,6 +121,9 @@ enum Command {
     Fix(fix::FixArgs),
     #[cfg(feature = "git")]
     #[command(subcommand)]
+    Gerrit(gerrit::GerritCommand),
+    #[cfg(feature = "git")]
+    #[command(subcommand)]
     Git(git::GitCommand),
     Help(help::HelpArgs),
     Interdiff(interdiff::InterdiffArgs),
@@ -125,14 +134,16 @@ enum Command {
     #[command(visible_alias = "op")]
     Operation(operation::OperationCommand),
     Parallelize(parallelize::ParallelizeArgs),
+    #[command(subcommand)]
+    Patch(patch::PatchCommand),
     Prev(prev::PrevArgs),
     Rebase(rebase::RebaseArgs),
+    /// Redo the most recently undone operation (shortcut for `jj op redo`)
+    Redo(operation::redo::OperationRedoArgs),
     Resolve(resolve::ResolveArgs),
     Restore(restore::RestoreArgs),
     Revert(revert::RevertArgs),
     Root(root::RootArgs),
-    #[command(hide = true)]
-    // TODO: Flesh out.
     Run(run::RunArgs),
     Show(show::ShowArgs),
     Sign(sign::SignArgs),
@@ -167,6 +178,7 @@ pub fn run_command(ui: &mut Ui, command_helper: &CommandHelper) -> Result<(), Co
         Command::Backout(args) => backout::cmd_backout(ui, command_helper, args),
         #[cfg(feature = "bench")]
         Command::Bench(args) => bench::cmd_bench(ui, command_helper, args),
+        Command::Bisect(args) => bisect::cmd_bisect(ui, command_helper, args),
         Command::Bookmark(args) => bookmark::cmd_bookmark(ui, command_helper, args),
         Command::Branch(args) => {
             let cmd = renamed_cmd("branch", "bookmark", bookmark::cmd_bookmark);
@@ -183,6 +195,8 @@ pub fn run_command(ui: &mut Ui, command_helper: &CommandHelper) -> Result<(), Co
         Command::File(args) => file::cmd_file(ui, command_helper, args),
         Command::Fix(args) => fix::cmd_fix(ui, command_helper, args),
         #[cfg(feature = "git")]
+        Command::Gerrit(args) => gerrit::cmd_gerrit(ui, command_helper, args),
+        #[cfg(feature = "git")]
         Command::Git(args) => git::cmd_git(ui, command_helper, args),
         Command::Help(args) => help::cmd_help(ui, command_helper, args),
         Command::Interdiff(args) => interdiff::cmd_interdiff(ui, command_helper, args),
@@ -192,8 +206,10 @@ pub fn run_command(ui: &mut Ui, command_helper: &CommandHelper) -> Result<(), Co
         Command::Evolog(args) => evolog::cmd_evolog(ui, command_helper, args),
         Command::Operation(args) => operation::cmd_operation(ui, command_helper, args),
         Command::Parallelize(args) => parallelize::cmd_parallelize(ui, command_helper, args),
+        Command::Patch(args) => patch::cmd_patch(ui, command_helper, args),
         Command::Prev(args) => prev::cmd_prev(ui, command_helper, args),
         Command::Rebase(args) => rebase::cmd_rebase(ui, command_helper, args),
+        Command::Redo(args) => operation::redo::cmd_op_redo(ui, command_helper, args),
         Command::Resolve(args) => resolve::cmd_resolve(ui, command_helper, args),
         Command::Restore(args) => restore::cmd_restore(ui, command_helper, args),
         Command::Revert(args) => revert::cmd_revert(ui, command_helper, args),
@@ -19,10 +19,12 @@ use std::slice;
 use clap_complete::ArgValueCandidates;
 use itertools::Itertools as _;
 use jj_lib::op_walk;
+use jj_lib::revset::RevsetDiagnostics;
 
 use crate::cli_util::short_operation_hash;
 use crate::cli_util::CommandHelper;
 use crate::command_error::cli_error;
+use crate::command_error::print_parse_diagnostics;
 use crate::command_error::user_error;
 use crate::command_error::CommandError;
 use crate::complete;
@@ -30,6 +32,9 @@ use crate::ui::Ui;
 
 /// Abandon operation history
 ///
+/// The specified operations are removed from the operation log, and their
+/// descendants are reparented onto the nearest remaining ancestors.
+///
 /// To discard old operation history, use `jj op abandon ..<operation ID>`. It
 /// will abandon the specified operation and all its ancestors. The descendants
 /// will be reparented onto the root operation.
@@ -37,11 +42,17 @@ use crate::ui::Ui;
 /// To discard recent operations, use `jj op restore <operation ID>` followed
 /// by `jj op abandon <operation ID>..@-`.
 ///
+/// To discard the working-copy snapshots, use `jj op abandon 'snapshot() &
+/// ..@-'`. See [operation expressions] for the syntax.
+///
 /// The abandoned operations, commits, and other unreachable objects can later
 /// be garbage collected by using `jj util gc` command.
+///
+/// [operation expressions]:
+///     https://jj-vcs.github.io/jj/latest/operation-log/#operation-expressions
 #[derive(clap::Args, Clone, Debug)]
 pub struct OperationAbandonArgs {
-    /// The operation or operation range to abandon
+    /// The operations to abandon
     #[arg(add = ArgValueCandidates::new(complete::operations))]
     operation: String,
 }
@@ -63,35 +74,22 @@ pub fn cmd_op_abandon(
         return Err(cli_error("--at-op is not respected"));
     }
     let current_head_ops = op_walk::get_current_head_ops(op_store, op_heads_store.as_ref())?;
-    let resolve_op = |op_str| op_walk::resolve_op_at(op_store, &current_head_ops, op_str);
-    let (abandon_root_op, abandon_head_ops) =
-        if let Some((root_op_str, head_op_str)) = args.operation.split_once("..") {
-            let root_op = if root_op_str.is_empty() {
-                repo_loader.root_operation()
-            } else {
-                resolve_op(root_op_str)?
-            };
-            let head_ops = if head_op_str.is_empty() {
-                current_head_ops.clone()
-            } else {
-                vec![resolve_op(head_op_str)?]
-            };
-            (root_op, head_ops)
-        } else {
-            let op = resolve_op(&args.operation)?;
-            let parent_ops: Vec<_> = op.parents().try_collect()?;
-            let parent_op = match parent_ops.len() {
-                0 => return Err(user_error("Cannot abandon the root operation")),
-                1 => parent_ops.into_iter().next().unwrap(),
-                _ => return Err(user_error("Cannot abandon a merge operation")),
-            };
-            (parent_op, vec![op])
-        };
+    let mut diagnostics = RevsetDiagnostics::new();
+    let abandon_ops = op_walk::resolve_ops_at(
+        &mut diagnostics,
+        op_store,
+        &current_head_ops,
+        &args.operation,
+    )?;
+    print_parse_diagnostics(ui, "In operation expression", &diagnostics)?;
 
-    if let Some(op) = abandon_head_ops
+    if abandon_ops
         .iter()
-        .find(|op| current_head_ops.contains(op))
+        .any(|op| op.id() == op_store.root_operation_id())
     {
+        return Err(user_error("Cannot abandon the root operation"));
+    }
+    if let Some(op) = abandon_ops.iter().find(|op| current_head_ops.contains(op)) {
         let mut err = user_error(format!(
             "Cannot abandon the current operation {}",
             short_operation_hash(op.id())
@@ -103,17 +101,7 @@ pub fn cmd_op_abandon(
     }
 
     // Reparent descendants, count the number of abandoned operations.
-    let stats = op_walk::reparent_range(
-        op_store.as_ref(),
-        &abandon_head_ops,
-        &current_head_ops,
-        &abandon_root_op,
-    )?;
-    assert_eq!(
-        current_head_ops.len(),
-        stats.new_head_ids.len(),
-        "all current_head_ops should be reparented as they aren't included in abandon_head_ops"
-    );
+    let stats = op_walk::abandon_operations(op_store.as_ref(), &abandon_ops, &current_head_ops)?;
     let reparented_head_ops = || iter::zip(&current_head_ops, &stats.new_head_ids);
     if reparented_head_ops().all(|(old, new_id)| old.id() == new_id) {
         writeln!(ui.status(), "Nothing changed.")?;
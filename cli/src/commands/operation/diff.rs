@@ -101,10 +101,11 @@ pub fn cmd_op_diff(
     let from_op;
     let to_op;
     if args.from.is_some() || args.to.is_some() {
-        from_op = workspace_command.resolve_single_op(args.from.as_deref().unwrap_or("@"))?;
-        to_op = workspace_command.resolve_single_op(args.to.as_deref().unwrap_or("@"))?;
+        from_op = workspace_command.resolve_single_op(ui, args.from.as_deref().unwrap_or("@"))?;
+        to_op = workspace_command.resolve_single_op(ui, args.to.as_deref().unwrap_or("@"))?;
     } else {
-        to_op = workspace_command.resolve_single_op(args.operation.as_deref().unwrap_or("@"))?;
+        to_op =
+            workspace_command.resolve_single_op(ui, args.operation.as_deref().unwrap_or("@"))?;
         let to_op_parents: Vec<_> = to_op.parents().try_collect()?;
         from_op = repo_loader.merge_operations(to_op_parents, None)?;
     }
@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
+use std::collections::HashSet;
 use std::slice;
 
 use clap_complete::ArgValueCandidates;
@@ -20,10 +22,14 @@ use jj_lib::config::ConfigGetError;
 use jj_lib::config::ConfigGetResultExt as _;
 use jj_lib::graph::reverse_graph;
 use jj_lib::graph::GraphEdge;
+use jj_lib::graph::GraphNode;
+use jj_lib::object_id::ObjectId as _;
 use jj_lib::op_store::OpStoreError;
+use jj_lib::op_store::OperationId;
 use jj_lib::op_walk;
 use jj_lib::operation::Operation;
 use jj_lib::repo::RepoLoader;
+use jj_lib::revset::RevsetDiagnostics;
 use jj_lib::settings::UserSettings;
 
 use super::diff::show_op_diff;
@@ -31,6 +37,7 @@ use crate::cli_util::format_template;
 use crate::cli_util::CommandHelper;
 use crate::cli_util::LogContentFormat;
 use crate::cli_util::WorkspaceCommandEnvironment;
+use crate::command_error::print_parse_diagnostics;
 use crate::command_error::CommandError;
 use crate::commit_templater::CommitTemplateLanguage;
 use crate::complete;
@@ -39,7 +46,11 @@ use crate::diff_util::DiffFormatArgs;
 use crate::diff_util::DiffRenderer;
 use crate::formatter::Formatter;
 use crate::graphlog::get_graphlog;
+use crate::graphlog::GraphEdgeJson;
 use crate::graphlog::GraphStyle;
+use crate::json_util::JsonRecordWriter;
+use crate::json_util::OutputFormat;
+use crate::operation_templater::OperationJson;
 use crate::operation_templater::OperationTemplateLanguage;
 use crate::ui::Ui;
 
@@ -50,6 +61,20 @@ use crate::ui::Ui;
 /// to inspect the current state without mutation.
 #[derive(clap::Args, Clone, Debug)]
 pub struct OperationLogArgs {
+    /// Which operations to show
+    ///
+    /// If no operations are specified, all operations reachable from the
+    /// current operation are shown. See [operation expressions] for the syntax.
+    ///
+    /// [operation expressions]:
+    ///     https://jj-vcs.github.io/jj/latest/operation-log/#operation-expressions
+    #[arg(
+        long = "operations",
+        short = 'r',
+        value_name = "OPERATIONS",
+        add = ArgValueCandidates::new(complete::operations),
+    )]
+    operations: Vec<String>,
     /// Limit number of operations to show
     ///
     /// Applied after operations are reordered topologically, but before being
@@ -87,6 +112,21 @@ pub struct OperationLogArgs {
     patch: bool,
     #[command(flatten)]
     diff_format: DiffFormatArgs,
+    /// Print the operations in a machine-readable format
+    ///
+    /// Each operation is printed as a JSON record with the values of all
+    /// operation keywords. Unless `--no-graph` is specified, the record also
+    /// contains the edges of the node in the graph. See [JSON output] for
+    /// the schema.
+    ///
+    /// [JSON output]:
+    ///     https://jj-vcs.github.io/jj/latest/templates/#json-output
+    #[arg(
+        long,
+        value_name = "FORMAT",
+        conflicts_with_all = ["template", "op_diff", "patch", "DiffFormatArgs"],
+    )]
+    output: Option<OutputFormat>,
 }
 
 pub fn cmd_op_log(
@@ -204,21 +244,67 @@ fn do_op_log(
         None
     };
 
+    let iter: Box<dyn Iterator<Item = Result<_, OpStoreError>>> = if args.operations.is_empty() {
+        let iter = op_walk::walk_ancestors(slice::from_ref(current_op)).map(|op| {
+            let op = op?;
+            let ids = op.parent_ids();
+            let edges = ids.iter().cloned().map(GraphEdge::direct).collect_vec();
+            Ok((op, edges))
+        });
+        Box::new(iter)
+    } else {
+        let mut diagnostics = RevsetDiagnostics::new();
+        let mut op_ids = HashSet::new();
+        for text in &args.operations {
+            let ops = op_walk::resolve_ops_at(
+                &mut diagnostics,
+                repo_loader.op_store(),
+                slice::from_ref(current_op),
+                text,
+            )?;
+            op_ids.extend(ops.iter().map(|op| op.id().clone()));
+        }
+        print_parse_diagnostics(ui, "In operation expression", &diagnostics)?;
+        Box::new(filter_op_graph(current_op, &op_ids)?.into_iter().map(Ok))
+    };
+    let iter = iter.take(args.limit.unwrap_or(usize::MAX));
+
     ui.request_pager();
+    if let Some(output_format) = args.output {
+        let language = OperationTemplateLanguage::new(
+            repo_loader,
+            Some(current_op.id()),
+            workspace_env.operation_template_extensions(),
+        );
+        let mut writer = JsonRecordWriter::new(ui.stdout(), output_format);
+        let iter: Box<dyn Iterator<Item = _>> = if args.reversed {
+            Box::new(reverse_graph(iter, Operation::id)?.into_iter().map(Ok))
+        } else {
+            Box::new(iter)
+        };
+        for node in iter {
+            let (op, edges) = node?;
+            let edges = (!args.no_graph).then(|| {
+                edges
+                    .iter()
+                    .map(|edge| GraphEdgeJson::new(edge, |id| id.hex()))
+                    .collect()
+            });
+            writer.write(&OperationLogEntryJson {
+                operation: language.operation_to_json(&op)?,
+                edges,
+            })?;
+        }
+        writer.finish()?;
+        return Ok(());
+    }
+
     let mut formatter = ui.stdout_formatter();
     let formatter = formatter.as_mut();
-    let iter =
-        op_walk::walk_ancestors(slice::from_ref(current_op)).take(args.limit.unwrap_or(usize::MAX));
 
     if !args.no_graph {
         let mut raw_output = formatter.raw()?;
         let mut graph = get_graphlog(graph_style, raw_output.as_mut());
-        let iter = iter.map(|op| -> Result<_, OpStoreError> {
-            let op = op?;
-            let ids = op.parent_ids();
-            let edges = ids.iter().cloned().map(GraphEdge::direct).collect();
-            Ok((op, edges))
-        });
         let iter_nodes: Box<dyn Iterator<Item = _>> = if args.reversed {
             Box::new(reverse_graph(iter, Operation::id)?.into_iter().map(Ok))
         } else {
@@ -252,8 +338,8 @@ fn do_op_log(
         } else {
             Box::new(iter)
         };
-        for op in iter {
-            let op = op?;
+        for node in iter {
+            let (op, _edges) = node?;
             with_content_format.write(formatter, |formatter| template.format(&op, formatter))?;
             if let Some(show) = &maybe_show_op_diff {
                 show(ui, formatter, &op, &with_content_format)?;
@@ -264,6 +350,65 @@ fn do_op_log(
     Ok(())
 }
 
+/// Operation log entry of JSON output.
+#[derive(serde::Serialize)]
+struct OperationLogEntryJson {
+    #[serde(flatten)]
+    operation: OperationJson,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    edges: Option<Vec<GraphEdgeJson>>,
+}
+
+/// Walks the ancestors of the `current_op`, and returns the operations in
+/// `op_ids` with edges to their nearest ancestors in `op_ids`.
+fn filter_op_graph(
+    current_op: &Operation,
+    op_ids: &HashSet<OperationId>,
+) -> Result<Vec<GraphNode<Operation, OperationId>>, OpStoreError> {
+    let mut remaining_ids = op_ids.clone();
+    let mut walked_ops = vec![];
+    for op in op_walk::walk_ancestors(slice::from_ref(current_op)) {
+        if remaining_ids.is_empty() {
+            break;
+        }
+        let op = op?;
+        remaining_ids.remove(op.id());
+        walked_ops.push(op);
+    }
+
+    // Nearest ancestors in `op_ids` of each walked operation, including itself.
+    let mut nearest_ids_map: HashMap<OperationId, Vec<OperationId>> = HashMap::new();
+    let mut nodes = vec![];
+    for op in walked_ops.into_iter().rev() {
+        let mut edges = vec![];
+        let mut nearest_ids = vec![];
+        for parent_id in op.parent_ids() {
+            match nearest_ids_map.get(parent_id).map(Vec::as_slice) {
+                Some([id]) if id == parent_id => {
+                    edges.push(GraphEdge::direct(parent_id.clone()));
+                }
+                Some([]) | None => edges.push(GraphEdge::missing(parent_id.clone())),
+                Some(ids) => edges.extend(ids.iter().cloned().map(GraphEdge::indirect)),
+            }
+            nearest_ids.extend(
+                nearest_ids_map
+                    .get(parent_id)
+                    .into_iter()
+                    .flatten()
+                    .cloned(),
+            );
+        }
+        if op_ids.contains(op.id()) {
+            nearest_ids_map.insert(op.id().clone(), vec![op.id().clone()]);
+            nodes.push((op, edges.into_iter().unique().collect()));
+        } else {
+            nearest_ids_map.insert(op.id().clone(), nearest_ids.into_iter().unique().collect());
+        }
+    }
+    nodes.reverse();
+    Ok(nodes)
+}
+
 fn get_node_template(style: GraphStyle, settings: &UserSettings) -> Result<String, ConfigGetError> {
     let symbol = settings.get_string("templates.op_log_node").optional()?;
     let default = if style.is_ascii() {
@@ -0,0 +1,61 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use jj_lib::object_id::ObjectId as _;
+
+use super::undo::undo_state;
+use super::undo::REDO_TAG;
+use crate::cli_util::CommandHelper;
+use crate::command_error::user_error_with_hint;
+use crate::command_error::CommandError;
+use crate::ui::Ui;
+
+/// Redo the most recently undone operation
+///
+/// Operations undone by `jj undo` can be redone in reverse order. Any other
+/// operation clears the redo history.
+#[derive(clap::Args, Clone, Debug)]
+pub struct OperationRedoArgs {}
+
+pub fn cmd_op_redo(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    _args: &OperationRedoArgs,
+) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui)?;
+    let repo = workspace_command.repo();
+    let mut state = undo_state(repo.loader(), repo.operation())?;
+    let Some(redo_op) = state.redo_stack.pop() else {
+        return Err(user_error_with_hint(
+            "Nothing to redo",
+            "Only operations undone by `jj undo` can be redone, and only until another operation \
+             is made.",
+        ));
+    };
+
+    let mut tx = workspace_command.start_transaction();
+    let repo_loader = tx.base_repo().loader();
+    let cursor_repo = repo_loader.load_at(&state.cursor)?;
+    let redo_repo = repo_loader.load_at(&redo_op)?;
+    tx.repo_mut().merge(&cursor_repo, &redo_repo)?;
+    if let Some(mut formatter) = ui.status_formatter() {
+        write!(formatter, "Redid operation: ")?;
+        let template = tx.base_workspace_helper().operation_summary_template();
+        template.format(&redo_op, formatter.as_mut())?;
+        writeln!(formatter)?;
+    }
+    tx.set_tag(REDO_TAG.to_owned(), redo_op.id().hex());
+    tx.finish(ui, format!("redo operation {}", redo_op.id().hex()))?;
+    Ok(())
+}
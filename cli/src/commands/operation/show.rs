@@ -56,7 +56,7 @@ pub fn cmd_op_show(
     let workspace_env = workspace_command.env();
     let repo_loader = workspace_command.workspace().repo_loader();
     let settings = workspace_command.settings();
-    let op = workspace_command.resolve_single_op(&args.operation)?;
+    let op = workspace_command.resolve_single_op(ui, &args.operation)?;
     let parents: Vec<_> = op.parents().try_collect()?;
     let parent_op = repo_loader.merge_operations(parents, None)?;
     let parent_repo = repo_loader.load_at(&parent_op)?;
@@ -15,14 +15,16 @@
 use clap_complete::ArgValueCandidates;
 use itertools::Itertools as _;
 use jj_lib::object_id::ObjectId as _;
-use jj_lib::op_store::OpStoreError;
+use jj_lib::op_store::OperationId;
 use jj_lib::operation::Operation;
 use jj_lib::repo::Repo as _;
+use jj_lib::repo::RepoLoader;
 
 use super::view_with_desired_portions_restored;
 use super::UndoWhatToRestore;
 use super::DEFAULT_UNDO_WHAT;
 use crate::cli_util::CommandHelper;
+use crate::command_error::internal_error_with_message;
 use crate::command_error::user_error;
 use crate::command_error::CommandError;
 use crate::complete;
@@ -32,11 +34,17 @@ use crate::ui::Ui;
 ///
 /// This undoes an individual operation by applying the inverse of the
 /// operation.
+///
+/// When no operation is specified, repeated undos walk back through the
+/// operation log one operation at a time, and `jj op redo` can re-apply the
+/// undone operations. Any other operation clears the redo history.
 #[derive(clap::Args, Clone, Debug)]
 pub struct OperationUndoArgs {
     /// The operation to undo
     ///
-    /// Use `jj op log` to find an operation to undo.
+    /// Use `jj op log` to find an operation to undo. If this is `@` and the
+    /// current operation was created by `jj undo` or `jj redo`, the operation
+    /// before the restored state is undone instead.
     #[arg(default_value = "@", add = ArgValueCandidates::new(complete::operations))]
     operation: String,
 
@@ -47,13 +55,67 @@ pub struct OperationUndoArgs {
     what: Vec<UndoWhatToRestore>,
 }
 
-fn is_undo(op: &Operation, parent_op: &Operation) -> Result<bool, OpStoreError> {
-    let grand_parents: Vec<_> = parent_op.parents().try_collect()?;
-    if let [grand_parent_op] = &grand_parents[..] {
-        Ok(op.view_id() == grand_parent_op.view_id())
-    } else {
-        Ok(false)
+/// Operation tag pointing to the operation undone by `jj undo`.
+pub(super) const UNDO_TAG: &str = "undo";
+/// Operation tag pointing to the operation redone by `jj redo`.
+pub(super) const REDO_TAG: &str = "redo";
+
+/// Position in the undo history, derived from the chain of undo and redo
+/// operations leading to an operation.
+pub(super) struct UndoState {
+    /// The operation whose state the repo is at.
+    pub cursor: Operation,
+    /// Undone operations that can be redone, the most recently undone last.
+    pub redo_stack: Vec<Operation>,
+}
+
+pub(super) fn undo_state(
+    repo_loader: &RepoLoader,
+    op: &Operation,
+) -> Result<UndoState, CommandError> {
+    let load_tagged_op = |hex: &str| -> Result<Operation, CommandError> {
+        let id = OperationId::try_from_hex(hex).map_err(|err| {
+            internal_error_with_message(format!("Invalid operation id in tag: {hex}"), err)
+        })?;
+        Ok(repo_loader.load_operation(&id)?)
+    };
+    // Collect consecutive undo/redo operations up to the last regular one.
+    let mut chain = vec![];
+    let mut op = op.clone();
+    loop {
+        let tags = &op.metadata().tags;
+        let entry = if let Some(hex) = tags.get(UNDO_TAG) {
+            (UNDO_TAG, load_tagged_op(hex)?)
+        } else if let Some(hex) = tags.get(REDO_TAG) {
+            (REDO_TAG, load_tagged_op(hex)?)
+        } else {
+            break;
+        };
+        let parent_op = match op.parents().exactly_one() {
+            Ok(parent_op) => parent_op?,
+            Err(_) => break,
+        };
+        chain.push(entry);
+        op = parent_op;
     }
+    let mut state = UndoState {
+        cursor: op,
+        redo_stack: vec![],
+    };
+    for (kind, target_op) in chain.into_iter().rev() {
+        if kind == UNDO_TAG {
+            // The parent may itself be an undo or redo operation, in which
+            // case the repo is at the state it restored.
+            if let Some(parent_op) = target_op.parents().next().transpose()? {
+                state.cursor = undo_state(repo_loader, &parent_op)?.cursor;
+            }
+            state.redo_stack.push(target_op);
+        } else {
+            state.redo_stack.pop();
+            state.cursor = target_op;
+        }
+    }
+    Ok(state)
 }
 
 pub fn cmd_op_undo(
@@ -62,7 +124,12 @@ pub fn cmd_op_undo(
     args: &OperationUndoArgs,
 ) -> Result<(), CommandError> {
     let mut workspace_command = command.workspace_helper(ui)?;
-    let bad_op = workspace_command.resolve_single_op(&args.operation)?;
+    let bad_op = if args.operation == "@" {
+        let repo = workspace_command.repo();
+        undo_state(repo.loader(), repo.operation())?.cursor
+    } else {
+        workspace_command.resolve_single_op(ui, &args.operation)?
+    };
     let mut parent_ops = bad_op.parents();
     let Some(parent_op) = parent_ops.next().transpose()? else {
         return Err(user_error("Cannot undo repo initialization"));
@@ -88,20 +155,10 @@ pub fn cmd_op_undo(
         template.format(&bad_op, formatter.as_mut())?;
         writeln!(formatter)?;
     }
-    tx.finish(ui, format!("undo operation {}", bad_op.id().hex()))?;
-
-    if args.operation == "@" && is_undo(&bad_op, &parent_op)? {
-        writeln!(
-            ui.hint_default(),
-            "This action reverted an 'undo' operation. The repository is now in the same state as \
-             it was before the original 'undo'."
-        )?;
-        writeln!(
-            ui.hint_default(),
-            "If your goal is to undo multiple operations, consider using `jj op log` to see past \
-             states, and `jj op restore` to restore one of these states."
-        )?;
+    if args.operation == "@" {
+        tx.set_tag(UNDO_TAG.to_owned(), bad_op.id().hex());
     }
+    tx.finish(ui, format!("undo operation {}", bad_op.id().hex()))?;
 
     Ok(())
 }
@@ -0,0 +1,229 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashSet;
+use std::io;
+use std::io::Read as _;
+
+use clap::ArgGroup;
+use clap_complete::ArgValueCandidates;
+use indexmap::IndexSet;
+use itertools::Itertools as _;
+use jj_lib::backend::CommitId;
+use jj_lib::backend::Signature;
+use jj_lib::patch::apply_patch;
+use jj_lib::patch::parse_patches;
+use jj_lib::patch::Patch;
+use jj_lib::repo::Repo as _;
+use jj_lib::rewrite::merge_commit_trees;
+use tracing::instrument;
+
+use crate::cli_util::compute_commit_location;
+use crate::cli_util::print_updated_commits;
+use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::command_error::user_error_with_message;
+use crate::command_error::CommandError;
+use crate::complete;
+use crate::ui::Ui;
+
+/// Apply patches as new commits
+///
+/// The patches can be unified diffs such as the output of `jj diff --git`, or
+/// emails such as the output of `git format-patch`. A file can contain several
+/// emails in mbox format. Each patch is applied as a new commit on top of the
+/// previous one, at the given location.
+///
+/// The author and description of the new commits are taken from the `From:`,
+/// `Date:`, and `Subject:` headers and the body of the emails.
+///
+/// Hunks which don't apply cleanly result in conflicts in the new commits,
+/// which can be resolved like any other conflict.
+///
+/// Paths are taken relative to the workspace root, after stripping the `a/`
+/// and `b/` prefixes. Conflicted files are patched on each side of the
+/// conflict.
+///
+/// The data of `GIT binary patch` sections isn't decoded. A binary file can
+/// only be changed if the `index` line has the full id of the new contents and
+/// they're already in the repo.
+#[derive(clap::Args, Clone, Debug)]
+#[command(group(ArgGroup::new("location").args(&["destination", "insert_after", "insert_before"]).required(true).multiple(true)))]
+pub(crate) struct PatchApplyArgs {
+    /// Patch files to apply in order, or `-` to read from stdin
+    #[arg(required = true, value_name = "FILES", value_hint = clap::ValueHint::FilePath)]
+    paths: Vec<String>,
+    /// The revision(s) to apply the patches on top of
+    #[arg(
+        long, short,
+        value_name = "REVSETS",
+        add = ArgValueCandidates::new(complete::all_revisions),
+    )]
+    destination: Option<Vec<RevisionArg>>,
+    /// The revision(s) to insert the patches after (can be repeated to create
+    /// a merge commit)
+    #[arg(
+        long,
+        short = 'A',
+        visible_alias = "after",
+        conflicts_with = "destination",
+        value_name = "REVSETS",
+        add = ArgValueCandidates::new(complete::all_revisions),
+    )]
+    insert_after: Option<Vec<RevisionArg>>,
+    /// The revision(s) to insert the patches before (can be repeated to create
+    /// a merge commit)
+    #[arg(
+        long,
+        short = 'B',
+        visible_alias = "before",
+        conflicts_with = "destination",
+        value_name = "REVSETS",
+        add = ArgValueCandidates::new(complete::mutable_revisions)
+    )]
+    insert_before: Option<Vec<RevisionArg>>,
+}
+
+#[instrument(skip_all)]
+pub(crate) fn cmd_patch_apply(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &PatchApplyArgs,
+) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui)?;
+    let mut patches: Vec<Patch> = vec![];
+    for path in &args.paths {
+        let input = if path == "-" {
+            let mut input = vec![];
+            io::stdin().read_to_end(&mut input)?;
+            input
+        } else {
+            std::fs::read(command.cwd().join(path))
+                .map_err(|err| user_error_with_message(format!("Failed to read {path}"), err))?
+        };
+        let parsed = parse_patches(&input)
+            .map_err(|err| user_error_with_message(format!("Failed to parse {path}"), err))?;
+        for patch in parsed {
+            if patch.files.is_empty() {
+                let subject = patch.description.lines().next().unwrap_or_default();
+                writeln!(
+                    ui.warning_default(),
+                    "Skipping patch without changes in {path}: {subject}"
+                )?;
+            } else {
+                patches.push(patch);
+            }
+        }
+    }
+    if patches.is_empty() {
+        writeln!(ui.status(), "No patches to apply.")?;
+        return Ok(());
+    }
+    let (new_parent_ids, new_child_ids) = compute_commit_location(
+        ui,
+        &workspace_command,
+        args.destination.as_deref(),
+        args.insert_after.as_deref(),
+        args.insert_before.as_deref(),
+        "patched commits",
+    )?;
+    let transaction_description = if patches.len() == 1 {
+        "apply patch".to_owned()
+    } else {
+        format!("apply {} patches", patches.len())
+    };
+
+    let mut tx = workspace_command.start_transaction();
+    let original_parent_commit_ids: HashSet<_> = new_parent_ids.iter().cloned().collect();
+    let new_parents: Vec<_> = new_parent_ids
+        .iter()
+        .map(|id| tx.repo().store().get_commit(id))
+        .try_collect()?;
+    let mut new_base_tree = merge_commit_trees(tx.repo(), &new_parents)?;
+    let mut parent_ids = new_parent_ids;
+
+    let mut patched_commits = vec![];
+    for (index, patch) in patches.iter().enumerate() {
+        let new_tree = apply_patch(&new_base_tree, patch).map_err(|err| {
+            user_error_with_message(format!("Failed to apply patch #{}", index + 1), err)
+        })?;
+        let mut commit_builder = tx
+            .repo_mut()
+            .new_commit(parent_ids.clone(), new_tree.id())
+            .set_description(&patch.description);
+        if let Some(author) = &patch.author {
+            let timestamp = author
+                .timestamp
+                .unwrap_or(commit_builder.author().timestamp);
+            commit_builder = commit_builder.set_author(Signature {
+                name: author.name.clone(),
+                email: author.email.clone(),
+                timestamp,
+            });
+        }
+        let new_commit = commit_builder.write()?;
+        parent_ids = vec![new_commit.id().clone()];
+        patched_commits.push(new_commit);
+        new_base_tree = new_tree;
+    }
+
+    // Rebase new children onto the last patched commit.
+    let new_head_ids: Vec<_> = parent_ids;
+    let children_commit_ids_set: HashSet<CommitId> = new_child_ids.iter().cloned().collect();
+    let mut num_rebased = 0;
+    tx.repo_mut()
+        .transform_descendants(new_child_ids, |mut rewriter| {
+            if children_commit_ids_set.contains(rewriter.old_commit().id()) {
+                let mut child_new_parent_ids = IndexSet::new();
+                for old_parent_id in rewriter.old_commit().parent_ids() {
+                    // Replace the new parents of the patched commits with the
+                    // last patched commit, since we are "inserting" the new
+                    // commits in between them and the new children.
+                    if original_parent_commit_ids.contains(old_parent_id) {
+                        child_new_parent_ids.extend(new_head_ids.clone());
+                    } else {
+                        child_new_parent_ids.insert(old_parent_id.clone());
+                    }
+                }
+                child_new_parent_ids.extend(new_head_ids.clone());
+                rewriter.set_new_parents(child_new_parent_ids.into_iter().collect());
+            }
+            num_rebased += 1;
+            rewriter.rebase()?.write()?;
+            Ok(())
+        })?;
+
+    if let Some(mut formatter) = ui.status_formatter() {
+        if patched_commits.len() == 1 {
+            writeln!(formatter, "Applied patch as follows:")?;
+        } else {
+            writeln!(
+                formatter,
+                "Applied {} patches as follows:",
+                patched_commits.len()
+            )?;
+        }
+        print_updated_commits(
+            formatter.as_mut(),
+            &tx.commit_summary_template(),
+            &patched_commits,
+        )?;
+        if num_rebased > 0 {
+            writeln!(formatter, "Rebased {num_rebased} descendant commits")?;
+        }
+    }
+    tx.finish(ui, transaction_description)?;
+
+    Ok(())
+}
@@ -0,0 +1,41 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod apply;
+
+use clap::Subcommand;
+use tracing::instrument;
+
+use self::apply::cmd_patch_apply;
+use self::apply::PatchApplyArgs;
+use crate::cli_util::CommandHelper;
+use crate::command_error::CommandError;
+use crate::ui::Ui;
+
+/// Work with patches in unified diff format
+#[derive(Subcommand, Clone, Debug)]
+pub(crate) enum PatchCommand {
+    Apply(PatchApplyArgs),
+}
+
+#[instrument(skip_all)]
+pub(crate) fn cmd_patch(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    subcommand: &PatchCommand,
+) -> Result<(), CommandError> {
+    match subcommand {
+        PatchCommand::Apply(args) => cmd_patch_apply(ui, command, args),
+    }
+}
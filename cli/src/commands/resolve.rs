@@ -88,7 +88,10 @@ pub(crate) fn cmd_resolve(
     let tree = commit.tree()?;
     let conflicts = tree
         .conflicts()
-        .filter(|path| matcher.matches(&path.0))
+        .filter(|(path, value)| match value {
+            Ok(value) => matcher.matches_value(tree.store(), path, &value.map(Option::as_ref)),
+            Err(_) => matcher.matches(path),
+        })
         .collect_vec();
     if conflicts.is_empty() {
         return Err(cli_error(if args.paths.is_empty() {
@@ -14,33 +14,79 @@
 
 //! This file contains the internal implementation of `run`.
 
+use std::collections::HashMap;
+use std::fs;
+use std::io::Write as _;
+use std::path::Path;
+use std::process::Command;
+use std::process::Output;
+use std::sync::Arc;
+
+use clap_complete::ArgValueCandidates;
 use itertools::Itertools as _;
+use jj_lib::backend::CommitId;
+use jj_lib::backend::MergedTreeId;
+use jj_lib::commit::Commit;
+use jj_lib::commit::CommitIteratorExt as _;
+use jj_lib::local_working_copy::TreeState;
+use jj_lib::merged_tree::MergedTree;
+use jj_lib::object_id::ObjectId as _;
+use jj_lib::repo::Repo as _;
+use jj_lib::store::Store;
+use jj_lib::working_copy::CheckoutOptions;
+use jj_lib::working_copy::SnapshotOptions;
+use rayon::iter::IntoParallelRefIterator as _;
+use rayon::iter::ParallelIterator as _;
+use tracing::instrument;
 
 use crate::cli_util::CommandHelper;
 use crate::cli_util::RevisionArg;
+use crate::command_error::internal_error;
+use crate::command_error::internal_error_with_message;
 use crate::command_error::user_error;
 use crate::command_error::CommandError;
+use crate::complete;
 use crate::ui::Ui;
 
-/// (**Stub**, does not work yet) Run a command across a set of revisions.
+/// Run a command across a set of revisions.
+///
+/// Each revision is checked out into its own temporary working copy under the
+/// `.jj` directory, and the command is run there through the system shell
+/// (`sh -c` on Unix, `cmd /C` on Windows). Up to `--jobs` commands run in
+/// parallel. The temporary working copies are removed once all commands have
+/// finished.
 ///
+/// After all commands have finished, a table with the outcome for each
+/// revision is printed. The command fails if any of the invocations failed.
 ///
-/// All recorded state will be persisted in the `.jj` directory, so occasionally
-/// a `jj run --clean` is needed to clean up disk space.
+/// With `--rewrite`, changes made to the files by the command are recorded in
+/// the corresponding revision if the command succeeded. Revisions where the
+/// command failed are left unchanged. The selected revisions keep the content
+/// the command left behind, even if their ancestors were rewritten too, so
+/// this never results in new conflicts among them. Other descendants are
+/// rebased on top. This is similar to `jj fix`, but the command sees the whole
+/// working copy instead of one file at a time.
 ///
 /// # Example
 ///
-/// # Run pre-commit on your local work
-/// $ jj run 'pre-commit run .github/pre-commit.yaml' -r (trunk()..@) -j 4
+/// # Run the tests on all of your local work
+/// $ jj run 'cargo test' -r 'mutable()' -j 4
 ///
-/// This allows pre-commit integration and other funny stuff.
+/// # Format each revision in place
+/// $ jj run 'cargo fmt' -r 'trunk()..@' --rewrite
 #[derive(clap::Args, Clone, Debug)]
 #[command(verbatim_doc_comment)]
 pub struct RunArgs {
     /// The command to run across all selected revisions.
     shell_command: String,
-    /// The revisions to change.
-    #[arg(long, short, default_value = "@", value_name = "REVSETS")]
+    /// The revisions to run the command on.
+    #[arg(
+        long,
+        short,
+        default_value = "@",
+        value_name = "REVSETS",
+        add = ArgValueCandidates::new(complete::all_revisions)
+    )]
     revisions: Vec<RevisionArg>,
     /// A no-op option to match the interface of `git rebase -x`.
     #[arg(short = 'x', hide = true)]
@@ -48,23 +94,236 @@ pub struct RunArgs {
     /// How many processes should run in parallel, uses by default all cores.
     #[arg(long, short)]
     jobs: Option<usize>,
+    /// Record changes made to the files by the command in the revisions
+    ///
+    /// Changes are only recorded where the command succeeded. Descendants
+    /// which weren't selected are rebased on top of them.
+    #[arg(long)]
+    rewrite: bool,
+}
+
+/// The outcome of running the command in a single revision.
+struct RunResult {
+    output: Output,
+    /// The tree of the temporary working copy after the command finished, if
+    /// it was snapshotted. It isn't snapshotted if the command failed.
+    new_tree_id: Option<MergedTreeId>,
 }
 
+#[instrument(skip_all)]
 pub fn cmd_run(ui: &mut Ui, command: &CommandHelper, args: &RunArgs) -> Result<(), CommandError> {
-    let workspace_command = command.workspace_helper(ui)?;
-    let _resolved_commits: Vec<_> = workspace_command
+    let mut workspace_command = command.workspace_helper(ui)?;
+    let resolved_commits: Vec<Commit> = workspace_command
         .parse_union_revsets(ui, &args.revisions)?
         .evaluate_to_commits()?
         .try_collect()?;
+    if args.rewrite {
+        workspace_command.check_rewritable(resolved_commits.iter().ids())?;
+    }
     // Jobs are resolved in this order:
     // 1. Commandline argument iff > 0.
     // 2. the amount of cores available.
     // 3. a single job, if all of the above fails.
-    let _jobs = match args.jobs {
+    let jobs = match args.jobs {
         Some(0) | None => std::thread::available_parallelism().map(|t| t.into()).ok(),
         Some(jobs) => Some(jobs),
     }
     // Fallback to a single user-visible job.
     .unwrap_or(1usize);
-    Err(user_error("This is a stub, do not use"))
+
+    let store = workspace_command.repo().store().clone();
+    let checkout_options = workspace_command.checkout_options();
+    let auto_tracking_matcher = workspace_command.auto_tracking_matcher(ui)?;
+    let snapshot_policy = workspace_command.snapshot_policy(ui)?;
+    let snapshot_options = args
+        .rewrite
+        .then(|| {
+            workspace_command.snapshot_options_with_start_tracking_matcher(
+                auto_tracking_matcher.as_ref(),
+                &snapshot_policy,
+            )
+        })
+        .transpose()?;
+    let run_dir = workspace_command.workspace_root().join(".jj").join("run");
+    fs::create_dir_all(&run_dir).map_err(|err| {
+        internal_error_with_message(
+            "Failed to create directory for temporary working copies",
+            err,
+        )
+    })?;
+
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(jobs)
+        .build()
+        .map_err(internal_error)?;
+    let results: HashMap<CommitId, RunResult> = pool.install(|| {
+        resolved_commits
+            .par_iter()
+            .map(|commit| {
+                let result = run_in_temporary_working_copy(
+                    &store,
+                    &run_dir,
+                    commit,
+                    &args.shell_command,
+                    &checkout_options,
+                    snapshot_options.as_ref(),
+                )?;
+                Ok((commit.id().clone(), result))
+            })
+            .collect::<Result<_, CommandError>>()
+    })?;
+
+    let mut num_failed = 0;
+    for commit in &resolved_commits {
+        let result = &results[commit.id()];
+        if result.output.status.success() {
+            continue;
+        }
+        num_failed += 1;
+        let commit_summary = workspace_command.format_commit_summary(commit);
+        if args.rewrite {
+            writeln!(
+                ui.warning_default(),
+                "Command failed in {commit_summary}, skipped rewriting it"
+            )?;
+        } else {
+            writeln!(ui.warning_default(), "Command failed in {commit_summary}")?;
+        }
+        ui.stderr().write_all(&result.output.stdout)?;
+        ui.stderr().write_all(&result.output.stderr)?;
+    }
+
+    {
+        let mut formatter = ui.stdout_formatter();
+        for commit in &resolved_commits {
+            if results[commit.id()].output.status.success() {
+                write!(formatter.labeled("run passed"), "PASS")?;
+            } else {
+                write!(formatter.labeled("run failed"), "FAIL")?;
+            }
+            write!(formatter, " ")?;
+            workspace_command.write_commit_summary(formatter.as_mut(), commit)?;
+            writeln!(formatter)?;
+        }
+    }
+
+    if args.rewrite {
+        let mut tx = workspace_command.start_transaction();
+        let mut num_rewritten = 0;
+        tx.repo_mut().transform_descendants(
+            resolved_commits.iter().ids().cloned().collect(),
+            |rewriter| {
+                let old_commit_id = rewriter.old_commit().id().clone();
+                let new_tree_id = results
+                    .get(&old_commit_id)
+                    .and_then(|result| result.new_tree_id.as_ref())
+                    .filter(|tree_id| *tree_id != rewriter.old_commit().tree_id());
+                if let Some(new_tree_id) = new_tree_id {
+                    // The command saw the full content of the revision, so its
+                    // result replaces the tree even if an ancestor was also
+                    // rewritten, like `jj fix` does.
+                    rewriter
+                        .reparent()
+                        .set_tree_id(new_tree_id.clone())
+                        .write()?;
+                    num_rewritten += 1;
+                } else if rewriter.parents_changed() {
+                    rewriter.rebase()?.write()?;
+                }
+                Ok(())
+            },
+        )?;
+        writeln!(
+            ui.status(),
+            "Rewrote {num_rewritten} commit{} of {} checked.",
+            if num_rewritten == 1 { "" } else { "s" },
+            resolved_commits.len()
+        )?;
+        tx.finish(
+            ui,
+            format!(
+                "run command '{}' in {num_rewritten} commit{}",
+                args.shell_command,
+                if num_rewritten == 1 { "" } else { "s" },
+            ),
+        )?;
+    }
+
+    if num_failed > 0 {
+        return Err(user_error(format!(
+            "Command failed in {num_failed} of {} revisions",
+            resolved_commits.len()
+        )));
+    }
+    Ok(())
+}
+
+/// Checks out `commit` in a new working copy under `run_dir`, and runs the
+/// `shell_command` there.
+///
+/// If `snapshot_options` is set, the working copy is snapshotted after the
+/// command finished successfully. The working copy is removed when this
+/// returns.
+fn run_in_temporary_working_copy(
+    store: &Arc<Store>,
+    run_dir: &Path,
+    commit: &Commit,
+    shell_command: &str,
+    checkout_options: &CheckoutOptions,
+    snapshot_options: Option<&SnapshotOptions>,
+) -> Result<RunResult, CommandError> {
+    let temp_dir = tempfile::Builder::new()
+        .prefix(&format!("{}-", commit.id().hex()))
+        .tempdir_in(run_dir)
+        .map_err(|err| {
+            internal_error_with_message("Failed to create temporary working copy", err)
+        })?;
+    let working_copy_path = temp_dir.path().join("working_copy");
+    let state_path = temp_dir.path().join("state");
+    for path in [&working_copy_path, &state_path] {
+        fs::create_dir(path).map_err(|err| {
+            internal_error_with_message("Failed to create temporary working copy", err)
+        })?;
+    }
+    let mut tree_state = TreeState::init(store.clone(), working_copy_path.clone(), state_path)
+        .map_err(internal_error)?;
+    let tree: MergedTree = commit.tree()?;
+    tree_state
+        .check_out(&tree, checkout_options)
+        .map_err(internal_error)?;
+
+    tracing::debug!(?working_copy_path, commit_id = ?commit.id(), "running command");
+    let output = shell_command_builder(shell_command)
+        .current_dir(&working_copy_path)
+        .output()
+        .map_err(|err| user_error(format!("Failed to run '{shell_command}': {err}")))?;
+    tracing::debug!(status = ?output.status, commit_id = ?commit.id(), "command exited:");
+
+    // A failed command may have left partial changes behind, which shouldn't
+    // be recorded
+    let new_tree_id = match snapshot_options {
+        Some(options) if output.status.success() => {
+            tree_state.snapshot(options)?;
+            Some(tree_state.current_tree_id().clone())
+        }
+        _ => None,
+    };
+    Ok(RunResult {
+        output,
+        new_tree_id,
+    })
+}
+
+#[cfg(unix)]
+pub(crate) fn shell_command_builder(shell_command: &str) -> Command {
+    let mut command = Command::new("sh");
+    command.arg("-c").arg(shell_command);
+    command
+}
+
+#[cfg(windows)]
+pub(crate) fn shell_command_builder(shell_command: &str) -> Command {
+    let mut command = Command::new("cmd");
+    command.arg("/C").arg(shell_command);
+    command
 }
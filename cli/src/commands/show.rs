@@ -21,6 +21,8 @@ use crate::cli_util::RevisionArg;
 use crate::command_error::CommandError;
 use crate::complete;
 use crate::diff_util::DiffFormatArgs;
+use crate::json_util::write_json_record;
+use crate::json_util::OutputFormat;
 use crate::ui::Ui;
 
 /// Show commit description and changes in a revision
@@ -53,6 +55,24 @@ pub(crate) struct ShowArgs {
     /// Do not show the patch
     #[arg(long, conflicts_with = "DiffFormatArgs")]
     no_patch: bool,
+    /// Print the revision in a machine-readable format
+    ///
+    /// The revision is printed as a JSON record with the values of all commit
+    /// keywords. The patch isn't included. See [JSON output] for the schema.
+    ///
+    /// [JSON output]:
+    ///     https://jj-vcs.github.io/jj/latest/templates/#json-output
+    #[arg(
+        long,
+        value_name = "FORMAT",
+        conflicts_with_all = ["template", "DiffFormatArgs"],
+    )]
+    output: Option<OutputFormat>,
+    /// Verify the signature of the revision in the JSON output
+    ///
+    /// This runs the signing backend if the revision is signed, so it's slow.
+    #[arg(long, requires = "output")]
+    verify_signatures: bool,
 }
 
 #[instrument(skip_all)]
@@ -63,6 +83,15 @@ pub(crate) fn cmd_show(
 ) -> Result<(), CommandError> {
     let workspace_command = command.workspace_helper(ui)?;
     let commit = workspace_command.resolve_single_rev(ui, &args.revision)?;
+    if let Some(output_format) = args.output {
+        let language = workspace_command.commit_template_language();
+        let record = language
+            .json_serializer()?
+            .verify_signatures(args.verify_signatures)
+            .to_json(&commit)?;
+        write_json_record(ui.stdout(), output_format, &record)?;
+        return Ok(());
+    }
     let template_string = match &args.template {
         Some(value) => value.to_string(),
         None => workspace_command.settings().get_string("templates.show")?,
@@ -18,12 +18,16 @@ use itertools::Itertools as _;
 use jj_lib::commit::Commit;
 use jj_lib::commit::CommitIteratorExt as _;
 use jj_lib::repo::Repo as _;
+use jj_lib::signing::SigStatus;
 use jj_lib::signing::SignBehavior;
+use jj_lib::signing::SignError;
 
 use crate::cli_util::print_updated_commits;
+use crate::cli_util::short_commit_hash;
 use crate::cli_util::CommandHelper;
 use crate::cli_util::RevisionArg;
 use crate::command_error::user_error_with_hint;
+use crate::command_error::user_error_with_message;
 use crate::command_error::CommandError;
 use crate::complete;
 use crate::ui::Ui;
@@ -41,7 +45,8 @@ pub struct SignArgs {
     /// If no revisions are specified, this defaults to the `revsets.sign`
     /// setting.
     ///
-    /// Note that revisions are always re-signed.
+    /// Note that revisions are always re-signed, unless `--missing` is
+    /// given.
     ///
     /// While that leads to discomfort for users, which sign with hardware
     /// devices, as of now we cannot reliably check if a commit is already
@@ -59,6 +64,12 @@ pub struct SignArgs {
     /// The key used for signing
     #[arg(long)]
     key: Option<String>,
+
+    /// Only sign revisions without a signature, or with an invalid signature
+    ///
+    /// This verifies the existing signatures, which may be slow.
+    #[arg(long)]
+    missing: bool,
 }
 
 pub fn cmd_sign(ui: &mut Ui, command: &CommandHelper, args: &SignArgs) -> Result<(), CommandError> {
@@ -78,7 +89,20 @@ pub fn cmd_sign(ui: &mut Ui, command: &CommandHelper, args: &SignArgs) -> Result
         workspace_command.parse_union_revsets(ui, &args.revisions)?
     };
 
-    let to_sign: IndexSet<Commit> = revset_expression.evaluate_to_commits()?.try_collect()?;
+    let mut to_sign: IndexSet<Commit> = revset_expression.evaluate_to_commits()?.try_collect()?;
+    if args.missing {
+        let mut missing = IndexSet::new();
+        for commit in to_sign {
+            if !has_valid_signature(&commit)? {
+                missing.insert(commit);
+            }
+        }
+        to_sign = missing;
+        if to_sign.is_empty() {
+            writeln!(ui.status(), "No commits with missing signatures.")?;
+            return Ok(());
+        }
+    }
 
     workspace_command.check_rewritable(to_sign.iter().ids())?;
 
@@ -151,3 +175,18 @@ pub fn cmd_sign(ui: &mut Ui, command: &CommandHelper, args: &SignArgs) -> Result
 
     Ok(())
 }
+
+/// Checks if the commit is signed, and the signature matches the commit.
+fn has_valid_signature(commit: &Commit) -> Result<bool, CommandError> {
+    match commit.verification() {
+        Ok(verification) => Ok(verification.is_some_and(|v| v.status != SigStatus::Bad)),
+        Err(SignError::InvalidSignatureFormat) => Ok(false),
+        Err(err) => Err(user_error_with_message(
+            format!(
+                "Failed to verify signature of commit {}",
+                short_commit_hash(commit.id())
+            ),
+            err,
+        )),
+    }
+}
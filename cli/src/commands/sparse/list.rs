@@ -26,6 +26,9 @@ use crate::ui::Ui;
 /// By default, a newly cloned or initialized repo will have have a pattern
 /// matching all files from the repo root. That pattern is rendered as `.` (a
 /// single period).
+///
+/// If a sparse profile is in use, the patterns from the profile are listed
+/// after the other patterns.
 #[derive(clap::Args, Clone, Debug)]
 pub struct SparseListArgs {}
 
@@ -43,5 +46,15 @@ pub fn cmd_sparse_list(
             path.to_fs_path_unchecked(Path::new("")).display()
         )?;
     }
+    if let Some(profile) = workspace_command.working_copy().sparse_profile()? {
+        writeln!(
+            ui.status(),
+            "Patterns from sparse profile {}:",
+            profile.name
+        )?;
+        for pattern in profile.patterns() {
+            writeln!(ui.stdout(), "{pattern}")?;
+        }
+    }
     Ok(())
 }
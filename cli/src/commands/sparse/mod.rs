@@ -16,6 +16,7 @@ mod edit;
 mod list;
 mod reset;
 mod set;
+mod r#use;
 
 use clap::Subcommand;
 use jj_lib::repo_path::RepoPathBuf;
@@ -25,6 +26,8 @@ use self::edit::cmd_sparse_edit;
 use self::edit::SparseEditArgs;
 use self::list::cmd_sparse_list;
 use self::list::SparseListArgs;
+use self::r#use::cmd_sparse_use;
+use self::r#use::SparseUseArgs;
 use self::reset::cmd_sparse_reset;
 use self::reset::SparseResetArgs;
 use self::set::cmd_sparse_set;
@@ -44,6 +47,7 @@ pub(crate) enum SparseCommand {
     List(SparseListArgs),
     Reset(SparseResetArgs),
     Set(SparseSetArgs),
+    Use(SparseUseArgs),
 }
 
 #[instrument(skip_all)]
@@ -57,6 +61,7 @@ pub(crate) fn cmd_sparse(
         SparseCommand::List(args) => cmd_sparse_list(ui, command, args),
         SparseCommand::Reset(args) => cmd_sparse_reset(ui, command, args),
         SparseCommand::Set(args) => cmd_sparse_set(ui, command, args),
+        SparseCommand::Use(args) => cmd_sparse_use(ui, command, args),
     }
 }
 
@@ -15,12 +15,15 @@
 use jj_lib::repo_path::RepoPathBuf;
 use tracing::instrument;
 
-use super::update_sparse_patterns_with;
+use crate::cli_util::print_checkout_stats;
 use crate::cli_util::CommandHelper;
+use crate::command_error::internal_error_with_message;
 use crate::command_error::CommandError;
 use crate::ui::Ui;
 
 /// Reset the patterns to include all files in the working copy
+///
+/// This also stops using the sparse profile, if any.
 #[derive(clap::Args, Clone, Debug)]
 pub struct SparseResetArgs {}
 
@@ -31,7 +34,19 @@ pub fn cmd_sparse_reset(
     _args: &SparseResetArgs,
 ) -> Result<(), CommandError> {
     let mut workspace_command = command.workspace_helper(ui)?;
-    update_sparse_patterns_with(ui, &mut workspace_command, |_ui, _old_patterns| {
-        Ok(vec![RepoPathBuf::root()])
-    })
+    let checkout_options = workspace_command.checkout_options();
+    let (mut locked_ws, wc_commit) = workspace_command.start_working_copy_mutation()?;
+    let stats = locked_ws
+        .locked_wc()
+        .set_sparse_patterns(vec![RepoPathBuf::root()], &checkout_options)
+        .map_err(|err| internal_error_with_message("Failed to update working copy paths", err))?;
+    // All files are already checked out, so this doesn't change any files.
+    locked_ws
+        .locked_wc()
+        .set_sparse_profile(None, &checkout_options)
+        .map_err(|err| internal_error_with_message("Failed to update working copy paths", err))?;
+    let operation_id = locked_ws.locked_wc().old_operation_id().clone();
+    locked_ws.finish(operation_id)?;
+    print_checkout_stats(ui, &stats, &wc_commit)?;
+    Ok(())
 }
@@ -0,0 +1,75 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::Write as _;
+
+use jj_lib::sparse_profile::SparseProfile;
+use tracing::instrument;
+
+use crate::cli_util::print_checkout_stats;
+use crate::cli_util::CommandHelper;
+use crate::command_error::internal_error_with_message;
+use crate::command_error::CommandError;
+use crate::ui::Ui;
+
+/// Check out the paths selected by a sparse profile committed to the repo
+///
+/// The profile named `NAME` is read from the file `.jj-sparse/NAME` in the
+/// parent of the working-copy commit. Each line of the file is a fileset
+/// expression relative to the repo root, such as `frontend` or
+/// `glob:"docs/**/*.md"`. Lines starting with `#` are ignored.
+/// Include `.jj-sparse` in the profile to keep the profile files in the
+/// working copy.
+///
+/// The profile replaces the current patterns, but more paths can be added
+/// with `jj sparse set --add`. The profile is read again whenever the
+/// working-copy commit changes, so changes to the profile file take effect
+/// once it's committed to the parent of the working-copy commit. Use `jj
+/// sparse reset` to stop using the profile.
+#[derive(clap::Args, Clone, Debug)]
+pub struct SparseUseArgs {
+    /// Name of the profile
+    #[arg(value_name = "NAME")]
+    name: String,
+}
+
+#[instrument(skip_all)]
+pub fn cmd_sparse_use(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &SparseUseArgs,
+) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui)?;
+    let checkout_options = workspace_command.checkout_options();
+    let repo = workspace_command.repo().clone();
+    let (mut locked_ws, wc_commit) = workspace_command.start_working_copy_mutation()?;
+    let profile = SparseProfile::resolve(&wc_commit.parent_tree(repo.as_ref())?, &args.name)?;
+    // Add the paths selected by the profile before removing the other paths
+    // so the files in both aren't rewritten.
+    let added_stats = locked_ws
+        .locked_wc()
+        .set_sparse_profile(Some(profile), &checkout_options)
+        .map_err(|err| internal_error_with_message("Failed to update working copy paths", err))?;
+    let removed_stats = locked_ws
+        .locked_wc()
+        .set_sparse_patterns(vec![], &checkout_options)
+        .map_err(|err| internal_error_with_message("Failed to update working copy paths", err))?;
+    let operation_id = locked_ws.locked_wc().old_operation_id().clone();
+    locked_ws.finish(operation_id)?;
+    writeln!(ui.status(), "Using sparse profile {}", args.name)?;
+    let mut stats = added_stats;
+    stats.removed_files += removed_stats.removed_files;
+    print_checkout_stats(ui, &stats, &wc_commit)?;
+    Ok(())
+}
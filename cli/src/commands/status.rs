@@ -19,6 +19,7 @@ use jj_lib::copies::CopyRecords;
 use jj_lib::repo::Repo as _;
 use jj_lib::revset::RevsetExpression;
 use jj_lib::revset::RevsetFilterPredicate;
+use jj_lib::working_copy::UntrackedReason;
 use tracing::instrument;
 
 use crate::cli_util::print_conflicted_paths;
@@ -84,7 +85,8 @@ pub(crate) fn cmd_status(
                 writeln!(formatter, "Working copy changes:")?;
                 let mut copy_records = CopyRecords::default();
                 for parent in wc_commit.parent_ids() {
-                    let records = get_copy_records(repo.store(), parent, wc_commit.id(), &matcher)?;
+                    let records =
+                        get_copy_records(repo.store(), parent, wc_commit.id(), &tree, &matcher)?;
                     copy_records.add_records(records)?;
                 }
                 let diff_renderer = workspace_command.diff_renderer(vec![DiffFormat::Summary]);
@@ -103,9 +105,14 @@ pub(crate) fn cmd_status(
             if wc_has_untracked {
                 writeln!(formatter, "Untracked paths:")?;
                 formatter.with_label("diff", |formatter| {
-                    for path in snapshot_stats.untracked_paths.keys() {
+                    for (path, reason) in &snapshot_stats.untracked_paths {
                         let ui_path = workspace_command.path_converter().format_file_path(path);
-                        writeln!(formatter.labeled("untracked"), "? {ui_path}")?;
+                        match untracked_reason_label(reason) {
+                            Some(label) => {
+                                writeln!(formatter.labeled("untracked"), "? {ui_path} ({label})")?;
+                            }
+                            None => writeln!(formatter.labeled("untracked"), "? {ui_path}")?,
+                        }
                     }
                     io::Result::Ok(())
                 })?;
@@ -211,3 +218,14 @@ pub(crate) fn cmd_status(
 
     Ok(())
 }
+
+/// Returns a short explanation for files left untracked by the snapshot
+/// settings. Files that just aren't auto-tracked aren't annotated.
+fn untracked_reason_label(reason: &UntrackedReason) -> Option<String> {
+    match reason {
+        UntrackedReason::FileTooLarge { .. } => Some("too large".to_owned()),
+        UntrackedReason::GeneratedBinary { format } => Some(format!("generated binary: {format}")),
+        UntrackedReason::IgnoredByDefault => Some("ignored by default".to_owned()),
+        UntrackedReason::FileNotAutoTracked => None,
+    }
+}
@@ -0,0 +1,164 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use clap_complete::ArgValueCandidates;
+use itertools::Itertools as _;
+use jj_lib::object_id::ObjectId as _;
+use jj_lib::op_store::RefTarget;
+use jj_lib::str_util::StringPattern;
+
+use super::find_tags;
+use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::command_error::user_error_with_hint;
+use crate::command_error::CommandError;
+use crate::commands::bookmark::is_fast_forward;
+use crate::complete;
+use crate::ui::Ui;
+
+/// Move existing tags to target revision
+///
+/// If tag names are given, the specified tags will be updated to point to the
+/// target revision.
+///
+/// If `--from` options are given, tags currently pointing to the specified
+/// revisions will be updated. The tags can also be filtered by names.
+///
+/// Example: move the `nightly` tag to the working-copy parent
+///
+/// $ jj tag move nightly --to @-
+#[derive(clap::Args, Clone, Debug)]
+#[command(group(clap::ArgGroup::new("source").multiple(true).required(true)))]
+pub struct TagMoveArgs {
+    /// Move tags from the given revisions
+    #[arg(
+        long, short,
+        group = "source",
+        value_name = "REVSETS",
+        add = ArgValueCandidates::new(complete::all_revisions),
+    )]
+    from: Vec<RevisionArg>,
+
+    /// Move tags to this revision
+    #[arg(
+        long, short,
+        value_name = "REVSET",
+        add = ArgValueCandidates::new(complete::all_revisions),
+    )]
+    to: RevisionArg,
+
+    /// Allow moving tags backwards or sideways
+    #[arg(long, short = 'B')]
+    allow_backwards: bool,
+
+    /// Move tags matching the given name patterns
+    ///
+    /// By default, the specified name matches exactly. Use `glob:` prefix to
+    /// select tags by [wildcard pattern].
+    ///
+    /// [wildcard pattern]:
+    ///     https://jj-vcs.github.io/jj/latest/revsets/#string-patterns
+    #[arg(
+        group = "source",
+        value_parser = StringPattern::parse,
+        add = ArgValueCandidates::new(complete::local_tags),
+    )]
+    names: Vec<StringPattern>,
+}
+
+pub fn cmd_tag_move(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &TagMoveArgs,
+) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui)?;
+    let repo = workspace_command.repo().clone();
+    let target_commit = workspace_command.resolve_single_rev(ui, &args.to)?;
+    let matched_tags = {
+        let mut tags = if !args.names.is_empty() {
+            find_tags(repo.view(), &args.names)?
+        } else {
+            repo.view()
+                .tags()
+                .iter()
+                .map(|(name, target)| (name.as_ref(), target))
+                .collect()
+        };
+        if !args.from.is_empty() {
+            let is_source_commit = workspace_command
+                .parse_union_revsets(ui, &args.from)?
+                .evaluate()?
+                .containing_fn();
+            let mut source_tags = vec![];
+            for (name, target) in tags {
+                if target
+                    .added_ids()
+                    .map(&is_source_commit)
+                    .process_results(|mut matched| matched.any(|m| m))?
+                {
+                    source_tags.push((name, target));
+                }
+            }
+            tags = source_tags;
+        }
+        // Noop matches aren't error, but should be excluded from stats.
+        tags.retain(|(_, old_target)| old_target.as_normal() != Some(target_commit.id()));
+        tags
+    };
+
+    if matched_tags.is_empty() {
+        writeln!(ui.status(), "No tags to update.")?;
+        return Ok(());
+    }
+
+    if !args.allow_backwards {
+        if let Some((name, _)) = matched_tags
+            .iter()
+            .find(|(_, old_target)| !is_fast_forward(repo.as_ref(), old_target, target_commit.id()))
+        {
+            return Err(user_error_with_hint(
+                format!(
+                    "Refusing to move tag backwards or sideways: {name}",
+                    name = name.as_symbol()
+                ),
+                "Use --allow-backwards to allow it.",
+            ));
+        }
+    }
+
+    let mut tx = workspace_command.start_transaction();
+    for (name, _) in &matched_tags {
+        tx.repo_mut()
+            .set_tag_target(name, RefTarget::normal(target_commit.id().clone()));
+    }
+
+    if let Some(mut formatter) = ui.status_formatter() {
+        write!(formatter, "Moved {} tags to ", matched_tags.len())?;
+        tx.write_commit_summary(formatter.as_mut(), &target_commit)?;
+        writeln!(formatter)?;
+    }
+
+    tx.finish(
+        ui,
+        format!(
+            "point tag {names} to commit {id}",
+            names = matched_tags
+                .iter()
+                .map(|(name, _)| name.as_symbol())
+                .join(", "),
+            id = target_commit.id().hex()
+        ),
+    )?;
+    Ok(())
+}
@@ -0,0 +1,149 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use clap_complete::ArgValueCandidates;
+use itertools::Itertools as _;
+use jj_lib::object_id::ObjectId as _;
+use jj_lib::op_store::RefTarget;
+use jj_lib::ref_name::RefNameBuf;
+
+use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::command_error::user_error_with_hint;
+use crate::command_error::CommandError;
+use crate::commands::bookmark::is_fast_forward;
+use crate::complete;
+use crate::revset_util;
+use crate::ui::Ui;
+
+/// Create or update a tag to point to a certain commit
+///
+/// In a Git-backed repo, tags are exported as lightweight tags unless a
+/// `--message` is given, in which case they are exported as annotated tags.
+#[derive(clap::Args, Clone, Debug)]
+pub struct TagSetArgs {
+    /// The tag's target revision
+    #[arg(
+        long, short,
+        visible_alias = "to",
+        value_name = "REVSET",
+        add = ArgValueCandidates::new(complete::all_revisions),
+    )]
+    revision: RevisionArg,
+
+    /// Allow moving the tag backwards or sideways
+    #[arg(long, short = 'B')]
+    allow_backwards: bool,
+
+    /// Create annotated tags with the given message
+    ///
+    /// This is only supported in Git-backed repos.
+    #[arg(long, short, value_name = "MESSAGE")]
+    message: Option<String>,
+
+    /// The tags to update
+    #[arg(
+        required = true,
+        value_parser = revset_util::parse_tag_name,
+        add = ArgValueCandidates::new(complete::local_tags),
+    )]
+    names: Vec<RefNameBuf>,
+}
+
+pub fn cmd_tag_set(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &TagSetArgs,
+) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui)?;
+    let target_commit = workspace_command.resolve_single_rev(ui, &args.revision)?;
+    let repo = workspace_command.repo().as_ref();
+    let tag_names = &args.names;
+    let mut new_tag_count = 0;
+    let mut moved_tag_count = 0;
+    for name in tag_names {
+        let old_target = repo.view().get_tag(name);
+        if old_target.is_absent() {
+            new_tag_count += 1;
+        } else if old_target.as_normal() != Some(target_commit.id()) {
+            moved_tag_count += 1;
+        }
+        if !args.allow_backwards && !is_fast_forward(repo, old_target, target_commit.id()) {
+            return Err(user_error_with_hint(
+                format!(
+                    "Refusing to move tag backwards or sideways: {name}",
+                    name = name.as_symbol()
+                ),
+                "Use --allow-backwards to allow it.",
+            ));
+        }
+    }
+
+    let mut tx = workspace_command.start_transaction();
+    for tag_name in tag_names {
+        if let Some(message) = &args.message {
+            set_annotated_tag(&mut tx, tag_name, &target_commit, message)?;
+        } else {
+            tx.repo_mut()
+                .set_tag_target(tag_name, RefTarget::normal(target_commit.id().clone()));
+        }
+    }
+
+    if let Some(mut formatter) = ui.status_formatter() {
+        if new_tag_count > 0 {
+            write!(formatter, "Created {new_tag_count} tags pointing to ")?;
+            tx.write_commit_summary(formatter.as_mut(), &target_commit)?;
+            writeln!(formatter)?;
+        }
+        if moved_tag_count > 0 {
+            write!(formatter, "Moved {moved_tag_count} tags to ")?;
+            tx.write_commit_summary(formatter.as_mut(), &target_commit)?;
+            writeln!(formatter)?;
+        }
+    }
+
+    tx.finish(
+        ui,
+        format!(
+            "point tag {names} to commit {id}",
+            names = tag_names.iter().map(|n| n.as_symbol()).join(", "),
+            id = target_commit.id().hex()
+        ),
+    )?;
+    Ok(())
+}
+
+#[cfg(feature = "git")]
+fn set_annotated_tag(
+    tx: &mut crate::cli_util::WorkspaceCommandTransaction,
+    name: &jj_lib::ref_name::RefName,
+    target_commit: &jj_lib::commit::Commit,
+    message: &str,
+) -> Result<(), CommandError> {
+    let tagger = tx.settings().signature();
+    jj_lib::git::set_annotated_tag(tx.repo_mut(), name, target_commit.id(), message, &tagger)?;
+    Ok(())
+}
+
+#[cfg(not(feature = "git"))]
+fn set_annotated_tag(
+    _tx: &mut crate::cli_util::WorkspaceCommandTransaction,
+    _name: &jj_lib::ref_name::RefName,
+    _target_commit: &jj_lib::commit::Commit,
+    _message: &str,
+) -> Result<(), CommandError> {
+    Err(crate::command_error::user_error(
+        "Annotated tags are only supported in Git-backed repos",
+    ))
+}
@@ -34,7 +34,8 @@ use crate::ui::Ui;
 /// How to handle sparse patterns when creating a new workspace.
 #[derive(clap::ValueEnum, Clone, Debug, Eq, PartialEq)]
 enum SparseInheritance {
-    /// Copy all sparse patterns from the current workspace.
+    /// Copy all sparse patterns and the sparse profile from the current
+    /// workspace.
     Copy,
     /// Include all files in the new workspace.
     Full,
@@ -136,23 +137,26 @@ pub fn cmd_workspace_add(
 
     let sparsity = match args.sparse_patterns {
         SparseInheritance::Full => None,
-        SparseInheritance::Empty => Some(vec![]),
+        SparseInheritance::Empty => Some((vec![], None)),
         SparseInheritance::Copy => {
-            let sparse_patterns = old_workspace_command
-                .working_copy()
-                .sparse_patterns()?
-                .to_vec();
-            Some(sparse_patterns)
+            let working_copy = old_workspace_command.working_copy();
+            let sparse_patterns = working_copy.sparse_patterns()?.to_vec();
+            let sparse_profile = working_copy.sparse_profile()?.cloned();
+            Some((sparse_patterns, sparse_profile))
         }
     };
 
-    if let Some(sparse_patterns) = sparsity {
+    if let Some((sparse_patterns, sparse_profile)) = sparsity {
         let checkout_options = new_workspace_command.checkout_options();
         let (mut locked_ws, _wc_commit) = new_workspace_command.start_working_copy_mutation()?;
         locked_ws
             .locked_wc()
             .set_sparse_patterns(sparse_patterns, &checkout_options)
             .map_err(|err| internal_error_with_message("Failed to set sparse patterns", err))?;
+        locked_ws
+            .locked_wc()
+            .set_sparse_profile(sparse_profile, &checkout_options)
+            .map_err(|err| internal_error_with_message("Failed to set sparse profile", err))?;
         let operation_id = locked_ws.locked_wc().old_operation_id().clone();
         locked_ws.finish(operation_id)?;
     }
@@ -75,5 +75,10 @@ pub fn cmd_workspace_forget(
     };
 
     tx.finish(ui, description)?;
+    let workspace_names = wss.iter().map(|ws| ws.as_ref()).collect_vec();
+    workspace_command
+        .workspace()
+        .workspace_store()
+        .forget(&workspace_names)?;
     Ok(())
 }
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use jj_lib::file_util;
 use jj_lib::repo::Repo as _;
 use tracing::instrument;
 
@@ -20,6 +21,9 @@ use crate::command_error::CommandError;
 use crate::ui::Ui;
 
 /// List workspaces
+///
+/// Workspaces whose root directory is missing or has been moved are reported
+/// along with the commands to re-link or forget them.
 #[derive(clap::Args, Clone, Debug)]
 pub struct WorkspaceListArgs {}
 
@@ -39,5 +43,42 @@ pub fn cmd_workspace_list(
         template.format(&commit, formatter.as_mut())?;
         writeln!(formatter)?;
     }
+    drop(formatter);
+
+    let workspace_store = workspace_command.workspace().workspace_store();
+    for name in repo.view().wc_commit_ids().keys() {
+        let Some(recorded_root) = workspace_store.get_workspace_path(name)? else {
+            continue;
+        };
+        if name == workspace_command.workspace_name() {
+            let workspace_root = workspace_command.workspace_root();
+            if recorded_root != workspace_root {
+                writeln!(
+                    ui.warning_default(),
+                    "Workspace {name} has been moved from \"{old}\"",
+                    name = name.as_symbol(),
+                    old = recorded_root.display(),
+                )?;
+                writeln!(
+                    ui.hint_default(),
+                    "Run `jj workspace move {new}` to update its location",
+                    new = file_util::relative_path(command.cwd(), workspace_root).display(),
+                )?;
+            }
+        } else if !recorded_root.join(".jj").is_dir() {
+            writeln!(
+                ui.warning_default(),
+                "Workspace {name} is missing from \"{old}\"",
+                name = name.as_symbol(),
+                old = recorded_root.display(),
+            )?;
+            writeln!(
+                ui.hint_default(),
+                "Run `jj workspace move --workspace {name} <new path>` if it has been moved, or \
+                 `jj workspace forget {name}` if it has been deleted",
+                name = name.as_symbol(),
+            )?;
+        }
+    }
     Ok(())
 }
@@ -0,0 +1,172 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+
+use clap_complete::ArgValueCandidates;
+use jj_lib::file_util;
+use jj_lib::file_util::IoResultExt as _;
+use jj_lib::ref_name::WorkspaceName;
+use jj_lib::ref_name::WorkspaceNameBuf;
+use jj_lib::workspace::write_repo_path_file;
+use jj_lib::workspace_store::SimpleWorkspaceStore;
+use jj_lib::workspace_store::WorkspaceStore as _;
+use tracing::instrument;
+
+use crate::cli_util::CommandHelper;
+use crate::command_error::user_error;
+use crate::command_error::user_error_with_hint;
+use crate::command_error::CommandError;
+use crate::complete;
+use crate::ui::Ui;
+
+/// Move a workspace to another directory
+///
+/// The workspace root directory is moved to the destination, and the links
+/// between the repo and the workspace are updated. If the workspace hosts the
+/// repo (i.e. it is the workspace the repo was created in), the other
+/// workspaces are pointed to the new repo location.
+///
+/// If the workspace directory has already been moved by other means, specify
+/// its new location as the destination to only update the links.
+#[derive(clap::Args, Clone, Debug)]
+pub struct WorkspaceMoveArgs {
+    /// The workspace to move (defaults to the current workspace)
+    #[arg(long, add = ArgValueCandidates::new(complete::workspaces))]
+    workspace: Option<WorkspaceNameBuf>,
+    /// The new location of the workspace root
+    #[arg(value_hint = clap::ValueHint::DirPath)]
+    destination: String,
+}
+
+#[instrument(skip_all)]
+pub fn cmd_workspace_move(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &WorkspaceMoveArgs,
+) -> Result<(), CommandError> {
+    let workspace_command = command.workspace_helper(ui)?;
+    let workspace_name = args
+        .workspace
+        .as_deref()
+        .unwrap_or_else(|| workspace_command.workspace_name());
+    if workspace_command
+        .repo()
+        .view()
+        .get_wc_commit_id(workspace_name)
+        .is_none()
+    {
+        return Err(user_error(format!(
+            "No such workspace: {}",
+            workspace_name.as_symbol()
+        )));
+    }
+    let workspace_store = workspace_command.workspace().workspace_store();
+    let recorded_root = workspace_store.get_workspace_path(workspace_name)?;
+    let old_root = if workspace_name == workspace_command.workspace_name() {
+        workspace_command.workspace_root().to_owned()
+    } else {
+        recorded_root.clone().ok_or_else(|| {
+            user_error_with_hint(
+                format!(
+                    "The location of workspace {} is unknown",
+                    workspace_name.as_symbol()
+                ),
+                "Run any command from within the workspace to record its location",
+            )
+        })?
+    };
+    let destination = file_util::normalize_path(&command.cwd().join(&args.destination));
+
+    let new_root = if is_workspace_root(&old_root) {
+        if dunce::canonicalize(&destination).is_ok_and(|path| path == old_root) {
+            old_root.clone()
+        } else if destination.exists() {
+            return Err(user_error(format!(
+                "Destination \"{}\" already exists",
+                args.destination
+            )));
+        } else {
+            fs::rename(&old_root, &destination).context(&old_root)?;
+            dunce::canonicalize(&destination).context(&destination)?
+        }
+    } else if is_workspace_root(&destination) {
+        // The workspace has already been moved.
+        dunce::canonicalize(&destination).context(&destination)?
+    } else {
+        return Err(user_error(format!(
+            "No workspace found at \"{}\"",
+            args.destination
+        )));
+    };
+    if recorded_root.as_ref() == Some(&new_root) && new_root == old_root {
+        writeln!(ui.status(), "Nothing changed.")?;
+        return Ok(());
+    }
+
+    relink_workspace(
+        workspace_command.repo_path(),
+        workspace_name,
+        &old_root,
+        &new_root,
+    )?;
+    if new_root == old_root {
+        writeln!(
+            ui.status(),
+            "Updated the location of workspace {}",
+            workspace_name.as_symbol()
+        )?;
+    } else {
+        writeln!(
+            ui.status(),
+            "Moved workspace {} to \"{}\"",
+            workspace_name.as_symbol(),
+            file_util::relative_path(command.cwd(), &new_root).display()
+        )?;
+    }
+    Ok(())
+}
+
+fn is_workspace_root(path: &Path) -> bool {
+    path.join(".jj").is_dir()
+}
+
+/// Records the `new_root` of the workspace. If the workspace hosts the repo,
+/// the other workspaces are pointed to the new repo location.
+fn relink_workspace(
+    repo_path: &Path,
+    workspace_name: &WorkspaceName,
+    old_root: &Path,
+    new_root: &Path,
+) -> Result<(), CommandError> {
+    let hosts_repo = repo_path.starts_with(old_root);
+    let new_repo_path: PathBuf = match repo_path.strip_prefix(old_root) {
+        Ok(relative_path) => new_root.join(relative_path),
+        Err(_) => repo_path.to_owned(),
+    };
+    let workspace_store = SimpleWorkspaceStore::load(&new_repo_path);
+    workspace_store.add(workspace_name, new_root)?;
+    if !hosts_repo {
+        return Ok(());
+    }
+    for (name, root) in workspace_store.list()? {
+        let jj_dir = root.join(".jj");
+        if name != *workspace_name && jj_dir.join("repo").is_file() {
+            write_repo_path_file(&jj_dir, &new_repo_path)?;
+        }
+    }
+    Ok(())
+}
@@ -71,6 +71,10 @@ pub fn cmd_workspace_rename(
         new = new_name.as_symbol()
     ))?;
     locked_ws.finish(repo.op_id().clone())?;
+    workspace_command
+        .workspace()
+        .workspace_store()
+        .rename(&old_name, new_name)?;
 
     Ok(())
 }
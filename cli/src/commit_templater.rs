@@ -60,6 +60,7 @@ use jj_lib::revset::RevsetParseContext;
 use jj_lib::revset::UserRevsetExpression;
 use jj_lib::settings::UserSettings;
 use jj_lib::signing::SigStatus;
+use jj_lib::signing::SigTrust;
 use jj_lib::signing::SignError;
 use jj_lib::signing::SignResult;
 use jj_lib::signing::Verification;
@@ -68,11 +69,14 @@ use jj_lib::trailer;
 use jj_lib::trailer::Trailer;
 use once_cell::unsync::OnceCell;
 use pollster::FutureExt as _;
+use serde::Serialize;
 
 use crate::diff_util;
 use crate::diff_util::DiffStats;
 use crate::formatter::Formatter;
+use crate::json_util::SignatureJson;
 use crate::revset_util;
+use crate::revset_util::UserRevsetEvaluationError;
 use crate::template_builder;
 use crate::template_builder::expect_plain_text_expression;
 use crate::template_builder::merge_fn_map;
@@ -384,6 +388,18 @@ impl<'repo> CommitTemplateLanguage<'repo> {
         self.cache_extensions.get::<T>()
     }
 
+    /// Creates a serializer of commits to records of JSON output.
+    pub fn json_serializer(
+        &self,
+    ) -> Result<CommitJsonSerializer<'_, 'repo>, UserRevsetEvaluationError> {
+        let revset = try_evaluate_revset_expression(self, &self.immutable_expression)?;
+        Ok(CommitJsonSerializer {
+            language: self,
+            is_immutable: revset.containing_fn(),
+            verify_signatures: false,
+        })
+    }
+
     pub fn wrap_commit(
         property: impl TemplateProperty<Output = Commit> + 'repo,
     ) -> CommitTemplatePropertyKind<'repo> {
@@ -1168,19 +1184,26 @@ fn evaluate_revset_expression<'repo>(
     span: pest::Span<'_>,
     expression: &UserRevsetExpression,
 ) -> Result<Box<dyn Revset + 'repo>, TemplateParseError> {
-    let make_error = || TemplateParseError::expression("Failed to evaluate revset", span);
+    try_evaluate_revset_expression(language, expression).map_err(|err| {
+        TemplateParseError::expression("Failed to evaluate revset", span).with_source(err)
+    })
+}
+
+fn try_evaluate_revset_expression<'repo>(
+    language: &CommitTemplateLanguage<'repo>,
+    expression: &UserRevsetExpression,
+) -> Result<Box<dyn Revset + 'repo>, UserRevsetEvaluationError> {
     let repo = language.repo;
     let symbol_resolver = revset_util::default_symbol_resolver(
         repo,
         language.revset_parse_context.extensions.symbol_resolvers(),
         language.id_prefix_context,
     );
-    let revset = expression
+    expression
         .resolve_user_expression(repo, &symbol_resolver)
-        .map_err(|err| make_error().with_source(err))?
+        .map_err(UserRevsetEvaluationError::Resolution)?
         .evaluate(repo)
-        .map_err(|err| make_error().with_source(err))?;
-    Ok(revset)
+        .map_err(UserRevsetEvaluationError::Evaluation)
 }
 
 fn evaluate_user_revset<'repo>(
@@ -1816,15 +1839,21 @@ impl TreeDiff {
         commit: &Commit,
         matcher: Rc<dyn Matcher>,
     ) -> BackendResult<Self> {
+        let to_tree = commit.tree()?;
         let mut copy_records = CopyRecords::default();
         for parent in commit.parent_ids() {
-            let records =
-                diff_util::get_copy_records(repo.store(), parent, commit.id(), &*matcher)?;
+            let records = diff_util::get_copy_records(
+                repo.store(),
+                parent,
+                commit.id(),
+                &to_tree,
+                &*matcher,
+            )?;
             copy_records.add_records(records)?;
         }
         Ok(TreeDiff {
             from_tree: commit.parent_tree(repo)?,
-            to_tree: commit.tree()?,
+            to_tree,
             matcher,
             copy_records,
         })
@@ -2236,6 +2265,13 @@ impl CryptographicSignature {
         self.verify().map(|verification| verification.status)
     }
 
+    fn trust(&self) -> SignResult<SigTrust> {
+        self.commit
+            .trust()
+            .transpose()
+            .expect("must have signature")
+    }
+
     /// Defaults to empty string if key is not present.
     fn key(&self) -> SignResult<String> {
         self.verify()
@@ -2267,6 +2303,18 @@ fn builtin_cryptographic_signature_methods<'repo>(
             Ok(L::wrap_string(out_property))
         },
     );
+    map.insert(
+        "trust",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.and_then(|sig| match sig.trust() {
+                Ok(trust) => Ok(trust.to_string()),
+                Err(SignError::InvalidSignatureFormat) => Ok(SigTrust::Unknown.to_string()),
+                Err(err) => Err(err.into()),
+            });
+            Ok(L::wrap_string(out_property))
+        },
+    );
     map.insert(
         "key",
         |_language, _diagnostics, _build_ctx, self_property, function| {
@@ -2291,6 +2339,8 @@ pub struct AnnotationLine {
     pub commit: Commit,
     pub content: BString,
     pub line_number: usize,
+    /// Path of the file in the commit, if it differs from the annotated path.
+    pub original_path: Option<RepoPathBuf>,
     pub first_line_in_hunk: bool,
 }
 
@@ -2323,6 +2373,14 @@ fn builtin_annotation_line_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'r
             Ok(L::wrap_integer(out_property))
         },
     );
+    map.insert(
+        "original_path",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.map(|line| line.original_path);
+            Ok(L::wrap_repo_path_opt(out_property))
+        },
+    );
     map.insert(
         "first_line_in_hunk",
         |_language, _diagnostics, _build_ctx, self_property, function| {
@@ -2367,3 +2425,180 @@ fn builtin_trailer_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, Tra
     );
     map
 }
+
+/// Commit record of JSON output.
+///
+/// The fields are the values of the commit keywords which don't take
+/// arguments.
+#[derive(Clone, Debug, Serialize)]
+pub struct CommitJson {
+    pub commit_id: String,
+    pub change_id: String,
+    pub parents: Vec<String>,
+    pub description: String,
+    pub trailers: Vec<TrailerJson>,
+    pub author: SignatureJson,
+    pub committer: SignatureJson,
+    pub mine: bool,
+    pub signed: bool,
+    /// Only present if signatures are verified, which is slow.
+    pub signature: Option<CryptographicSignatureJson>,
+    pub working_copies: Vec<String>,
+    pub current_working_copy: bool,
+    pub bookmarks: Vec<CommitRefJson>,
+    pub local_bookmarks: Vec<CommitRefJson>,
+    pub remote_bookmarks: Vec<CommitRefJson>,
+    pub tags: Vec<CommitRefJson>,
+    pub git_refs: Vec<CommitRefJson>,
+    pub git_head: bool,
+    pub divergent: bool,
+    pub hidden: bool,
+    pub immutable: bool,
+    pub conflict: bool,
+    pub empty: bool,
+    pub root: bool,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct TrailerJson {
+    pub key: String,
+    pub value: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct CommitRefJson {
+    pub name: String,
+    pub remote: Option<String>,
+    /// Target commit ids. There are more than one if the ref is conflicted.
+    pub target: Vec<String>,
+    pub present: bool,
+    pub conflict: bool,
+    pub tracked: bool,
+    pub synced: bool,
+}
+
+impl CommitRefJson {
+    fn new(commit_ref: &CommitRef) -> Self {
+        CommitRefJson {
+            name: commit_ref.name.clone(),
+            remote: commit_ref.remote.clone(),
+            target: commit_ref.target.added_ids().map(|id| id.hex()).collect(),
+            present: commit_ref.is_present(),
+            conflict: commit_ref.has_conflict(),
+            tracked: commit_ref.is_tracked(),
+            synced: commit_ref.synced,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct CryptographicSignatureJson {
+    /// One of `"good"`, `"bad"`, `"unknown"`, or `"invalid"`.
+    pub status: String,
+    /// One of `"verified"`, `"unknown"`, or `"expired"`.
+    pub trust: String,
+    pub key: String,
+    pub display: String,
+}
+
+impl CryptographicSignatureJson {
+    fn new(signature: &CryptographicSignature) -> SignResult<Self> {
+        match signature.verify() {
+            Ok(verification) => Ok(CryptographicSignatureJson {
+                status: verification.status.to_string(),
+                trust: signature.trust()?.to_string(),
+                key: verification.key.unwrap_or_default(),
+                display: verification.display.unwrap_or_default(),
+            }),
+            Err(SignError::InvalidSignatureFormat) => Ok(CryptographicSignatureJson {
+                status: "invalid".to_owned(),
+                trust: SigTrust::Unknown.to_string(),
+                key: String::new(),
+                display: String::new(),
+            }),
+            Err(err) => Err(err),
+        }
+    }
+}
+
+/// Converts commits to records of JSON output.
+pub struct CommitJsonSerializer<'a, 'repo> {
+    language: &'a CommitTemplateLanguage<'repo>,
+    is_immutable: Box<RevsetContainingFn<'repo>>,
+    verify_signatures: bool,
+}
+
+impl CommitJsonSerializer<'_, '_> {
+    /// Sets whether to verify signatures, which runs the signing backend for
+    /// each signed commit.
+    pub fn verify_signatures(mut self, verify_signatures: bool) -> Self {
+        self.verify_signatures = verify_signatures;
+        self
+    }
+
+    pub fn to_json(&self, commit: &Commit) -> Result<CommitJson, TemplatePropertyError> {
+        let language = self.language;
+        let repo = language.repo;
+        let view = repo.view();
+        let refs_to_json = |refs: &[Rc<CommitRef>], predicate: fn(&CommitRef) -> bool| {
+            refs.iter()
+                .filter(|commit_ref| predicate(commit_ref))
+                .map(|commit_ref| CommitRefJson::new(commit_ref))
+                .collect_vec()
+        };
+        let bookmarks = language
+            .keyword_cache
+            .bookmarks_index(repo)
+            .get(commit.id());
+        let tags = language.keyword_cache.tags_index(repo).get(commit.id());
+        let git_refs = language.keyword_cache.git_refs_index(repo).get(commit.id());
+        // The given commit could be hidden in e.g. `jj evolog`.
+        let divergent = repo
+            .resolve_change_id(commit.change_id())
+            .is_some_and(|entries| entries.len() > 1);
+        let signature = CryptographicSignature::new(commit.clone())
+            .filter(|_| self.verify_signatures)
+            .map(|signature| CryptographicSignatureJson::new(&signature))
+            .transpose()?;
+        Ok(CommitJson {
+            commit_id: commit.id().hex(),
+            change_id: commit.change_id().reverse_hex(),
+            parents: commit.parent_ids().iter().map(|id| id.hex()).collect(),
+            description: commit.description().to_owned(),
+            trailers: trailer::parse_description_trailers(commit.description())
+                .into_iter()
+                .map(|trailer| TrailerJson {
+                    key: trailer.key,
+                    value: trailer.value,
+                })
+                .collect(),
+            author: SignatureJson::new(commit.author())?,
+            committer: SignatureJson::new(commit.committer())?,
+            mine: commit.author().email == language.revset_parse_context.user_email,
+            signed: commit.is_signed(),
+            signature,
+            working_copies: view
+                .wc_commit_ids()
+                .iter()
+                .filter(|(_, wc_commit_id)| *wc_commit_id == commit.id())
+                .map(|(name, _)| name.as_str().to_owned())
+                .collect(),
+            current_working_copy: Some(commit.id())
+                == view.get_wc_commit_id(&language.workspace_name),
+            bookmarks: refs_to_json(bookmarks, |commit_ref| {
+                commit_ref.is_local() || !commit_ref.synced
+            }),
+            local_bookmarks: refs_to_json(bookmarks, CommitRef::is_local),
+            remote_bookmarks: refs_to_json(bookmarks, CommitRef::is_remote),
+            tags: refs_to_json(tags, |_| true),
+            git_refs: refs_to_json(git_refs, |_| true),
+            git_head: view.git_head().added_ids().contains(commit.id()),
+            divergent,
+            hidden: commit.is_hidden(repo),
+            immutable: (self.is_immutable)(commit.id())?,
+            conflict: commit.has_conflict()?,
+            empty: commit.is_empty(repo)?,
+            root: commit.id() == repo.store().root_commit_id(),
+        })
+    }
+}
@@ -100,6 +100,24 @@ pub fn tracked_bookmarks() -> Vec<CompletionCandidate> {
     })
 }
 
+pub fn local_tags() -> Vec<CompletionCandidate> {
+    with_jj(|jj, _| {
+        let output = jj
+            .build()
+            .arg("tag")
+            .arg("list")
+            .arg("--template")
+            .arg(r#"name ++ "\n""#)
+            .output()
+            .map_err(user_error)?;
+
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(CompletionCandidate::new)
+            .collect())
+    })
+}
+
 pub fn untracked_bookmarks() -> Vec<CompletionCandidate> {
     with_jj(|jj, settings| {
         let output = jj
@@ -71,12 +71,15 @@ use jj_lib::rewrite::rebase_to_dest_parent;
 use jj_lib::settings::UserSettings;
 use jj_lib::store::Store;
 use pollster::FutureExt as _;
+use serde::Serialize;
 use thiserror::Error;
 use tracing::instrument;
 use unicode_width::UnicodeWidthStr as _;
 
 use crate::config::CommandNameAndArgs;
 use crate::formatter::Formatter;
+use crate::json_util::write_json_record;
+use crate::json_util::OutputFormat;
 use crate::merge_tools;
 use crate::merge_tools::generate_diff;
 use crate::merge_tools::invoke_external_diff;
@@ -90,7 +93,7 @@ use crate::ui::Ui;
 #[derive(clap::Args, Clone, Debug)]
 #[command(next_help_heading = "Diff Formatting Options")]
 #[command(group(clap::ArgGroup::new("short-format").args(&["summary", "stat", "types", "name_only"])))]
-#[command(group(clap::ArgGroup::new("long-format").args(&["git", "color_words", "tool"])))]
+#[command(group(clap::ArgGroup::new("long-format").args(&["git", "color_words", "json", "tool"])))]
 pub struct DiffFormatArgs {
     /// For each path, show only whether it was modified, added, or deleted
     #[arg(long, short)]
@@ -119,6 +122,14 @@ pub struct DiffFormatArgs {
     /// Show a word-level diff with changes indicated only by color
     #[arg(long)]
     pub color_words: bool,
+    /// Show a machine-readable diff in JSON format
+    ///
+    /// The diff is printed as a single JSON record with per-file status,
+    /// modes, and hunks. See
+    /// https://jj-vcs.github.io/jj/latest/templates/#json-diff-output for the
+    /// format.
+    #[arg(long)]
+    pub json: bool,
     /// Generate diff by external command
     #[arg(long)]
     pub tool: Option<String>,
@@ -144,6 +155,7 @@ pub enum DiffFormat {
     NameOnly,
     Git(Box<UnifiedDiffOptions>),
     ColorWords(Box<ColorWordsDiffOptions>),
+    Json(Box<UnifiedDiffOptions>),
     Tool(Box<ExternalMergeTool>),
 }
 
@@ -154,7 +166,10 @@ impl DiffFormat {
             | DiffFormat::Stat(_)
             | DiffFormat::Types
             | DiffFormat::NameOnly => true,
-            DiffFormat::Git(_) | DiffFormat::ColorWords(_) | DiffFormat::Tool(_) => false,
+            DiffFormat::Git(_)
+            | DiffFormat::ColorWords(_)
+            | DiffFormat::Json(_)
+            | DiffFormat::Tool(_) => false,
         }
     }
 }
@@ -221,6 +236,11 @@ fn diff_formats_from_args(
         options.merge_args(args);
         formats.push(DiffFormat::ColorWords(Box::new(options)));
     }
+    if args.json {
+        let mut options = UnifiedDiffOptions::from_settings(settings)?;
+        options.merge_args(args);
+        formats.push(DiffFormat::Json(Box::new(options)));
+    }
     if let Some(name) = &args.tool {
         let tool = merge_tools::get_external_tool_config(settings, name)?
             .unwrap_or_else(|| ExternalMergeTool::with_program(name));
@@ -262,6 +282,11 @@ fn default_diff_format(
             options.merge_args(args);
             Ok(DiffFormat::ColorWords(Box::new(options)))
         }
+        "json" => {
+            let mut options = UnifiedDiffOptions::from_settings(settings)?;
+            options.merge_args(args);
+            Ok(DiffFormat::Json(Box::new(options)))
+        }
         name => Err(ConfigGetError::Type {
             name: "ui.diff.format".to_owned(),
             error: format!("Invalid diff format: {name}").into(),
@@ -396,6 +421,17 @@ impl<'a> DiffRenderer<'a> {
                         self.conflict_marker_style,
                     )?;
                 }
+                DiffFormat::Json(options) => {
+                    let tree_diff =
+                        from_tree.diff_stream_with_copies(to_tree, matcher, copy_records);
+                    show_json_diff(
+                        formatter,
+                        store,
+                        tree_diff,
+                        options,
+                        self.conflict_marker_style,
+                    )?;
+                }
                 DiffFormat::Tool(tool) => {
                     match tool.diff_invocation_mode {
                         DiffToolMode::FileByFile => {
@@ -470,7 +506,8 @@ impl<'a> DiffRenderer<'a> {
         let to_tree = commit.tree()?;
         let mut copy_records = CopyRecords::default();
         for parent_id in commit.parent_ids() {
-            let records = get_copy_records(self.repo.store(), parent_id, commit.id(), matcher)?;
+            let records =
+                get_copy_records(self.repo.store(), parent_id, commit.id(), &to_tree, matcher)?;
             copy_records.add_records(records)?;
         }
         self.show_diff(
@@ -485,16 +522,31 @@ impl<'a> DiffRenderer<'a> {
     }
 }
 
+/// Returns the copy records between `root` and `head` of which target matches
+/// the `matcher`. The target is matched against its value in `head_tree`, the
+/// tree that the copies are shown in.
 pub fn get_copy_records<'a>(
     store: &'a Store,
     root: &CommitId,
     head: &CommitId,
+    head_tree: &'a MergedTree,
     matcher: &'a dyn Matcher,
 ) -> BackendResult<impl Iterator<Item = BackendResult<CopyRecord>> + use<'a>> {
     // TODO: teach backend about matching path prefixes?
     let stream = store.get_copy_records(None, root, head)?;
     // TODO: test record.source as well? should be AND-ed or OR-ed?
-    Ok(block_on_stream(stream).filter_ok(|record| matcher.matches(&record.target)))
+    Ok(block_on_stream(stream).filter_map(move |record| {
+        let record = match record {
+            Ok(record) => record,
+            Err(err) => return Some(Err(err)),
+        };
+        match head_tree.path_value(&record.target) {
+            Ok(value) => matcher
+                .matches_value(store, &record.target, &value.map(Option::as_ref))
+                .then_some(Ok(record)),
+            Err(err) => Some(Err(err)),
+        }
+    }))
 }
 
 /// How conflicts are processed and rendered in diffs.
@@ -1059,16 +1111,12 @@ fn file_content_for_diff<T>(
     map_resolved: impl FnOnce(BString) -> T,
 ) -> BackendResult<FileContent<T>> {
     // If this is a binary file, don't show the full contents.
-    // Determine whether it's binary by whether the first 8k bytes contain a null
-    // character; this is the same heuristic used by git as of writing: https://github.com/git/git/blob/eea0e59ffbed6e33d171ace5be13cde9faa41639/xdiff-interface.c#L192-L198
-    const PEEK_SIZE: usize = 8000;
     // TODO: currently we look at the whole file, even though for binary files we
     // only need to know the file size. To change that we'd have to extend all
     // the data backends to support getting the length.
     let contents = BString::new(file.read_all(path)?);
-    let start = &contents[..PEEK_SIZE.min(contents.len())];
     Ok(FileContent {
-        is_binary: start.contains(&b'\0'),
+        is_binary: files::is_binary_content(&contents),
         contents: map_resolved(contents),
     })
 }
@@ -1426,12 +1474,12 @@ fn git_diff_part(
             };
         }
         MaterializedTreeValue::GitSubmodule(id) => {
-            // TODO: What should we actually do here?
-            mode = "040000";
+            // Same representation as `git diff` without `--submodule`
+            mode = "160000";
             hash = id.hex();
             content = FileContent {
                 is_binary: false,
-                contents: BString::default(),
+                contents: format!("Subproject commit {hash}\n").into(),
             };
         }
         MaterializedTreeValue::FileConflict(file) => {
@@ -1650,26 +1698,26 @@ where
     [left_lines, right_lines]
 }
 
+// "If the chunk size is 0, the first number is one lower than one would
+// expect." - https://www.artima.com/weblogs/viewpost.jsp?thread=164293
+//
+// The POSIX spec also states that "the ending line number of an empty range
+// shall be the number of the preceding line, or 0 if the range is at the
+// start of the file."
+// - https://pubs.opengroup.org/onlinepubs/9799919799/utilities/diff.html
+fn to_line_number(range: Range<usize>) -> usize {
+    if range.is_empty() {
+        range.start
+    } else {
+        range.start + 1
+    }
+}
+
 fn show_unified_diff_hunks(
     formatter: &mut dyn Formatter,
     contents: [&BStr; 2],
     options: &UnifiedDiffOptions,
 ) -> io::Result<()> {
-    // "If the chunk size is 0, the first number is one lower than one would
-    // expect." - https://www.artima.com/weblogs/viewpost.jsp?thread=164293
-    //
-    // The POSIX spec also states that "the ending line number of an empty range
-    // shall be the number of the preceding line, or 0 if the range is at the
-    // start of the file."
-    // - https://pubs.opengroup.org/onlinepubs/9799919799/utilities/diff.html
-    fn to_line_number(range: Range<usize>) -> usize {
-        if range.is_empty() {
-            range.start
-        } else {
-            range.start + 1
-        }
-    }
-
     for hunk in unified_diff_hunks(contents, options) {
         writeln!(
             formatter.labeled("hunk_header"),
@@ -1809,6 +1857,292 @@ pub fn show_git_diff(
     .block_on()
 }
 
+/// Diff between two trees, in the format of `jj diff --json`.
+#[derive(Serialize)]
+struct DiffJson {
+    files: Vec<FileDiffJson>,
+}
+
+#[derive(Serialize)]
+struct FileDiffJson {
+    path: String,
+    status: &'static str,
+    /// Source path if the file was copied or renamed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    source: Option<String>,
+    /// `None` if the file is absent.
+    old: Option<FileSideJson>,
+    new: Option<FileSideJson>,
+    /// Empty if either side is binary or isn't valid UTF-8.
+    hunks: Vec<DiffHunkJson>,
+}
+
+#[derive(Serialize)]
+struct FileSideJson {
+    #[serde(rename = "type")]
+    file_type: &'static str,
+    /// Octal mode string or `None` if the executable bit is ambiguous.
+    mode: Option<&'static str>,
+    /// `None` if the file is conflicted.
+    id: Option<String>,
+    /// Also set for text that isn't valid UTF-8, which JSON strings can't
+    /// represent.
+    binary: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    conflict: Option<ConflictJson>,
+}
+
+#[derive(Serialize)]
+struct ConflictJson {
+    removes: Vec<Option<ConflictSideJson>>,
+    adds: Vec<Option<ConflictSideJson>>,
+}
+
+impl ConflictJson {
+    fn new<T>(merge: &Merge<T>, mut to_side: impl FnMut(&T) -> Option<ConflictSideJson>) -> Self {
+        ConflictJson {
+            removes: merge.removes().map(&mut to_side).collect(),
+            adds: merge.adds().map(&mut to_side).collect(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct ConflictSideJson {
+    #[serde(rename = "type")]
+    file_type: &'static str,
+    id: String,
+    /// Text of a conflicted file. `None` if the side is binary or isn't valid
+    /// UTF-8.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    contents: Option<String>,
+}
+
+#[derive(Serialize)]
+struct DiffHunkJson {
+    old_start: usize,
+    old_lines: usize,
+    new_start: usize,
+    new_lines: usize,
+    lines: Vec<DiffLineJson>,
+}
+
+#[derive(Serialize)]
+struct DiffLineJson {
+    #[serde(rename = "type")]
+    line_type: &'static str,
+    content: String,
+}
+
+fn json_diff_side(
+    path: &RepoPath,
+    value: MaterializedTreeValue,
+    conflict_marker_style: ConflictMarkerStyle,
+) -> Result<(Option<FileSideJson>, FileContent<BString>), DiffRenderError> {
+    let mut side;
+    let mut content: FileContent<BString>;
+    match value {
+        MaterializedTreeValue::Absent => {
+            let content = FileContent {
+                is_binary: false,
+                contents: BString::default(),
+            };
+            return Ok((None, content));
+        }
+        MaterializedTreeValue::AccessDenied(err) => {
+            return Err(DiffRenderError::AccessDenied {
+                path: path.as_internal_file_string().to_owned(),
+                source: err,
+            });
+        }
+        MaterializedTreeValue::File(mut file) => {
+            content = file_content_for_diff(path, &mut file, |content| content)?;
+            side = FileSideJson {
+                file_type: "file",
+                mode: Some(if file.executable { "100755" } else { "100644" }),
+                id: Some(file.id.hex()),
+                binary: content.is_binary,
+                conflict: None,
+            };
+        }
+        MaterializedTreeValue::Symlink { id, target } => {
+            side = FileSideJson {
+                file_type: "symlink",
+                mode: Some("120000"),
+                id: Some(id.hex()),
+                binary: false,
+                conflict: None,
+            };
+            content = FileContent {
+                is_binary: false,
+                contents: target.into(),
+            };
+        }
+        MaterializedTreeValue::GitSubmodule(id) => {
+            side = FileSideJson {
+                file_type: "git-submodule",
+                mode: Some("160000"),
+                id: Some(id.hex()),
+                binary: false,
+                conflict: None,
+            };
+            content = FileContent {
+                is_binary: false,
+                contents: format!("Subproject commit {}\n", id.hex()).into(),
+            };
+        }
+        MaterializedTreeValue::FileConflict(file) => {
+            let sides = Merge::from_vec(file.ids.iter().zip(file.contents.iter()).collect_vec());
+            let conflict = ConflictJson::new(&sides, |(id, contents)| {
+                let id = id.as_ref()?;
+                let contents = std::str::from_utf8(contents)
+                    .ok()
+                    .filter(|contents| !files::is_binary_content(contents.as_bytes()))
+                    .map(str::to_owned);
+                Some(ConflictSideJson {
+                    file_type: "file",
+                    id: id.hex(),
+                    contents,
+                })
+            });
+            let is_binary = file
+                .contents
+                .iter()
+                .any(|side| files::is_binary_content(side));
+            side = FileSideJson {
+                file_type: "conflict",
+                mode: file
+                    .executable
+                    .map(|executable| if executable { "100755" } else { "100644" }),
+                id: None,
+                binary: is_binary,
+                conflict: Some(conflict),
+            };
+            content = FileContent {
+                is_binary,
+                contents: materialize_merge_result_to_bytes(&file.contents, conflict_marker_style),
+            };
+        }
+        MaterializedTreeValue::OtherConflict { id } => {
+            let conflict = ConflictJson::new(&id, |value| {
+                let (file_type, id) = match value.as_ref()? {
+                    TreeValue::File { id, .. } => ("file", id.hex()),
+                    TreeValue::Symlink(id) => ("symlink", id.hex()),
+                    TreeValue::Tree(id) => ("tree", id.hex()),
+                    TreeValue::GitSubmodule(id) => ("git-submodule", id.hex()),
+                    TreeValue::Conflict(id) => ("conflict", id.hex()),
+                };
+                Some(ConflictSideJson {
+                    file_type,
+                    id,
+                    contents: None,
+                })
+            });
+            side = FileSideJson {
+                file_type: "conflict",
+                mode: None,
+                id: None,
+                binary: false,
+                conflict: Some(conflict),
+            };
+            content = FileContent {
+                is_binary: false,
+                contents: id.describe().into(),
+            };
+        }
+        MaterializedTreeValue::Tree(_) => {
+            panic!("Unexpected tree in diff at path {path:?}");
+        }
+    }
+    if !content.is_binary && std::str::from_utf8(&content.contents).is_err() {
+        content.is_binary = true;
+        side.binary = true;
+    }
+    Ok((Some(side), content))
+}
+
+fn json_diff_hunks(contents: [&BStr; 2], options: &UnifiedDiffOptions) -> Vec<DiffHunkJson> {
+    unified_diff_hunks(contents, options)
+        .into_iter()
+        .map(|hunk| {
+            let lines = hunk
+                .lines
+                .iter()
+                .map(|(line_type, tokens)| {
+                    let line_type = match line_type {
+                        DiffLineType::Context => "context",
+                        DiffLineType::Removed => "removed",
+                        DiffLineType::Added => "added",
+                    };
+                    let content: BString = tokens
+                        .iter()
+                        .flat_map(|(_, content)| content.iter().copied())
+                        .collect();
+                    DiffLineJson {
+                        line_type,
+                        content: content.to_string(),
+                    }
+                })
+                .collect();
+            DiffHunkJson {
+                old_start: to_line_number(hunk.left_line_range.clone()),
+                old_lines: hunk.left_line_range.len(),
+                new_start: to_line_number(hunk.right_line_range.clone()),
+                new_lines: hunk.right_line_range.len(),
+                lines,
+            }
+        })
+        .collect()
+}
+
+pub fn show_json_diff(
+    formatter: &mut dyn Formatter,
+    store: &Store,
+    tree_diff: BoxStream<CopiesTreeDiffEntry>,
+    options: &UnifiedDiffOptions,
+    conflict_marker_style: ConflictMarkerStyle,
+) -> Result<(), DiffRenderError> {
+    let diff_stream = materialized_diff_stream(store, tree_diff);
+    let files = diff_stream
+        .map(|MaterializedTreeDiffEntry { path, values }| {
+            let (left_value, right_value) = values?;
+            let status = match (&left_value, &right_value) {
+                _ if path.copy_operation() == Some(CopyOperation::Copy) => "copied",
+                _ if path.copy_operation() == Some(CopyOperation::Rename) => "renamed",
+                (MaterializedTreeValue::Absent, _) => "added",
+                (_, MaterializedTreeValue::Absent) => "removed",
+                _ => "modified",
+            };
+            let source = path
+                .copy_operation()
+                .map(|_| path.source().as_internal_file_string().to_owned());
+            let (old, left_content) =
+                json_diff_side(path.source(), left_value, conflict_marker_style)?;
+            let (new, right_content) =
+                json_diff_side(path.target(), right_value, conflict_marker_style)?;
+            let hunks = if left_content.is_binary || right_content.is_binary {
+                vec![]
+            } else {
+                json_diff_hunks(
+                    [&left_content.contents, &right_content.contents].map(BStr::new),
+                    options,
+                )
+            };
+            Ok::<_, DiffRenderError>(FileDiffJson {
+                path: path.target().as_internal_file_string().to_owned(),
+                status,
+                source,
+                old,
+                new,
+                hunks,
+            })
+        })
+        .try_collect()
+        .block_on()?;
+    write_json_record(formatter, OutputFormat::Json, &DiffJson { files })?;
+    Ok(())
+}
+
 #[instrument(skip_all)]
 pub fn show_diff_summary(
     formatter: &mut dyn Formatter,
@@ -30,6 +30,7 @@ use crossterm::terminal::Clear;
 use crossterm::terminal::ClearType;
 use indoc::writedoc;
 use itertools::Itertools as _;
+use jj_lib::backend::CommitId;
 #[cfg(feature = "git2")]
 use jj_lib::config::ConfigGetResultExt as _;
 use jj_lib::fmt_util::binary_prefix;
@@ -38,8 +39,11 @@ use jj_lib::git::FailedRefExportReason;
 use jj_lib::git::GitExportStats;
 use jj_lib::git::GitImportStats;
 use jj_lib::git::GitRefKind;
+use jj_lib::git_lfs;
+use jj_lib::git_lfs::LfsObjectStore;
 use jj_lib::op_store::RefTarget;
 use jj_lib::op_store::RemoteRef;
+use jj_lib::ref_name::RemoteName;
 use jj_lib::ref_name::RemoteRefSymbol;
 use jj_lib::repo::ReadonlyRepo;
 use jj_lib::repo::Repo;
@@ -613,6 +617,71 @@ export or their "parent" bookmarks."#,
             )?;
         }
     }
+    if !stats.failed_tags.is_empty() {
+        writeln!(ui.warning_default(), "Failed to export some tags:")?;
+        let mut formatter = ui.stderr_formatter();
+        for (symbol, reason) in &stats.failed_tags {
+            write!(formatter, "  ")?;
+            write!(
+                formatter.labeled("tag"),
+                "{name}",
+                name = symbol.name.as_symbol()
+            )?;
+            for err in iter::successors(Some(reason as &dyn error::Error), |err| err.source()) {
+                write!(formatter, ": {err}")?;
+            }
+            writeln!(formatter)?;
+        }
+    }
+    Ok(())
+}
+
+/// Downloads the Git LFS objects referenced by commits fetched from the
+/// `remotes`. Only remotes in the local file system are supported.
+pub fn fetch_lfs_objects(
+    ui: &Ui,
+    base_repo: &ReadonlyRepo,
+    repo: &dyn Repo,
+    remotes: &[&RemoteName],
+) -> Result<(), CommandError> {
+    let local_store = LfsObjectStore::for_store(repo.store())?;
+    let bases = base_repo.view().heads().iter().cloned().collect_vec();
+    let mut num_copied = 0;
+    for &remote in remotes {
+        let Some(remote_store) = git_lfs::remote_lfs_store(repo.store(), remote)? else {
+            continue;
+        };
+        let heads = repo
+            .view()
+            .remote_bookmarks(remote)
+            .flat_map(|(_, remote_ref)| remote_ref.target.added_ids())
+            .cloned()
+            .collect_vec();
+        num_copied += git_lfs::copy_lfs_objects(repo, &heads, &bases, &remote_store, &local_store)?;
+    }
+    if num_copied > 0 {
+        writeln!(ui.status(), "Downloaded {num_copied} Git LFS objects")?;
+    }
+    Ok(())
+}
+
+/// Uploads the Git LFS objects referenced by commits in `::heads ~ ::bases`
+/// to the `remote`. Only remotes in the local file system are supported.
+pub fn push_lfs_objects(
+    ui: &Ui,
+    repo: &dyn Repo,
+    remote: &RemoteName,
+    heads: &[CommitId],
+    bases: &[CommitId],
+) -> Result<(), CommandError> {
+    let Some(remote_store) = git_lfs::remote_lfs_store(repo.store(), remote)? else {
+        return Ok(());
+    };
+    let local_store = LfsObjectStore::for_store(repo.store())?;
+    let num_copied = git_lfs::copy_lfs_objects(repo, heads, bases, &local_store, &remote_store)?;
+    if num_copied > 0 {
+        writeln!(ui.status(), "Uploaded {num_copied} Git LFS objects")?;
+    }
     Ok(())
 }
 
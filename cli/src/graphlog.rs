@@ -36,6 +36,30 @@ pub trait GraphLog<K: Clone + Eq + Hash> {
     fn width(&self, id: &K, edges: &[GraphEdge<K>]) -> usize;
 }
 
+/// Edge of a graph node in JSON output.
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct GraphEdgeJson {
+    /// Id of the target node.
+    pub target: String,
+    /// One of `"direct"`, `"indirect"`, or `"missing"`.
+    #[serde(rename = "type")]
+    pub edge_type: &'static str,
+}
+
+impl GraphEdgeJson {
+    pub fn new<K>(edge: &GraphEdge<K>, format_id: impl FnOnce(&K) -> String) -> Self {
+        let edge_type = match edge.edge_type {
+            GraphEdgeType::Direct => "direct",
+            GraphEdgeType::Indirect => "indirect",
+            GraphEdgeType::Missing => "missing",
+        };
+        GraphEdgeJson {
+            target: format_id(&edge.target),
+            edge_type,
+        }
+    }
+}
+
 pub struct SaplingGraphLog<'writer, R> {
     renderer: R,
     writer: &'writer mut dyn Write,
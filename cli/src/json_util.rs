@@ -0,0 +1,200 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Machine-readable JSON output of commands.
+
+use std::io;
+use std::io::Write;
+
+use chrono::SecondsFormat;
+use jj_lib::backend::Signature;
+use jj_lib::backend::Timestamp;
+use serde::Serialize;
+
+use crate::time_util::datetime_from_timestamp;
+use crate::time_util::TimestampOutOfRange;
+
+/// Version of the schema of JSON records.
+///
+/// It's incremented when a field is removed or its meaning changes. Fields may
+/// be added without changing the version.
+pub const JSON_SCHEMA_VERSION: u32 = 1;
+
+/// Format of machine-readable output.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Eq, PartialEq)]
+pub enum OutputFormat {
+    /// JSON array of records
+    Json,
+    /// One JSON record per line
+    Ndjson,
+}
+
+#[derive(Serialize)]
+struct VersionedRecord<'a, T> {
+    schema_version: u32,
+    #[serde(flatten)]
+    record: &'a T,
+}
+
+fn to_versioned_record<T>(record: &T) -> VersionedRecord<'_, T> {
+    VersionedRecord {
+        schema_version: JSON_SCHEMA_VERSION,
+        record,
+    }
+}
+
+/// Writes records as soon as they are produced, so the output of a long
+/// command can be consumed incrementally.
+pub struct JsonRecordWriter<W> {
+    writer: W,
+    format: OutputFormat,
+    count: usize,
+}
+
+impl<W: Write> JsonRecordWriter<W> {
+    pub fn new(writer: W, format: OutputFormat) -> Self {
+        JsonRecordWriter {
+            writer,
+            format,
+            count: 0,
+        }
+    }
+
+    pub fn write(&mut self, record: &impl Serialize) -> io::Result<()> {
+        let record = to_versioned_record(record);
+        match self.format {
+            OutputFormat::Json => {
+                let separator = if self.count == 0 { "[\n" } else { ",\n" };
+                self.writer.write_all(separator.as_bytes())?;
+                let text = serde_json::to_string_pretty(&record)?;
+                // Strings are escaped, so each line belongs to the structure.
+                for (i, line) in text.lines().enumerate() {
+                    if i > 0 {
+                        writeln!(self.writer)?;
+                    }
+                    write!(self.writer, "  {line}")?;
+                }
+            }
+            OutputFormat::Ndjson => {
+                serde_json::to_writer(&mut self.writer, &record)?;
+                writeln!(self.writer)?;
+            }
+        }
+        self.count += 1;
+        Ok(())
+    }
+
+    pub fn finish(mut self) -> io::Result<()> {
+        match self.format {
+            OutputFormat::Json if self.count == 0 => writeln!(self.writer, "[]")?,
+            OutputFormat::Json => writeln!(self.writer, "\n]")?,
+            OutputFormat::Ndjson => {}
+        }
+        self.writer.flush()
+    }
+}
+
+/// Writes a single record, which isn't wrapped in an array.
+pub fn write_json_record(
+    mut writer: impl Write,
+    format: OutputFormat,
+    record: &impl Serialize,
+) -> io::Result<()> {
+    let record = to_versioned_record(record);
+    match format {
+        OutputFormat::Json => serde_json::to_writer_pretty(&mut writer, &record)?,
+        OutputFormat::Ndjson => serde_json::to_writer(&mut writer, &record)?,
+    }
+    writeln!(writer)
+}
+
+/// Formats the timestamp in RFC 3339 format, preserving its time zone.
+pub fn timestamp_to_json(timestamp: &Timestamp) -> Result<String, TimestampOutOfRange> {
+    let datetime = datetime_from_timestamp(timestamp)?;
+    Ok(datetime.to_rfc3339_opts(SecondsFormat::Millis, false))
+}
+
+/// Author or committer of a commit.
+#[derive(Clone, Debug, Serialize)]
+pub struct SignatureJson {
+    pub name: String,
+    pub email: String,
+    pub timestamp: String,
+}
+
+impl SignatureJson {
+    pub fn new(signature: &Signature) -> Result<Self, TimestampOutOfRange> {
+        Ok(SignatureJson {
+            name: signature.name.clone(),
+            email: signature.email.clone(),
+            timestamp: timestamp_to_json(&signature.timestamp)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn write_records(format: OutputFormat, records: &[serde_json::Value]) -> String {
+        let mut output = vec![];
+        let mut writer = JsonRecordWriter::new(&mut output, format);
+        for record in records {
+            writer.write(record).unwrap();
+        }
+        writer.finish().unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn test_json_record_writer() {
+        let records = [json!({"a": "x\ny"}), json!({"b": [1, 2]})];
+        insta::assert_snapshot!(write_records(OutputFormat::Json, &records), @r#"
+        [
+          {
+            "schema_version": 1,
+            "a": "x\ny"
+          },
+          {
+            "schema_version": 1,
+            "b": [
+              1,
+              2
+            ]
+          }
+        ]
+        "#);
+        insta::assert_snapshot!(write_records(OutputFormat::Ndjson, &records), @r#"
+        {"schema_version":1,"a":"x\ny"}
+        {"schema_version":1,"b":[1,2]}
+        "#);
+
+        insta::assert_snapshot!(write_records(OutputFormat::Json, &[]), @"[]");
+        insta::assert_snapshot!(write_records(OutputFormat::Ndjson, &[]), @"");
+    }
+
+    #[test]
+    fn test_timestamp_to_json() {
+        let timestamp = Timestamp {
+            timestamp: jj_lib::backend::MillisSinceEpoch(1_000_000_001_234),
+            tz_offset: -420,
+        };
+        assert_eq!(
+            timestamp_to_json(&timestamp).unwrap(),
+            "2001-09-08T18:46:41.234-07:00"
+        );
+    }
+}
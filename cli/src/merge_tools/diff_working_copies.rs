@@ -8,13 +8,13 @@ use std::sync::Arc;
 
 use futures::StreamExt as _;
 use jj_lib::backend::MergedTreeId;
-use jj_lib::conflicts::ConflictMarkerStyle;
 use jj_lib::fsmonitor::FsmonitorSettings;
 use jj_lib::gitignore::GitIgnoreFile;
 use jj_lib::local_working_copy::TreeState;
 use jj_lib::local_working_copy::TreeStateError;
 use jj_lib::matchers::EverythingMatcher;
 use jj_lib::matchers::Matcher;
+use jj_lib::matchers::NothingMatcher;
 use jj_lib::merged_tree::MergedTree;
 use jj_lib::merged_tree::TreeDiffEntry;
 use jj_lib::repo_path::RepoPathBuf;
@@ -281,7 +281,7 @@ diff editing in mind and be a little inaccurate.
     pub fn snapshot_results(
         self,
         base_ignores: Arc<GitIgnoreFile>,
-        conflict_marker_style: ConflictMarkerStyle,
+        options: CheckoutOptions,
     ) -> Result<MergedTreeId, DiffEditError> {
         if let Some(path) = self.instructions_path_to_cleanup {
             std::fs::remove_file(path).ok();
@@ -298,7 +298,12 @@ diff editing in mind and be a little inaccurate.
             progress: None,
             start_tracking_matcher: &EverythingMatcher,
             max_new_file_size: u64::MAX,
-            conflict_marker_style,
+            new_file_size_limits: &[],
+            ignore_by_default_matcher: &NothingMatcher,
+            untrack_binaries: false,
+            force_tracking_matcher: &NothingMatcher,
+            conflict_marker_style: options.conflict_marker_style,
+            conflict_marker_style_overrides: options.conflict_marker_style_overrides,
         })?;
         Ok(output_tree_state.current_tree_id().clone())
     }
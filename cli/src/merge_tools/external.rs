@@ -14,6 +14,7 @@ use jj_lib::conflicts;
 use jj_lib::conflicts::choose_materialized_conflict_marker_len;
 use jj_lib::conflicts::materialize_merge_result_to_bytes_with_marker_len;
 use jj_lib::conflicts::ConflictMarkerStyle;
+use jj_lib::conflicts::ConflictMarkerStyleOverrides;
 use jj_lib::conflicts::MIN_CONFLICT_MARKER_LEN;
 use jj_lib::gitignore::GitIgnoreFile;
 use jj_lib::matchers::Matcher;
@@ -374,13 +375,16 @@ pub fn edit_diff_external(
     matcher: &dyn Matcher,
     instructions: Option<&str>,
     base_ignores: Arc<GitIgnoreFile>,
-    default_conflict_marker_style: ConflictMarkerStyle,
+    default_options: &CheckoutOptions,
 ) -> Result<MergedTreeId, DiffEditError> {
-    let conflict_marker_style = editor
-        .conflict_marker_style
-        .unwrap_or(default_conflict_marker_style);
-    let options = CheckoutOptions {
-        conflict_marker_style,
+    // The style configured for the tool applies to all files since the tool
+    // may only understand that style.
+    let options = match editor.conflict_marker_style {
+        Some(conflict_marker_style) => CheckoutOptions {
+            conflict_marker_style,
+            conflict_marker_style_overrides: ConflictMarkerStyleOverrides::default(),
+        },
+        None => default_options.clone(),
     };
 
     let got_output_field = find_all_variables(&editor.edit_args).contains(&"output");
@@ -411,7 +415,7 @@ pub fn edit_diff_external(
         }));
     }
 
-    diffedit_wc.snapshot_results(base_ignores, options.conflict_marker_style)
+    diffedit_wc.snapshot_results(base_ignores, options)
 }
 
 /// Generates textual diff by the specified `tool` and writes into `writer`.
@@ -429,6 +433,7 @@ pub fn generate_diff(
         .unwrap_or(default_conflict_marker_style);
     let options = CheckoutOptions {
         conflict_marker_style,
+        conflict_marker_style_overrides: ConflictMarkerStyleOverrides::default(),
     };
     let store = left_tree.store();
     let diff_wc = check_out_trees(store, left_tree, right_tree, matcher, None, &options)?;
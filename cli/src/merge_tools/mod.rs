@@ -27,6 +27,7 @@ use jj_lib::config::ConfigGetResultExt as _;
 use jj_lib::config::ConfigNamePathBuf;
 use jj_lib::conflicts::try_materialize_file_conflict_value;
 use jj_lib::conflicts::ConflictMarkerStyle;
+use jj_lib::conflicts::ConflictMarkerStyleOverrides;
 use jj_lib::conflicts::MaterializedFileConflictValue;
 use jj_lib::gitignore::GitIgnoreFile;
 use jj_lib::matchers::Matcher;
@@ -39,6 +40,7 @@ use jj_lib::repo_path::RepoPath;
 use jj_lib::repo_path::RepoPathBuf;
 use jj_lib::repo_path::RepoPathUiConverter;
 use jj_lib::settings::UserSettings;
+use jj_lib::working_copy::CheckoutOptions;
 use jj_lib::working_copy::SnapshotError;
 use pollster::FutureExt as _;
 use thiserror::Error;
@@ -229,6 +231,7 @@ pub struct DiffEditor {
     base_ignores: Arc<GitIgnoreFile>,
     use_instructions: bool,
     conflict_marker_style: ConflictMarkerStyle,
+    conflict_marker_style_overrides: ConflictMarkerStyleOverrides,
 }
 
 impl DiffEditor {
@@ -239,10 +242,17 @@ impl DiffEditor {
         settings: &UserSettings,
         base_ignores: Arc<GitIgnoreFile>,
         conflict_marker_style: ConflictMarkerStyle,
+        conflict_marker_style_overrides: ConflictMarkerStyleOverrides,
     ) -> Result<Self, MergeToolConfigError> {
         let tool = DiffTool::get_tool_config(settings, name)?
             .unwrap_or_else(|| DiffTool::external(ExternalMergeTool::with_program(name)));
-        Self::new_inner(tool, settings, base_ignores, conflict_marker_style)
+        Self::new_inner(
+            tool,
+            settings,
+            base_ignores,
+            conflict_marker_style,
+            conflict_marker_style_overrides,
+        )
     }
 
     /// Loads the default diff editor from the settings.
@@ -251,6 +261,7 @@ impl DiffEditor {
         settings: &UserSettings,
         base_ignores: Arc<GitIgnoreFile>,
         conflict_marker_style: ConflictMarkerStyle,
+        conflict_marker_style_overrides: ConflictMarkerStyleOverrides,
     ) -> Result<Self, MergeToolConfigError> {
         let args = editor_args_from_settings(ui, settings, "ui.diff-editor")?;
         let tool = if let CommandNameAndArgs::String(name) = &args {
@@ -259,7 +270,13 @@ impl DiffEditor {
             None
         }
         .unwrap_or_else(|| DiffTool::external(ExternalMergeTool::with_edit_args(&args)));
-        Self::new_inner(tool, settings, base_ignores, conflict_marker_style)
+        Self::new_inner(
+            tool,
+            settings,
+            base_ignores,
+            conflict_marker_style,
+            conflict_marker_style_overrides,
+        )
     }
 
     fn new_inner(
@@ -267,12 +284,14 @@ impl DiffEditor {
         settings: &UserSettings,
         base_ignores: Arc<GitIgnoreFile>,
         conflict_marker_style: ConflictMarkerStyle,
+        conflict_marker_style_overrides: ConflictMarkerStyleOverrides,
     ) -> Result<Self, MergeToolConfigError> {
         Ok(DiffEditor {
             tool,
             base_ignores,
             use_instructions: settings.get_bool("ui.diff-instructions")?,
             conflict_marker_style,
+            conflict_marker_style_overrides,
         })
     }
 
@@ -307,7 +326,12 @@ impl DiffEditor {
                     matcher,
                     instructions.as_deref(),
                     self.base_ignores.clone(),
-                    self.conflict_marker_style,
+                    &CheckoutOptions {
+                        conflict_marker_style: self.conflict_marker_style,
+                        conflict_marker_style_overrides: self
+                            .conflict_marker_style_overrides
+                            .clone(),
+                    },
                 )
             }
         }
@@ -497,6 +521,7 @@ mod tests {
                 &settings,
                 GitIgnoreFile::empty(),
                 ConflictMarkerStyle::Diff,
+                ConflictMarkerStyleOverrides::default(),
             )
             .map(|editor| editor.tool)
         };
@@ -572,6 +597,7 @@ mod tests {
                 &settings,
                 GitIgnoreFile::empty(),
                 ConflictMarkerStyle::Diff,
+                ConflictMarkerStyleOverrides::default(),
             )
             .map(|editor| editor.tool)
         };
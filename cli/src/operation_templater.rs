@@ -14,6 +14,7 @@
 
 use std::any::Any;
 use std::cmp::Ordering;
+use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::io;
 
@@ -24,7 +25,9 @@ use jj_lib::op_store::OperationId;
 use jj_lib::operation::Operation;
 use jj_lib::repo::RepoLoader;
 use jj_lib::settings::UserSettings;
+use serde::Serialize;
 
+use crate::json_util::timestamp_to_json;
 use crate::template_builder;
 use crate::template_builder::merge_fn_map;
 use crate::template_builder::BuildContext;
@@ -41,6 +44,7 @@ use crate::templater::PlainTextFormattedProperty;
 use crate::templater::Template;
 use crate::templater::TemplateFormatter;
 use crate::templater::TemplateProperty;
+use crate::templater::TemplatePropertyError;
 use crate::templater::TemplatePropertyExt as _;
 use crate::templater::TimestampRange;
 
@@ -136,6 +140,35 @@ impl OperationTemplateLanguage {
         self.cache_extensions.get::<T>()
     }
 
+    /// Converts the operation to a record of JSON output.
+    pub fn operation_to_json(
+        &self,
+        op: &Operation,
+    ) -> Result<OperationJson, TemplatePropertyError> {
+        let metadata = op.metadata();
+        Ok(OperationJson {
+            id: op.id().hex(),
+            parents: op.parent_ids().iter().map(|id| id.hex()).collect(),
+            description: metadata.description.clone(),
+            tags: metadata
+                .tags
+                .iter()
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect(),
+            time: TimestampRangeJson {
+                start: timestamp_to_json(&metadata.start_time)?,
+                end: timestamp_to_json(&metadata.end_time)?,
+            },
+            user: OperationUserJson {
+                name: metadata.username.clone(),
+                hostname: metadata.hostname.clone(),
+            },
+            snapshot: metadata.is_snapshot,
+            current_operation: Some(op.id()) == self.current_op_id.as_ref(),
+            root: op.id() == self.repo_loader.op_store().root_operation_id(),
+        })
+    }
+
     pub fn wrap_operation(
         property: impl TemplateProperty<Output = Operation> + 'static,
     ) -> OperationTemplatePropertyKind {
@@ -303,6 +336,7 @@ fn builtin_operation_methods() -> OperationTemplateBuildMethodFnMap<Operation> {
                 op.metadata()
                     .tags
                     .iter()
+                    .sorted()
                     .map(|(key, value)| format!("{key}: {value}"))
                     .join("\n")
             });
@@ -386,3 +420,32 @@ fn builtin_operation_id_methods() -> OperationTemplateBuildMethodFnMap<Operation
     );
     map
 }
+
+/// Operation record of JSON output.
+///
+/// The fields are the values of the operation keywords which don't take
+/// arguments.
+#[derive(Clone, Debug, Serialize)]
+pub struct OperationJson {
+    pub id: String,
+    pub parents: Vec<String>,
+    pub description: String,
+    pub tags: BTreeMap<String, String>,
+    pub time: TimestampRangeJson,
+    pub user: OperationUserJson,
+    pub snapshot: bool,
+    pub current_operation: bool,
+    pub root: bool,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct TimestampRangeJson {
+    pub start: String,
+    pub end: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct OperationUserJson {
+    pub name: String,
+    pub hostname: String,
+}
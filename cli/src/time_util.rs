@@ -50,7 +50,7 @@ impl<'a> FormattingItems<'a> {
 #[error("Out-of-range date")]
 pub struct TimestampOutOfRange;
 
-fn datetime_from_timestamp(
+pub fn datetime_from_timestamp(
     context: &Timestamp,
 ) -> Result<DateTime<FixedOffset>, TimestampOutOfRange> {
     let utc = match Utc.timestamp_opt(
@@ -14,6 +14,7 @@ mod test_acls;
 mod test_advance_bookmarks;
 mod test_alias;
 mod test_backout_command;
+mod test_bisect_command;
 mod test_bookmark_command;
 mod test_builtin_aliases;
 mod test_commit_command;
@@ -32,19 +33,23 @@ mod test_edit_command;
 mod test_evolog_command;
 mod test_file_annotate_command;
 mod test_file_chmod_command;
+mod test_file_search_command;
 mod test_file_show_command;
 mod test_file_track_untrack_commands;
 mod test_fix_command;
 mod test_generate_md_cli_help;
+mod test_gerrit_upload;
 mod test_git_clone;
 mod test_git_colocated;
 mod test_git_fetch;
 mod test_git_import_export;
 mod test_git_init;
+mod test_git_lfs;
 mod test_git_private_commits;
 mod test_git_push;
 mod test_git_remotes;
 mod test_git_root;
+mod test_git_submodules;
 mod test_gitignores;
 mod test_global_opts;
 mod test_help_command;
@@ -55,6 +60,7 @@ mod test_new_command;
 mod test_next_prev_commands;
 mod test_operations;
 mod test_parallelize_command;
+mod test_patch_command;
 mod test_rebase_command;
 mod test_repo_change_report;
 mod test_resolve_command;
@@ -62,6 +68,7 @@ mod test_restore_command;
 mod test_revert_command;
 mod test_revset_output;
 mod test_root;
+mod test_run_command;
 mod test_shell_completion;
 mod test_show_command;
 mod test_sign_unsign_commands;
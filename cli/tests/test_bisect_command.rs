@@ -0,0 +1,201 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::common::create_commit_with_files;
+use crate::common::TestEnvironment;
+
+#[test]
+fn test_bisect_manual() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    create_commit_with_files(&work_dir, "a", &[], &[("file", "ok\n")]);
+    create_commit_with_files(&work_dir, "b", &["a"], &[("file", "ok\n")]);
+    create_commit_with_files(&work_dir, "c", &["b"], &[("file", "bad\n")]);
+    create_commit_with_files(&work_dir, "d", &["c"], &[("file", "bad\n")]);
+    create_commit_with_files(&work_dir, "e", &["d"], &[("file", "bad\n")]);
+
+    // Marking without a bisection in progress is an error
+    let output = work_dir.run_jj(["bisect", "good"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: No bisection in progress
+    Hint: Use `jj bisect start` to start one.
+    [EOF]
+    [exit status: 1]
+    ");
+
+    let output = work_dir.run_jj(["bisect", "start", "--good=a", "--bad=e"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Working copy  (@) now at: wqnwkozp 096e584c (empty) (no description set)
+    Parent commit (@-)      : royxmykx 98692e2b c | c
+    Now testing: royxmykx 98692e2b c | c
+    [EOF]
+    ");
+
+    // Can't start another bisection
+    let output = work_dir.run_jj(["bisect", "start", "--good=a", "--bad=e"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: A bisection is already in progress
+    Hint: Use `jj bisect reset` to stop it.
+    [EOF]
+    [exit status: 1]
+    ");
+
+    let output = work_dir.run_jj(["bisect", "bad"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Working copy  (@) now at: kxryzmor eba8aa65 (empty) (no description set)
+    Parent commit (@-)      : zsuskuln df9eb324 b | (empty) b
+    Added 0 files, modified 1 files, removed 0 files
+    Now testing: zsuskuln df9eb324 b | (empty) b
+    [EOF]
+    ");
+
+    // Undo takes back the last decision
+    let output = work_dir.run_jj(["undo"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Undid operation: f876660d2782 (2001-02-03 08:05:21) bisect: mark 98692e2be010 as bad
+    Working copy  (@) now at: wqnwkozp 096e584c (empty) (no description set)
+    Parent commit (@-)      : royxmykx 98692e2b c | c
+    Added 0 files, modified 1 files, removed 0 files
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["bisect", "good"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Working copy  (@) now at: uyznsvlq 9851bb4b (empty) (no description set)
+    Parent commit (@-)      : vruxwmqv a9b033d3 d | (empty) d
+    Now testing: vruxwmqv a9b033d3 d | (empty) d
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["bisect", "bad"]);
+    insta::assert_snapshot!(output, @r"
+    The first bad revision is: vruxwmqv a9b033d3 d | (empty) d
+    [EOF]
+    ");
+
+    // No revision left to test
+    let output = work_dir.run_jj(["bisect", "good"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: The bisection is finished. Specify the revisions to mark explicitly.
+    [EOF]
+    [exit status: 1]
+    ");
+
+    let output = work_dir.run_jj(["bisect", "reset"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Stopped bisecting.
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["bisect", "reset"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: No bisection in progress
+    Hint: Use `jj bisect start` to start one.
+    [EOF]
+    [exit status: 1]
+    ");
+}
+
+#[test]
+fn test_bisect_skip() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    create_commit_with_files(&work_dir, "a", &[], &[]);
+    create_commit_with_files(&work_dir, "b", &["a"], &[]);
+    create_commit_with_files(&work_dir, "c", &["b"], &[]);
+
+    work_dir
+        .run_jj(["bisect", "start", "--good=a", "--bad=c"])
+        .success();
+    let output = work_dir.run_jj(["bisect", "skip"]);
+    insta::assert_snapshot!(output, @r"
+    The first bad revision could be any of these, because some were skipped:
+      royxmykx c5c00ff0 c | (empty) c
+      zsuskuln 22a82dfa b | (empty) b
+    [EOF]
+    ");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_bisect_command() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    create_commit_with_files(&work_dir, "a", &[], &[("file", "ok\n")]);
+    create_commit_with_files(&work_dir, "b", &["a"], &[("file", "ok\n")]);
+    create_commit_with_files(&work_dir, "c", &["b"], &[("file", "ok\n")]);
+    create_commit_with_files(&work_dir, "d", &["c"], &[("file", "bad\n")]);
+    create_commit_with_files(&work_dir, "e", &["d"], &[("file", "bad\n")]);
+
+    let output = work_dir.run_jj([
+        "bisect",
+        "start",
+        "--good=a",
+        "--bad=e",
+        "--command=grep -q ok file",
+    ]);
+    insta::assert_snapshot!(output, @r"
+    The first bad revision is: vruxwmqv 2ae1d59c d | d
+    [EOF]
+    ------- stderr -------
+    Working copy  (@) now at: kmkuslsw f3786faa (empty) (no description set)
+    Parent commit (@-)      : royxmykx e162e037 c | (empty) c
+    Added 0 files, modified 1 files, removed 0 files
+    Now testing: royxmykx e162e037 c | (empty) c
+    Working copy  (@) now at: msksykpx bbd2ca3b (empty) (no description set)
+    Parent commit (@-)      : vruxwmqv 2ae1d59c d | d
+    Added 0 files, modified 1 files, removed 0 files
+    Now testing: vruxwmqv 2ae1d59c d | d
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["op", "log", "-n5", "-Tdescription"]);
+    insta::assert_snapshot!(output, @r"
+    @  bisect: mark 2ae1d59cd73b as bad
+    ○  bisect: mark e162e037e7f4 as good
+    ○  bisect start
+    ○  create bookmark e pointing to commit 67ef57fb23f4f3bbeb7028b98490157f1510ef7b
+    ○  new empty commit
+    [EOF]
+    ");
+
+    // Exit statuses above 127 abort the bisection
+    work_dir.run_jj(["bisect", "reset"]).success();
+    work_dir
+        .run_jj(["bisect", "start", "--good=a", "--bad=e"])
+        .success();
+    let output = work_dir.run_jj(["bisect", "run", "--command=exit 200"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Working copy  (@) now at: nkmrtpmo 3033c0bf (empty) (no description set)
+    Parent commit (@-)      : royxmykx e162e037 c | (empty) c
+    Now testing: royxmykx e162e037 c | (empty) c
+    Error: Command 'exit 200' failed with exit status: 200, aborting bisection
+    [EOF]
+    [exit status: 1]
+    ");
+}
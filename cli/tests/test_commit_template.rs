@@ -1610,6 +1610,74 @@ fn test_signature_templates() {
     ");
 }
 
+#[test]
+fn test_signature_trust() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    test_env.add_config("signing.backend = 'test'");
+    let work_dir = test_env.work_dir("repo");
+
+    // The test backend trusts a key if it is the committer's email
+    work_dir.run_jj(["new", "-m", "unsigned"]).success();
+    work_dir.run_jj(["new", "-m", "untrusted"]).success();
+    work_dir.run_jj(["new", "-m", "trusted"]).success();
+    work_dir
+        .run_jj(["sign", "-r", "@-", "--key", "someone@example.com"])
+        .success();
+    work_dir
+        .run_jj(["sign", "-r", "@", "--key", "test.user@example.com"])
+        .success();
+
+    let template = r#"
+    if(signature,
+      signature.status() ++ " " ++ signature.trust(),
+      "no signature",
+    ) ++ " " ++ description"#;
+    let output = work_dir.run_jj(["log", "-r", "~root()", "-T", template]);
+    insta::assert_snapshot!(output, @r"
+    @  good verified trusted
+    ○  good unknown untrusted
+    ○  no signature unsigned
+    ○  no signature
+    │
+    ~
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["log", "-r", "signed(trust:verified)", "-T", template]);
+    insta::assert_snapshot!(output, @r"
+    @  good verified trusted
+    │
+    ~
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["log", "-r", "signed(trust:unknown)", "-T", template]);
+    insta::assert_snapshot!(output, @r"
+    ○  good unknown untrusted
+    │
+    ~
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["log", "-r", "signed(trust:expired)", "-T", template]);
+    insta::assert_snapshot!(output, @"");
+
+    let output = work_dir.run_jj(["log", "-r", "signed(trust:trusted)"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Failed to parse revset: Invalid signature pattern
+    Caused by:
+    1:  --> 1:8
+      |
+    1 | signed(trust:trusted)
+      |        ^-----------^
+      |
+      = Invalid signature pattern
+    2: Invalid signature trust: trusted
+    [EOF]
+    [exit status: 1]
+    ");
+}
+
 #[test]
 fn test_log_git_format_patch_template() {
     let test_env = TestEnvironment::default();
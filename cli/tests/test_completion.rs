@@ -976,6 +976,7 @@ fn test_config() {
     let output = test_env.complete_fish(["config", "get", "c"]);
     insta::assert_snapshot!(output, @r"
     core.fsmonitor	Whether to use an external filesystem monitor, useful for large repos
+    core.op-heads-store	How new repos record the operation heads. `cas` doesn't rely on file locking, which is useful for repos on network file systems such as NFS
     core.watchman.register-snapshot-trigger	Whether to use triggers to monitor for changes in the background.
     [EOF]
     ");
@@ -985,6 +986,7 @@ fn test_config() {
     colors	Mapping from jj formatter labels to colors
     core
     core.fsmonitor	Whether to use an external filesystem monitor, useful for large repos
+    core.op-heads-store	How new repos record the operation heads. `cas` doesn't rely on file locking, which is useful for repos on network file systems such as NFS
     core.watchman
     core.watchman.register-snapshot-trigger	Whether to use triggers to monitor for changes in the background.
     [EOF]
@@ -993,6 +995,7 @@ fn test_config() {
     let output = test_env.complete_fish(["log", "--config", "c"]);
     insta::assert_snapshot!(output, @r"
     core.fsmonitor=	Whether to use an external filesystem monitor, useful for large repos
+    core.op-heads-store=	How new repos record the operation heads. `cas` doesn't rely on file locking, which is useful for repos on network file systems such as NFS
     core.watchman.register-snapshot-trigger=	Whether to use triggers to monitor for changes in the background.
     [EOF]
     ");
@@ -1002,6 +1005,7 @@ fn test_config() {
     ui.conflict-marker-style=diff
     ui.conflict-marker-style=snapshot
     ui.conflict-marker-style=git
+    ui.conflict-marker-style=zdiff3
     [EOF]
     ");
 
@@ -153,6 +153,55 @@ fn test_debug_reindex() {
     ");
 }
 
+#[test]
+fn test_debug_index_changed_paths() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    work_dir.write_file("file1", "");
+    work_dir.run_jj(["commit", "-m", "add file1"]).success();
+    work_dir.write_file("file2", "");
+    work_dir.run_jj(["commit", "-m", "add file2"]).success();
+    let output = work_dir.run_jj(["debug", "index-changed-paths"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Finished indexing changed paths of 8 commits.
+    [EOF]
+    ");
+
+    // New commits are indexed automatically
+    work_dir.write_file("file1", "modified");
+    work_dir
+        .run_jj(["describe", "-m", "modify file1"])
+        .success();
+    let output = work_dir.run_jj(["log", "-r", "files(file1)", "-T", "description"]);
+    insta::assert_snapshot!(output, @r"
+    @  modify file1
+    ~  (elided revisions)
+    ○  add file1
+    │
+    ~
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["debug", "index"]);
+    insta::assert_snapshot!(filter_index_stats(output), @r"
+    Number of commits: 10
+    Number of merges: 0
+    Max generation number: 3
+    Number of heads: 7
+    Number of changes: 4
+    Stats per level:
+      Level 0:
+        Number of commits: 8
+        Name: [hash]
+      Level 1:
+        Number of commits: 2
+        Name: [hash]
+    Number of commits with changed paths: 10
+    [EOF]
+    ");
+}
+
 #[test]
 fn test_debug_tree() {
     let test_env = TestEnvironment::default();
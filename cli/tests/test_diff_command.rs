@@ -501,6 +501,114 @@ fn test_diff_types() {
     }
 }
 
+#[test]
+fn test_diff_file_value_filesets() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.write_file("text", "text\n");
+    work_dir.write_file("binary", b"bin\0ary\n");
+    work_dir.write_file("large", "x".repeat(2048));
+    work_dir.write_file("script", "#!/bin/sh\n");
+    work_dir.run_jj(["file", "chmod", "x", "script"]).success();
+
+    let output = work_dir.run_jj(["diff", "--summary", "~binary()"]);
+    insta::assert_snapshot!(output, @r"
+    A large
+    A script
+    A text
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["diff", "--summary", "executable() | binary()"]);
+    insta::assert_snapshot!(output, @r"
+    A binary
+    A script
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["diff", "--summary", "file_size('>1K')"]);
+    insta::assert_snapshot!(output, @r"
+    A large
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["diff", "--summary", "file_size('<=10') ~ executable()"]);
+    insta::assert_snapshot!(output, @r"
+    A binary
+    A text
+    [EOF]
+    ");
+
+    // Only the files that still match are shown
+    work_dir.run_jj(["new"]).success();
+    work_dir.write_file("binary", "text now\n");
+    let output = work_dir.run_jj(["diff", "--summary", "binary()"]);
+    insta::assert_snapshot!(output, @r"
+    M binary
+    [EOF]
+    ");
+
+    // Renames are matched by the value of the target
+    work_dir.run_jj(["new"]).success();
+    work_dir.remove_file("text");
+    work_dir.write_file("text2", "text\n");
+    work_dir.run_jj(["file", "chmod", "x", "text2"]).success();
+    work_dir.remove_file("script");
+    work_dir.write_file("script2", "#!/bin/sh\n");
+    work_dir.run_jj(["file", "chmod", "x", "script2"]).success();
+    let output = work_dir.run_jj(["diff", "--summary", "executable()"]);
+    insta::assert_snapshot!(output, @r"
+    R {script => script2}
+    R {text => text2}
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["diff", "--summary", "~executable()"]);
+    insta::assert_snapshot!(output, @r"
+    D text
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["diff", "file_size('>1Q')"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Failed to parse fileset: Invalid file size: unrecognized unit prefix
+    Caused by:  --> 1:11
+      |
+    1 | file_size('>1Q')
+      |           ^---^
+      |
+      = Invalid file size: unrecognized unit prefix
+    [EOF]
+    [exit status: 1]
+    ");
+}
+
+#[test]
+fn test_diff_conflicts_fileset() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    create_commit_with_files(&work_dir, "a", &[], &[("file", "a\n"), ("other", "a\n")]);
+    create_commit_with_files(&work_dir, "b", &[], &[("file", "b\n")]);
+    create_commit_with_files(&work_dir, "merge", &["a", "b"], &[]);
+
+    let output = work_dir.run_jj(["file", "list", "-r=merge", "conflicts()"]);
+    insta::assert_snapshot!(output, @r"
+    file
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["file", "list", "-r=merge", "~conflicts()"]);
+    insta::assert_snapshot!(output, @r"
+    other
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["diff", "--summary", "--from=a", "--to=merge", "conflicts()"]);
+    insta::assert_snapshot!(output, @r"
+    M file
+    [EOF]
+    ");
+}
+
 #[test]
 fn test_diff_name_only() {
     let test_env = TestEnvironment::default();
@@ -3448,6 +3556,342 @@ fn test_diff_binary() {
     ");
 }
 
+#[test]
+fn test_diff_json() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.write_file("file1", "foo\n");
+    work_dir.write_file("file2", "1\n2\n3\n4\n");
+    work_dir.write_file("file3", "1\n");
+    work_dir.run_jj(["new"]).success();
+    work_dir.remove_file("file1");
+    work_dir.write_file("file2", "1\n5\n3\n");
+    work_dir.write_file("file4", "foo\n");
+    work_dir.write_file("file5.png", b"\x89PNG\r\n\x1a\nabcdefg\0");
+    work_dir.run_jj(["file", "chmod", "x", "file3"]).success();
+
+    let output = work_dir.run_jj(["diff", "--json", "--context=1"]);
+    insta::assert_snapshot!(output, @r#"
+    {
+      "schema_version": 1,
+      "files": [
+        {
+          "path": "file2",
+          "status": "modified",
+          "old": {
+            "type": "file",
+            "mode": "100644",
+            "id": "94ebaf900161394059478fd88aec30e59092a1d7",
+            "binary": false
+          },
+          "new": {
+            "type": "file",
+            "mode": "100644",
+            "id": "1ffc51b472f7e610e9904dcd42424f0f569c7693",
+            "binary": false
+          },
+          "hunks": [
+            {
+              "old_start": 1,
+              "old_lines": 4,
+              "new_start": 1,
+              "new_lines": 3,
+              "lines": [
+                {
+                  "type": "context",
+                  "content": "1\n"
+                },
+                {
+                  "type": "removed",
+                  "content": "2\n"
+                },
+                {
+                  "type": "added",
+                  "content": "5\n"
+                },
+                {
+                  "type": "context",
+                  "content": "3\n"
+                },
+                {
+                  "type": "removed",
+                  "content": "4\n"
+                }
+              ]
+            }
+          ]
+        },
+        {
+          "path": "file3",
+          "status": "modified",
+          "old": {
+            "type": "file",
+            "mode": "100644",
+            "id": "d00491fd7e5bb6fa28c517a0bb32b8b506539d4d",
+            "binary": false
+          },
+          "new": {
+            "type": "file",
+            "mode": "100755",
+            "id": "d00491fd7e5bb6fa28c517a0bb32b8b506539d4d",
+            "binary": false
+          },
+          "hunks": []
+        },
+        {
+          "path": "file4",
+          "status": "renamed",
+          "source": "file1",
+          "old": {
+            "type": "file",
+            "mode": "100644",
+            "id": "257cc5642cb1a054f08cc83f2d943e56fd3ebe99",
+            "binary": false
+          },
+          "new": {
+            "type": "file",
+            "mode": "100644",
+            "id": "257cc5642cb1a054f08cc83f2d943e56fd3ebe99",
+            "binary": false
+          },
+          "hunks": []
+        },
+        {
+          "path": "file5.png",
+          "status": "added",
+          "old": null,
+          "new": {
+            "type": "file",
+            "mode": "100644",
+            "id": "2b65b23c22953db3bdeca4a1ec6fc5c5d7c53cb2",
+            "binary": true
+          },
+          "hunks": []
+        }
+      ]
+    }
+    [EOF]
+    "#);
+
+    // Same as `--json`
+    let output = work_dir.run_jj(["diff", "--config=ui.diff.format=json", "file3"]);
+    insta::assert_snapshot!(output, @r#"
+    {
+      "schema_version": 1,
+      "files": [
+        {
+          "path": "file3",
+          "status": "modified",
+          "old": {
+            "type": "file",
+            "mode": "100644",
+            "id": "d00491fd7e5bb6fa28c517a0bb32b8b506539d4d",
+            "binary": false
+          },
+          "new": {
+            "type": "file",
+            "mode": "100755",
+            "id": "d00491fd7e5bb6fa28c517a0bb32b8b506539d4d",
+            "binary": false
+          },
+          "hunks": []
+        }
+      ]
+    }
+    [EOF]
+    "#);
+}
+
+#[test]
+fn test_diff_json_non_utf8() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    create_commit_with_files(&work_dir, "base", &[], &[("file", "base\n")]);
+    create_commit_with_files(&work_dir, "left", &["base"], &[]);
+    work_dir.write_file("file", b"caf\xe9\n");
+    create_commit_with_files(&work_dir, "right", &["base"], &[("file", "right\n")]);
+    work_dir.run_jj(["new", "left", "right"]).success();
+
+    // Latin-1 text can't be represented in JSON strings
+    let output = work_dir.run_jj(["diff", "--json", "--from=base", "--to=left"]);
+    insta::assert_snapshot!(output, @r#"
+    {
+      "schema_version": 1,
+      "files": [
+        {
+          "path": "file",
+          "status": "modified",
+          "old": {
+            "type": "file",
+            "mode": "100644",
+            "id": "df967b96a579e45a18b8251732d16804b2e56a55",
+            "binary": false
+          },
+          "new": {
+            "type": "file",
+            "mode": "100644",
+            "id": "6f83395d973c448cdb70a7b21f7fc8018797acf6",
+            "binary": true
+          },
+          "hunks": []
+        }
+      ]
+    }
+    [EOF]
+    "#);
+    let output = work_dir.run_jj(["diff", "--json", "--from=base", "--to=@"]);
+    insta::assert_snapshot!(output, @r#"
+    {
+      "schema_version": 1,
+      "files": [
+        {
+          "path": "file",
+          "status": "modified",
+          "old": {
+            "type": "file",
+            "mode": "100644",
+            "id": "df967b96a579e45a18b8251732d16804b2e56a55",
+            "binary": false
+          },
+          "new": {
+            "type": "conflict",
+            "mode": "100644",
+            "id": null,
+            "binary": true,
+            "conflict": {
+              "removes": [
+                {
+                  "type": "file",
+                  "id": "df967b96a579e45a18b8251732d16804b2e56a55",
+                  "contents": "base\n"
+                }
+              ],
+              "adds": [
+                {
+                  "type": "file",
+                  "id": "6f83395d973c448cdb70a7b21f7fc8018797acf6"
+                },
+                {
+                  "type": "file",
+                  "id": "c376d892e8b105bd712d06ec5162b5f31ce949c3",
+                  "contents": "right\n"
+                }
+              ]
+            }
+          },
+          "hunks": []
+        }
+      ]
+    }
+    [EOF]
+    "#);
+}
+
+#[test]
+fn test_diff_json_conflict() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    create_commit_with_files(&work_dir, "base", &[], &[("file", "base\n")]);
+    create_commit_with_files(&work_dir, "left", &["base"], &[("file", "left\n")]);
+    create_commit_with_files(&work_dir, "right", &["base"], &[("file", "right\n")]);
+    work_dir.run_jj(["new", "left", "right"]).success();
+
+    let output = work_dir.run_jj(["diff", "--json", "--from=base", "--to=@"]);
+    insta::assert_snapshot!(output, @r#"
+    {
+      "schema_version": 1,
+      "files": [
+        {
+          "path": "file",
+          "status": "modified",
+          "old": {
+            "type": "file",
+            "mode": "100644",
+            "id": "df967b96a579e45a18b8251732d16804b2e56a55",
+            "binary": false
+          },
+          "new": {
+            "type": "conflict",
+            "mode": "100644",
+            "id": null,
+            "binary": false,
+            "conflict": {
+              "removes": [
+                {
+                  "type": "file",
+                  "id": "df967b96a579e45a18b8251732d16804b2e56a55",
+                  "contents": "base\n"
+                }
+              ],
+              "adds": [
+                {
+                  "type": "file",
+                  "id": "45cf141ba67d59203f02a54f03162f3fcef57830",
+                  "contents": "left\n"
+                },
+                {
+                  "type": "file",
+                  "id": "c376d892e8b105bd712d06ec5162b5f31ce949c3",
+                  "contents": "right\n"
+                }
+              ]
+            }
+          },
+          "hunks": [
+            {
+              "old_start": 1,
+              "old_lines": 1,
+              "new_start": 1,
+              "new_lines": 7,
+              "lines": [
+                {
+                  "type": "removed",
+                  "content": "base\n"
+                },
+                {
+                  "type": "added",
+                  "content": "<<<<<<< Conflict 1 of 1\n"
+                },
+                {
+                  "type": "added",
+                  "content": "%%%%%%% Changes from base to side #1\n"
+                },
+                {
+                  "type": "added",
+                  "content": "-base\n"
+                },
+                {
+                  "type": "added",
+                  "content": "+left\n"
+                },
+                {
+                  "type": "added",
+                  "content": "+++++++ Contents of side #2\n"
+                },
+                {
+                  "type": "added",
+                  "content": "right\n"
+                },
+                {
+                  "type": "added",
+                  "content": ">>>>>>> Conflict 1 of 1 ends\n"
+                }
+              ]
+            }
+          ]
+        }
+      ]
+    }
+    [EOF]
+    "#);
+}
+
 #[test]
 fn test_diff_revisions() {
     let test_env = TestEnvironment::default();
@@ -427,6 +427,80 @@ fn test_diffedit_external_tool_conflict_marker_style() {
     ");
 }
 
+#[test]
+fn test_diffedit_external_tool_conflict_marker_style_overrides() {
+    let mut test_env = TestEnvironment::default();
+    let edit_script = test_env.set_up_fake_diff_editor();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    // Create a conflict in two files
+    work_dir.write_file("file", "base\n");
+    work_dir.write_file("file.lock", "base\n");
+    work_dir.run_jj(["commit", "-m", "base"]).success();
+    work_dir.write_file("file", "a\n");
+    work_dir.write_file("file.lock", "a\n");
+    work_dir.run_jj(["describe", "-m", "side-a"]).success();
+    work_dir
+        .run_jj(["new", "description(base)", "-m", "side-b"])
+        .success();
+    work_dir.write_file("file", "b\n");
+    work_dir.write_file("file.lock", "b\n");
+    work_dir
+        .run_jj(["new", "description(side-a)", "description(side-b)"])
+        .success();
+
+    // Use "snapshot" conflict markers for lock files
+    test_env.add_config(r#"ui.conflict-marker-style-overrides."glob:*.lock" = "snapshot""#);
+
+    std::fs::write(
+        &edit_script,
+        ["dump file file-after", "dump file.lock file.lock-after"].join("\0"),
+    )
+    .unwrap();
+    let output = work_dir.run_jj(["diffedit", "--from", "description(base)"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Nothing changed.
+    [EOF]
+    ");
+    // Overrides should apply to files in diff editor
+    insta::assert_snapshot!(
+        std::fs::read_to_string(test_env.env_root().join("file-after")).unwrap(), @r"
+    <<<<<<< Conflict 1 of 1
+    %%%%%%% Changes from base to side #1
+    -base
+    +a
+    +++++++ Contents of side #2
+    b
+    >>>>>>> Conflict 1 of 1 ends
+    ");
+    insta::assert_snapshot!(
+        std::fs::read_to_string(test_env.env_root().join("file.lock-after")).unwrap(), @r"
+    <<<<<<< Conflict 1 of 1
+    +++++++ Contents of side #1
+    a
+    ------- Contents of base
+    base
+    +++++++ Contents of side #2
+    b
+    >>>>>>> Conflict 1 of 1 ends
+    ");
+
+    // Files should be conflicted with no changes
+    let output = work_dir.run_jj(["st"]);
+    insta::assert_snapshot!(output, @r"
+    The working copy has no changes.
+    Working copy  (@) : mzvwutvl 583351db (conflict) (empty) (no description set)
+    Parent commit (@-): rlvkpnrz 260901f3 side-a
+    Parent commit (@-): zsuskuln 4979315b side-b
+    Warning: There are unresolved conflicts at these paths:
+    file         2-sided conflict
+    file.lock    2-sided conflict
+    [EOF]
+    ");
+}
+
 #[test]
 fn test_diffedit_3pane() {
     let mut test_env = TestEnvironment::default();
@@ -50,6 +50,161 @@ fn test_annotate_linear() {
     ");
 }
 
+#[test]
+fn test_annotate_rename() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.write_file("file.txt", "line1\nline2\nline3\n");
+    work_dir.run_jj(["commit", "-m=initial"]).success();
+
+    let sub_dir = work_dir.create_dir("dir");
+    std::fs::rename(
+        work_dir.root().join("file.txt"),
+        work_dir.root().join("dir").join("renamed.txt"),
+    )
+    .unwrap();
+    append_to_file(&work_dir.root().join("dir").join("renamed.txt"), "line4");
+    work_dir.run_jj(["commit", "-m=rename"]).success();
+
+    let output = work_dir.run_jj(["file", "annotate", "dir/renamed.txt"]);
+    insta::assert_snapshot!(output, @r"
+    qpvuntsm test.use 2001-02-03 08:05:08 file.txt    1: line1
+    qpvuntsm test.use 2001-02-03 08:05:08 file.txt    2: line2
+    qpvuntsm test.use 2001-02-03 08:05:08 file.txt    3: line3
+    rlvkpnrz test.use 2001-02-03 08:05:09    4: line4
+    [EOF]
+    ");
+
+    // The original path is relative to the current directory
+    let output = sub_dir.run_jj(["file", "annotate", "renamed.txt"]);
+    insta::assert_snapshot!(output, @r"
+    qpvuntsm test.use 2001-02-03 08:05:08 ../file.txt    1: line1
+    qpvuntsm test.use 2001-02-03 08:05:08 ../file.txt    2: line2
+    qpvuntsm test.use 2001-02-03 08:05:08 ../file.txt    3: line3
+    rlvkpnrz test.use 2001-02-03 08:05:09    4: line4
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_annotate_ignore_revisions() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.write_file("file.txt", "line1\nline2\nline3\n");
+    work_dir.run_jj(["commit", "-m=initial"]).success();
+    work_dir.write_file("file.txt", "LINE1\nLINE2\nline3\n");
+    work_dir.run_jj(["commit", "-m=reformat"]).success();
+    work_dir.write_file("file.txt", "LINE1\nLINE2\nline3\nline4\n");
+    work_dir.run_jj(["describe", "-m=append"]).success();
+
+    let output = work_dir.run_jj(["file", "annotate", "file.txt"]);
+    insta::assert_snapshot!(output, @r"
+    rlvkpnrz test.use 2001-02-03 08:05:09    1: LINE1
+    rlvkpnrz test.use 2001-02-03 08:05:09    2: LINE2
+    qpvuntsm test.use 2001-02-03 08:05:08    3: line3
+    kkmpptxz test.use 2001-02-03 08:05:10    4: line4
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj([
+        "file",
+        "annotate",
+        "file.txt",
+        "--ignore=description(reformat)",
+    ]);
+    insta::assert_snapshot!(output, @r"
+    qpvuntsm test.use 2001-02-03 08:05:08    1: LINE1
+    qpvuntsm test.use 2001-02-03 08:05:08    2: LINE2
+    qpvuntsm test.use 2001-02-03 08:05:08    3: line3
+    kkmpptxz test.use 2001-02-03 08:05:10    4: line4
+    [EOF]
+    ");
+
+    // Ignored revisions can be configured
+    test_env.add_config("revsets.annotate-ignore = 'description(reformat)'");
+    let output = work_dir.run_jj(["file", "annotate", "file.txt"]);
+    insta::assert_snapshot!(output, @r"
+    qpvuntsm test.use 2001-02-03 08:05:08    1: LINE1
+    qpvuntsm test.use 2001-02-03 08:05:08    2: LINE2
+    qpvuntsm test.use 2001-02-03 08:05:08    3: line3
+    kkmpptxz test.use 2001-02-03 08:05:10    4: line4
+    [EOF]
+    ");
+    let output = work_dir.run_jj([
+        "file",
+        "annotate",
+        "file.txt",
+        "--ignore=description(append)",
+    ]);
+    insta::assert_snapshot!(output, @r"
+    qpvuntsm test.use 2001-02-03 08:05:08    1: LINE1
+    qpvuntsm test.use 2001-02-03 08:05:08    2: LINE2
+    qpvuntsm test.use 2001-02-03 08:05:08    3: line3
+    kkmpptxz test.use 2001-02-03 08:05:10    4: line4
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_annotate_line_range() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.write_file("file.txt", "line1\n");
+    work_dir.run_jj(["commit", "-m=initial"]).success();
+    append_to_file(&work_dir.root().join("file.txt"), "line2");
+    work_dir.run_jj(["commit", "-m=commit1"]).success();
+    append_to_file(&work_dir.root().join("file.txt"), "line3\nline4");
+    work_dir.run_jj(["describe", "-m=commit2"]).success();
+
+    let output = work_dir.run_jj(["file", "annotate", "file.txt", "-L2,3"]);
+    insta::assert_snapshot!(output, @r"
+    rlvkpnrz test.use 2001-02-03 08:05:09    2: line2
+    kkmpptxz test.use 2001-02-03 08:05:10    3: line3
+    [EOF]
+    ");
+
+    // The range may extend past the end of the file
+    let output = work_dir.run_jj(["file", "annotate", "file.txt", "-L", "4,10"]);
+    insta::assert_snapshot!(output, @r"
+    kkmpptxz test.use 2001-02-03 08:05:10    4: line4
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["file", "annotate", "file.txt", "-L3,2"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    error: invalid value '3,2' for '--lines <START,END>': Line range start 3 is after end 2
+
+    For more information, try '--help'.
+    [EOF]
+    [exit status: 2]
+    ");
+    let output = work_dir.run_jj(["file", "annotate", "file.txt", "-L0,2"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    error: invalid value '0,2' for '--lines <START,END>': Invalid line number: 0
+
+    For more information, try '--help'.
+    [EOF]
+    [exit status: 2]
+    ");
+    let output = work_dir.run_jj(["file", "annotate", "file.txt", "-L2"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    error: invalid value '2' for '--lines <START,END>': Line range must be in the form START,END
+
+    For more information, try '--help'.
+    [EOF]
+    [exit status: 2]
+    ");
+}
+
 #[test]
 fn test_annotate_merge() {
     let test_env = TestEnvironment::default();
@@ -0,0 +1,117 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::common::TestEnvironment;
+
+#[test]
+fn test_search() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.write_file("file1", "foo\nbar\nfoobar\n");
+    work_dir.create_dir("dir");
+    work_dir.write_file("dir/file2", "baz\nfood\n");
+    work_dir.write_file("binary", b"foo\0");
+    work_dir.run_jj(["new"]).success();
+    work_dir.write_file("file1", "changed\n");
+
+    // Searches the working-copy commit by default
+    let output = work_dir.run_jj(["file", "search", "foo"]);
+    insta::assert_snapshot!(output, @r"
+    dir/file2:2:food
+    [EOF]
+    ");
+
+    // Can search another revision
+    let output = work_dir.run_jj(["file", "search", "-r@-", "foo"]);
+    insta::assert_snapshot!(output, @r"
+    dir/file2:2:food
+    file1:1:foo
+    file1:3:foobar
+    [EOF]
+    ");
+
+    // The pattern is a regular expression
+    let output = work_dir.run_jj(["file", "search", "-r@-", "^ba"]);
+    insta::assert_snapshot!(output, @r"
+    dir/file2:1:baz
+    file1:2:bar
+    [EOF]
+    ");
+
+    // Can restrict the search to some paths
+    let output = work_dir.run_jj(["file", "search", "-r@-", "foo", "dir"]);
+    insta::assert_snapshot!(output, @r"
+    dir/file2:2:food
+    [EOF]
+    ");
+
+    // Unmatched paths should generate warnings
+    let output = work_dir.run_jj(["file", "search", "-r@-", "foo", "file1", "non-existent"]);
+    insta::assert_snapshot!(output, @r"
+    file1:1:foo
+    file1:3:foobar
+    [EOF]
+    ------- stderr -------
+    Warning: No matching entries for paths: non-existent
+    [EOF]
+    ");
+
+    // Invalid pattern
+    let output = work_dir.run_jj(["file", "search", "("]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    error: invalid value '(' for '<PATTERN>': regex parse error:
+        (
+        ^
+    error: unclosed group
+
+    For more information, try '--help'.
+    [EOF]
+    [exit status: 2]
+    ");
+}
+
+#[test]
+fn test_search_conflict() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.write_file("file", "line\nbase\n");
+    work_dir.run_jj(["new"]).success();
+    work_dir.write_file("file", "line\nleft\n");
+    work_dir
+        .run_jj(["bookmark", "create", "-r@", "left"])
+        .success();
+    work_dir.run_jj(["new", "@-"]).success();
+    work_dir.write_file("file", "line\nright\n");
+    work_dir
+        .run_jj(["bookmark", "create", "-r@", "right"])
+        .success();
+    work_dir.run_jj(["new", "left", "right"]).success();
+
+    // All terms of the conflict are searched
+    let output = work_dir.run_jj(["file", "search", "line|left|right|base"]);
+    insta::assert_snapshot!(output, @r"
+    file:1:(side #1):line
+    file:2:(side #1):left
+    file:1:(side #2):line
+    file:2:(side #2):right
+    file:1:(base):line
+    file:2:(base):base
+    [EOF]
+    ");
+}
@@ -197,6 +197,33 @@ fn test_auto_track() {
     ");
 }
 
+#[test]
+fn test_track_value_predicates() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    work_dir.write_file("file1", "initial");
+
+    // New files are matched by path, so their content can't be used
+    let output = work_dir.run_jj(["file", "track", "file_size('<1KB')"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Functions matching file content or metadata, such as `file_size()`, can't be used here
+    [EOF]
+    [exit status: 1]
+    ");
+
+    let output = work_dir.run_jj(["file", "list", "--config=snapshot.auto-track='binary()'"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Config error: Invalid `snapshot.auto-track`
+    Caused by: Functions matching file content or metadata, such as `file_size()`, can't be used here
+    For help, see https://jj-vcs.github.io/jj/latest/config/ or use `jj help -k config`.
+    [EOF]
+    [exit status: 1]
+    ");
+}
+
 #[test]
 fn test_track_ignored() {
     let test_env = TestEnvironment::default();
@@ -227,3 +254,95 @@ fn test_track_ignored() {
     [EOF]
     ");
 }
+
+#[test]
+fn test_track_force() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config(
+        r#"
+        snapshot.max-new-file-size = 100
+        snapshot.untrack-binaries = true
+        snapshot.ignore-by-default = ["glob:'*.log'"]
+        "#,
+    );
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.write_file("large", vec![b'a'; 200]);
+    work_dir.write_file("app", b"\x7fELF\x02\x01\x01\x00");
+    work_dir.write_file("debug.log", "log\n");
+    work_dir.write_file("trace.log", "log\n");
+
+    // Explicitly tracking the paths doesn't override the snapshot settings
+    let output = work_dir.run_jj(["file", "track", "app", "debug.log"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Warning: Refused to snapshot some files:
+      large: 200.0B (200 bytes); the maximum size allowed is 100.0B (100 bytes)
+    Warning: Refused to track some files:
+      app: generated binary (ELF)
+      debug.log: ignored by default
+    Hint: Run `jj file track --force app debug.log` to track them anyway.
+    Hint: This is to prevent large files from being added by accident. You can fix this by:
+      - Adding the file to `.gitignore`
+      - Run `jj config set --repo snapshot.max-new-file-size 200`
+        This will increase the maximum file size allowed for new files, in this repository only.
+      - Run `jj --config snapshot.max-new-file-size=200 file track large`
+        This will increase the maximum file size allowed for new files, for this command only.
+      - Run `jj file track --force large`
+        This will track the files regardless of their size.
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["file", "list"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Warning: Refused to snapshot some files:
+      large: 200.0B (200 bytes); the maximum size allowed is 100.0B (100 bytes)
+    Hint: This is to prevent large files from being added by accident. You can fix this by:
+      - Adding the file to `.gitignore`
+      - Run `jj config set --repo snapshot.max-new-file-size 200`
+        This will increase the maximum file size allowed for new files, in this repository only.
+      - Run `jj --config snapshot.max-new-file-size=200 st`
+        This will increase the maximum file size allowed for new files, for this command only.
+    [EOF]
+    ");
+
+    // Tracking with --force does
+    let output = work_dir.run_jj(["file", "track", "--force", "large", "app", "debug.log"]);
+    insta::assert_snapshot!(output, @"");
+    let output = work_dir.run_jj(["file", "list"]);
+    insta::assert_snapshot!(output, @r"
+    app
+    debug.log
+    large
+    [EOF]
+    ");
+
+    // Forced paths are tracked again when they're re-created
+    work_dir.remove_file("debug.log");
+    work_dir.run_jj(["new"]).success();
+    work_dir.write_file("debug.log", "log\n");
+    let output = work_dir.run_jj(["status"]);
+    insta::assert_snapshot!(output, @r"
+    Working copy changes:
+    A debug.log
+    Untracked paths:
+    ? trace.log (ignored by default)
+    Working copy  (@) : royxmykx ee6226e3 (no description set)
+    Parent commit (@-): qpvuntsm 5f48347c (no description set)
+    [EOF]
+    ");
+
+    // Untracking forgets that the path was forced
+    let output = work_dir.run_jj(["file", "untrack", "debug.log"]);
+    insta::assert_snapshot!(output, @"");
+    work_dir.remove_file("debug.log");
+    work_dir.run_jj(["new"]).success();
+    work_dir.write_file("debug.log", "log\n");
+    let output = work_dir.run_jj(["file", "list"]);
+    insta::assert_snapshot!(output, @r"
+    app
+    large
+    [EOF]
+    ");
+}
@@ -0,0 +1,132 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use testutils::git;
+
+use crate::common::TestEnvironment;
+
+/// Returns the messages of the commits uploaded to `refs/for/main`, and
+/// deletes the ref like Gerrit does.
+fn take_uploaded_messages(repo: &gix::Repository) -> String {
+    let mut reference = repo.find_reference("refs/for/main").unwrap();
+    let head = reference.peel_to_commit().unwrap();
+    let messages = head
+        .ancestors()
+        .all()
+        .unwrap()
+        .map(|info| info.unwrap().object().unwrap())
+        .take_while(|commit| !commit.message_raw_sloppy().starts_with(b"initial"))
+        .map(|commit| commit.message_raw_sloppy().to_string())
+        .collect::<Vec<_>>()
+        .join("----\n");
+    reference.delete().unwrap();
+    messages
+}
+
+#[test]
+fn test_gerrit_upload() {
+    let test_env = TestEnvironment::default();
+    let remote_repo = git::init_bare(test_env.env_root().join("remote"));
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    work_dir
+        .run_jj(["git", "remote", "add", "origin", "../remote"])
+        .success();
+    work_dir.run_jj(["commit", "-m", "initial"]).success();
+    work_dir
+        .run_jj(["bookmark", "create", "-r@-", "main"])
+        .success();
+    work_dir
+        .run_jj(["git", "push", "--allow-new", "-b", "main"])
+        .success();
+
+    work_dir.write_file("file", "a\n");
+    work_dir.run_jj(["commit", "-m", "first"]).success();
+    work_dir.write_file("file", "b\n");
+    work_dir
+        .run_jj(["describe", "-m", "second\n\nChange-Id: I0123456789abcdef\n"])
+        .success();
+
+    // Commits without description can't be uploaded
+    work_dir.run_jj(["new"]).success();
+    let output = work_dir.run_jj(["gerrit", "upload", "-r@", "--for", "main"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Won't upload commit 5686c8e4cb34 since it has no description
+    Hint: Rejected commit: vruxwmqv 5686c8e4 (empty) (no description set)
+    [EOF]
+    [exit status: 1]
+    ");
+
+    // Ancestors not on the remote are uploaded too
+    let output = work_dir.run_jj(["gerrit", "upload", "-r@-", "--for", "main", "--dry-run"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Commits to upload to origin for review on main:
+      royxmykx 198732db second
+      kkmpptxz fbd7b0d9 first
+    Dry-run requested, not uploading.
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["gerrit", "upload", "-r@-", "--for", "main"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Commits to upload to origin for review on main:
+      royxmykx 198732db second
+      kkmpptxz fbd7b0d9 first
+    [EOF]
+    ");
+    insta::assert_snapshot!(take_uploaded_messages(&remote_repo), @r"
+    second
+
+    Change-Id: I0123456789abcdef
+    ----
+    first
+
+    Change-Id: Iffdaa62087a280bddc5e3d3ff933b8ae6a6a6964
+    ");
+
+    // The local commits are unchanged
+    let output = work_dir.run_jj(["log", "-r::@-", "-T", "description"]);
+    insta::assert_snapshot!(output, @r"
+    ○  second
+    │
+    │  Change-Id: I0123456789abcdef
+    ○  first
+    ◆  initial
+    ◆
+    [EOF]
+    ");
+
+    // Re-uploading a rewritten commit uses the same Change-Id
+    work_dir
+        .run_jj(["describe", "-r@--", "-m", "first (v2)"])
+        .success();
+    work_dir
+        .run_jj(["gerrit", "upload", "-r@--", "--for", "main"])
+        .success();
+    insta::assert_snapshot!(take_uploaded_messages(&remote_repo), @r"
+    first (v2)
+
+    Change-Id: Iffdaa62087a280bddc5e3d3ff933b8ae6a6a6964
+    ");
+
+    // Nothing to upload
+    let output = work_dir.run_jj(["gerrit", "upload", "-rmain", "--for", "main"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    No changes to upload.
+    [EOF]
+    ");
+}
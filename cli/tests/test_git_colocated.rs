@@ -962,7 +962,7 @@ fn test_git_colocated_undo_head_move() {
     let output = work_dir.run_jj(["undo"]);
     insta::assert_snapshot!(output, @r"
     ------- stderr -------
-    Undid operation: b50ec983d1c1 (2001-02-03 08:05:13) new empty commit
+    Undid operation: d58b05cc072b (2001-02-03 08:05:13) new empty commit
     Working copy  (@) now at: royxmykx eb08b363 (empty) (no description set)
     Parent commit (@-)      : qpvuntsm 230dd059 (empty) (no description set)
     [EOF]
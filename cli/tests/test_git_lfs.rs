@@ -0,0 +1,89 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use testutils::git;
+
+use crate::common::TestEnvironment;
+
+#[test]
+fn test_git_lfs_push_and_fetch() {
+    let test_env = TestEnvironment::default();
+    git::init_bare(test_env.env_root().join("remote"));
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    work_dir
+        .run_jj(["git", "remote", "add", "origin", "../remote"])
+        .success();
+
+    // Files tracked by LFS are stored as pointers
+    work_dir.write_file(".gitattributes", "*.bin filter=lfs -text\n");
+    work_dir.write_file("data.bin", "large\n");
+    work_dir.write_file("small.txt", "small\n");
+    work_dir.run_jj(["commit", "-m", "add files"]).success();
+    let output = work_dir.run_jj(["file", "show", "-r@-", "data.bin"]);
+    insta::assert_snapshot!(output, @r"
+    version https://git-lfs.github.com/spec/v1
+    oid sha256:9bfce334a37bd1bc1d36b0370195b31fe9a9389dd43d0873891a3544ef1140a1
+    size 6
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["file", "show", "-r@-", "small.txt"]);
+    insta::assert_snapshot!(output, @r"
+    small
+    [EOF]
+    ");
+
+    // The objects are uploaded along with the commits
+    work_dir
+        .run_jj(["bookmark", "create", "-r@-", "main"])
+        .success();
+    let output = work_dir.run_jj(["git", "push", "--allow-new"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Changes to push to origin:
+      Add bookmark main to b5554e87e031
+    Uploaded 1 Git LFS objects
+    [EOF]
+    ");
+
+    // Cloning downloads the objects, and the pointers are replaced on checkout
+    let output = test_env.run_jj_in(".", ["git", "clone", "remote", "clone"]);
+    insta::assert_snapshot!(output, @r#"
+    ------- stderr -------
+    Fetching into new repo in "$TEST_ENV/clone"
+    bookmark: main@origin [new] untracked
+    Downloaded 1 Git LFS objects
+    [EOF]
+    "#);
+    let clone_dir = test_env.work_dir("clone");
+    clone_dir.run_jj(["new", "main@origin"]).success();
+    insta::assert_snapshot!(clone_dir.read_file("data.bin"), @"large");
+
+    // Objects of fetched commits are downloaded
+    work_dir.write_file("data.bin", "modified large\n");
+    work_dir.run_jj(["commit", "-m", "modify"]).success();
+    work_dir
+        .run_jj(["bookmark", "move", "--to=@-", "main"])
+        .success();
+    work_dir.run_jj(["git", "push"]).success();
+    let output = clone_dir.run_jj(["git", "fetch"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    bookmark: main@origin [updated] untracked
+    Downloaded 1 Git LFS objects
+    [EOF]
+    ");
+    clone_dir.run_jj(["new", "main@origin"]).success();
+    insta::assert_snapshot!(clone_dir.read_file("data.bin"), @"modified large");
+}
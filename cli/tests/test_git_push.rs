@@ -2496,6 +2496,106 @@ fn test_git_push_sign_on_push() {
     ");
 }
 
+#[test]
+fn test_git_push_sign_behavior_on_push() {
+    let test_env = TestEnvironment::default();
+    set_up(&test_env);
+    test_env.add_config(
+        r#"
+    signing.backend = "test"
+    signing.key = "impeccable"
+    signing.behavior = "on-push"
+    "#,
+    );
+    let work_dir = test_env.work_dir("local");
+    let template = r#"
+    separate(" ",
+      description.first_line(),
+      if(signature, "(signed)"),
+    )
+    "#;
+    work_dir
+        .run_jj(["new", "bookmark2", "-m", "commit to be signed"])
+        .success();
+    work_dir
+        .run_jj([
+            "new",
+            "-m",
+            "commit by someone else",
+            "--config=user.email=someone@example.com",
+        ])
+        .success();
+    work_dir
+        .run_jj(["bookmark", "set", "bookmark2", "-r@"])
+        .success();
+    work_dir
+        .run_jj(["new", "-m", "commit which should not be signed"])
+        .success();
+    // Commits aren't signed when created
+    let output = work_dir.run_jj(["log", "-T", template, "-r", "bookmark2-::"]);
+    insta::assert_snapshot!(output, @r"
+    @  commit which should not be signed
+    ○  commit by someone else
+    ○  commit to be signed
+    │
+    ~
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["git", "push"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Updated signatures of 2 commits
+    Rebased 1 descendant commits
+    Changes to push to origin:
+      Move forward bookmark bookmark2 from 8476341eb395 to f1d4faf14d87
+    Working copy  (@) now at: kpqxywon 4c660c5e (empty) commit which should not be signed
+    Parent commit (@-)      : yostqsxw f1d4faf1 bookmark2 | (empty) commit by someone else
+    [EOF]
+    ");
+    // All commits in the pushed range are signed
+    let output = work_dir.run_jj(["log", "-T", template, "-r", "bookmark2-::"]);
+    insta::assert_snapshot!(output, @r"
+    @  commit which should not be signed
+    ○  commit by someone else (signed)
+    ○  commit to be signed (signed)
+    │
+    ~
+    [EOF]
+    ");
+
+    // Signatures of own commits are kept when the commits are rewritten, but
+    // others' commits are signed again on the next push
+    work_dir
+        .run_jj([
+            "describe",
+            "-r",
+            "bookmark2-",
+            "-m",
+            "commit to be signed (v2)",
+        ])
+        .success();
+    let output = work_dir.run_jj(["log", "-T", template, "-r", "bookmark2-::"]);
+    insta::assert_snapshot!(output, @r"
+    @  commit which should not be signed
+    ○  commit by someone else
+    ○  commit to be signed (v2) (signed)
+    │
+    ~
+    [EOF]
+    ");
+    work_dir.run_jj(["git", "push"]).success();
+    let output = work_dir.run_jj(["log", "-T", template, "-r", "bookmark2-::"]);
+    insta::assert_snapshot!(output, @r"
+    @  commit which should not be signed
+    ○  commit by someone else (signed)
+    ○  commit to be signed (v2) (signed)
+    │
+    ~
+    [EOF]
+    ");
+}
+
 #[test]
 fn test_git_push_rejected_by_remote() {
     let test_env = TestEnvironment::default();
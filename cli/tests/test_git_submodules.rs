@@ -0,0 +1,257 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use testutils::git;
+
+use crate::common::TestEnvironment;
+
+/// Creates a superproject Git repo at "super" referring to a submodule repo
+/// at "sub". Returns the ids of the two commits in the submodule repo, the
+/// first of which is recorded in the superproject.
+fn set_up_superproject(test_env: &TestEnvironment) -> (gix::ObjectId, gix::ObjectId) {
+    let sub_repo = git::init(test_env.env_root().join("sub"));
+    let sub_commit1 = git::add_commit(&sub_repo, "refs/heads/main", "file", b"1\n", "1", &[]);
+    let sub_commit2 = git::add_commit(
+        &sub_repo,
+        "refs/heads/main",
+        "file",
+        b"2\n",
+        "2",
+        &[sub_commit1.commit_id],
+    );
+
+    let super_repo = git::init(test_env.env_root().join("super"));
+    let gitmodules = b"[submodule \"module\"]\n\tpath = module\n\turl = ../sub\n";
+    let mut tree_editor = super_repo
+        .edit_tree(gix::ObjectId::empty_tree(super_repo.object_hash()))
+        .unwrap();
+    tree_editor
+        .upsert(
+            ".gitmodules",
+            gix::object::tree::EntryKind::Blob,
+            super_repo.write_blob(gitmodules).unwrap(),
+        )
+        .unwrap();
+    tree_editor
+        .upsert(
+            "module",
+            gix::object::tree::EntryKind::Commit,
+            sub_commit1.commit_id,
+        )
+        .unwrap();
+    let tree_id = tree_editor.write().unwrap().detach();
+    git::write_commit(
+        &super_repo,
+        "refs/heads/main",
+        tree_id,
+        "add submodule",
+        &[],
+    );
+    git::set_symbolic_reference(&super_repo, "HEAD", "refs/heads/main");
+    (sub_commit1.commit_id, sub_commit2.commit_id)
+}
+
+#[test]
+fn test_git_submodule_checkout_and_snapshot() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config(r#"git.submodules.allowed-url-schemes = ["file"]"#);
+    let (_sub_commit1, sub_commit2) = set_up_superproject(&test_env);
+    test_env
+        .run_jj_in(".", ["git", "clone", "super", "repo"])
+        .success();
+    let work_dir = test_env.work_dir("repo");
+
+    // The submodule is checked out at the recorded commit
+    assert_eq!(work_dir.read_file("module/file"), "1\n");
+    let output = work_dir.run_jj(["status"]);
+    insta::assert_snapshot!(output, @r"
+    The working copy has no changes.
+    Working copy  (@) : sqpuoqvx a9caf78c (empty) (no description set)
+    Parent commit (@-): oorkpxzx bf2ff710 main | add submodule
+    [EOF]
+    ");
+
+    // Moving the submodule HEAD is recorded in the working-copy commit
+    let sub_repo = git::open(work_dir.root().join("module"));
+    git::set_head_to_id(&sub_repo, sub_commit2);
+    let output = work_dir.run_jj(["status"]);
+    insta::assert_snapshot!(output, @r"
+    Working copy changes:
+    M module
+    Working copy  (@) : sqpuoqvx 06c75b7a (no description set)
+    Parent commit (@-): oorkpxzx bf2ff710 main | add submodule
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["diff", "--git"]);
+    insta::assert_snapshot!(output, @r"
+    diff --git a/module b/module
+    index 741bee124c..f847795657 160000
+    --- a/module
+    +++ b/module
+    @@ -1,1 +1,1 @@
+    -Subproject commit 741bee124c71d924dc96783981c886ca062f47dd
+    +Subproject commit f8477956579863ffbd45f3804f6a1066223651c7
+    [EOF]
+    ");
+
+    // Checking out another commit updates the submodule
+    let output = work_dir.run_jj(["new", "main"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Working copy  (@) now at: mzvwutvl 62b7115e (empty) (no description set)
+    Parent commit (@-)      : oorkpxzx bf2ff710 main | add submodule
+    Added 0 files, modified 1 files, removed 0 files
+    [EOF]
+    ");
+    assert_eq!(work_dir.read_file("module/file"), "1\n");
+    let output = work_dir.run_jj(["status"]);
+    insta::assert_snapshot!(output, @r"
+    The working copy has no changes.
+    Working copy  (@) : mzvwutvl 62b7115e (empty) (no description set)
+    Parent commit (@-): oorkpxzx bf2ff710 main | add submodule
+    [EOF]
+    ");
+
+    // Going back checks out the moved submodule commit
+    let output = work_dir.run_jj(["edit", "sqpuoqvx"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Working copy  (@) now at: sqpuoqvx 06c75b7a (no description set)
+    Parent commit (@-)      : oorkpxzx bf2ff710 main | add submodule
+    Added 0 files, modified 1 files, removed 0 files
+    [EOF]
+    ");
+    assert_eq!(work_dir.read_file("module/file"), "2\n");
+}
+
+#[test]
+fn test_git_submodule_auto_update_disabled() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config(
+        r#"
+        git.submodules.auto-update = false
+        git.submodules.allowed-url-schemes = ["file"]
+        "#,
+    );
+    set_up_superproject(&test_env);
+    test_env
+        .run_jj_in(".", ["git", "clone", "super", "repo"])
+        .success();
+    let work_dir = test_env.work_dir("repo");
+
+    // Submodules aren't fetched if disabled
+    assert!(!work_dir.root().join("module").join("file").exists());
+}
+
+#[test]
+fn test_git_submodule_stale_after_checkout() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config(
+        r#"
+        git.submodules.auto-update = false
+        git.submodules.allowed-url-schemes = ["file"]
+        "#,
+    );
+    let (sub_commit1, sub_commit2) = set_up_superproject(&test_env);
+    test_env
+        .run_jj_in(
+            ".",
+            [
+                "git",
+                "clone",
+                "--config=git.submodules.auto-update=true",
+                "super",
+                "repo",
+            ],
+        )
+        .success();
+    let work_dir = test_env.work_dir("repo");
+    assert_eq!(work_dir.read_file("module/file"), "1\n");
+
+    // Bump the submodule upstream
+    let super_repo = git::open(test_env.env_root().join("super"));
+    let main_id = super_repo.find_reference("refs/heads/main").unwrap().id();
+    let mut tree_editor = super_repo
+        .edit_tree(main_id.object().unwrap().peel_to_tree().unwrap().id)
+        .unwrap();
+    tree_editor
+        .upsert("module", gix::object::tree::EntryKind::Commit, sub_commit2)
+        .unwrap();
+    let tree_id = tree_editor.write().unwrap().detach();
+    git::write_commit(
+        &super_repo,
+        "refs/heads/main",
+        tree_id,
+        "bump submodule",
+        &[main_id.detach()],
+    );
+    work_dir.run_jj(["git", "fetch"]).success();
+
+    // The submodule isn't updated, but the stale commit it's still at isn't
+    // recorded as a change either
+    let output = work_dir.run_jj(["new", "main"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Working copy  (@) now at: kkmpptxz fd1644b6 (empty) (no description set)
+    Parent commit (@-)      : lnxwytss 8c53ada5 main | bump submodule
+    Added 0 files, modified 1 files, removed 0 files
+    [EOF]
+    ");
+    assert_eq!(work_dir.read_file("module/file"), "1\n");
+    let output = work_dir.run_jj(["status"]);
+    insta::assert_snapshot!(output, @r"
+    The working copy has no changes.
+    Working copy  (@) : kkmpptxz fd1644b6 (empty) (no description set)
+    Parent commit (@-): lnxwytss 8c53ada5 main | bump submodule
+    [EOF]
+    ");
+
+    // Moving the submodule HEAD afterwards is recorded
+    let sub_repo = git::open(work_dir.root().join("module"));
+    git::set_head_to_id(&sub_repo, sub_commit2);
+    let output = work_dir.run_jj(["diff", "--git"]);
+    insta::assert_snapshot!(output, @"");
+    git::set_head_to_id(&sub_repo, sub_commit1);
+    let output = work_dir.run_jj(["diff", "--git"]);
+    insta::assert_snapshot!(output, @r"
+    diff --git a/module b/module
+    index f847795657..741bee124c 160000
+    --- a/module
+    +++ b/module
+    @@ -1,1 +1,1 @@
+    -Subproject commit f8477956579863ffbd45f3804f6a1066223651c7
+    +Subproject commit 741bee124c71d924dc96783981c886ca062f47dd
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_git_submodule_disallowed_url_scheme() {
+    let test_env = TestEnvironment::default();
+    set_up_superproject(&test_env);
+    let output = test_env.run_jj_in(".", ["git", "clone", "super", "repo"]);
+    insta::assert_snapshot!(output, @r#"
+    ------- stderr -------
+    Fetching into new repo in "$TEST_ENV/repo"
+    bookmark: main@origin [new] untracked
+    Setting the revset alias `trunk()` to `main@origin`
+    Warning: Failed to update Git submodules: Submodule module has URL '$TEST_ENV/sub' whose scheme isn't allowed by git.submodules.allowed-url-schemes
+    Working copy  (@) now at: sqpuoqvx a9caf78c (empty) (no description set)
+    Parent commit (@-)      : oorkpxzx bf2ff710 main | add submodule
+    Added 2 files, modified 0 files, removed 0 files
+    [EOF]
+    "#);
+    let work_dir = test_env.work_dir("repo");
+    assert!(!work_dir.root().join("module").join("file").exists());
+}
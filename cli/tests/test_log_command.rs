@@ -1706,3 +1706,137 @@ fn test_log_full_description_template() {
     [EOF]
     ");
 }
+
+#[test]
+fn test_log_json_output() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir
+        .run_jj([
+            "describe",
+            "-m",
+            "first\n\nSigned-off-by: Test User <test.user@example.com>",
+        ])
+        .success();
+    work_dir
+        .run_jj(["bookmark", "create", "-r@", "main"])
+        .success();
+    work_dir.run_jj(["new", "-m", "second"]).success();
+
+    // Each record contains the graph edges of the node
+    let output = work_dir.run_jj(["log", "--output=json", "-r@-"]);
+    insta::assert_snapshot!(output, @r#"
+    [
+      {
+        "schema_version": 1,
+        "commit_id": "ca543367ef19122500ffb1c1838c3d551f4cd107",
+        "change_id": "qpvuntsmwlqtpsluzzsnyyzlmlwvmlnu",
+        "parents": [
+          "0000000000000000000000000000000000000000"
+        ],
+        "description": "first\n\nSigned-off-by: Test User <test.user@example.com>\n",
+        "trailers": [
+          {
+            "key": "Signed-off-by",
+            "value": "Test User <test.user@example.com>"
+          }
+        ],
+        "author": {
+          "name": "Test User",
+          "email": "test.user@example.com",
+          "timestamp": "2001-02-03T04:05:08.000+07:00"
+        },
+        "committer": {
+          "name": "Test User",
+          "email": "test.user@example.com",
+          "timestamp": "2001-02-03T04:05:08.000+07:00"
+        },
+        "mine": true,
+        "signed": false,
+        "signature": null,
+        "working_copies": [],
+        "current_working_copy": false,
+        "bookmarks": [
+          {
+            "name": "main",
+            "remote": null,
+            "target": [
+              "ca543367ef19122500ffb1c1838c3d551f4cd107"
+            ],
+            "present": true,
+            "conflict": false,
+            "tracked": false,
+            "synced": true
+          }
+        ],
+        "local_bookmarks": [
+          {
+            "name": "main",
+            "remote": null,
+            "target": [
+              "ca543367ef19122500ffb1c1838c3d551f4cd107"
+            ],
+            "present": true,
+            "conflict": false,
+            "tracked": false,
+            "synced": true
+          }
+        ],
+        "remote_bookmarks": [],
+        "tags": [],
+        "git_refs": [],
+        "git_head": false,
+        "divergent": false,
+        "hidden": false,
+        "immutable": false,
+        "conflict": false,
+        "empty": true,
+        "root": false,
+        "edges": [
+          {
+            "target": "0000000000000000000000000000000000000000",
+            "type": "missing"
+          }
+        ]
+      }
+    ]
+    [EOF]
+    "#);
+
+    // Elided revisions are represented as indirect edges
+    let output = work_dir.run_jj(["log", "--output=ndjson", "-r", "@ | root()"]);
+    insta::assert_snapshot!(output, @r#"
+    {"schema_version":1,"commit_id":"7881f7da498f4c94b254eac871fb3737a01bfb7f","change_id":"zsuskulnrvyrovkzqrwmxqlsskqntxvp","parents":["ca543367ef19122500ffb1c1838c3d551f4cd107"],"description":"second\n","trailers":[],"author":{"name":"Test User","email":"test.user@example.com","timestamp":"2001-02-03T04:05:10.000+07:00"},"committer":{"name":"Test User","email":"test.user@example.com","timestamp":"2001-02-03T04:05:10.000+07:00"},"mine":true,"signed":false,"signature":null,"working_copies":["default"],"current_working_copy":true,"bookmarks":[],"local_bookmarks":[],"remote_bookmarks":[],"tags":[],"git_refs":[],"git_head":false,"divergent":false,"hidden":false,"immutable":false,"conflict":false,"empty":true,"root":false,"edges":[{"target":"0000000000000000000000000000000000000000","type":"indirect"}]}
+    {"schema_version":1,"commit_id":"0000000000000000000000000000000000000000","change_id":"zzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzz","parents":[],"description":"","trailers":[],"author":{"name":"","email":"","timestamp":"1970-01-01T00:00:00.000+00:00"},"committer":{"name":"","email":"","timestamp":"1970-01-01T00:00:00.000+00:00"},"mine":false,"signed":false,"signature":null,"working_copies":[],"current_working_copy":false,"bookmarks":[],"local_bookmarks":[],"remote_bookmarks":[],"tags":[],"git_refs":[],"git_head":false,"divergent":false,"hidden":false,"immutable":true,"conflict":false,"empty":true,"root":true,"edges":[]}
+    [EOF]
+    "#);
+
+    // No edges without graph
+    let output = work_dir.run_jj(["log", "--output=ndjson", "--no-graph", "-r@"]);
+    insta::assert_snapshot!(output, @r#"
+    {"schema_version":1,"commit_id":"7881f7da498f4c94b254eac871fb3737a01bfb7f","change_id":"zsuskulnrvyrovkzqrwmxqlsskqntxvp","parents":["ca543367ef19122500ffb1c1838c3d551f4cd107"],"description":"second\n","trailers":[],"author":{"name":"Test User","email":"test.user@example.com","timestamp":"2001-02-03T04:05:10.000+07:00"},"committer":{"name":"Test User","email":"test.user@example.com","timestamp":"2001-02-03T04:05:10.000+07:00"},"mine":true,"signed":false,"signature":null,"working_copies":["default"],"current_working_copy":true,"bookmarks":[],"local_bookmarks":[],"remote_bookmarks":[],"tags":[],"git_refs":[],"git_head":false,"divergent":false,"hidden":false,"immutable":false,"conflict":false,"empty":true,"root":false}
+    [EOF]
+    "#);
+
+    let output = work_dir.run_jj(["log", "--output=json", "-rnone()"]);
+    insta::assert_snapshot!(output, @r"
+    []
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["log", "--output=ndjson", "-rnone()"]);
+    insta::assert_snapshot!(output, @"");
+
+    let output = work_dir.run_jj(["log", "--output=json", "-Tcommit_id"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    error: the argument '--output <FORMAT>' cannot be used with '--template <TEMPLATE>'
+
+    Usage: jj log --output <FORMAT> [FILESETS]...
+
+    For more information, try '--help'.
+    [EOF]
+    [exit status: 2]
+    ");
+}
@@ -203,6 +203,104 @@ fn test_op_log_limit() {
     ");
 }
 
+#[test]
+fn test_op_log_operations() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.run_jj(["commit", "-m", "commit 1"]).success();
+    work_dir.write_file("file", "");
+    work_dir.run_jj(["commit", "-m", "commit 2"]).success();
+    work_dir.run_jj(["describe", "-m", "description"]).success();
+
+    let template = r#"-Tdescription.first_line() ++ "\n""#;
+    let output = work_dir.run_jj(["op", "log", template, "-r", "@-"]);
+    insta::assert_snapshot!(output, @r"
+    ○  commit 2d301418885dc099d8b89c6040cf742e60583316
+    │
+    ~
+    [EOF]
+    ");
+
+    // Intermediate operations are elided
+    let output = work_dir.run_jj([
+        "op",
+        "log",
+        template,
+        "-r",
+        r#"description(glob:"commit*")"#,
+    ]);
+    insta::assert_snapshot!(output, @r"
+    ○  commit 2d301418885dc099d8b89c6040cf742e60583316
+    ○  commit 230dd059e1b059aefc0da06a2e5a7dbf22362f22
+    │
+    ~
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["op", "log", template, "-r", "snapshot()", "-r", "@"]);
+    insta::assert_snapshot!(output, @r"
+    @  describe commit 36ddff3d2f9c35d95f262c8e2bdc894e0964381f
+    ○  snapshot working copy
+    │
+    ~
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj([
+        "op",
+        "log",
+        template,
+        "--no-graph",
+        "--reversed",
+        "-r",
+        r#"tags(args:"*commit*") ~ @--"#,
+    ]);
+    insta::assert_snapshot!(output, @r"
+    commit 230dd059e1b059aefc0da06a2e5a7dbf22362f22
+    commit 2d301418885dc099d8b89c6040cf742e60583316
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["op", "log", template, "-r", "none()"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Failed to parse operation expression: Function `none` doesn't exist
+    Caused by:  --> 1:1
+      |
+    1 | none()
+      | ^--^
+      |
+      = Function `none` doesn't exist
+    [EOF]
+    [exit status: 1]
+    ");
+
+    let output = work_dir.run_jj(["op", "log", template, "-r", "@-+"]);
+    insta::assert_snapshot!(output, @r"
+    @  describe commit 36ddff3d2f9c35d95f262c8e2bdc894e0964381f
+    │
+    ~
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["op", "log", template, "-r", "@-x"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Failed to parse operation expression: Syntax error
+    Caused by:  --> 1:3
+      |
+    1 | @-x
+      |   ^---
+      |
+      = expected <EOI>, `-`, `+`, `::`, `..`, `|`, `&`, or `~`
+    Hint: See https://jj-vcs.github.io/jj/latest/revsets/ or use `jj help -k revsets` for revsets syntax and how to quote symbols.
+    [EOF]
+    [exit status: 1]
+    ");
+}
+
 #[test]
 fn test_op_log_no_graph() {
     let test_env = TestEnvironment::default();
@@ -344,6 +442,75 @@ fn test_op_log_no_graph_null_terminated() {
     insta::assert_debug_snapshot!(output.stdout.normalized(), @r#""ef17\0f412\0eac7\00000\0""#);
 }
 
+#[test]
+fn test_op_log_json_output() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    work_dir.run_jj(["commit", "-m", "message1"]).success();
+
+    let output = work_dir.run_jj(["op", "log", "--output=json", "-n1"]);
+    insta::assert_snapshot!(output, @r#"
+    [
+      {
+        "schema_version": 1,
+        "id": "f412c3b6bbc9abe3609fa1e87baff0b2f8c34a6d518449de1b6dcdfc59b766f4648ce9ed1eca7eacdc12580fb5741dcfae81caaf56833309173ed82cc9f188f2",
+        "parents": [
+          "eac759b9ab75793fd3da96e60939fb48f2cd2b2a9c1f13ffe723cf620f3005b8d3e7e923634a07ea39513e4f2f360c87b9ad5d331cf90d7a844864b83b72eba1"
+        ],
+        "description": "commit 230dd059e1b059aefc0da06a2e5a7dbf22362f22",
+        "tags": {
+          "args": "jj commit -m message1"
+        },
+        "time": {
+          "start": "2001-02-03T04:05:08.000+07:00",
+          "end": "2001-02-03T04:05:08.000+07:00"
+        },
+        "user": {
+          "name": "test-username",
+          "hostname": "host.example.com"
+        },
+        "snapshot": false,
+        "current_operation": true,
+        "root": false,
+        "edges": [
+          {
+            "target": "eac759b9ab75793fd3da96e60939fb48f2cd2b2a9c1f13ffe723cf620f3005b8d3e7e923634a07ea39513e4f2f360c87b9ad5d331cf90d7a844864b83b72eba1",
+            "type": "direct"
+          }
+        ]
+      }
+    ]
+    [EOF]
+    "#);
+
+    let output = work_dir.run_jj(["op", "log", "--output=ndjson", "--reversed"]);
+    insta::assert_snapshot!(output, @r#"
+    {"schema_version":1,"id":"00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000","parents":[],"description":"","tags":{},"time":{"start":"1970-01-01T00:00:00.000+00:00","end":"1970-01-01T00:00:00.000+00:00"},"user":{"name":"","hostname":""},"snapshot":false,"current_operation":false,"root":true,"edges":[{"target":"eac759b9ab75793fd3da96e60939fb48f2cd2b2a9c1f13ffe723cf620f3005b8d3e7e923634a07ea39513e4f2f360c87b9ad5d331cf90d7a844864b83b72eba1","type":"direct"}]}
+    {"schema_version":1,"id":"eac759b9ab75793fd3da96e60939fb48f2cd2b2a9c1f13ffe723cf620f3005b8d3e7e923634a07ea39513e4f2f360c87b9ad5d331cf90d7a844864b83b72eba1","parents":["00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000"],"description":"add workspace 'default'","tags":{},"time":{"start":"2001-02-03T04:05:07.000+07:00","end":"2001-02-03T04:05:07.000+07:00"},"user":{"name":"test-username","hostname":"host.example.com"},"snapshot":false,"current_operation":false,"root":false,"edges":[{"target":"f412c3b6bbc9abe3609fa1e87baff0b2f8c34a6d518449de1b6dcdfc59b766f4648ce9ed1eca7eacdc12580fb5741dcfae81caaf56833309173ed82cc9f188f2","type":"direct"}]}
+    {"schema_version":1,"id":"f412c3b6bbc9abe3609fa1e87baff0b2f8c34a6d518449de1b6dcdfc59b766f4648ce9ed1eca7eacdc12580fb5741dcfae81caaf56833309173ed82cc9f188f2","parents":["eac759b9ab75793fd3da96e60939fb48f2cd2b2a9c1f13ffe723cf620f3005b8d3e7e923634a07ea39513e4f2f360c87b9ad5d331cf90d7a844864b83b72eba1"],"description":"commit 230dd059e1b059aefc0da06a2e5a7dbf22362f22","tags":{"args":"jj commit -m message1"},"time":{"start":"2001-02-03T04:05:08.000+07:00","end":"2001-02-03T04:05:08.000+07:00"},"user":{"name":"test-username","hostname":"host.example.com"},"snapshot":false,"current_operation":true,"root":false,"edges":[]}
+    [EOF]
+    "#);
+
+    let output = work_dir.run_jj(["op", "log", "--output=ndjson", "--no-graph", "-r@-"]);
+    insta::assert_snapshot!(output, @r#"
+    {"schema_version":1,"id":"eac759b9ab75793fd3da96e60939fb48f2cd2b2a9c1f13ffe723cf620f3005b8d3e7e923634a07ea39513e4f2f360c87b9ad5d331cf90d7a844864b83b72eba1","parents":["00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000"],"description":"add workspace 'default'","tags":{},"time":{"start":"2001-02-03T04:05:07.000+07:00","end":"2001-02-03T04:05:07.000+07:00"},"user":{"name":"test-username","hostname":"host.example.com"},"snapshot":false,"current_operation":false,"root":false}
+    [EOF]
+    "#);
+
+    let output = work_dir.run_jj(["op", "log", "--output=json", "--op-diff"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    error: the argument '--output <FORMAT>' cannot be used with '--op-diff'
+
+    Usage: jj operation log --output <FORMAT>
+
+    For more information, try '--help'.
+    [EOF]
+    [exit status: 2]
+    ");
+}
+
 #[test]
 fn test_op_log_template() {
     let test_env = TestEnvironment::default();
@@ -702,14 +869,15 @@ fn test_op_abandon_ancestors() {
     [EOF]
     ");
     insta::assert_snapshot!(work_dir.run_jj(["debug", "local-working-copy", "--ignore-working-copy"]), @r#"
-    Current operation: OperationId("0699d720d0cecd80fb7d765c45955708c61b12feb1d7ed9ff2777ae719471f04ffed3c1dc24efdbf94bdb74426065d6fa9a4f0862a89db2c8c8e359eefc45462")
+    Current operation: OperationId("04c6651abc19910748e23badfa374001b260603e9c97d57f2f11218b573c98b7c9320a2be6227d9ceb4bfc34885344457564804c9e9173d1d9bc814179cd239f")
     Current tree: Merge(Resolved(TreeId("4b825dc642cb6eb9a060e54bf8d69288fbee4904")))
     [EOF]
     "#);
     insta::assert_snapshot!(work_dir.run_jj(["op", "log"]), @r"
-    @  0699d720d0ce test-username@host.example.com 2001-02-03 04:05:21.000 +07:00 - 2001-02-03 04:05:21.000 +07:00
+    @  04c6651abc19 test-username@host.example.com 2001-02-03 04:05:21.000 +07:00 - 2001-02-03 04:05:21.000 +07:00
     │  undo operation d92d0753399f732e438bdd88fa7e5214cba2a310d120ec1714028a514c7116bcf04b4a0b26c04dbecf0a917f1d4c8eb05571b8816dd98b0502aaf321e92500b3
     │  args: jj undo
+    │  undo: d92d0753399f732e438bdd88fa7e5214cba2a310d120ec1714028a514c7116bcf04b4a0b26c04dbecf0a917f1d4c8eb05571b8816dd98b0502aaf321e92500b3
     ○  8545e0137524 test-username@host.example.com 2001-02-03 04:05:09.000 +07:00 - 2001-02-03 04:05:09.000 +07:00
     │  commit 81a4ef3dd421f3184289df1c58bd3a16ea1e3d8e
     │  args: jj commit -m 'commit 2'
@@ -725,13 +893,79 @@ fn test_op_abandon_ancestors() {
     [EOF]
     ");
     insta::assert_snapshot!(work_dir.run_jj(["op", "log", "-n1"]), @r"
-    @  0699d720d0ce test-username@host.example.com 2001-02-03 04:05:21.000 +07:00 - 2001-02-03 04:05:21.000 +07:00
+    @  04c6651abc19 test-username@host.example.com 2001-02-03 04:05:21.000 +07:00 - 2001-02-03 04:05:21.000 +07:00
     │  undo operation d92d0753399f732e438bdd88fa7e5214cba2a310d120ec1714028a514c7116bcf04b4a0b26c04dbecf0a917f1d4c8eb05571b8816dd98b0502aaf321e92500b3
     │  args: jj undo
+    │  undo: d92d0753399f732e438bdd88fa7e5214cba2a310d120ec1714028a514c7116bcf04b4a0b26c04dbecf0a917f1d4c8eb05571b8816dd98b0502aaf321e92500b3
     [EOF]
     ");
 }
 
+#[test]
+fn test_op_abandon_set() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.write_file("file1", "");
+    work_dir.run_jj(["commit", "-m", "commit 1"]).success();
+    work_dir.write_file("file2", "");
+    work_dir.run_jj(["commit", "-m", "commit 2"]).success();
+    work_dir.write_file("file3", "");
+    work_dir.run_jj(["status"]).success();
+    let template = r#"-Tdescription.first_line() ++ "\n""#;
+    insta::assert_snapshot!(work_dir.run_jj(["op", "log", template]), @r"
+    @  snapshot working copy
+    ○  commit a6cd5f9235cce74459ce5dee8c5f92a809b1563f
+    ○  snapshot working copy
+    ○  commit 1c867a0762e30de4591890ea208849f793742c1b
+    ○  snapshot working copy
+    ○  add workspace 'default'
+    ○
+    [EOF]
+    ");
+
+    // Abandon the snapshot operations except for the current one.
+    let output = work_dir.run_jj(["op", "abandon", "snapshot() & ..@-"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Abandoned 2 operations and reparented 3 descendant operations.
+    [EOF]
+    ");
+    insta::assert_snapshot!(work_dir.run_jj(["op", "log", template]), @r"
+    @  snapshot working copy
+    ○  commit a6cd5f9235cce74459ce5dee8c5f92a809b1563f
+    ○  commit 1c867a0762e30de4591890ea208849f793742c1b
+    ○  add workspace 'default'
+    ○
+    [EOF]
+    ");
+
+    // Can't abandon the root operation.
+    let output = work_dir.run_jj(["op", "abandon", "root() | @-"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Cannot abandon the root operation
+    [EOF]
+    [exit status: 1]
+    ");
+
+    // Bad expression
+    let output = work_dir.run_jj(["op", "abandon", "commit()"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Failed to parse operation expression: Function `commit` doesn't exist
+    Caused by:  --> 1:1
+      |
+    1 | commit()
+      | ^----^
+      |
+      = Function `commit` doesn't exist
+    [EOF]
+    [exit status: 1]
+    ");
+}
+
 #[test]
 fn test_op_abandon_without_updating_working_copy() {
     let test_env = TestEnvironment::default();
@@ -843,7 +1077,7 @@ fn test_op_abandon_multiple_heads() {
     let output = work_dir.run_jj(["op", "abandon", prev_op_id]);
     insta::assert_snapshot!(output, @r"
     ------- stderr -------
-    Abandoned 1 operations and reparented 2 descendant operations.
+    Abandoned 1 operations and reparented 1 descendant operations.
     [EOF]
     ");
 
@@ -1047,7 +1281,7 @@ fn test_op_summary_diff_template() {
     ]);
     insta::assert_snapshot!(output, @r"
     From operation: [38;5;4m000000000000[39m [38;5;2mroot()[39m
-      To operation: [38;5;4me3792fce5b1f[39m ([38;5;6m2001-02-03 08:05:09[39m) undo operation ac20a4ff47914da9a2e43677b94455b86383bfb9227374d6531ecee85b9ff9230eeb96416a24bb27e7477aa18d50c01810e97c6a008b5c584224650846f4c05b
+      To operation: [38;5;4me378433b4a01[39m ([38;5;6m2001-02-03 08:05:09[39m) undo operation ac20a4ff47914da9a2e43677b94455b86383bfb9227374d6531ecee85b9ff9230eeb96416a24bb27e7477aa18d50c01810e97c6a008b5c584224650846f4c05b
 
     Changed commits:
     ○  [38;5;2m+[39m [1m[38;5;5mq[0m[38;5;8mpvuntsm[39m [1m[38;5;4m2[0m[38;5;8m30dd059[39m [38;5;2m(empty)[39m [38;5;2m(no description set)[39m
@@ -1065,7 +1299,7 @@ fn test_op_summary_diff_template() {
     let output = work_dir.run_jj(["op", "undo", "--color=debug"]);
     insta::assert_snapshot!(output, @r"
     ------- stderr -------
-    Undid operation: [38;5;4m<<operation id short::2301f6e6ec31>>[39m<<operation:: (>>[38;5;6m<<operation time end local format::2001-02-03 08:05:11>>[39m<<operation::) >><<operation description first_line::new empty commit>>
+    Undid operation: [38;5;4m<<operation id short::2fb25fccc13b>>[39m<<operation:: (>>[38;5;6m<<operation time end local format::2001-02-03 08:05:11>>[39m<<operation::) >><<operation description first_line::new empty commit>>
     [EOF]
     ");
     let output = work_dir.run_jj([
@@ -1079,7 +1313,7 @@ fn test_op_summary_diff_template() {
     ]);
     insta::assert_snapshot!(output, @r"
     From operation: [38;5;4m<<operation id short::000000000000>>[39m<<operation:: >>[38;5;2m<<operation root::root()>>[39m
-      To operation: [38;5;4m<<operation id short::d208ae1b4e3c>>[39m<<operation:: (>>[38;5;6m<<operation time end local format::2001-02-03 08:05:12>>[39m<<operation::) >><<operation description first_line::undo operation 2301f6e6ec31931a9b0a594742d6035a44c05250d1707f7f8678e888b11a98773ef07bf0e8008a5bccddf7114da4a35d1a1b1f7efa37c1e6c80d6bdb8f0d7a90>>
+      To operation: [38;5;4m<<operation id short::c4e684cbd3c9>>[39m<<operation:: (>>[38;5;6m<<operation time end local format::2001-02-03 08:05:12>>[39m<<operation::) >><<operation description first_line::undo operation 2fb25fccc13bee4308468b08bc79e470b55707a7a2218b4b67ee56247794497e6d6b8fb1f1669fa4b8abafe4b07c598813e12492a48d148ec308e51c2604a5c1>>
 
     Changed commits:
     ○  [38;5;2m<<diff added::+>>[39m [1m[38;5;5m<<change_id shortest prefix::q>>[0m[38;5;8m<<change_id shortest rest::pvuntsm>>[39m [1m[38;5;4m<<commit_id shortest prefix::2>>[0m[38;5;8m<<commit_id shortest rest::30dd059>>[39m [38;5;2m<<empty::(empty)>>[39m [38;5;2m<<empty description placeholder::(no description set)>>[39m
@@ -0,0 +1,307 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use indoc::indoc;
+
+use crate::common::create_commit_with_files;
+use crate::common::CommandOutput;
+use crate::common::TestEnvironment;
+use crate::common::TestWorkDir;
+
+const MBOX: &str = indoc! {"
+    From 2443ea76b0b1c531326908326aab7020abab8e6c Mon Sep 17 00:00:00 2001
+    From: Some One <some.one@example.com>
+    Date: Tue, 3 Jun 2025 10:00:00 +0200
+    Subject: [PATCH 1/2] file: capitalize first line
+
+    Longer description
+    of the change.
+    ---
+     file | 2 +-
+     1 file changed, 1 insertion(+), 1 deletion(-)
+
+    diff --git a/file b/file
+    index 7898192..1234567 100644
+    --- a/file
+    +++ b/file
+    @@ -1,2 +1,2 @@
+    -a
+    +A
+     b
+    --
+    2.49.0
+
+    From 14e954305d4b5b5c5f7d0ba84e6a00a3b8d0dd88 Mon Sep 17 00:00:00 2001
+    From: =?UTF-8?q?J=C3=B6rg?= <jorg@example.com>
+    Date: Tue, 3 Jun 2025 11:00:00 +0200
+    Subject: [PATCH 2/2] new: add file
+
+    ---
+    diff --git a/new b/new
+    new file mode 100755
+    --- /dev/null
+    +++ b/new
+    @@ -0,0 +1 @@
+    +new
+    --
+    2.49.0
+"};
+
+#[test]
+fn test_patch_apply_mbox() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    create_commit_with_files(&work_dir, "a", &[], &[("file", "a\nb\nc\n")]);
+    create_commit_with_files(&work_dir, "b", &["a"], &[]);
+    work_dir.write_file("patches.mbox", MBOX);
+
+    // Applying without a location is an error
+    let output = work_dir.run_jj(["patch", "apply", "patches.mbox"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    error: the following required arguments were not provided:
+      <--destination <REVSETS>|--insert-after <REVSETS>|--insert-before <REVSETS>>
+
+    Usage: jj patch apply <--destination <REVSETS>|--insert-after <REVSETS>|--insert-before <REVSETS>> <FILES>...
+
+    For more information, try '--help'.
+    [EOF]
+    [exit status: 2]
+    ");
+
+    let output = work_dir.run_jj(["patch", "apply", "patches.mbox", "-A", "a"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Applied 2 patches as follows:
+      yqosqzyt 8aa8d8db file: capitalize first line
+      spxsnpux 280ee9c6 new: add file
+    Rebased 1 descendant commits
+    Working copy  (@) now at: zsuskuln b12e86c1 b | b
+    Parent commit (@-)      : spxsnpux 280ee9c6 new: add file
+    Added 1 files, modified 1 files, removed 0 files
+    [EOF]
+    ");
+    insta::assert_snapshot!(get_log_output(&work_dir), @r"
+    @  b12e86c1ff5f Test User b
+    ○  280ee9c65696 Jörg new: add file
+    ○  8aa8d8db878d Some One file: capitalize first line
+    ○  e31e27d11488 Test User a
+    ◆  000000000000
+    [EOF]
+    ");
+    let output = work_dir.run_jj([
+        "log",
+        "--no-graph",
+        "-r",
+        "a+ | a++",
+        "-T",
+        "builtin_log_detailed",
+    ]);
+    insta::assert_snapshot!(output, @r"
+    Commit ID: 280ee9c656961b6568c11234171f96d4dc846312
+    Change ID: spxsnpuxtvxqmrpprwlyptpvywrzkoyl
+    Author   : Jörg <jorg@example.com> (2025-06-03 20:00:00)
+    Committer: Test User <test.user@example.com> (2001-02-03 08:05:13)
+
+        new: add file
+
+    Commit ID: 8aa8d8db878d2b9637f2799fcd8b2050a607b5fa
+    Change ID: yqosqzytrlswkspswpqrmlplxylrzsnz
+    Author   : Some One <some.one@example.com> (2025-06-03 19:00:00)
+    Committer: Test User <test.user@example.com> (2001-02-03 08:05:13)
+
+        file: capitalize first line
+
+        Longer description
+        of the change.
+
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["diff", "--git", "-r", "a+ | a++"]);
+    insta::assert_snapshot!(output, @r"
+    diff --git a/file b/file
+    index de980441c3..4f213f7002 100644
+    --- a/file
+    +++ b/file
+    @@ -1,3 +1,3 @@
+    -a
+    +A
+     b
+     c
+    diff --git a/new b/new
+    new file mode 100755
+    index 0000000000..3e757656cf
+    --- /dev/null
+    +++ b/new
+    @@ -0,0 +1,1 @@
+    +new
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_patch_apply_stdin() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    create_commit_with_files(&work_dir, "a", &[], &[("file", "a\nb\nc\n")]);
+    let patch = work_dir
+        .run_jj(["diff", "--git", "-r", "a"])
+        .success()
+        .stdout;
+
+    // A plain diff is applied without changing the description
+    let output = work_dir.run_jj_with(|cmd| {
+        cmd.args(["patch", "apply", "-", "-d", "root()"])
+            .write_stdin(patch.raw())
+    });
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Applied patch as follows:
+      mzvwutvl 12a06163 (no description set)
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["diff", "-s", "--from", "a", "--to", "root()+ ~ a"]);
+    insta::assert_snapshot!(output, @"");
+
+    // A patch without changes is skipped
+    let output = work_dir.run_jj_with(|cmd| {
+        cmd.args(["patch", "apply", "-", "-d", "root()"])
+            .write_stdin("Subject: nothing\n\nno diff\n")
+    });
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Warning: Skipping patch without changes in -: nothing
+    No patches to apply.
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_patch_apply_conflict() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    create_commit_with_files(&work_dir, "a", &[], &[("file", "x\nb\nc\n")]);
+    work_dir.write_file("patches.mbox", MBOX);
+
+    let output = work_dir.run_jj(["patch", "apply", "patches.mbox", "-d", "a"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Applied 2 patches as follows:
+      zsuskuln 0d8d2dd5 (conflict) file: capitalize first line
+      rzvqmyuk b21be381 (conflict) new: add file
+    New conflicts appeared in 2 commits:
+      rzvqmyuk b21be381 (conflict) new: add file
+      zsuskuln 0d8d2dd5 (conflict) file: capitalize first line
+    Hint: To resolve the conflicts, start by updating to the first one:
+      jj new zsuskuln
+    Then use `jj resolve`, or edit the conflict markers in the file directly.
+    Once the conflicts are resolved, you may want to inspect the result with `jj diff`.
+    Then run `jj squash` to move the resolution into the conflicted commit.
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["file", "show", "-r", "a+", "file"]);
+    insta::assert_snapshot!(output, @r"
+    <<<<<<< Conflict 1 of 1
+    %%%%%%% Changes from base to side #1
+    -a
+    +x
+    +++++++ Contents of side #2
+    A
+    >>>>>>> Conflict 1 of 1 ends
+    b
+    c
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_patch_apply_to_conflicted_file() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    create_commit_with_files(&work_dir, "a", &[], &[("file", "x\nb\nc\n")]);
+    work_dir.write_file("1.patch", "--- a/file\n+++ b/file\n@@ -1 +1 @@\n-a\n+A\n");
+    work_dir.write_file("2.patch", "--- a/file\n+++ b/file\n@@ -3 +3 @@\n-c\n+C\n");
+
+    // The second patch applies to the file conflicted by the first one
+    work_dir
+        .run_jj(["patch", "apply", "1.patch", "2.patch", "-d", "a"])
+        .success();
+    let output = work_dir.run_jj(["file", "show", "-r", "a++", "file"]);
+    insta::assert_snapshot!(output, @r"
+    <<<<<<< Conflict 1 of 1
+    %%%%%%% Changes from base to side #1
+    -a
+    +x
+    +++++++ Contents of side #2
+    A
+    >>>>>>> Conflict 1 of 1 ends
+    b
+    C
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_patch_apply_errors() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    let output = work_dir.run_jj(["patch", "apply", "missing.patch", "-d@"]);
+    insta::assert_snapshot!(output.normalize_stderr_with(|s| s.replace("\\", "/")), @r"
+    ------- stderr -------
+    Error: Failed to read missing.patch
+    Caused by: No such file or directory (os error 2)
+    [EOF]
+    [exit status: 1]
+    ");
+
+    work_dir.write_file("bad.patch", "--- a/file\n+++ b/file\n@@ -1 +1 @@\n");
+    let output = work_dir.run_jj(["patch", "apply", "bad.patch", "-d@"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Failed to parse bad.patch
+    Caused by: Truncated hunk at line 4
+    [EOF]
+    [exit status: 1]
+    ");
+
+    work_dir.write_file(
+        "missing-file.patch",
+        "diff --git a/file b/renamed\nrename from file\nrename to renamed\n",
+    );
+    let output = work_dir.run_jj(["patch", "apply", "missing-file.patch", "-d@"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Failed to apply patch #1
+    Caused by: File file doesn't exist
+    [EOF]
+    [exit status: 1]
+    ");
+}
+
+#[must_use]
+fn get_log_output(work_dir: &TestWorkDir) -> CommandOutput {
+    let template =
+        r#"commit_id.short() ++ " " ++ author.name() ++ " " ++ description.first_line()"#;
+    work_dir.run_jj(["log", "-T", template])
+}
@@ -1130,6 +1130,54 @@ fn test_resolve_conflicts_with_executable() {
     ");
 }
 
+#[test]
+fn test_resolve_file_value_filesets() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    // Create conflicts in "file1" and "file2", where one side of "file1" set
+    // the executable bit.
+    create_commit_with_files(
+        &work_dir,
+        "base",
+        &[],
+        &[("file1", "base1\n"), ("file2", "base2\n")],
+    );
+    create_commit_with_files(
+        &work_dir,
+        "a",
+        &["base"],
+        &[("file1", "a1\n"), ("file2", "a2\n")],
+    );
+    create_commit_with_files(
+        &work_dir,
+        "b",
+        &["base"],
+        &[("file1", "b1\n"), ("file2", "b2\n")],
+    );
+    work_dir.run_jj(["file", "chmod", "x", "file1"]).success();
+    create_commit_with_files(&work_dir, "conflict", &["a", "b"], &[]);
+
+    let output = work_dir.run_jj(["resolve", "--list", "executable()"]);
+    insta::assert_snapshot!(output, @r"
+    file1    2-sided conflict including an executable
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["resolve", "--list", "~executable()"]);
+    insta::assert_snapshot!(output, @r"
+    file2    2-sided conflict
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["resolve", "--list", "file1 ~ executable()"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: No conflicts found at the given path(s)
+    [EOF]
+    [exit status: 2]
+    ");
+}
+
 #[test]
 fn test_resolve_change_delete_executable() {
     let mut test_env = TestEnvironment::default();
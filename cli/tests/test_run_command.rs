@@ -0,0 +1,167 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::common::create_commit_with_files;
+use crate::common::TestEnvironment;
+
+#[cfg(unix)]
+#[test]
+fn test_run_reports_each_revision() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    create_commit_with_files(&work_dir, "a", &[], &[("file", "ok\n")]);
+    create_commit_with_files(&work_dir, "b", &["a"], &[("file", "bad\n")]);
+    create_commit_with_files(&work_dir, "c", &["b"], &[("file", "ok\n")]);
+
+    let output = work_dir.run_jj(["run", "grep -q ok file", "-r", "a::c", "-j", "2"]);
+    insta::assert_snapshot!(output, @r"
+    PASS royxmykx 6e0d0ec9 c | c
+    FAIL zsuskuln cc478c46 b | b
+    PASS rlvkpnrz afd1bd91 a | a
+    [EOF]
+    ------- stderr -------
+    Warning: Command failed in zsuskuln cc478c46 b | b
+    Error: Command failed in 1 of 3 revisions
+    [EOF]
+    [exit status: 1]
+    ");
+
+    let output = work_dir.run_jj(["run", "test -f file", "-r", "a|c"]);
+    insta::assert_snapshot!(output, @r"
+    PASS royxmykx 6e0d0ec9 c | c
+    PASS rlvkpnrz afd1bd91 a | a
+    [EOF]
+    ");
+
+    // The temporary working copies are cleaned up
+    assert!(work_dir
+        .root()
+        .join(".jj")
+        .join("run")
+        .read_dir()
+        .unwrap()
+        .next()
+        .is_none());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_run_rewrite() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    create_commit_with_files(&work_dir, "a", &[], &[("file", "a\n")]);
+    create_commit_with_files(&work_dir, "b", &["a"], &[("file", "a\nb\n")]);
+    create_commit_with_files(&work_dir, "c", &["b"], &[("other", "c\n")]);
+
+    // Without --rewrite, changes made by the command are discarded
+    let output = work_dir.run_jj(["run", "echo new >new", "-r", "a"]);
+    insta::assert_snapshot!(output, @r"
+    PASS rlvkpnrz 560b6cc8 a | a
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["file", "list", "-r", "c"]);
+    insta::assert_snapshot!(output, @r"
+    file
+    other
+    [EOF]
+    ");
+
+    // Changes are applied to the selected revisions, and descendants are
+    // rebased on top
+    let output = work_dir.run_jj([
+        "run",
+        "echo \"$(cat file)\" >copy",
+        "-r",
+        "a|b",
+        "--rewrite",
+    ]);
+    insta::assert_snapshot!(output, @r"
+    PASS zsuskuln cc48f49e b | b
+    PASS rlvkpnrz 560b6cc8 a | a
+    [EOF]
+    ------- stderr -------
+    Rewrote 2 commits of 2 checked.
+    Working copy  (@) now at: royxmykx 53137c1b c | c
+    Parent commit (@-)      : zsuskuln 1c788743 b | b
+    Added 1 files, modified 0 files, removed 0 files
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["file", "show", "-r", "a", "copy"]);
+    insta::assert_snapshot!(output, @r"
+    a
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["file", "show", "-r", "c", "copy"]);
+    insta::assert_snapshot!(output, @r"
+    a
+    b
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["file", "list", "-r", "c"]);
+    insta::assert_snapshot!(output, @r"
+    copy
+    file
+    other
+    [EOF]
+    ");
+
+    // Changes left behind by a failed command aren't recorded
+    let output = work_dir.run_jj([
+        "run",
+        "echo partial >>file; test -e other || exit 1",
+        "-r",
+        "b|c",
+        "--rewrite",
+    ]);
+    insta::assert_snapshot!(output, @r"
+    PASS royxmykx 53137c1b c | c
+    FAIL zsuskuln 1c788743 b | b
+    [EOF]
+    ------- stderr -------
+    Warning: Command failed in zsuskuln 1c788743 b | b, skipped rewriting it
+    Rewrote 1 commit of 2 checked.
+    Working copy  (@) now at: royxmykx 9144cf6b c | c
+    Parent commit (@-)      : zsuskuln 1c788743 b | b
+    Added 0 files, modified 1 files, removed 0 files
+    Error: Command failed in 1 of 2 revisions
+    [EOF]
+    [exit status: 1]
+    ");
+    let output = work_dir.run_jj(["file", "show", "-r", "b", "file"]);
+    insta::assert_snapshot!(output, @r"
+    a
+    b
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["file", "show", "-r", "c", "file"]);
+    insta::assert_snapshot!(output, @r"
+    a
+    b
+    partial
+    [EOF]
+    ");
+
+    // Immutable revisions can't be rewritten
+    let output = work_dir.run_jj(["run", "true", "-r", "root()", "--rewrite"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: The root commit 000000000000 is immutable
+    [EOF]
+    [exit status: 1]
+    ");
+}
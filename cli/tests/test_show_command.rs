@@ -363,3 +363,127 @@ fn test_show_relative_timestamps() {
     [EOF]
     ");
 }
+
+#[test]
+fn test_show_json_output() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.write_file("file1", "foo\n");
+    work_dir.run_jj(["describe", "-m", "add file1"]).success();
+
+    let output = work_dir.run_jj(["show", "--output=json"]);
+    insta::assert_snapshot!(output, @r#"
+    {
+      "schema_version": 1,
+      "commit_id": "ee807f7deadc1688384de66d352e49a8e875039a",
+      "change_id": "qpvuntsmwlqtpsluzzsnyyzlmlwvmlnu",
+      "parents": [
+        "0000000000000000000000000000000000000000"
+      ],
+      "description": "add file1\n",
+      "trailers": [],
+      "author": {
+        "name": "Test User",
+        "email": "test.user@example.com",
+        "timestamp": "2001-02-03T04:05:08.000+07:00"
+      },
+      "committer": {
+        "name": "Test User",
+        "email": "test.user@example.com",
+        "timestamp": "2001-02-03T04:05:08.000+07:00"
+      },
+      "mine": true,
+      "signed": false,
+      "signature": null,
+      "working_copies": [
+        "default"
+      ],
+      "current_working_copy": true,
+      "bookmarks": [],
+      "local_bookmarks": [],
+      "remote_bookmarks": [],
+      "tags": [],
+      "git_refs": [],
+      "git_head": false,
+      "divergent": false,
+      "hidden": false,
+      "immutable": false,
+      "conflict": false,
+      "empty": false,
+      "root": false
+    }
+    [EOF]
+    "#);
+
+    let output = work_dir.run_jj(["show", "--output=ndjson", "root()"]);
+    insta::assert_snapshot!(output, @r#"
+    {"schema_version":1,"commit_id":"0000000000000000000000000000000000000000","change_id":"zzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzz","parents":[],"description":"","trailers":[],"author":{"name":"","email":"","timestamp":"1970-01-01T00:00:00.000+00:00"},"committer":{"name":"","email":"","timestamp":"1970-01-01T00:00:00.000+00:00"},"mine":false,"signed":false,"signature":null,"working_copies":[],"current_working_copy":false,"bookmarks":[],"local_bookmarks":[],"remote_bookmarks":[],"tags":[],"git_refs":[],"git_head":false,"divergent":false,"hidden":false,"immutable":true,"conflict":false,"empty":true,"root":true}
+    [EOF]
+    "#);
+
+    let output = work_dir.run_jj(["show", "--output=json", "--git"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    error: the argument '--output <FORMAT>' cannot be used with:
+      --summary
+      --stat
+      --types
+      --name-only
+      --git
+      --color-words
+      --json
+      --tool <TOOL>
+      --context <CONTEXT>
+      --ignore-all-space
+      --ignore-space-change
+
+    Usage: jj show --output <FORMAT> --git [REVSET]
+
+    For more information, try '--help'.
+    [EOF]
+    [exit status: 2]
+    ");
+}
+
+#[test]
+fn test_show_json_output_signed() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config(
+        r#"
+    signing.behavior = "own"
+    signing.backend = "test"
+    signing.key = "impeccable"
+    "#,
+    );
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    work_dir.run_jj(["describe", "-m", "signed"]).success();
+
+    // Signatures aren't verified by default
+    let output = work_dir.run_jj(["show", "--output=ndjson"]);
+    insta::assert_snapshot!(output, @r#"
+    {"schema_version":1,"commit_id":"8c03c0474d086d6def083d44f8ae1f7570728725","change_id":"qpvuntsmwlqtpsluzzsnyyzlmlwvmlnu","parents":["0000000000000000000000000000000000000000"],"description":"signed\n","trailers":[],"author":{"name":"Test User","email":"test.user@example.com","timestamp":"2001-02-03T04:05:08.000+07:00"},"committer":{"name":"Test User","email":"test.user@example.com","timestamp":"2001-02-03T04:05:08.000+07:00"},"mine":true,"signed":true,"signature":null,"working_copies":["default"],"current_working_copy":true,"bookmarks":[],"local_bookmarks":[],"remote_bookmarks":[],"tags":[],"git_refs":[],"git_head":false,"divergent":false,"hidden":false,"immutable":false,"conflict":false,"empty":true,"root":false}
+    [EOF]
+    "#);
+
+    let output = work_dir.run_jj(["show", "--output=ndjson", "--verify-signatures"]);
+    insta::assert_snapshot!(output, @r#"
+    {"schema_version":1,"commit_id":"8c03c0474d086d6def083d44f8ae1f7570728725","change_id":"qpvuntsmwlqtpsluzzsnyyzlmlwvmlnu","parents":["0000000000000000000000000000000000000000"],"description":"signed\n","trailers":[],"author":{"name":"Test User","email":"test.user@example.com","timestamp":"2001-02-03T04:05:08.000+07:00"},"committer":{"name":"Test User","email":"test.user@example.com","timestamp":"2001-02-03T04:05:08.000+07:00"},"mine":true,"signed":true,"signature":{"status":"good","trust":"unknown","key":"impeccable","display":"test-display"},"working_copies":["default"],"current_working_copy":true,"bookmarks":[],"local_bookmarks":[],"remote_bookmarks":[],"tags":[],"git_refs":[],"git_head":false,"divergent":false,"hidden":false,"immutable":false,"conflict":false,"empty":true,"root":false}
+    [EOF]
+    "#);
+
+    let output = work_dir.run_jj(["show", "--verify-signatures"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    error: the following required arguments were not provided:
+      --output <FORMAT>
+
+    Usage: jj show --output <FORMAT> --verify-signatures [REVSET]
+
+    For more information, try '--help'.
+    [EOF]
+    [exit status: 2]
+    ");
+}
@@ -279,6 +279,63 @@ backend = "test"
     ");
 }
 
+#[test]
+fn test_sign_missing() {
+    let test_env = TestEnvironment::default();
+
+    test_env.add_config(
+        r#"
+[ui]
+show-cryptographic-signatures = true
+
+[signing]
+behavior = "keep"
+backend = "test"
+"#,
+    );
+
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    work_dir.run_jj(["commit", "-m", "one"]).success();
+    work_dir.run_jj(["commit", "-m", "two"]).success();
+    work_dir.run_jj(["commit", "-m", "three"]).success();
+    work_dir.run_jj(["sign", "-r", "@--"]).success();
+
+    // Only the unsigned commits are signed
+    let output = work_dir.run_jj(["sign", "--missing", "-r", "..@-"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Signed 2 commits:
+      qpvuntsm 8174ec98 (empty) one
+      kkmpptxz bcfaa4c3 (empty) three
+    Rebased 2 descendant commits
+    Working copy  (@) now at: zsuskuln eeb8c985 (empty) (no description set)
+    Parent commit (@-)      : kkmpptxz bcfaa4c3 (empty) three
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["log", "-r", "all()"]);
+    insta::assert_snapshot!(output, @r"
+    @  zsuskuln test.user@example.com 2001-02-03 08:05:12 eeb8c985
+    │  (empty) (no description set)
+    ○  kkmpptxz test.user@example.com 2001-02-03 08:05:12 bcfaa4c3 [✓︎]
+    │  (empty) three
+    ○  rlvkpnrz test.user@example.com 2001-02-03 08:05:12 6500b275 [✓︎]
+    │  (empty) two
+    ○  qpvuntsm test.user@example.com 2001-02-03 08:05:12 8174ec98 [✓︎]
+    │  (empty) one
+    ◆  zzzzzzzz root() 00000000
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["sign", "--missing", "-r", "..@-"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    No commits with missing signatures.
+    [EOF]
+    ");
+}
+
 #[test]
 fn test_abort_with_error_if_no_signing_backend_is_configured() {
     let test_env = TestEnvironment::default();
@@ -201,3 +201,149 @@ fn test_sparse_editor_avoids_unc() {
     // over 260 chars.
     assert_eq!(edited_path, dunce::simplified(&edited_path));
 }
+
+#[test]
+fn test_sparse_profile() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.write_file(
+        ".jj-sparse/frontend",
+        "# The web UI\n.jj-sparse\nweb\nglob:'docs/*.md'\n",
+    );
+    work_dir.write_file("web/index.html", "contents");
+    work_dir.write_file("docs/guide.md", "contents");
+    work_dir.write_file("docs/notes.txt", "contents");
+    work_dir.write_file("server/main.rs", "contents");
+    work_dir.run_jj(["new"]).success();
+
+    // The profile is read from the parent of the working-copy commit
+    let output = work_dir.run_jj(["sparse", "use", "frontend"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Using sparse profile frontend
+    Added 0 files, modified 0 files, removed 2 files
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["sparse", "list"]);
+    insta::assert_snapshot!(output, @r"
+    .jj-sparse
+    web
+    glob:'docs/*.md'
+    [EOF]
+    ------- stderr -------
+    Patterns from sparse profile frontend:
+    [EOF]
+    ");
+    assert!(work_dir.root().join("web/index.html").exists());
+    assert!(work_dir.root().join("docs/guide.md").exists());
+    assert!(!work_dir.root().join("docs/notes.txt").exists());
+    assert!(!work_dir.root().join("server/main.rs").exists());
+
+    // More paths can be added on top of the profile
+    let output = work_dir.run_jj(["sparse", "set", "--add", "docs"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Added 1 files, modified 0 files, removed 0 files
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["sparse", "list"]);
+    insta::assert_snapshot!(output, @r"
+    docs
+    .jj-sparse
+    web
+    glob:'docs/*.md'
+    [EOF]
+    ------- stderr -------
+    Patterns from sparse profile frontend:
+    [EOF]
+    ");
+
+    // Changes to the profile take effect once committed to the parent
+    work_dir.write_file(
+        ".jj-sparse/frontend",
+        "# The web UI\n.jj-sparse\nweb\nserver\n",
+    );
+    assert!(!work_dir.root().join("server/main.rs").exists());
+    let output = work_dir.run_jj(["new"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Working copy  (@) now at: yqosqzyt 512a858e (empty) (no description set)
+    Parent commit (@-)      : rlvkpnrz d5ad5aa3 (no description set)
+    Added 1 files, modified 0 files, removed 0 files
+    [EOF]
+    ");
+    assert!(work_dir.root().join("server/main.rs").exists());
+    let output = work_dir.run_jj(["sparse", "list"]);
+    insta::assert_snapshot!(output, @r"
+    docs
+    .jj-sparse
+    web
+    server
+    [EOF]
+    ------- stderr -------
+    Patterns from sparse profile frontend:
+    [EOF]
+    ");
+
+    // The previous version of the profile is kept if it's missing
+    work_dir
+        .run_jj(["bookmark", "create", "-r@-", "profile"])
+        .success();
+    let output = work_dir.run_jj(["new", "root()"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Warning: Failed to update the working copy to the new sparse profile: No such sparse profile: frontend
+    Hint: The working copy still uses the previous version of the profile.
+    Working copy  (@) now at: znkkpsqq 10dd328b (empty) (no description set)
+    Parent commit (@-)      : zzzzzzzz 00000000 (empty) (no description set)
+    Added 0 files, modified 0 files, removed 5 files
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["sparse", "list"]);
+    insta::assert_snapshot!(output, @r"
+    docs
+    .jj-sparse
+    web
+    server
+    [EOF]
+    ------- stderr -------
+    Patterns from sparse profile frontend:
+    [EOF]
+    ");
+
+    // Unknown or invalid profiles are rejected
+    let output = work_dir.run_jj(["sparse", "use", "backend"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: No such sparse profile: backend
+    [EOF]
+    [exit status: 1]
+    ");
+    work_dir.run_jj(["new", "profile"]).success();
+    work_dir.write_file(".jj-sparse/bad", "web\nfoo(\n");
+    work_dir.run_jj(["new"]).success();
+    let output = work_dir.run_jj(["sparse", "use", "bad"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Failed to parse sparse profile bad
+    Caused by:  --> 1:5
+      |
+    1 | foo(
+      |     ^---
+      |
+      = expected <expression>
+    [EOF]
+    [exit status: 1]
+    ");
+
+    // Reset stops using the profile
+    let output = work_dir.run_jj(["sparse", "reset"]);
+    insta::assert_snapshot!(output, @"");
+    let output = work_dir.run_jj(["sparse", "list"]);
+    insta::assert_snapshot!(output, @r"
+    .
+    [EOF]
+    ");
+}
@@ -467,3 +467,51 @@ fn test_status_untracked_files() {
     [EOF]
     ");
 }
+
+#[test]
+fn test_status_untracked_by_snapshot_settings() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config(
+        r#"
+        snapshot.max-new-file-size = 100
+        snapshot.untrack-binaries = true
+        snapshot.ignore-by-default = ["glob:'**/*.log'"]
+        snapshot.file-size-limits."assets" = 1000
+        "#,
+    );
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.write_file("small", "small\n");
+    work_dir.write_file("large", vec![b'a'; 200]);
+    work_dir.write_file("assets/large", vec![b'a'; 200]);
+    work_dir.write_file("assets/huge", vec![b'a'; 2000]);
+    work_dir.write_file("app", b"\x7fELF\x02\x01\x01\x00");
+    work_dir.write_file("sub/debug.log", "log\n");
+
+    let output = work_dir.run_jj(["status"]);
+    insta::assert_snapshot!(output.normalize_backslash(), @r"
+    Working copy changes:
+    A assets/large
+    A small
+    Untracked paths:
+    ? app (generated binary: ELF)
+    ? assets/huge (too large)
+    ? large (too large)
+    ? sub/debug.log (ignored by default)
+    Working copy  (@) : qpvuntsm 22fd9ec4 (no description set)
+    Parent commit (@-): zzzzzzzz 00000000 (empty) (no description set)
+    [EOF]
+    ------- stderr -------
+    Warning: Refused to snapshot some files:
+      assets/huge: 2.0KiB (2000 bytes); the maximum size allowed is 1.0KiB (1000 bytes)
+      large: 200.0B (200 bytes); the maximum size allowed is 100.0B (100 bytes)
+    Hint: This is to prevent large files from being added by accident. You can fix this by:
+      - Adding the file to `.gitignore`
+      - Run `jj config set --repo snapshot.max-new-file-size 2000`
+        This will increase the maximum file size allowed for new files, in this repository only.
+      - Run `jj --config snapshot.max-new-file-size=2000 st`
+        This will increase the maximum file size allowed for new files, for this command only.
+    [EOF]
+    ");
+}
@@ -125,3 +125,242 @@ fn test_tag_list() {
     [EOF]
     ");
 }
+
+#[test]
+fn test_tag_set_delete() {
+    let test_env = TestEnvironment::default();
+    test_env
+        .run_jj_in(".", ["git", "init", "--colocate", "repo"])
+        .success();
+    let work_dir = test_env.work_dir("repo");
+    let git_repo = git::open(work_dir.root());
+
+    work_dir.run_jj(["commit", "-mcommit1"]).success();
+    work_dir.run_jj(["commit", "-mcommit2"]).success();
+
+    let output = work_dir.run_jj(["tag", "set", "-r@--", "v1", "v2"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Created 2 tags pointing to qpvuntsm caf975d0 (empty) commit1
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["tag", "set", "-r@-", "v1", "v3"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Created 1 tags pointing to rlvkpnrz d0a19ea4 (empty) commit2
+    Moved 1 tags to rlvkpnrz d0a19ea4 (empty) commit2
+    [EOF]
+    ");
+    insta::assert_snapshot!(work_dir.run_jj(["tag", "list"]), @r"
+    v1: rlvkpnrz d0a19ea4 (empty) commit2
+    v2: qpvuntsm caf975d0 (empty) commit1
+    v3: rlvkpnrz d0a19ea4 (empty) commit2
+    [EOF]
+    ");
+
+    // The tags are exported to Git
+    let tag_target = |name: &str| {
+        git_repo
+            .find_reference(name)
+            .ok()
+            .map(|git_ref| git_ref.id().to_string())
+    };
+    assert!(tag_target("refs/tags/v1").is_some());
+    assert_eq!(tag_target("refs/tags/v1"), tag_target("refs/tags/v3"));
+    assert_ne!(tag_target("refs/tags/v1"), tag_target("refs/tags/v2"));
+
+    // Moving backwards is refused
+    let output = work_dir.run_jj(["tag", "set", "-r@--", "v1"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Refusing to move tag backwards or sideways: v1
+    Hint: Use --allow-backwards to allow it.
+    [EOF]
+    [exit status: 1]
+    ");
+    let output = work_dir.run_jj(["tag", "set", "-r@--", "--allow-backwards", "v1"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Moved 1 tags to qpvuntsm caf975d0 (empty) commit1
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["tag", "delete", "glob:v[12]"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Deleted 2 tags.
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["tag", "delete", "v1"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: No such tag: v1
+    [EOF]
+    [exit status: 1]
+    ");
+    insta::assert_snapshot!(work_dir.run_jj(["tag", "list"]), @r"
+    v3: rlvkpnrz d0a19ea4 (empty) commit2
+    [EOF]
+    ");
+    assert_eq!(tag_target("refs/tags/v1"), None);
+    assert_eq!(tag_target("refs/tags/v2"), None);
+    assert!(tag_target("refs/tags/v3").is_some());
+
+    // The tags aren't resurrected by the next import
+    work_dir.run_jj(["git", "import"]).success();
+    insta::assert_snapshot!(work_dir.run_jj(["tag", "list"]), @r"
+    v3: rlvkpnrz d0a19ea4 (empty) commit2
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_tag_move() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.run_jj(["commit", "-mcommit1"]).success();
+    work_dir.run_jj(["commit", "-mcommit2"]).success();
+    work_dir
+        .run_jj(["tag", "set", "-r@--", "foo", "bar"])
+        .success();
+    work_dir.run_jj(["tag", "set", "-r@-", "baz"]).success();
+
+    let output = work_dir.run_jj(["tag", "move", "--to=@-", "glob:ba?"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Moved 1 tags to rlvkpnrz d0a19ea4 (empty) commit2
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["tag", "move", "--from=@--", "--to=@-"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Moved 1 tags to rlvkpnrz d0a19ea4 (empty) commit2
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["tag", "move", "--to=@-", "foo"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    No tags to update.
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["tag", "move", "--to=@--", "foo"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Refusing to move tag backwards or sideways: foo
+    Hint: Use --allow-backwards to allow it.
+    [EOF]
+    [exit status: 1]
+    ");
+    let output = work_dir.run_jj(["tag", "move", "--to=@--", "--allow-backwards", "foo"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Moved 1 tags to qpvuntsm caf975d0 (empty) commit1
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["tag", "move", "--to=@-", "nonexistent"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: No such tag: nonexistent
+    [EOF]
+    [exit status: 1]
+    ");
+    insta::assert_snapshot!(work_dir.run_jj(["tag", "list"]), @r"
+    bar: rlvkpnrz d0a19ea4 (empty) commit2
+    baz: rlvkpnrz d0a19ea4 (empty) commit2
+    foo: qpvuntsm caf975d0 (empty) commit1
+    [EOF]
+    ");
+
+    // Tags created in jj are exported by `jj git export`
+    work_dir.run_jj(["git", "export"]).success();
+    let git_repo = git::open(work_dir.root().join(".jj/repo/store/git"));
+    assert!(git_repo.find_reference("refs/tags/foo").is_ok());
+}
+
+#[test]
+fn test_tag_set_annotated() {
+    let test_env = TestEnvironment::default();
+    test_env
+        .run_jj_in(".", ["git", "init", "--colocate", "repo"])
+        .success();
+    let work_dir = test_env.work_dir("repo");
+    let git_repo = git::open(work_dir.root());
+
+    work_dir.run_jj(["commit", "-mcommit1"]).success();
+    let output = work_dir.run_jj(["tag", "set", "-r@-", "-m", "Release 1.0", "v1.0"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Created 1 tags pointing to qpvuntsm caf975d0 (empty) commit1
+    [EOF]
+    ");
+    insta::assert_snapshot!(work_dir.run_jj(["tag", "list"]), @r"
+    v1.0: qpvuntsm caf975d0 (empty) commit1
+    [EOF]
+    ");
+
+    let tag = git_repo
+        .find_reference("refs/tags/v1.0")
+        .unwrap()
+        .id()
+        .object()
+        .unwrap()
+        .into_tag();
+    let tag = tag.decode().unwrap();
+    assert_eq!(tag.name, "v1.0");
+    assert_eq!(tag.message, "Release 1.0");
+    assert_eq!(tag.tagger.unwrap().email, "test.user@example.com");
+
+    // Moving an annotated tag rewrites it as a lightweight tag
+    work_dir.run_jj(["new"]).success();
+    work_dir.run_jj(["commit", "-mcommit2"]).success();
+    let output = work_dir.run_jj(["tag", "move", "--to=@-", "v1.0"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Moved 1 tags to mzvwutvl 82cb0ecd (empty) commit2
+    [EOF]
+    ");
+    let git_ref = git_repo.find_reference("refs/tags/v1.0").unwrap();
+    assert!(git_ref.id().object().unwrap().kind.is_commit());
+}
+
+#[test]
+fn test_tag_set_annotated_undo() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    let git_repo = git::open(work_dir.root().join(".jj/repo/store/git"));
+
+    // The annotation of an unexported tag is undone with the tag
+    work_dir.run_jj(["commit", "-mcommit1"]).success();
+    work_dir
+        .run_jj(["tag", "set", "-r@-", "-m", "Release 1.0", "v1.0"])
+        .success();
+    let output = work_dir.run_jj(["undo"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Undid operation: 7b2af89d066e (2001-02-03 08:05:09) point tag v1.0 to commit caf975d0989a4e84d6d2fd1047ea03663244969c
+    [EOF]
+    ");
+
+    // Setting the tag without a message exports a lightweight tag
+    work_dir.run_jj(["tag", "set", "-r@-", "v1.0"]).success();
+    work_dir.run_jj(["git", "export"]).success();
+    let git_ref = git_repo.find_reference("refs/tags/v1.0").unwrap();
+    assert!(git_ref.id().object().unwrap().kind.is_commit());
+
+    // An annotated tag is exported after it's set again with a message
+    work_dir
+        .run_jj(["tag", "set", "-r@-", "-m", "Release 1.0", "v1.0"])
+        .success();
+    work_dir.run_jj(["git", "export"]).success();
+    let tag = git_repo
+        .find_reference("refs/tags/v1.0")
+        .unwrap()
+        .id()
+        .object()
+        .unwrap()
+        .into_tag();
+    assert_eq!(tag.decode().unwrap().message, "Release 1.0");
+}
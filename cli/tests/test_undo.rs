@@ -407,7 +407,7 @@ fn test_bookmark_track_untrack_undo() {
     [EOF]
     ");
 
-    work_dir.run_jj(["undo"]).success();
+    work_dir.run_jj(["redo"]).success();
     insta::assert_snapshot!(get_bookmark_output(&work_dir), @r"
     feature1: qpvuntsm 8da1cfc8 (empty) commit
     feature1@origin: qpvuntsm 8da1cfc8 (empty) commit
@@ -435,37 +435,118 @@ fn test_bookmark_track_untrack_undo() {
 }
 
 #[test]
-fn test_shows_a_warning_when_undoing_an_undo_operation_as_bare_jj_undo() {
+fn test_undo_redo_stack() {
     let test_env = TestEnvironment::default();
     test_env.run_jj_in(".", ["git", "init", "repo"]).success();
     let work_dir = test_env.work_dir("repo");
 
-    // Double-undo creation of child
-    work_dir.run_jj(["new"]).success();
-    work_dir.run_jj(["undo"]).success();
+    work_dir.run_jj(["describe", "-m", "first"]).success();
+    work_dir.run_jj(["describe", "-m", "second"]).success();
+    work_dir.run_jj(["describe", "-m", "third"]).success();
+
+    // Repeated undos walk back through the operation log
     let output = work_dir.run_jj(["undo"]);
     insta::assert_snapshot!(output, @r"
     ------- stderr -------
-    Undid operation: 2d5b73a97567 (2001-02-03 08:05:09) undo operation 289cb69a8458456474a77cc432e8009b99f039cdcaf19ba4526753e97d70fee3fd0f410ff2b7c1d10cf0c2501702e7a85d58f9d813cdca567c377431ec4d2b97
-    Working copy  (@) now at: rlvkpnrz 65b6b74e (empty) (no description set)
-    Parent commit (@-)      : qpvuntsm 230dd059 (empty) (no description set)
-    Hint: This action reverted an 'undo' operation. The repository is now in the same state as it was before the original 'undo'.
-    Hint: If your goal is to undo multiple operations, consider using `jj op log` to see past states, and `jj op restore` to restore one of these states.
+    Undid operation: 21531adfd7cf (2001-02-03 08:05:10) describe commit 53aecb7ce88042051caf0cdcad69078542757d79
+    Working copy  (@) now at: qpvuntsm 53aecb7c (empty) second
+    Parent commit (@-)      : zzzzzzzz 00000000 (empty) (no description set)
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["undo"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Undid operation: 3d5b67a0e95f (2001-02-03 08:05:09) describe commit fa15625b4a986997697639dfc2844138900c79f2
+    Working copy  (@) now at: qpvuntsm fa15625b (empty) first
+    Parent commit (@-)      : zzzzzzzz 00000000 (empty) (no description set)
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["log", "-r@", "-T", "description"]);
+    insta::assert_snapshot!(output, @r"
+    @  first
+    │
+    ~
+    [EOF]
+    ");
+
+    // Redo walks forward again
+    let output = work_dir.run_jj(["redo"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Redid operation: 3d5b67a0e95f (2001-02-03 08:05:09) describe commit fa15625b4a986997697639dfc2844138900c79f2
+    Working copy  (@) now at: qpvuntsm 53aecb7c (empty) second
+    Parent commit (@-)      : zzzzzzzz 00000000 (empty) (no description set)
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["log", "-r@", "-T", "description"]);
+    insta::assert_snapshot!(output, @r"
+    @  second
+    │
+    ~
+    [EOF]
+    ");
+
+    // Undo after redo undoes the redone operation
+    let output = work_dir.run_jj(["undo"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Undid operation: 3d5b67a0e95f (2001-02-03 08:05:09) describe commit fa15625b4a986997697639dfc2844138900c79f2
+    Working copy  (@) now at: qpvuntsm fa15625b (empty) first
+    Parent commit (@-)      : zzzzzzzz 00000000 (empty) (no description set)
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["op", "log", "-n3"]);
+    insta::assert_snapshot!(output, @r"
+    @  b17f0956a240 test-username@host.example.com 2001-02-03 04:05:16.000 +07:00 - 2001-02-03 04:05:16.000 +07:00
+    │  undo operation 3d5b67a0e95f11046a99190ca3fe114095a87c9b9aa7668b1b3e4bb97ad6de6a4dd3e0231ecdaf4a9a4f1a2dd7d460dd933732f224331e099eab818741329afb
+    │  args: jj undo
+    │  undo: 3d5b67a0e95f11046a99190ca3fe114095a87c9b9aa7668b1b3e4bb97ad6de6a4dd3e0231ecdaf4a9a4f1a2dd7d460dd933732f224331e099eab818741329afb
+    ○  d812700f81ac test-username@host.example.com 2001-02-03 04:05:14.000 +07:00 - 2001-02-03 04:05:14.000 +07:00
+    │  redo operation 3d5b67a0e95f11046a99190ca3fe114095a87c9b9aa7668b1b3e4bb97ad6de6a4dd3e0231ecdaf4a9a4f1a2dd7d460dd933732f224331e099eab818741329afb
+    │  args: jj redo
+    │  redo: 3d5b67a0e95f11046a99190ca3fe114095a87c9b9aa7668b1b3e4bb97ad6de6a4dd3e0231ecdaf4a9a4f1a2dd7d460dd933732f224331e099eab818741329afb
+    ○  f14b50f6167e test-username@host.example.com 2001-02-03 04:05:12.000 +07:00 - 2001-02-03 04:05:12.000 +07:00
+    │  undo operation 3d5b67a0e95f11046a99190ca3fe114095a87c9b9aa7668b1b3e4bb97ad6de6a4dd3e0231ecdaf4a9a4f1a2dd7d460dd933732f224331e099eab818741329afb
+    │  args: jj undo
+    │  undo: 3d5b67a0e95f11046a99190ca3fe114095a87c9b9aa7668b1b3e4bb97ad6de6a4dd3e0231ecdaf4a9a4f1a2dd7d460dd933732f224331e099eab818741329afb
     [EOF]
     ");
 
-    // Double-undo creation of sibling
-    work_dir.run_jj(["new", "@-"]).success();
+    // A new operation clears the redo stack
+    work_dir.run_jj(["describe", "-m", "fourth"]).success();
+    let output = work_dir.run_jj(["redo"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Nothing to redo
+    Hint: Only operations undone by `jj undo` can be redone, and only until another operation is made.
+    [EOF]
+    [exit status: 1]
+    ");
+    let output = work_dir.run_jj(["undo"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Undid operation: 944e5eced03e (2001-02-03 08:05:18) describe commit fa15625b4a986997697639dfc2844138900c79f2
+    Working copy  (@) now at: qpvuntsm fa15625b (empty) first
+    Parent commit (@-)      : zzzzzzzz 00000000 (empty) (no description set)
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["log", "-r@", "-T", "description"]);
+    insta::assert_snapshot!(output, @r"
+    @  first
+    │
+    ~
+    [EOF]
+    ");
+
+    // Undoing past the root operation fails
+    work_dir.run_jj(["undo"]).success();
     work_dir.run_jj(["undo"]).success();
     let output = work_dir.run_jj(["undo"]);
     insta::assert_snapshot!(output, @r"
     ------- stderr -------
-    Undid operation: b16799358b33 (2001-02-03 08:05:12) undo operation b14487c6d6d98f7f575ea03c48ed92d899c2a0ecbe9458221b6fc11af2bf6d918c9620cae1f8268012b0e25c7dd6f78b19ec628d0504a0830dc562d6625ba9ec
-    Working copy  (@) now at: mzvwutvl 167f90e7 (empty) (no description set)
-    Parent commit (@-)      : qpvuntsm 230dd059 (empty) (no description set)
-    Hint: This action reverted an 'undo' operation. The repository is now in the same state as it was before the original 'undo'.
-    Hint: If your goal is to undo multiple operations, consider using `jj op log` to see past states, and `jj op restore` to restore one of these states.
+    Error: Cannot undo repo initialization
     [EOF]
+    [exit status: 1]
     ");
 }
 
@@ -482,7 +563,7 @@ fn test_shows_no_warning_when_undoing_a_specific_undo_change() {
     let output = work_dir.run_jj(["undo", &op_id_hex]);
     insta::assert_snapshot!(output, @r"
     ------- stderr -------
-    Undid operation: 2d5b73a97567 (2001-02-03 08:05:09) undo operation 289cb69a8458456474a77cc432e8009b99f039cdcaf19ba4526753e97d70fee3fd0f410ff2b7c1d10cf0c2501702e7a85d58f9d813cdca567c377431ec4d2b97
+    Undid operation: 87e3b280a0bb (2001-02-03 08:05:09) undo operation 289cb69a8458456474a77cc432e8009b99f039cdcaf19ba4526753e97d70fee3fd0f410ff2b7c1d10cf0c2501702e7a85d58f9d813cdca567c377431ec4d2b97
     Working copy  (@) now at: rlvkpnrz 65b6b74e (empty) (no description set)
     Parent commit (@-)      : qpvuntsm 230dd059 (empty) (no description set)
     [EOF]
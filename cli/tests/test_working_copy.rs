@@ -84,6 +84,8 @@ fn test_snapshot_large_file() {
         This will increase the maximum file size allowed for new files, in this repository only.
       - Run `jj --config snapshot.max-new-file-size=11264 file track large large2`
         This will increase the maximum file size allowed for new files, for this command only.
+      - Run `jj file track --force large large2`
+        This will track the files regardless of their size.
     [EOF]
     ");
 
@@ -260,6 +262,184 @@ fn test_materialize_and_snapshot_different_conflict_markers() {
     ");
 }
 
+#[test]
+fn test_conflict_marker_style_overrides() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    test_env.add_config(r#"ui.conflict-marker-style = "git""#);
+    work_dir
+        .run_jj([
+            "config",
+            "set",
+            "--repo",
+            r#"ui.conflict-marker-style-overrides."glob:*.lock""#,
+            "snapshot",
+        ])
+        .success();
+    work_dir
+        .run_jj([
+            "config",
+            "set",
+            "--repo",
+            r#"ui.conflict-marker-style-overrides."glob:*.txt""#,
+            "zdiff3",
+        ])
+        .success();
+
+    // Create the same conflict in several files
+    let base = "line 1\nline 2\nline 3\n";
+    let side_a = "line 1\ncommon\nline 2 - a\nline 3\n";
+    let side_b = "line 1\ncommon\nline 2 - b\nline 3\n";
+    for (description, content) in [("base", base), ("side-a", side_a)] {
+        for path in ["file", "file.lock", "file.txt"] {
+            work_dir.write_file(path, content);
+        }
+        work_dir.run_jj(["commit", "-m", description]).success();
+    }
+    work_dir
+        .run_jj(["new", "description(base)", "-m", "side-b"])
+        .success();
+    for path in ["file", "file.lock", "file.txt"] {
+        work_dir.write_file(path, side_b);
+    }
+    work_dir
+        .run_jj(["new", "description(side-a)", "description(side-b)"])
+        .success();
+
+    // Files not matching any override use the default style
+    insta::assert_snapshot!(work_dir.read_file("file"), @r"
+    line 1
+    <<<<<<< Side #1 (Conflict 1 of 1)
+    common
+    line 2 - a
+    ||||||| Base
+    line 2
+    =======
+    common
+    line 2 - b
+    >>>>>>> Side #2 (Conflict 1 of 1 ends)
+    line 3
+    ");
+    insta::assert_snapshot!(work_dir.read_file("file.lock"), @r"
+    line 1
+    <<<<<<< Conflict 1 of 1
+    +++++++ Contents of side #1
+    common
+    line 2 - a
+    ------- Contents of base
+    line 2
+    +++++++ Contents of side #2
+    common
+    line 2 - b
+    >>>>>>> Conflict 1 of 1 ends
+    line 3
+    ");
+    insta::assert_snapshot!(work_dir.read_file("file.txt"), @r"
+    line 1
+    common
+    <<<<<<< Side #1 (Conflict 1 of 1)
+    line 2 - a
+    ||||||| Base
+    line 2
+    =======
+    line 2 - b
+    >>>>>>> Side #2 (Conflict 1 of 1 ends)
+    line 3
+    ");
+
+    // Edited conflicts are parsed back from the overridden style
+    work_dir.write_file(
+        "file.lock",
+        indoc! {"
+            line 1
+            <<<<<<<
+            +++++++
+            common
+            line 2 - A
+            -------
+            line 2
+            +++++++
+            common
+            line 2 - b
+            >>>>>>>
+            line 3
+        "},
+    );
+    insta::assert_snapshot!(work_dir.run_jj(["diff", "--git"]), @r"
+    diff --git a/file.lock b/file.lock
+    --- a/file.lock
+    +++ b/file.lock
+    @@ -1,7 +1,7 @@
+     line 1
+     <<<<<<< Side #1 (Conflict 1 of 1)
+     common
+    -line 2 - a
+    +line 2 - A
+     ||||||| Base
+     line 2
+     =======
+    [EOF]
+    ");
+
+    // Editing a "zdiff3" file outside the conflict keeps the lines moved out of
+    // the conflict on the sides, so the base is unchanged
+    work_dir.write_file(
+        "file.txt",
+        indoc! {"
+            line 1
+            common
+            <<<<<<< Side #1 (Conflict 1 of 1)
+            line 2 - a
+            ||||||| Base
+            line 2
+            =======
+            line 2 - b
+            >>>>>>> Side #2 (Conflict 1 of 1 ends)
+            line three
+        "},
+    );
+    insta::assert_snapshot!(work_dir.run_jj(["diff", "--git", "file.txt"]), @r"
+    diff --git a/file.txt b/file.txt
+    --- a/file.txt
+    +++ b/file.txt
+    @@ -8,4 +8,4 @@
+     common
+     line 2 - b
+     >>>>>>> Side #2 (Conflict 1 of 1 ends)
+    -line 3
+    +line three
+    [EOF]
+    ");
+
+    // Invalid filesets are reported
+    work_dir
+        .run_jj([
+            "config",
+            "set",
+            "--repo",
+            r#"ui.conflict-marker-style-overrides."bad:*""#,
+            "git",
+        ])
+        .success();
+    insta::assert_snapshot!(work_dir.run_jj(["st"]), @r"
+    ------- stderr -------
+    Error: Failed to parse fileset: Invalid file pattern
+    Caused by:
+    1:  --> 1:1
+      |
+    1 | bad:*
+      | ^---^
+      |
+      = Invalid file pattern
+    2: Invalid file pattern kind `bad:`
+    Hint: See https://jj-vcs.github.io/jj/latest/filesets/#file-patterns or `jj help -k filesets` for valid prefixes.
+    [EOF]
+    [exit status: 1]
+    ");
+}
+
 #[test]
 fn test_snapshot_invalid_ignore_pattern() {
     let test_env = TestEnvironment::default();
@@ -1358,6 +1358,196 @@ fn test_workspaces_rename_workspace() {
     ");
 }
 
+/// Test moving a secondary workspace
+#[test]
+fn test_workspaces_move_secondary() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "main"]).success();
+    let main_dir = test_env.work_dir("main");
+    main_dir
+        .run_jj(["workspace", "add", "--name", "second", "../secondary"])
+        .success();
+
+    let output = main_dir.run_jj(["workspace", "move", "--workspace", "second", "../moved"]);
+    insta::assert_snapshot!(output, @r#"
+    ------- stderr -------
+    Moved workspace second to "../moved"
+    [EOF]
+    "#);
+    assert!(!test_env.env_root().join("secondary").exists());
+
+    let moved_dir = test_env.work_dir("moved");
+    let output = moved_dir.run_jj(["workspace", "list"]);
+    insta::assert_snapshot!(output, @r"
+    default: qpvuntsm 230dd059 (empty) (no description set)
+    second: uuqppmxq 57d63245 (empty) (no description set)
+    [EOF]
+    ");
+
+    // Moving to the same location is a no-op
+    let output = moved_dir.run_jj(["workspace", "move", "."]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Nothing changed.
+    [EOF]
+    ");
+
+    // Cannot move onto an existing directory
+    let output = moved_dir.run_jj(["workspace", "move", "../main"]);
+    insta::assert_snapshot!(output, @r#"
+    ------- stderr -------
+    Error: Destination "../main" already exists
+    [EOF]
+    [exit status: 1]
+    "#);
+
+    // Move it back
+    let output = main_dir.run_jj(["workspace", "move", "--workspace", "second", "../secondary"]);
+    insta::assert_snapshot!(output, @r#"
+    ------- stderr -------
+    Moved workspace second to "../secondary"
+    [EOF]
+    "#);
+
+    let output = main_dir.run_jj(["workspace", "move", "--workspace", "unknown", "../x"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: No such workspace: unknown
+    [EOF]
+    [exit status: 1]
+    ");
+}
+
+/// Test moving the workspace that hosts the repo
+#[test]
+fn test_workspaces_move_main() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "main"]).success();
+    let main_dir = test_env.work_dir("main");
+    main_dir
+        .run_jj(["workspace", "add", "--name", "second", "../secondary"])
+        .success();
+    let secondary_dir = test_env.work_dir("secondary");
+
+    let output = main_dir.run_jj(["workspace", "move", "../main-moved"]);
+    insta::assert_snapshot!(output, @r#"
+    ------- stderr -------
+    Moved workspace default to "../main-moved"
+    [EOF]
+    "#);
+
+    // The secondary workspace follows the repo
+    insta::assert_snapshot!(get_log_output(&secondary_dir), @r"
+    @  57d63245a308 second@
+    │ ○  230dd059e1b0 default@
+    ├─╯
+    ◆  000000000000
+    [EOF]
+    ");
+    let output = secondary_dir.run_jj(["workspace", "list"]);
+    insta::assert_snapshot!(output, @r"
+    default: qpvuntsm 230dd059 (empty) (no description set)
+    second: uuqppmxq 57d63245 (empty) (no description set)
+    [EOF]
+    ");
+}
+
+/// Test updating the location of workspaces moved without `jj workspace move`
+#[test]
+fn test_workspaces_moved_externally() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "main"]).success();
+    test_env
+        .work_dir("main")
+        .run_jj(["workspace", "add", "--name", "second", "../secondary"])
+        .success();
+    std::fs::rename(
+        test_env.env_root().join("main"),
+        test_env.env_root().join("main-moved"),
+    )
+    .unwrap();
+    let main_dir = test_env.work_dir("main-moved");
+    let secondary_dir = test_env.work_dir("secondary");
+
+    // The secondary workspace can no longer find the repo
+    let output = secondary_dir.run_jj(["status"]);
+    assert!(!output.status.success());
+
+    let output = main_dir.run_jj(["workspace", "list"]);
+    insta::assert_snapshot!(output.normalize_backslash(), @r#"
+    default: qpvuntsm 230dd059 (empty) (no description set)
+    second: uuqppmxq 57d63245 (empty) (no description set)
+    [EOF]
+    ------- stderr -------
+    Warning: Workspace default has been moved from "$TEST_ENV/main"
+    Hint: Run `jj workspace move .` to update its location
+    [EOF]
+    "#);
+
+    let output = main_dir.run_jj(["workspace", "move", "."]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Updated the location of workspace default
+    [EOF]
+    ");
+    insta::assert_snapshot!(get_log_output(&secondary_dir), @r"
+    @  57d63245a308 second@
+    │ ○  230dd059e1b0 default@
+    ├─╯
+    ◆  000000000000
+    [EOF]
+    ");
+
+    // A deleted workspace is reported as missing
+    std::fs::remove_dir_all(test_env.env_root().join("secondary")).unwrap();
+    let output = main_dir.run_jj(["workspace", "list"]);
+    insta::assert_snapshot!(output.normalize_backslash(), @r#"
+    default: qpvuntsm 230dd059 (empty) (no description set)
+    second: uuqppmxq 57d63245 (empty) (no description set)
+    [EOF]
+    ------- stderr -------
+    Warning: Workspace second is missing from "$TEST_ENV/secondary"
+    Hint: Run `jj workspace move --workspace second <new path>` if it has been moved, or `jj workspace forget second` if it has been deleted
+    [EOF]
+    "#);
+    main_dir.run_jj(["workspace", "forget", "second"]).success();
+    let output = main_dir.run_jj(["workspace", "list"]);
+    insta::assert_snapshot!(output, @r"
+    default: qpvuntsm 230dd059 (empty) (no description set)
+    [EOF]
+    ");
+}
+
+/// Test that workspaces created before the workspace store existed are
+/// recorded when they're loaded
+#[test]
+fn test_workspaces_register_on_load() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "main"]).success();
+    let main_dir = test_env.work_dir("main");
+    main_dir
+        .run_jj(["workspace", "add", "--name", "second", "../secondary"])
+        .success();
+    std::fs::remove_dir_all(main_dir.root().join(".jj/repo/workspace_store")).unwrap();
+
+    // The location of the secondary workspace is unknown until it's loaded
+    let output = main_dir.run_jj(["workspace", "move", "--workspace", "second", "../moved"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: The location of workspace second is unknown
+    Hint: Run any command from within the workspace to record its location
+    [EOF]
+    [exit status: 1]
+    ");
+    test_env.work_dir("secondary").run_jj(["status"]).success();
+    let output = main_dir.run_jj(["workspace", "move", "--workspace", "second", "../moved"]);
+    insta::assert_snapshot!(output, @r#"
+    ------- stderr -------
+    Moved workspace second to "../moved"
+    [EOF]
+    "#);
+}
+
 #[must_use]
 fn get_log_output(work_dir: &TestWorkDir) -> CommandOutput {
     let template = r#"
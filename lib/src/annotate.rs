@@ -20,12 +20,14 @@
 
 use std::collections::hash_map;
 use std::collections::HashMap;
+use std::collections::HashSet;
 use std::iter;
 use std::ops::Range;
 use std::rc::Rc;
 
 use bstr::BStr;
 use bstr::BString;
+use futures::StreamExt as _;
 use itertools::Itertools as _;
 use pollster::FutureExt as _;
 
@@ -37,6 +39,7 @@ use crate::conflicts::materialize_merge_result_to_bytes;
 use crate::conflicts::materialize_tree_value;
 use crate::conflicts::ConflictMarkerStyle;
 use crate::conflicts::MaterializedTreeValue;
+use crate::copies::CopyRecords;
 use crate::diff::Diff;
 use crate::diff::DiffHunkKind;
 use crate::fileset::FilesetExpression;
@@ -47,11 +50,22 @@ use crate::repo::Repo;
 use crate::repo_path::RepoPath;
 use crate::repo_path::RepoPathBuf;
 use crate::revset::ResolvedRevsetExpression;
+use crate::revset::RevsetContainingFn;
 use crate::revset::RevsetEvaluationError;
 use crate::revset::RevsetExpression;
 use crate::revset::RevsetFilterPredicate;
 use crate::store::Store;
 
+/// Commit and file path where a line originated.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LineOrigin {
+    /// Commit ID where the line was introduced.
+    pub commit_id: CommitId,
+    /// Path of the file in that commit. This differs from the annotated path
+    /// if the file was renamed or copied since.
+    pub path: RepoPathBuf,
+}
+
 /// Annotation results for a specific file
 #[derive(Clone, Debug)]
 pub struct FileAnnotation {
@@ -60,28 +74,28 @@ pub struct FileAnnotation {
 }
 
 impl FileAnnotation {
-    /// Returns iterator over `(commit_id, line)`s.
+    /// Returns iterator over `(line_origin, line)`s.
     ///
-    /// For each line, `Ok(commit_id)` points to the originator commit of the
+    /// For each line, `Ok(line_origin)` points to the originator commit of the
     /// line. If no originator commit was found within the domain,
-    /// `Err(commit_id)` should be set. It points to the root (or boundary)
+    /// `Err(line_origin)` should be set. It points to the root (or boundary)
     /// commit where the line exists.
     ///
     /// The `line` includes newline character.
-    pub fn lines(&self) -> impl Iterator<Item = (Result<&CommitId, &CommitId>, &BStr)> {
+    pub fn lines(&self) -> impl Iterator<Item = (Result<&LineOrigin, &LineOrigin>, &BStr)> {
         itertools::zip_eq(&self.line_map, self.text.split_inclusive(|b| *b == b'\n'))
-            .map(|(commit_id, line)| (commit_id.as_ref(), line.as_ref()))
+            .map(|(line_origin, line)| (line_origin.as_ref(), line.as_ref()))
     }
 
-    /// Returns iterator over `(commit_id, line_range)`s.
+    /// Returns iterator over `(line_origin, line_range)`s.
     ///
-    /// See [`Self::lines()`] for `commit_id`s.
+    /// See [`Self::lines()`] for `line_origin`s.
     ///
     /// The `line_range` is a slice range in the file `text`. Consecutive ranges
-    /// having the same `commit_id` are not compacted.
+    /// having the same `line_origin` are not compacted.
     pub fn line_ranges(
         &self,
-    ) -> impl Iterator<Item = (Result<&CommitId, &CommitId>, Range<usize>)> {
+    ) -> impl Iterator<Item = (Result<&LineOrigin, &LineOrigin>, Range<usize>)> {
         let ranges = self
             .text
             .split_inclusive(|b| *b == b'\n')
@@ -91,24 +105,24 @@ impl FileAnnotation {
                 Some(start..*total)
             });
         itertools::zip_eq(&self.line_map, ranges)
-            .map(|(commit_id, range)| (commit_id.as_ref(), range))
+            .map(|(line_origin, range)| (line_origin.as_ref(), range))
     }
 
-    /// Returns iterator over compacted `(commit_id, line_range)`s.
+    /// Returns iterator over compacted `(line_origin, line_range)`s.
     ///
-    /// Consecutive ranges having the same `commit_id` are merged into one.
+    /// Consecutive ranges having the same `line_origin` are merged into one.
     pub fn compact_line_ranges(
         &self,
-    ) -> impl Iterator<Item = (Result<&CommitId, &CommitId>, Range<usize>)> {
+    ) -> impl Iterator<Item = (Result<&LineOrigin, &LineOrigin>, Range<usize>)> {
         let mut ranges = self.line_ranges();
         let mut acc = ranges.next();
         iter::from_fn(move || {
-            let (acc_commit_id, acc_range) = acc.as_mut()?;
-            for (cur_commit_id, cur_range) in ranges.by_ref() {
-                if *acc_commit_id == cur_commit_id {
+            let (acc_line_origin, acc_range) = acc.as_mut()?;
+            for (cur_line_origin, cur_range) in ranges.by_ref() {
+                if *acc_line_origin == cur_line_origin {
                     acc_range.end = cur_range.end;
                 } else {
-                    return acc.replace((cur_commit_id, cur_range));
+                    return acc.replace((cur_line_origin, cur_range));
                 }
             }
             acc.take()
@@ -124,10 +138,10 @@ impl FileAnnotation {
 /// Annotation process for a specific file.
 #[derive(Clone, Debug)]
 pub struct FileAnnotator {
-    // If we add copy-tracing support, file_path might be tracked by state.
-    file_path: RepoPathBuf,
     original_text: BString,
     state: AnnotationState,
+    follow_copies: bool,
+    ignored_revisions: Option<Rc<ResolvedRevsetExpression>>,
 }
 
 impl FileAnnotator {
@@ -136,7 +150,7 @@ impl FileAnnotator {
     /// If the file is not found, the result would be empty.
     pub fn from_commit(starting_commit: &Commit, file_path: &RepoPath) -> BackendResult<Self> {
         let source = Source::load(starting_commit, file_path)?;
-        Ok(Self::with_source(starting_commit.id(), file_path, source))
+        Ok(Self::with_source(starting_commit.id(), source))
     }
 
     /// Initializes annotator for a specific file path starting with the given
@@ -150,29 +164,64 @@ impl FileAnnotator {
         file_path: &RepoPath,
         starting_text: impl Into<Vec<u8>>,
     ) -> Self {
-        let source = Source::new(BString::new(starting_text.into()));
-        Self::with_source(starting_commit_id, file_path, source)
+        let source = Source::new(file_path.to_owned(), BString::new(starting_text.into()));
+        Self::with_source(starting_commit_id, source)
     }
 
-    fn with_source(
-        starting_commit_id: &CommitId,
-        file_path: &RepoPath,
-        mut source: Source,
-    ) -> Self {
+    fn with_source(starting_commit_id: &CommitId, mut source: Source) -> Self {
         source.fill_line_map();
         let original_text = source.text.clone();
+        let starting_origin = LineOrigin {
+            commit_id: starting_commit_id.clone(),
+            path: source.path.clone(),
+        };
         let state = AnnotationState {
-            original_line_map: vec![Err(starting_commit_id.clone()); source.line_map.len()],
+            original_line_map: vec![Err(starting_origin); source.line_map.len()],
             commit_source_map: HashMap::from([(starting_commit_id.clone(), source)]),
-            num_unresolved_roots: 0,
+            unresolved_roots: HashSet::new(),
         };
         FileAnnotator {
-            file_path: file_path.to_owned(),
             original_text,
             state,
+            follow_copies: false,
+            ignored_revisions: None,
         }
     }
 
+    /// Sets whether to follow renames and copies of the file.
+    ///
+    /// If enabled, lines of a file which was renamed or copied are attributed
+    /// to the commits that introduced them at the source path. Copies are
+    /// looked up in the copy records of the backend.
+    pub fn set_follow_copies(&mut self, follow_copies: bool) {
+        self.follow_copies = follow_copies;
+    }
+
+    /// Sets revisions to skip, such as mass reformatting commits.
+    ///
+    /// Lines changed by the ignored revisions are attributed to the lines at
+    /// the same position in their parents.
+    pub fn set_ignored_revisions(&mut self, revisions: Rc<ResolvedRevsetExpression>) {
+        self.ignored_revisions = Some(revisions);
+    }
+
+    /// Restricts the computation to the lines in the `line_range` of the file
+    /// at the starting commit. The range is 0-based.
+    ///
+    /// Lines outside of the range are left unresolved, and the computation
+    /// stops as soon as all lines in the range are resolved.
+    pub fn restrict_to_lines(&mut self, line_range: Range<usize>) {
+        self.state.commit_source_map.retain(|_, source| {
+            source
+                .line_map
+                .retain(|(_, original)| line_range.contains(original));
+            !source.line_map.is_empty()
+        });
+        self.state
+            .unresolved_roots
+            .retain(|id| self.state.commit_source_map.contains_key(id));
+    }
+
     /// Computes line-by-line annotation within the `domain`.
     ///
     /// The `domain` expression narrows the range of ancestors to search. It
@@ -183,7 +232,13 @@ impl FileAnnotator {
         repo: &dyn Repo,
         domain: &Rc<ResolvedRevsetExpression>,
     ) -> Result<(), RevsetEvaluationError> {
-        process_commits(repo, &mut self.state, domain, &self.file_path)
+        process_commits(
+            repo,
+            &mut self.state,
+            domain,
+            self.follow_copies,
+            self.ignored_revisions.as_ref(),
+        )
     }
 
     /// Remaining commit ids to visit from.
@@ -209,8 +264,8 @@ struct AnnotationState {
     original_line_map: OriginalLineMap,
     /// Commits to file line mappings and contents.
     commit_source_map: HashMap<CommitId, Source>,
-    /// Number of unresolved root commits in `commit_source_map`.
-    num_unresolved_roots: usize,
+    /// Unresolved root commits in `commit_source_map`.
+    unresolved_roots: HashSet<CommitId>,
 }
 
 /// Line mapping and file content at a certain commit.
@@ -219,14 +274,17 @@ struct Source {
     /// Mapping of line numbers in the file at the current commit to the
     /// original file, sorted by the line numbers at the current commit.
     line_map: Vec<(usize, usize)>,
+    /// Path of the file at the current commit.
+    path: RepoPathBuf,
     /// File content at the current commit.
     text: BString,
 }
 
 impl Source {
-    fn new(text: BString) -> Self {
+    fn new(path: RepoPathBuf, text: BString) -> Self {
         Source {
             line_map: Vec::new(),
+            path,
             text,
         }
     }
@@ -234,7 +292,27 @@ impl Source {
     fn load(commit: &Commit, file_path: &RepoPath) -> Result<Self, BackendError> {
         let tree = commit.tree()?;
         let text = get_file_contents(commit.store(), file_path, &tree)?;
-        Ok(Self::new(text))
+        Ok(Self::new(file_path.to_owned(), text))
+    }
+
+    /// Loads the file at `file_path` in the `parent_commit`, or the source
+    /// file it was copied from if `follow_copies` is enabled.
+    fn load_parent(
+        parent_commit: &Commit,
+        commit_id: &CommitId,
+        file_path: &RepoPath,
+        follow_copies: bool,
+    ) -> Result<Self, BackendError> {
+        let store = parent_commit.store();
+        let tree = parent_commit.tree()?;
+        let path = if follow_copies && tree.path_value(file_path)?.is_absent() {
+            find_copy_source(store, parent_commit.id(), commit_id, file_path)?
+                .unwrap_or_else(|| file_path.to_owned())
+        } else {
+            file_path.to_owned()
+        };
+        let text = get_file_contents(store, &path, &tree)?;
+        Ok(Self::new(path, text))
     }
 
     fn fill_line_map(&mut self) {
@@ -243,9 +321,9 @@ impl Source {
     }
 }
 
-/// List of commit IDs that originated lines, indexed by line numbers in the
-/// original file.
-type OriginalLineMap = Vec<Result<CommitId, CommitId>>;
+/// List of commits and paths that originated lines, indexed by line numbers in
+/// the original file.
+type OriginalLineMap = Vec<Result<LineOrigin, LineOrigin>>;
 
 /// Starting from the source commits, compute changes at that commit relative to
 /// its direct parents, updating the mappings as we go.
@@ -253,29 +331,84 @@ fn process_commits(
     repo: &dyn Repo,
     state: &mut AnnotationState,
     domain: &Rc<ResolvedRevsetExpression>,
-    file_name: &RepoPath,
+    follow_copies: bool,
+    ignored: Option<&Rc<ResolvedRevsetExpression>>,
 ) -> Result<(), RevsetEvaluationError> {
-    let predicate = RevsetFilterPredicate::File(FilesetExpression::file_path(file_name.to_owned()));
-    // TODO: If the domain isn't a contiguous range, changes masked out by it
-    // might not be caught by the closest ancestor revision. For example,
-    // domain=merges() would pick up almost nothing because merge revisions
-    // are usually empty. Perhaps, we want to query `files(file_path,
-    // within_sub_graph=domain)`, not `domain & files(file_path)`.
-    let heads = RevsetExpression::commits(state.commit_source_map.keys().cloned().collect());
-    let revset = heads
-        .union(&domain.intersection(&heads.ancestors()).filtered(predicate))
-        .evaluate(repo)?;
-
-    state.num_unresolved_roots = 0;
-    for node in revset.iter_graph() {
-        let (commit_id, edge_list) = node?;
-        process_commit(repo, file_name, state, &commit_id, &edge_list)?;
-        if state.commit_source_map.len() == state.num_unresolved_roots {
-            // No more lines to propagate to ancestors.
-            break;
+    state.unresolved_roots.clear();
+    // The revset is evaluated again whenever a copy source is found, so that
+    // the ancestors which modified the file at the source path are visited.
+    loop {
+        let file_paths: HashSet<RepoPathBuf> = state
+            .commit_source_map
+            .values()
+            .map(|source| source.path.clone())
+            .collect();
+        let predicate = RevsetFilterPredicate::File(FilesetExpression::union_all(
+            file_paths
+                .iter()
+                .sorted()
+                .map(|path| FilesetExpression::file_path(path.clone()))
+                .collect(),
+        ));
+        // TODO: If the domain isn't a contiguous range, changes masked out by it
+        // might not be caught by the closest ancestor revision. For example,
+        // domain=merges() would pick up almost nothing because merge revisions
+        // are usually empty. Perhaps, we want to query `files(file_path,
+        // within_sub_graph=domain)`, not `domain & files(file_path)`.
+        let heads = RevsetExpression::commits(
+            state
+                .commit_source_map
+                .keys()
+                .filter(|id| !state.unresolved_roots.contains(*id))
+                .cloned()
+                .collect(),
+        );
+        let revset = heads
+            .union(&domain.intersection(&heads.ancestors()).filtered(predicate))
+            .evaluate(repo)?;
+        let is_in_domain = if follow_copies {
+            Some(domain.clone().evaluate(repo)?.containing_fn())
+        } else {
+            None
+        };
+        let is_ignored = match ignored {
+            Some(ignored) => Some(ignored.clone().evaluate(repo)?.containing_fn()),
+            None => None,
+        };
+
+        let mut found_copy_source = false;
+        for node in revset.iter_graph() {
+            let (commit_id, edge_list) = node?;
+            let is_ignored = match &is_ignored {
+                Some(is_ignored) => is_ignored(&commit_id)?,
+                None => false,
+            };
+            process_commit(
+                repo,
+                state,
+                &commit_id,
+                &edge_list,
+                follow_copies,
+                is_in_domain.as_deref(),
+                is_ignored,
+            )?;
+            if state.commit_source_map.len() == state.unresolved_roots.len() {
+                // No more lines to propagate to ancestors.
+                return Ok(());
+            }
+            if state
+                .commit_source_map
+                .values()
+                .any(|source| !file_paths.contains(&source.path))
+            {
+                found_copy_source = true;
+                break;
+            }
+        }
+        if !found_copy_source {
+            return Ok(());
         }
     }
-    Ok(())
 }
 
 /// For a given commit, for each parent, we compare the version in the parent
@@ -283,67 +416,75 @@ fn process_commits(
 /// common. If the parent doesn't have the file, we skip it.
 fn process_commit(
     repo: &dyn Repo,
-    file_name: &RepoPath,
     state: &mut AnnotationState,
     current_commit_id: &CommitId,
     edges: &[GraphEdge<CommitId>],
-) -> Result<(), BackendError> {
+    follow_copies: bool,
+    is_in_domain: Option<&RevsetContainingFn>,
+    is_ignored: bool,
+) -> Result<(), RevsetEvaluationError> {
     let Some(mut current_source) = state.commit_source_map.remove(current_commit_id) else {
         return Ok(());
     };
 
+    let mut root_flags = Vec::with_capacity(edges.len());
     for parent_edge in edges {
         let parent_commit_id = &parent_edge.target;
         let parent_source = match state.commit_source_map.entry(parent_commit_id.clone()) {
             hash_map::Entry::Occupied(entry) => entry.into_mut(),
             hash_map::Entry::Vacant(entry) => {
                 let commit = repo.store().get_commit(entry.key())?;
-                entry.insert(Source::load(&commit, file_name)?)
+                entry.insert(Source::load_parent(
+                    &commit,
+                    current_commit_id,
+                    &current_source.path,
+                    follow_copies,
+                )?)
             }
         };
-
-        // For two versions of the same file, for all the lines in common,
-        // overwrite the new mapping in the results for the new commit. Let's
-        // say I have a file in commit A and commit B. We know that according to
-        // local line_map, in commit A, line 3 corresponds to line 7 of the
-        // original file. Now, line 3 in Commit A corresponds to line 6 in
-        // commit B. Then, we update local line_map to say that "Commit B line 6
-        // goes to line 7 of the original file". We repeat this for all lines in
-        // common in the two commits.
-        let mut current_lines = current_source.line_map.iter().copied().peekable();
-        let mut new_current_line_map = Vec::new();
-        let mut new_parent_line_map = Vec::new();
-        copy_same_lines_with(
-            &current_source.text,
-            &parent_source.text,
-            |current_start, parent_start, count| {
-                new_current_line_map
-                    .extend(current_lines.peeking_take_while(|&(cur, _)| cur < current_start));
-                while let Some((current, original)) =
-                    current_lines.next_if(|&(cur, _)| cur < current_start + count)
-                {
-                    let parent = parent_start + (current - current_start);
-                    new_parent_line_map.push((parent, original));
+        // A missing edge to a commit where the file had another path leads
+        // outside of the revset filtered by the current path, not necessarily
+        // outside of the domain.
+        let is_root = parent_edge.edge_type == GraphEdgeType::Missing
+            && match is_in_domain {
+                Some(is_in_domain) if parent_source.path != current_source.path => {
+                    !is_in_domain(parent_commit_id)?
                 }
-            },
-        );
-        new_current_line_map.extend(current_lines);
-        current_source.line_map = new_current_line_map;
-        parent_source.line_map = if parent_source.line_map.is_empty() {
-            new_parent_line_map
-        } else {
-            itertools::merge(parent_source.line_map.iter().copied(), new_parent_line_map).collect()
-        };
+                _ => true,
+            };
+        root_flags.push(is_root);
+        move_same_lines(&mut current_source, parent_source, false);
+    }
+
+    // Lines changed by an ignored commit are attributed to the lines at the
+    // same position in the changed hunk of the parent. Lines added beyond the
+    // size of the parent hunk stay attributed to the ignored commit.
+    if is_ignored {
+        for parent_edge in edges {
+            let parent_source = state
+                .commit_source_map
+                .get_mut(&parent_edge.target)
+                .unwrap();
+            move_same_lines(&mut current_source, parent_source, true);
+        }
+    }
+
+    for (parent_edge, is_root) in iter::zip(edges, root_flags) {
+        let parent_commit_id = &parent_edge.target;
+        let parent_source = &state.commit_source_map[parent_commit_id];
         if parent_source.line_map.is_empty() {
             state.commit_source_map.remove(parent_commit_id);
-        } else if parent_edge.edge_type == GraphEdgeType::Missing {
+        } else if is_root {
             // If an omitted parent had the file, leave these lines unresolved.
             // The origin of the unresolved lines is represented as
-            // Err(root_commit_id).
+            // Err(root_line_origin).
             for &(_, original_line_number) in &parent_source.line_map {
-                state.original_line_map[original_line_number] = Err(current_commit_id.clone());
+                state.original_line_map[original_line_number] = Err(LineOrigin {
+                    commit_id: current_commit_id.clone(),
+                    path: current_source.path.clone(),
+                });
             }
-            state.num_unresolved_roots += 1;
+            state.unresolved_roots.insert(parent_commit_id.clone());
         }
     }
 
@@ -351,17 +492,79 @@ fn process_commit(
     // original to the current commit, so we save this information in
     // original_line_map.
     for (_, original_line_number) in current_source.line_map {
-        state.original_line_map[original_line_number] = Ok(current_commit_id.clone());
+        state.original_line_map[original_line_number] = Ok(LineOrigin {
+            commit_id: current_commit_id.clone(),
+            path: current_source.path.clone(),
+        });
     }
 
     Ok(())
 }
 
+/// For two versions of the same file, for all the lines in common, overwrite
+/// the new mapping in the results for the new commit. Let's say I have a file
+/// in commit A and commit B. We know that according to local line_map, in
+/// commit A, line 3 corresponds to line 7 of the original file. Now, line 3 in
+/// Commit A corresponds to line 6 in commit B. Then, we update local line_map
+/// to say that "Commit B line 6 goes to line 7 of the original file". We repeat
+/// this for all lines in common in the two commits.
+///
+/// If `include_changed` is true, lines in changed hunks are also mapped to the
+/// parent lines at the same position.
+fn move_same_lines(current_source: &mut Source, parent_source: &mut Source, include_changed: bool) {
+    let mut current_lines = current_source.line_map.iter().copied().peekable();
+    let mut new_current_line_map = Vec::new();
+    let mut new_parent_line_map = Vec::new();
+    copy_same_lines_with(
+        &current_source.text,
+        &parent_source.text,
+        include_changed,
+        |current_start, parent_start, count| {
+            new_current_line_map
+                .extend(current_lines.peeking_take_while(|&(cur, _)| cur < current_start));
+            while let Some((current, original)) =
+                current_lines.next_if(|&(cur, _)| cur < current_start + count)
+            {
+                let parent = parent_start + (current - current_start);
+                new_parent_line_map.push((parent, original));
+            }
+        },
+    );
+    new_current_line_map.extend(current_lines);
+    current_source.line_map = new_current_line_map;
+    parent_source.line_map = if parent_source.line_map.is_empty() {
+        new_parent_line_map
+    } else {
+        itertools::merge(parent_source.line_map.iter().copied(), new_parent_line_map).collect()
+    };
+}
+
+/// Looks up the path which `file_path` in the commit `head_id` was copied or
+/// renamed from, relative to the commit `root_id`.
+fn find_copy_source(
+    store: &Store,
+    root_id: &CommitId,
+    head_id: &CommitId,
+    file_path: &RepoPath,
+) -> BackendResult<Option<RepoPathBuf>> {
+    let paths = [file_path.to_owned()];
+    let stream = store.get_copy_records(Some(&paths), root_id, head_id)?;
+    let mut copy_records = CopyRecords::default();
+    copy_records.add_records(stream.collect::<Vec<_>>().block_on())?;
+    Ok(copy_records
+        .for_target(file_path)
+        .map(|record| record.source.clone()))
+}
+
 /// For two files, calls `copy(current_start, parent_start, count)` for each
 /// range of contiguous lines in common (e.g. line 8-10 maps to line 9-11.)
+///
+/// If `include_changed` is true, `copy()` is also called for the leading lines
+/// of changed hunks, up to the size of the smaller side.
 fn copy_same_lines_with(
     current_contents: &[u8],
     parent_contents: &[u8],
+    include_changed: bool,
     mut copy: impl FnMut(usize, usize, usize),
 ) {
     let diff = Diff::by_line([current_contents, parent_contents]);
@@ -378,8 +581,14 @@ fn copy_same_lines_with(
             DiffHunkKind::Different => {
                 let current_output = hunk.contents[0];
                 let parent_output = hunk.contents[1];
-                current_line_counter += current_output.split_inclusive(|b| *b == b'\n').count();
-                parent_line_counter += parent_output.split_inclusive(|b| *b == b'\n').count();
+                let current_count = current_output.split_inclusive(|b| *b == b'\n').count();
+                let parent_count = parent_output.split_inclusive(|b| *b == b'\n').count();
+                let count = current_count.min(parent_count);
+                if include_changed && count > 0 {
+                    copy(current_line_counter, parent_line_counter, count);
+                }
+                current_line_counter += current_count;
+                parent_line_counter += parent_count;
             }
         }
     }
@@ -406,6 +615,13 @@ fn get_file_contents(
 mod tests {
     use super::*;
 
+    fn make_line_origin(commit_id: &CommitId) -> LineOrigin {
+        LineOrigin {
+            commit_id: commit_id.clone(),
+            path: RepoPathBuf::from_internal_string("file").unwrap(),
+        }
+    }
+
     #[test]
     fn test_lines_iterator_empty() {
         let annotation = FileAnnotation {
@@ -419,67 +635,67 @@ mod tests {
 
     #[test]
     fn test_lines_iterator_with_content() {
-        let commit_id1 = CommitId::from_hex("111111");
-        let commit_id2 = CommitId::from_hex("222222");
-        let commit_id3 = CommitId::from_hex("333333");
+        let origin1 = make_line_origin(&CommitId::from_hex("111111"));
+        let origin2 = make_line_origin(&CommitId::from_hex("222222"));
+        let origin3 = make_line_origin(&CommitId::from_hex("333333"));
         let annotation = FileAnnotation {
             line_map: vec![
-                Ok(commit_id1.clone()),
-                Ok(commit_id2.clone()),
-                Ok(commit_id3.clone()),
+                Ok(origin1.clone()),
+                Ok(origin2.clone()),
+                Ok(origin3.clone()),
             ],
             text: "foo\n\nbar\n".into(),
         };
         assert_eq!(
             annotation.lines().collect_vec(),
             vec![
-                (Ok(&commit_id1), "foo\n".as_ref()),
-                (Ok(&commit_id2), "\n".as_ref()),
-                (Ok(&commit_id3), "bar\n".as_ref()),
+                (Ok(&origin1), "foo\n".as_ref()),
+                (Ok(&origin2), "\n".as_ref()),
+                (Ok(&origin3), "bar\n".as_ref()),
             ]
         );
         assert_eq!(
             annotation.line_ranges().collect_vec(),
             vec![
-                (Ok(&commit_id1), 0..4),
-                (Ok(&commit_id2), 4..5),
-                (Ok(&commit_id3), 5..9),
+                (Ok(&origin1), 0..4),
+                (Ok(&origin2), 4..5),
+                (Ok(&origin3), 5..9),
             ]
         );
         assert_eq!(
             annotation.compact_line_ranges().collect_vec(),
             vec![
-                (Ok(&commit_id1), 0..4),
-                (Ok(&commit_id2), 4..5),
-                (Ok(&commit_id3), 5..9),
+                (Ok(&origin1), 0..4),
+                (Ok(&origin2), 4..5),
+                (Ok(&origin3), 5..9),
             ]
         );
     }
 
     #[test]
     fn test_lines_iterator_compaction() {
-        let commit_id1 = CommitId::from_hex("111111");
-        let commit_id2 = CommitId::from_hex("222222");
-        let commit_id3 = CommitId::from_hex("333333");
+        let origin1 = make_line_origin(&CommitId::from_hex("111111"));
+        let origin2 = make_line_origin(&CommitId::from_hex("222222"));
+        let origin3 = make_line_origin(&CommitId::from_hex("333333"));
         let annotation = FileAnnotation {
             line_map: vec![
-                Ok(commit_id1.clone()),
-                Ok(commit_id1.clone()),
-                Ok(commit_id2.clone()),
-                Ok(commit_id1.clone()),
-                Ok(commit_id3.clone()),
-                Ok(commit_id3.clone()),
-                Ok(commit_id3.clone()),
+                Ok(origin1.clone()),
+                Ok(origin1.clone()),
+                Ok(origin2.clone()),
+                Ok(origin1.clone()),
+                Ok(origin3.clone()),
+                Ok(origin3.clone()),
+                Ok(origin3.clone()),
             ],
             text: "\n".repeat(7).into(),
         };
         assert_eq!(
             annotation.compact_line_ranges().collect_vec(),
             vec![
-                (Ok(&commit_id1), 0..2),
-                (Ok(&commit_id2), 2..3),
-                (Ok(&commit_id1), 3..4),
-                (Ok(&commit_id3), 4..7),
+                (Ok(&origin1), 0..2),
+                (Ok(&origin2), 2..3),
+                (Ok(&origin1), 3..4),
+                (Ok(&origin3), 4..7),
             ]
         );
     }
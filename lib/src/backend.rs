@@ -17,6 +17,7 @@
 use std::any::Any;
 use std::collections::BTreeMap;
 use std::fmt::Debug;
+use std::io;
 use std::io::Read;
 use std::time::SystemTime;
 
@@ -423,6 +424,18 @@ pub trait Backend: Send + Sync + Debug {
 
     async fn read_file(&self, path: &RepoPath, id: &FileId) -> BackendResult<Box<dyn Read>>;
 
+    /// Returns the size of the file contents in bytes. The default
+    /// implementation reads the whole file, so backends that know the size
+    /// up front should override it.
+    async fn file_size(&self, path: &RepoPath, id: &FileId) -> BackendResult<u64> {
+        let mut reader = self.read_file(path, id).await?;
+        io::copy(&mut reader, &mut io::sink()).map_err(|err| BackendError::ReadFile {
+            path: path.to_owned(),
+            id: id.clone(),
+            source: err.into(),
+        })
+    }
+
     async fn write_file(
         &self,
         path: &RepoPath,
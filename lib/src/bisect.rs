@@ -0,0 +1,173 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Bisection of a range of commits to find the first bad commit.
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+use itertools::Itertools as _;
+use thiserror::Error;
+
+use crate::backend::CommitId;
+use crate::graph::GraphEdgeType;
+use crate::op_store::BisectState;
+use crate::repo::Repo;
+use crate::revset::RevsetEvaluationError;
+use crate::revset::RevsetExpression;
+
+/// Maximum number of commits whose ancestors are counted when choosing the
+/// next commit to test.
+const MAX_CANDIDATES: usize = 100;
+
+/// Result of testing a commit.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Evaluation {
+    /// The commit doesn't have the problem.
+    Good,
+    /// The commit has the problem.
+    Bad,
+    /// The commit couldn't be tested.
+    Skip,
+}
+
+/// What to do next to narrow down the first bad commit.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum BisectionStep {
+    /// The commit should be tested next.
+    Test(CommitId),
+    /// The first bad commit has been found.
+    Found(CommitId),
+    /// The first bad commit is one of these commits, which can't be told apart
+    /// because some of them were skipped.
+    Ambiguous(Vec<CommitId>),
+}
+
+/// Error that may occur while bisecting.
+#[derive(Debug, Error)]
+pub enum BisectError {
+    /// No commit has been marked as bad.
+    #[error("No commits have been marked as bad")]
+    NoBadCommits,
+    /// All bad commits are ancestors of the good commits.
+    #[error("The bad commits are ancestors of the good commits")]
+    EmptyRange,
+    /// Failed to evaluate the range of commits.
+    #[error(transparent)]
+    Evaluation(#[from] RevsetEvaluationError),
+}
+
+/// Records the result of testing `commit_id` in `state`, replacing any
+/// previous result for the same commit.
+pub fn mark(state: &mut BisectState, commit_id: &CommitId, evaluation: Evaluation) {
+    let BisectState { good, bad, skipped } = state;
+    for ids in [&mut *good, &mut *bad, &mut *skipped] {
+        ids.retain(|id| id != commit_id);
+    }
+    let ids = match evaluation {
+        Evaluation::Good => good,
+        Evaluation::Bad => bad,
+        Evaluation::Skip => skipped,
+    };
+    let pos = ids.partition_point(|id| id < commit_id);
+    ids.insert(pos, commit_id.clone());
+}
+
+/// Determines the next commit to test given the commits marked in `state`.
+///
+/// The first bad commit is searched for among the common ancestors of the bad
+/// commits, excluding ancestors of good commits. The commit to test is the one
+/// that splits that range most evenly, counting ancestors within the range, so
+/// histories with merges are bisected as well as linear ones. In large ranges,
+/// the commit is chosen among an evenly spaced sample of the range.
+pub fn next_step(repo: &dyn Repo, state: &BisectState) -> Result<BisectionStep, BisectError> {
+    let range = state
+        .bad
+        .iter()
+        .map(|id| RevsetExpression::commit(id.clone()).ancestors())
+        .reduce(|acc, ancestors| acc.intersection(&ancestors))
+        .ok_or(BisectError::NoBadCommits)?
+        .minus(&RevsetExpression::commits(state.good.clone()).ancestors());
+    // Nodes are ordered children first. The edges only point to commits within
+    // the range, and preserve reachability between them.
+    let nodes: Vec<_> = range.evaluate(repo)?.iter_graph().try_collect()?;
+    if nodes.is_empty() {
+        return Err(BisectError::EmptyRange);
+    }
+
+    let positions: HashMap<&CommitId, usize> = nodes
+        .iter()
+        .enumerate()
+        .map(|(pos, (id, _))| (id, pos))
+        .collect();
+    let parents: Vec<Vec<usize>> = nodes
+        .iter()
+        .map(|(_, edges)| {
+            edges
+                .iter()
+                .filter(|edge| edge.edge_type != GraphEdgeType::Missing)
+                .map(|edge| positions[&edge.target])
+                .collect()
+        })
+        .collect();
+
+    // Counting the ancestors of a commit takes a walk over the range, so only
+    // an evenly spaced sample of the untested commits is considered in large
+    // ranges.
+    let tested: HashSet<&CommitId> = state.bad.iter().chain(&state.skipped).collect();
+    let untested = (0..nodes.len())
+        .filter(|&pos| !tested.contains(&nodes[pos].0))
+        .collect_vec();
+    let step = untested.len().div_ceil(MAX_CANDIDATES).max(1);
+    let mut visited = vec![false; nodes.len()];
+    let best = untested
+        .iter()
+        .step_by(step)
+        .map(|&pos| {
+            let count = count_ancestors(&parents, pos, &mut visited);
+            (&nodes[pos].0, count.min(nodes.len() - count))
+        })
+        // max_by_key() returns the last maximum, so this prefers the first
+        // (i.e. newest) commit on ties.
+        .rev()
+        .max_by_key(|&(_, score)| score);
+    if let Some((id, _)) = best {
+        Ok(BisectionStep::Test(id.clone()))
+    } else if let [(id, _)] = nodes.as_slice() {
+        Ok(BisectionStep::Found(id.clone()))
+    } else {
+        Ok(BisectionStep::Ambiguous(
+            nodes.into_iter().map(|(id, _)| id).collect(),
+        ))
+    }
+}
+
+/// Counts the ancestors (including self) of the node at `pos` by walking the
+/// `parents` edges. `visited` is scratch space of the same length as `parents`.
+fn count_ancestors(parents: &[Vec<usize>], pos: usize, visited: &mut [bool]) -> usize {
+    visited.fill(false);
+    visited[pos] = true;
+    let mut stack = vec![pos];
+    let mut count = 0;
+    while let Some(pos) = stack.pop() {
+        count += 1;
+        for &parent in &parents[pos] {
+            if !visited[parent] {
+                visited[parent] = true;
+                stack.push(parent);
+            }
+        }
+    }
+    count
+}
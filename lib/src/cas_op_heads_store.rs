@@ -0,0 +1,448 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Operation heads store that doesn't rely on file locking.
+//!
+//! The set of op heads is recorded as a sequence of generations. Each update
+//! claims the next generation by creating its file without overwriting, which
+//! fails if a concurrent process claimed it first, in which case the update is
+//! retried on top of the winning generation. This only requires that creating
+//! a file exclusively is atomic, which holds for network file systems such as
+//! NFS where POSIX locks are unreliable.
+//!
+//! Readers start from the latest generation listed in the directory, and roll
+//! forward from there in case the listing is stale. Old generations are pruned
+//! in ascending order, keeping the most recent ones, so the directory stays
+//! small. A process that was stalled for long enough could claim a generation
+//! that has already been pruned. Since the previous generation would have been
+//! pruned before it, such a claim is detected and retried.
+//!
+//! Overwriting a single head file by atomic rename can't be used for the
+//! compare-and-swap, since a rename silently replaces whatever a concurrent
+//! process renamed into place after the state was read. Exclusively creating
+//! the file of the next generation fails instead, and the generation counter
+//! in its name tells readers which file is the latest.
+//!
+//! No lock is taken, not even to resolve divergent operations. Concurrent
+//! processes may then merge the same operations, which leaves their merges
+//! as divergent operations to be merged by the next process.
+
+#![allow(missing_docs)]
+
+use std::any::Any;
+use std::fs;
+use std::io;
+use std::io::Write as _;
+use std::path::Path;
+use std::path::PathBuf;
+use std::thread;
+use std::time::Duration;
+
+use tempfile::NamedTempFile;
+use thiserror::Error;
+
+use crate::backend::BackendInitError;
+use crate::file_util::IoResultExt as _;
+use crate::file_util::PathError;
+use crate::object_id::ObjectId as _;
+use crate::op_heads_store::OpHeadsStore;
+use crate::op_heads_store::OpHeadsStoreError;
+use crate::op_heads_store::OpHeadsStoreLock;
+use crate::op_store::OperationId;
+
+/// Error that may occur during [`CasOpHeadsStore`] initialization.
+#[derive(Debug, Error)]
+#[error("Failed to initialize compare-and-swap operation heads store")]
+pub struct CasOpHeadsStoreInitError(#[from] pub PathError);
+
+impl From<CasOpHeadsStoreInitError> for BackendInitError {
+    fn from(err: CasOpHeadsStoreInitError) -> Self {
+        BackendInitError(err.into())
+    }
+}
+
+/// Number of recent generations kept when pruning.
+const KEPT_GENERATIONS: u64 = 100;
+
+/// How many times to list the generations again if the latest one was pruned
+/// before it could be read.
+const MAX_READ_ATTEMPTS: usize = 10;
+
+#[derive(Debug)]
+pub struct CasOpHeadsStore {
+    dir: PathBuf,
+    kept_generations: u64,
+}
+
+/// Op heads recorded at a generation.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct OpHeadsState {
+    generation: u64,
+    op_heads: Vec<OperationId>,
+}
+
+impl OpHeadsState {
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut buf = format!("{}\n", self.generation);
+        for id in &self.op_heads {
+            buf.push_str(&id.hex());
+            buf.push('\n');
+        }
+        buf.into_bytes()
+    }
+
+    fn from_bytes(buf: &[u8]) -> Result<Self, OpHeadsStoreError> {
+        let invalid = || OpHeadsStoreError::Read("Invalid operation heads state".into());
+        let text = std::str::from_utf8(buf).map_err(|_| invalid())?;
+        let mut lines = text.lines();
+        let generation = lines
+            .next()
+            .and_then(|line| line.parse().ok())
+            .ok_or_else(invalid)?;
+        let op_heads = lines
+            .map(|line| OperationId::try_from_hex(line).map_err(|_| invalid()))
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            generation,
+            op_heads,
+        })
+    }
+}
+
+impl CasOpHeadsStore {
+    pub fn name() -> &'static str {
+        "cas_op_heads_store"
+    }
+
+    pub fn init(dir: &Path) -> Result<Self, CasOpHeadsStoreInitError> {
+        let generations_dir = dir.join("generations");
+        fs::create_dir(&generations_dir).context(&generations_dir)?;
+        let store = Self::load(dir);
+        // The initial generation is empty. Recording it makes the first update
+        // go through the same compare-and-swap as the others.
+        let state = OpHeadsState::default();
+        let path = store.generation_path(state.generation);
+        fs::write(&path, state.to_bytes()).context(&path)?;
+        Ok(store)
+    }
+
+    pub fn load(dir: &Path) -> Self {
+        Self {
+            dir: dir.to_owned(),
+            kept_generations: KEPT_GENERATIONS,
+        }
+    }
+
+    fn generations_dir(&self) -> PathBuf {
+        self.dir.join("generations")
+    }
+
+    fn generation_path(&self, generation: u64) -> PathBuf {
+        self.generations_dir().join(generation.to_string())
+    }
+
+    /// Lists the generations on disk in ascending order.
+    fn list_generations(&self) -> io::Result<Vec<u64>> {
+        let mut generations = Vec::new();
+        for entry in self.generations_dir().read_dir()? {
+            let name = entry?.file_name();
+            if let Some(generation) = name.to_str().and_then(|name| name.parse().ok()) {
+                generations.push(generation);
+            }
+        }
+        generations.sort_unstable();
+        Ok(generations)
+    }
+
+    fn read_generation(&self, generation: u64) -> Result<Option<OpHeadsState>, OpHeadsStoreError> {
+        let path = self.generation_path(generation);
+        match fs::read(&path) {
+            Ok(buf) => OpHeadsState::from_bytes(&buf).map(Some),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err)
+                .context(&path)
+                .map_err(|err| OpHeadsStoreError::Read(err.into())),
+        }
+    }
+
+    /// Reads the latest generation.
+    fn read_state(&self) -> Result<OpHeadsState, OpHeadsStoreError> {
+        let mut sleep = Duration::from_millis(1);
+        for _ in 0..MAX_READ_ATTEMPTS {
+            let generations = self
+                .list_generations()
+                .context(self.generations_dir())
+                .map_err(|err| OpHeadsStoreError::Read(err.into()))?;
+            let Some(&generation) = generations.last() else {
+                return Err(OpHeadsStoreError::Read(
+                    "Operation heads state is missing".into(),
+                ));
+            };
+            // The generation may have been pruned since it was listed.
+            let Some(mut state) = self.read_generation(generation)? else {
+                thread::sleep(sleep);
+                sleep = (sleep * 2).min(Duration::from_millis(100));
+                continue;
+            };
+            // The listing may be stale, especially on network file systems.
+            while let Some(next_state) = self.read_generation(state.generation + 1)? {
+                state = next_state;
+            }
+            return Ok(state);
+        }
+        Err(OpHeadsStoreError::Read(
+            "Operation heads state kept disappearing while reading".into(),
+        ))
+    }
+
+    /// Records `state` as its generation. Returns `false` if the generation
+    /// has already been claimed by another process.
+    fn try_claim_generation(&self, state: &OpHeadsState) -> io::Result<bool> {
+        let mut temp_file = NamedTempFile::new_in(&self.dir)?;
+        temp_file.write_all(&state.to_bytes())?;
+        let path = self.generation_path(state.generation);
+        match temp_file.persist_noclobber(&path) {
+            Ok(_) => {}
+            Err(err) if err.error.kind() == io::ErrorKind::AlreadyExists => return Ok(false),
+            Err(err) => return Err(err.error),
+        }
+        // If the previous generation has been pruned, this generation must
+        // have been claimed and pruned too while this process was stalled.
+        if !self.generation_path(state.generation - 1).try_exists()? {
+            match fs::remove_file(&path) {
+                Ok(()) => {}
+                // Pruned again by another process
+                Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+                Err(err) => return Err(err),
+            }
+            return Ok(false);
+        }
+        Ok(true)
+    }
+
+    /// Deletes the generations older than the `kept_generations` before
+    /// `latest_generation`, oldest first.
+    fn prune_generations(&self, latest_generation: u64) -> io::Result<()> {
+        let Some(min_generation) = latest_generation.checked_sub(self.kept_generations) else {
+            return Ok(());
+        };
+        for generation in self.list_generations()? {
+            if generation >= min_generation {
+                break;
+            }
+            match fs::remove_file(self.generation_path(generation)) {
+                Ok(()) => {}
+                // Pruned by another process
+                Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Lock that doesn't lock anything.
+struct CasOpHeadsStoreLock;
+
+impl OpHeadsStoreLock for CasOpHeadsStoreLock {}
+
+impl OpHeadsStore for CasOpHeadsStore {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        Self::name()
+    }
+
+    fn update_op_heads(
+        &self,
+        old_ids: &[OperationId],
+        new_id: &OperationId,
+    ) -> Result<(), OpHeadsStoreError> {
+        assert!(!old_ids.contains(new_id));
+        let write_err = |err: io::Error| OpHeadsStoreError::Write {
+            new_op_id: new_id.clone(),
+            source: err.into(),
+        };
+        loop {
+            let old_state = self.read_state()?;
+            let mut op_heads = old_state.op_heads;
+            op_heads.retain(|id| !old_ids.contains(id) && id != new_id);
+            op_heads.push(new_id.clone());
+            let new_state = OpHeadsState {
+                generation: old_state.generation + 1,
+                op_heads,
+            };
+            if self.try_claim_generation(&new_state).map_err(write_err)? {
+                // Failing to prune is harmless since readers only look at the
+                // latest generation.
+                if new_state.generation % self.kept_generations == 0 {
+                    self.prune_generations(new_state.generation).ok();
+                }
+                return Ok(());
+            }
+        }
+    }
+
+    fn get_op_heads(&self) -> Result<Vec<OperationId>, OpHeadsStoreError> {
+        Ok(self.read_state()?.op_heads)
+    }
+
+    fn lock(&self) -> Result<Box<dyn OpHeadsStoreLock + '_>, OpHeadsStoreError> {
+        // Updates are compare-and-swapped, so nothing needs to be locked.
+        Ok(Box::new(CasOpHeadsStoreLock))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use assert_matches::assert_matches;
+    use itertools::Itertools as _;
+
+    use super::*;
+
+    #[test]
+    fn test_state_roundtrip() {
+        let state = OpHeadsState {
+            generation: 3,
+            op_heads: vec![OperationId::from_hex("0123"), OperationId::from_hex("abcd")],
+        };
+        assert_eq!(OpHeadsState::from_bytes(&state.to_bytes()).unwrap(), state);
+        assert!(OpHeadsState::from_bytes(b"").is_err());
+        assert!(OpHeadsState::from_bytes(b"1\nxyz\n").is_err());
+    }
+
+    #[test]
+    fn test_update_op_heads() {
+        let temp_dir = testutils::new_temp_dir();
+        let store = CasOpHeadsStore::init(temp_dir.path()).unwrap();
+        let id = |hex: &'static str| OperationId::from_hex(hex);
+        assert_eq!(store.get_op_heads().unwrap(), vec![]);
+
+        store.update_op_heads(&[], &id("01")).unwrap();
+        store.update_op_heads(&[id("01")], &id("02")).unwrap();
+        store.update_op_heads(&[id("01")], &id("03")).unwrap();
+        assert_eq!(store.get_op_heads().unwrap(), vec![id("02"), id("03")]);
+        assert_eq!(store.read_state().unwrap().generation, 3);
+
+        // A generation that has already been claimed isn't overwritten
+        let stale_state = OpHeadsState {
+            generation: 1,
+            op_heads: vec![id("01")],
+        };
+        assert!(!store.try_claim_generation(&stale_state).unwrap());
+        store
+            .update_op_heads(&[id("02"), id("03")], &id("04"))
+            .unwrap();
+        let state = store.read_state().unwrap();
+        assert_eq!(state.generation, 4);
+        assert_eq!(state.op_heads, vec![id("04")]);
+        let store = CasOpHeadsStore::load(temp_dir.path());
+        assert_eq!(store.get_op_heads().unwrap(), vec![id("04")]);
+    }
+
+    #[test]
+    fn test_prune_generations() {
+        let temp_dir = testutils::new_temp_dir();
+        let mut store = CasOpHeadsStore::init(temp_dir.path()).unwrap();
+        store.kept_generations = 4;
+        let id = |n: u64| OperationId::new(n.to_be_bytes().to_vec());
+
+        for n in 1..=10 {
+            store.update_op_heads(&[], &id(n)).unwrap();
+            store.update_op_heads(&[id(n)], &id(n + 100)).unwrap();
+        }
+        // Generations are pruned every 4 generations, keeping the last 4
+        assert_eq!(store.list_generations().unwrap(), (16..=20).collect_vec());
+        assert_eq!(
+            store.get_op_heads().unwrap(),
+            (101..=110).map(id).collect_vec()
+        );
+
+        // A stalled process can't claim a generation that has been pruned
+        let stale_state = OpHeadsState {
+            generation: 10,
+            op_heads: vec![id(1)],
+        };
+        assert!(!store.try_claim_generation(&stale_state).unwrap());
+        assert_eq!(store.list_generations().unwrap(), (16..=20).collect_vec());
+        assert_eq!(store.read_state().unwrap().generation, 20);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_read_state_gives_up() {
+        let temp_dir = testutils::new_temp_dir();
+        let store = CasOpHeadsStore::init(temp_dir.path()).unwrap();
+        // A generation that is listed but can never be read, as if it were
+        // pruned every time it's listed
+        std::os::unix::fs::symlink("missing", store.generation_path(5)).unwrap();
+        assert_matches!(store.read_state(), Err(OpHeadsStoreError::Read(_)));
+        assert_matches!(store.get_op_heads(), Err(OpHeadsStoreError::Read(_)));
+
+        std::fs::remove_file(store.generation_path(5)).unwrap();
+        assert_eq!(store.read_state().unwrap().generation, 0);
+    }
+
+    #[test]
+    fn test_lock() {
+        let temp_dir = testutils::new_temp_dir();
+        let store = CasOpHeadsStore::init(temp_dir.path()).unwrap();
+        // Nothing is locked, so concurrent processes don't wait for each other
+        let _lock = store.lock().unwrap();
+        let _lock2 = store.lock().unwrap();
+        store
+            .update_op_heads(&[], &OperationId::from_hex("01"))
+            .unwrap();
+    }
+
+    #[test]
+    fn test_concurrent_updates() {
+        // Stress test with many writers, each of which replaces the op head it
+        // added last time. No update should be lost.
+        let temp_dir = testutils::new_temp_dir();
+        let dir = temp_dir.path();
+        CasOpHeadsStore::init(dir).unwrap();
+        let num_threads = 16;
+        let num_updates_per_thread = 50;
+        let id = |thread: u64, n: u64| OperationId::new([thread, n].map(u64::to_be_bytes).concat());
+        thread::scope(|s| {
+            for thread in 0..num_threads {
+                s.spawn(move || {
+                    let mut store = CasOpHeadsStore::load(dir);
+                    store.kept_generations = 10;
+                    store.update_op_heads(&[], &id(thread, 0)).unwrap();
+                    for n in 1..num_updates_per_thread {
+                        store
+                            .update_op_heads(&[id(thread, n - 1)], &id(thread, n))
+                            .unwrap();
+                    }
+                });
+            }
+        });
+        let store = CasOpHeadsStore::load(dir);
+        let state = store.read_state().unwrap();
+        // Claims can be retried if pruning raced with them
+        assert!(state.generation >= num_threads * num_updates_per_thread);
+        let expected_heads: HashSet<_> = (0..num_threads)
+            .map(|thread| id(thread, num_updates_per_thread - 1))
+            .collect();
+        assert_eq!(HashSet::from_iter(state.op_heads), expected_heads);
+        // At most the kept generations remain, plus those claimed while pruning
+        assert!(store.list_generations().unwrap().len() < 100);
+    }
+}
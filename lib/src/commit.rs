@@ -33,6 +33,7 @@ use crate::backend::Signature;
 use crate::merged_tree::MergedTree;
 use crate::repo::Repo;
 use crate::rewrite::merge_commit_trees;
+use crate::signing::SigTrust;
 use crate::signing::SignResult;
 use crate::signing::Verification;
 use crate::store::Store;
@@ -182,6 +183,20 @@ impl Commit {
             .map(|sig| self.store.signer().verify(&self.id, &sig.data, &sig.sig))
             .transpose()
     }
+
+    /// An even slower (but cached) way to get the trust in the signing key,
+    /// which is determined for the committer at the commit time.
+    pub fn trust(&self) -> SignResult<Option<SigTrust>> {
+        self.data
+            .secure_sig
+            .as_ref()
+            .map(|sig| {
+                self.store
+                    .signer()
+                    .trust(&self.id, &sig.data, &sig.sig, self.committer())
+            })
+            .transpose()
+    }
 }
 
 pub(crate) fn is_backend_commit_empty(
@@ -18,6 +18,7 @@ use std::io;
 use std::io::Read;
 use std::io::Write;
 use std::iter::zip;
+use std::sync::Arc;
 
 use bstr::BString;
 use bstr::ByteSlice as _;
@@ -42,6 +43,8 @@ use crate::diff::DiffHunk;
 use crate::diff::DiffHunkKind;
 use crate::files;
 use crate::files::MergeResult;
+use crate::fileset::FilesetExpression;
+use crate::matchers::Matcher;
 use crate::merge::Merge;
 use crate::merge::MergedTreeValue;
 use crate::repo_path::RepoPath;
@@ -281,6 +284,61 @@ pub enum ConflictMarkerStyle {
     Snapshot,
     /// Style which replicates Git's "diff3" style to support external tools.
     Git,
+    /// Style which replicates Git's "zdiff3" style, which is like "diff3" but
+    /// moves lines common to both sides out of the conflict region.
+    Zdiff3,
+}
+
+/// Conflict marker styles to use instead of the default style for files
+/// matching some filesets.
+#[derive(Clone, Debug, Default)]
+pub struct ConflictMarkerStyleOverrides {
+    overrides: Arc<[(FilesetExpression, ConflictMarkerStyle)]>,
+}
+
+impl ConflictMarkerStyleOverrides {
+    /// Creates overrides from a list of filesets and styles. The style of the
+    /// first fileset which matches a file takes precedence.
+    pub fn new(overrides: Vec<(FilesetExpression, ConflictMarkerStyle)>) -> Self {
+        Self {
+            overrides: overrides.into(),
+        }
+    }
+
+    /// Builds the matchers of the filesets once, so the style of many files
+    /// can be looked up. Files which no fileset matches use `default_style`.
+    pub fn to_style_matcher(
+        &self,
+        default_style: ConflictMarkerStyle,
+    ) -> ConflictMarkerStyleMatcher {
+        let overrides = self
+            .overrides
+            .iter()
+            .map(|(expression, style)| (expression.to_matcher(), *style))
+            .collect();
+        ConflictMarkerStyleMatcher {
+            overrides,
+            default_style,
+        }
+    }
+}
+
+/// Looks up the conflict marker style of files, built from
+/// [`ConflictMarkerStyleOverrides`].
+#[derive(Debug)]
+pub struct ConflictMarkerStyleMatcher {
+    overrides: Vec<(Box<dyn Matcher>, ConflictMarkerStyle)>,
+    default_style: ConflictMarkerStyle,
+}
+
+impl ConflictMarkerStyleMatcher {
+    /// Returns the conflict marker style to use for `path`.
+    pub fn style_for(&self, path: &RepoPath) -> ConflictMarkerStyle {
+        self.overrides
+            .iter()
+            .find(|(matcher, _)| matcher.matches(path))
+            .map_or(self.default_style, |(_, style)| *style)
+    }
 }
 
 /// Characters which can be repeated to form a conflict marker line when
@@ -486,6 +544,19 @@ fn materialize_conflict_hunks(
                         output,
                     )?;
                 }
+                (ConflictMarkerStyle::Zdiff3, [left, base, right]) => {
+                    let (prefix_len, suffix_len) = common_prefix_suffix_lines_len(left, right);
+                    output.write_all(&left[..prefix_len])?;
+                    materialize_git_style_conflict(
+                        &left[prefix_len..left.len() - suffix_len],
+                        base,
+                        &right[prefix_len..right.len() - suffix_len],
+                        &conflict_info,
+                        conflict_marker_len,
+                        output,
+                    )?;
+                    output.write_all(&left[left.len() - suffix_len..])?;
+                }
                 _ => {
                     materialize_jj_style_conflict(
                         hunk,
@@ -501,6 +572,26 @@ fn materialize_conflict_hunks(
     Ok(())
 }
 
+/// Returns the lengths in bytes of the lines at the start and at the end which
+/// are the same in `left` and `right`. The lines don't overlap.
+fn common_prefix_suffix_lines_len(left: &[u8], right: &[u8]) -> (usize, usize) {
+    let prefix_len = zip(left.lines_with_terminator(), right.lines_with_terminator())
+        .take_while(|(left_line, right_line)| left_line == right_line)
+        .map(|(line, _)| line.len())
+        .sum();
+    let (left, right) = (&left[prefix_len..], &right[prefix_len..]);
+    // Compare lines backwards. Since both remaining parts are different, the
+    // lines can't reach the common prefix.
+    let suffix_len = zip(
+        left.lines_with_terminator().rev(),
+        right.lines_with_terminator().rev(),
+    )
+    .take_while(|(left_line, right_line)| left_line == right_line)
+    .map(|(line, _)| line.len())
+    .sum();
+    (prefix_len, suffix_len)
+}
+
 fn materialize_git_style_conflict(
     left: &[u8],
     base: &[u8],
@@ -989,6 +1080,12 @@ pub async fn update_from_content(
         }
     }
 
+    if conflict_marker_style == ConflictMarkerStyle::Zdiff3 {
+        if let MergeResult::Conflict(old_hunks) = files::merge_hunks(&merge_hunk) {
+            restore_zdiff3_common_lines(&old_hunks, &mut hunks);
+        }
+    }
+
     let mut contents = simplified_file_ids.map(|_| vec![]);
     for hunk in hunks {
         if let Some(slice) = hunk.as_resolved() {
@@ -1042,6 +1139,59 @@ pub async fn update_from_content(
     Ok(new_file_ids)
 }
 
+/// Moves the lines which the "zdiff3" style wrote outside of the conflict
+/// markers back into both sides of the parsed conflict hunks. Otherwise, they
+/// would be parsed as resolved content and also be added to the base. Lines
+/// which were edited or are no longer next to their conflict stay resolved.
+fn restore_zdiff3_common_lines(old_hunks: &[Merge<BString>], hunks: &mut [Merge<BString>]) {
+    let old_conflicts = old_hunks.iter().filter(|hunk| !hunk.is_resolved());
+    let new_conflict_indices = hunks
+        .iter()
+        .positions(|hunk| !hunk.is_resolved())
+        .collect_vec();
+    if old_conflicts.clone().count() != new_conflict_indices.len() {
+        return;
+    }
+    for (old_hunk, index) in zip(old_conflicts, new_conflict_indices) {
+        let ([old_left, _, old_right], [left, base, right]) =
+            (old_hunk.as_slice(), hunks[index].as_slice())
+        else {
+            continue;
+        };
+        let (prefix_len, suffix_len) = common_prefix_suffix_lines_len(old_left, old_right);
+        let prefix = &old_left[..prefix_len];
+        let suffix = &old_left[old_left.len() - suffix_len..];
+        let before = index
+            .checked_sub(1)
+            .and_then(|i| hunks[i].as_resolved())
+            .filter(|content| !prefix.is_empty() && content.ends_with(prefix));
+        let after = hunks
+            .get(index + 1)
+            .and_then(|hunk| hunk.as_resolved())
+            .filter(|content| !suffix.is_empty() && content.starts_with(suffix));
+        let prefix = if before.is_some() { prefix } else { &[] };
+        let suffix = if after.is_some() { suffix } else { &[] };
+        if prefix.is_empty() && suffix.is_empty() {
+            continue;
+        }
+        let new_before = before.map(|content| content[..content.len() - prefix.len()].into());
+        let new_after = after.map(|content| content[suffix.len()..].into());
+        let with_common_lines =
+            |side: &BString| -> BString { [prefix, side, suffix].concat().into() };
+        hunks[index] = Merge::from_vec(vec![
+            with_common_lines(left),
+            base.clone(),
+            with_common_lines(right),
+        ]);
+        if let Some(content) = new_before {
+            hunks[index - 1] = Merge::resolved(content);
+        }
+        if let Some(content) = new_after {
+            hunks[index + 1] = Merge::resolved(content);
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
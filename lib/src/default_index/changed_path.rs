@@ -0,0 +1,547 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Index of paths changed by each commit.
+
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::fmt;
+use std::fmt::Debug;
+use std::fs::File;
+use std::io;
+use std::io::Read;
+use std::io::Write as _;
+use std::path::Path;
+use std::sync::Arc;
+
+use blake2::Blake2b512;
+use digest::Digest as _;
+use futures::StreamExt as _;
+use itertools::Itertools as _;
+use pollster::FutureExt as _;
+use tempfile::NamedTempFile;
+
+use super::composite::CompositeIndex;
+use crate::backend::BackendResult;
+use crate::backend::CommitId;
+use crate::commit::Commit;
+use crate::file_util::persist_content_addressed_temp_file;
+use crate::matchers::EverythingMatcher;
+use crate::merged_tree::resolve_file_values;
+use crate::object_id::ObjectId as _;
+use crate::repo_path::RepoPath;
+use crate::repo_path::RepoPathBuf;
+use crate::rewrite;
+use crate::store::Store;
+
+const CHANGED_PATH_SEGMENT_FILE_FORMAT_VERSION: u32 = 1;
+
+/// Index of paths changed by commits, compared to the auto-merged parents.
+///
+/// Unlike the commit index, the entries are looked up by commit id, so the
+/// index may cover a subset of the commits in the repository. Commits that
+/// aren't indexed have to be diffed by the caller.
+///
+/// File format:
+/// ```text
+/// u32: file format version
+/// u32: parent segment file name length (0 means root)
+/// <length number of bytes>: parent segment file name
+///
+/// u32: number of commits in the commit index when this segment was built
+/// u32: number of local commits
+/// u32: number of local paths
+/// u32: number of local path references
+///
+/// for each commit, sorted by commit id:
+///   <commit id length number of bytes>: commit id
+/// for each commit, sorted by commit id:
+///   u32: end offset of the commit's changed paths in the path references
+/// for each path reference:
+///   u32: position of the changed path in the local paths
+/// for each path, sorted by path:
+///   u32: end offset of the path in the path bytes
+/// <path bytes>: concatenated internal path strings
+/// ```
+pub struct ChangedPathIndex {
+    parent: Option<Arc<ChangedPathIndex>>,
+    name: String,
+    commit_id_length: usize,
+    num_indexed_commits: u32,
+    // Sorted commit ids of local commits
+    commit_ids: Vec<u8>,
+    path_ref_ends: Vec<u32>,
+    path_refs: Vec<u32>,
+    paths: Vec<RepoPathBuf>,
+}
+
+impl Debug for ChangedPathIndex {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("ChangedPathIndex")
+            .field("name", &self.name)
+            .field("parent", &self.parent)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ChangedPathIndex {
+    /// Loads both parent segments and local entries from the given file
+    /// `name`.
+    pub(super) fn load(
+        dir: &Path,
+        name: String,
+        commit_id_length: usize,
+    ) -> io::Result<Arc<ChangedPathIndex>> {
+        let mut file = File::open(dir.join(&name))?;
+        Self::load_from(&mut file, dir, name, commit_id_length)
+    }
+
+    fn load_from(
+        file: &mut dyn Read,
+        dir: &Path,
+        name: String,
+        commit_id_length: usize,
+    ) -> io::Result<Arc<ChangedPathIndex>> {
+        let format_version = read_u32(file)?;
+        if format_version != CHANGED_PATH_SEGMENT_FILE_FORMAT_VERSION {
+            return Err(invalid_data(format!(
+                "Found changed-path index format version {format_version}, expected version \
+                 {CHANGED_PATH_SEGMENT_FILE_FORMAT_VERSION}"
+            )));
+        }
+        let parent_filename_len = read_u32(file)?;
+        let parent = if parent_filename_len > 0 {
+            let mut parent_filename_bytes = vec![0; parent_filename_len as usize];
+            file.read_exact(&mut parent_filename_bytes)?;
+            let parent_filename = String::from_utf8(parent_filename_bytes)
+                .map_err(|_| invalid_data("parent file name is not valid UTF-8"))?;
+            Some(ChangedPathIndex::load(
+                dir,
+                parent_filename,
+                commit_id_length,
+            )?)
+        } else {
+            None
+        };
+        Self::load_with_parent_file(file, name, parent, commit_id_length)
+    }
+
+    fn load_with_parent_file(
+        file: &mut dyn Read,
+        name: String,
+        parent: Option<Arc<ChangedPathIndex>>,
+        commit_id_length: usize,
+    ) -> io::Result<Arc<ChangedPathIndex>> {
+        let num_indexed_commits = read_u32(file)?;
+        let num_local_commits = read_u32(file)? as usize;
+        let num_local_paths = read_u32(file)? as usize;
+        let num_path_refs = read_u32(file)? as usize;
+        let mut commit_ids = vec![0; num_local_commits * commit_id_length];
+        file.read_exact(&mut commit_ids)?;
+        let path_ref_ends = read_u32_vec(file, num_local_commits)?;
+        let path_refs = read_u32_vec(file, num_path_refs)?;
+        let path_ends = read_u32_vec(file, num_local_paths)?;
+        let mut path_bytes = vec![];
+        file.read_to_end(&mut path_bytes)?;
+
+        let is_valid_range = |ends: &[u32], len: usize| {
+            ends.iter().tuple_windows().all(|(start, end)| start <= end)
+                && ends.last().map_or(0, |&end| end as usize) == len
+        };
+        if !is_valid_range(&path_ref_ends, num_path_refs)
+            || !is_valid_range(&path_ends, path_bytes.len())
+            || path_refs.iter().any(|&pos| pos as usize >= num_local_paths)
+        {
+            return Err(invalid_data("changed-path index is corrupt"));
+        }
+        let paths = itertools::chain([0], path_ends.iter().copied())
+            .tuple_windows()
+            .map(|(start, end)| {
+                let path = std::str::from_utf8(&path_bytes[start as usize..end as usize])
+                    .map_err(|_| invalid_data("path is not valid UTF-8"))?;
+                RepoPathBuf::from_internal_string(path)
+                    .map_err(|_| invalid_data("path is not valid"))
+            })
+            .try_collect()?;
+        Ok(Arc::new(ChangedPathIndex {
+            parent,
+            name,
+            commit_id_length,
+            num_indexed_commits,
+            commit_ids,
+            path_ref_ends,
+            path_refs,
+            paths,
+        }))
+    }
+
+    pub(super) fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Number of commits in the commit index when this index was built.
+    /// Commits at lower positions are either indexed or have to be diffed.
+    pub(super) fn num_indexed_commits(&self) -> u32 {
+        self.num_indexed_commits
+    }
+
+    fn num_local_commits(&self) -> usize {
+        self.path_ref_ends.len()
+    }
+
+    /// Returns the number of commits indexed by this and the parent segments.
+    pub fn num_commits(&self) -> usize {
+        self.ancestor_segments()
+            .map(|segment| segment.num_local_commits())
+            .sum()
+    }
+
+    fn ancestor_segments(&self) -> impl Iterator<Item = &ChangedPathIndex> {
+        itertools::iterate(Some(self), |segment| {
+            segment.and_then(|s| s.parent.as_deref())
+        })
+        .while_some()
+    }
+
+    fn local_commit_id(&self, local_pos: usize) -> &[u8] {
+        let offset = local_pos * self.commit_id_length;
+        &self.commit_ids[offset..offset + self.commit_id_length]
+    }
+
+    fn local_changed_paths(&self, local_pos: usize) -> impl Iterator<Item = &RepoPath> {
+        let start = match local_pos {
+            0 => 0,
+            _ => self.path_ref_ends[local_pos - 1] as usize,
+        };
+        let end = self.path_ref_ends[local_pos] as usize;
+        self.path_refs[start..end]
+            .iter()
+            .map(|&pos| self.paths[pos as usize].as_ref())
+    }
+
+    fn local_commits(&self) -> impl Iterator<Item = (CommitId, Vec<RepoPathBuf>)> + '_ {
+        (0..self.num_local_commits()).map(|local_pos| {
+            let commit_id = CommitId::from_bytes(self.local_commit_id(local_pos));
+            let paths = self.local_changed_paths(local_pos).map(ToOwned::to_owned);
+            (commit_id, paths.collect())
+        })
+    }
+
+    /// Returns the paths changed by the given commit, or `None` if the commit
+    /// isn't indexed.
+    pub fn changed_paths(&self, commit_id: &CommitId) -> Option<impl Iterator<Item = &RepoPath>> {
+        self.ancestor_segments().find_map(|segment| {
+            let num_local_commits = segment.num_local_commits();
+            let local_pos = binary_search_by(num_local_commits, |local_pos| {
+                segment.local_commit_id(local_pos).cmp(commit_id.as_bytes())
+            })?;
+            Some(segment.local_changed_paths(local_pos))
+        })
+    }
+
+    fn has_id(&self, commit_id: &CommitId) -> bool {
+        self.changed_paths(commit_id).is_some()
+    }
+}
+
+/// In-memory changed-path index segment to be saved on top of the parent
+/// segment.
+pub(super) struct MutableChangedPathIndex {
+    parent: Option<Arc<ChangedPathIndex>>,
+    commit_id_length: usize,
+    num_indexed_commits: u32,
+    commits: BTreeMap<CommitId, Vec<RepoPathBuf>>,
+}
+
+impl MutableChangedPathIndex {
+    pub(super) fn full(commit_id_length: usize, num_indexed_commits: u32) -> Self {
+        MutableChangedPathIndex {
+            parent: None,
+            commit_id_length,
+            num_indexed_commits,
+            commits: BTreeMap::new(),
+        }
+    }
+
+    pub(super) fn incremental(parent: Arc<ChangedPathIndex>, num_indexed_commits: u32) -> Self {
+        MutableChangedPathIndex {
+            commit_id_length: parent.commit_id_length,
+            parent: Some(parent),
+            num_indexed_commits,
+            commits: BTreeMap::new(),
+        }
+    }
+
+    pub(super) fn has_id(&self, commit_id: &CommitId) -> bool {
+        self.commits.contains_key(commit_id)
+            || self
+                .parent
+                .as_ref()
+                .is_some_and(|parent| parent.has_id(commit_id))
+    }
+
+    pub(super) fn add_commit(&mut self, commit_id: CommitId, paths: Vec<RepoPathBuf>) {
+        self.commits.insert(commit_id, paths);
+    }
+
+    pub(super) fn num_local_commits(&self) -> usize {
+        self.commits.len()
+    }
+
+    /// Squashes the parent segments if they aren't much larger than this
+    /// segment, and saves the result in `dir`.
+    pub(super) fn squash_and_save_in(mut self, dir: &Path) -> io::Result<Arc<ChangedPathIndex>> {
+        while let Some(parent) = &self.parent {
+            if 2 * self.commits.len() < parent.num_local_commits() {
+                break;
+            }
+            let parent = self.parent.take().unwrap();
+            for (commit_id, paths) in parent.local_commits() {
+                self.commits.entry(commit_id).or_insert(paths);
+            }
+            self.parent = parent.parent.clone();
+        }
+        self.save_in(dir)
+    }
+
+    fn save_in(self, dir: &Path) -> io::Result<Arc<ChangedPathIndex>> {
+        if self.commits.is_empty() {
+            if let Some(parent) = self.parent {
+                return Ok(parent);
+            }
+        }
+
+        let mut buf = Vec::new();
+        buf.extend(CHANGED_PATH_SEGMENT_FILE_FORMAT_VERSION.to_le_bytes());
+        if let Some(parent) = &self.parent {
+            buf.extend(u32::try_from(parent.name.len()).unwrap().to_le_bytes());
+            buf.extend_from_slice(parent.name.as_bytes());
+        } else {
+            buf.extend(0_u32.to_le_bytes());
+        }
+        let local_entries_offset = buf.len();
+        self.serialize_local_entries(&mut buf);
+        let mut hasher = Blake2b512::new();
+        hasher.update(&buf);
+        let file_id_hex = hex::encode(hasher.finalize());
+        let file_path = dir.join(&file_id_hex);
+
+        let mut temp_file = NamedTempFile::new_in(dir)?;
+        let file = temp_file.as_file_mut();
+        file.write_all(&buf)?;
+        persist_content_addressed_temp_file(temp_file, file_path)?;
+
+        Ok(ChangedPathIndex::load_with_parent_file(
+            &mut &buf[local_entries_offset..],
+            file_id_hex,
+            self.parent,
+            self.commit_id_length,
+        )
+        .expect("in-memory changed-path index data should be valid and readable"))
+    }
+
+    fn serialize_local_entries(&self, buf: &mut Vec<u8>) {
+        let paths: BTreeSet<&RepoPath> =
+            self.commits.values().flatten().map(AsRef::as_ref).collect();
+        let path_positions: BTreeMap<&RepoPath, u32> = paths
+            .iter()
+            .enumerate()
+            .map(|(pos, &path)| (path, u32::try_from(pos).unwrap()))
+            .collect();
+        let num_path_refs: usize = self.commits.values().map(|paths| paths.len()).sum();
+
+        buf.extend(self.num_indexed_commits.to_le_bytes());
+        buf.extend(u32::try_from(self.commits.len()).unwrap().to_le_bytes());
+        buf.extend(u32::try_from(paths.len()).unwrap().to_le_bytes());
+        buf.extend(u32::try_from(num_path_refs).unwrap().to_le_bytes());
+        for commit_id in self.commits.keys() {
+            assert_eq!(commit_id.as_bytes().len(), self.commit_id_length);
+            buf.extend_from_slice(commit_id.as_bytes());
+        }
+        let mut path_ref_end = 0_u32;
+        for paths in self.commits.values() {
+            path_ref_end += u32::try_from(paths.len()).unwrap();
+            buf.extend(path_ref_end.to_le_bytes());
+        }
+        for path in self.commits.values().flatten() {
+            buf.extend(path_positions[path.as_ref()].to_le_bytes());
+        }
+        let mut path_end = 0_u32;
+        for path in &paths {
+            path_end += u32::try_from(path.as_internal_file_string().len()).unwrap();
+            buf.extend(path_end.to_le_bytes());
+        }
+        for path in &paths {
+            buf.extend_from_slice(path.as_internal_file_string().as_bytes());
+        }
+    }
+}
+
+/// Collects paths changed by the `commit` compared to its auto-merged
+/// parents.
+pub(super) fn collect_changed_paths(
+    store: &Arc<Store>,
+    index: &CompositeIndex,
+    commit: &Commit,
+) -> BackendResult<Vec<RepoPathBuf>> {
+    let parents: Vec<_> = commit.parents().try_collect()?;
+    if let [parent] = parents.as_slice() {
+        if commit.tree_id() == parent.tree_id() {
+            return Ok(vec![]);
+        }
+    }
+    let from_tree = rewrite::merge_commit_trees_no_resolve_without_repo(store, &index, &parents)?;
+    let to_tree = commit.tree()?;
+    let mut tree_diff = from_tree.diff_stream(&to_tree, &EverythingMatcher);
+    async {
+        let mut paths = vec![];
+        while let Some(entry) = tree_diff.next().await {
+            let (from_value, to_value) = entry.values?;
+            let from_value = resolve_file_values(store, &entry.path, from_value).await?;
+            if from_value != to_value {
+                paths.push(entry.path);
+            }
+        }
+        Ok(paths)
+    }
+    .block_on()
+}
+
+fn binary_search_by(len: usize, mut f: impl FnMut(usize) -> std::cmp::Ordering) -> Option<usize> {
+    let (mut low, mut high) = (0, len);
+    while low < high {
+        let mid = (low + high) / 2;
+        match f(mid) {
+            std::cmp::Ordering::Less => low = mid + 1,
+            std::cmp::Ordering::Greater => high = mid,
+            std::cmp::Ordering::Equal => return Some(mid),
+        }
+    }
+    None
+}
+
+fn read_u32(file: &mut dyn Read) -> io::Result<u32> {
+    let mut buf = [0; 4];
+    file.read_exact(&mut buf)?;
+    Ok(u32::from_le_bytes(buf))
+}
+
+fn read_u32_vec(file: &mut dyn Read, len: usize) -> io::Result<Vec<u32>> {
+    let mut buf = vec![0; len * 4];
+    file.read_exact(&mut buf)?;
+    Ok(buf
+        .chunks_exact(4)
+        .map(|chunk| u32::from_le_bytes(chunk.try_into().unwrap()))
+        .collect())
+}
+
+fn invalid_data(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::new_temp_dir;
+
+    fn commit_id(byte: u8) -> CommitId {
+        CommitId::new(vec![byte; 4])
+    }
+
+    fn repo_path_buf(value: &str) -> RepoPathBuf {
+        RepoPathBuf::from_internal_string(value).unwrap()
+    }
+
+    fn changed_paths(index: &ChangedPathIndex, commit_id: &CommitId) -> Option<Vec<String>> {
+        let paths = index.changed_paths(commit_id)?;
+        Some(
+            paths
+                .map(|path| path.as_internal_file_string().to_owned())
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_changed_path_index_save_and_load() {
+        let temp_dir = new_temp_dir();
+        let mut mutable_index = MutableChangedPathIndex::full(4, 3);
+        mutable_index.add_commit(commit_id(2), vec![repo_path_buf("b"), repo_path_buf("a/c")]);
+        mutable_index.add_commit(commit_id(1), vec![]);
+        mutable_index.add_commit(commit_id(3), vec![repo_path_buf("b")]);
+        let index = mutable_index.squash_and_save_in(temp_dir.path()).unwrap();
+        assert_eq!(index.num_commits(), 3);
+        assert_eq!(index.num_indexed_commits(), 3);
+
+        let loaded = ChangedPathIndex::load(temp_dir.path(), index.name().to_owned(), 4).unwrap();
+        for index in [&index, &loaded] {
+            assert_eq!(changed_paths(index, &commit_id(1)), Some(vec![]));
+            assert_eq!(
+                changed_paths(index, &commit_id(2)),
+                Some(vec!["b".to_owned(), "a/c".to_owned()])
+            );
+            assert_eq!(
+                changed_paths(index, &commit_id(3)),
+                Some(vec!["b".to_owned()])
+            );
+            assert_eq!(changed_paths(index, &commit_id(4)), None);
+        }
+    }
+
+    #[test]
+    fn test_changed_path_index_incremental() {
+        let temp_dir = new_temp_dir();
+        let mut mutable_index = MutableChangedPathIndex::full(4, 4);
+        for byte in 1..=4 {
+            mutable_index.add_commit(commit_id(byte), vec![repo_path_buf("a")]);
+        }
+        let base = mutable_index.squash_and_save_in(temp_dir.path()).unwrap();
+
+        // A small segment is stacked on top of the parent
+        let mut mutable_index = MutableChangedPathIndex::incremental(base.clone(), 5);
+        assert!(mutable_index.has_id(&commit_id(1)));
+        assert!(!mutable_index.has_id(&commit_id(5)));
+        mutable_index.add_commit(commit_id(5), vec![repo_path_buf("b")]);
+        let index = mutable_index.squash_and_save_in(temp_dir.path()).unwrap();
+        assert_eq!(index.parent.as_ref().unwrap().name(), base.name());
+        assert_eq!(index.num_local_commits(), 1);
+        assert_eq!(index.num_commits(), 5);
+        assert_eq!(
+            changed_paths(&index, &commit_id(1)),
+            Some(vec!["a".to_owned()])
+        );
+        assert_eq!(
+            changed_paths(&index, &commit_id(5)),
+            Some(vec!["b".to_owned()])
+        );
+
+        // Nothing to save
+        let mutable_index = MutableChangedPathIndex::incremental(index.clone(), 5);
+        let same = mutable_index.squash_and_save_in(temp_dir.path()).unwrap();
+        assert_eq!(same.name(), index.name());
+
+        // A segment of similar size is squashed into the parent
+        let mut mutable_index = MutableChangedPathIndex::incremental(index, 8);
+        for byte in 6..=8 {
+            mutable_index.add_commit(commit_id(byte), vec![repo_path_buf("c")]);
+        }
+        let squashed = mutable_index.squash_and_save_in(temp_dir.path()).unwrap();
+        assert!(squashed.parent.is_none());
+        assert_eq!(squashed.num_local_commits(), 8);
+        assert_eq!(squashed.num_indexed_commits(), 8);
+        assert_eq!(
+            changed_paths(&squashed, &commit_id(5)),
+            Some(vec!["b".to_owned()])
+        );
+    }
+}
@@ -530,7 +530,7 @@ impl Index for DefaultMutableIndex {
         expression: &ResolvedExpression,
         store: &Arc<Store>,
     ) -> Result<Box<dyn Revset + 'index>, RevsetEvaluationError> {
-        self.as_composite().evaluate_revset(expression, store)
+        self.as_composite().evaluate_revset(expression, store, None)
     }
 }
 
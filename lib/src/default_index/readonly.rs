@@ -27,6 +27,7 @@ use std::sync::Arc;
 use smallvec::smallvec;
 use thiserror::Error;
 
+use super::changed_path::ChangedPathIndex;
 use super::composite::AsCompositeIndex;
 use super::composite::ChangeIdIndexImpl;
 use super::composite::CompositeIndex;
@@ -591,21 +592,39 @@ impl IndexSegment for ReadonlyIndexSegment {
 
 /// Commit index backend which stores data on local disk.
 #[derive(Clone, Debug)]
-pub struct DefaultReadonlyIndex(Arc<ReadonlyIndexSegment>);
+pub struct DefaultReadonlyIndex {
+    segment: Arc<ReadonlyIndexSegment>,
+    changed_paths: Option<Arc<ChangedPathIndex>>,
+}
 
 impl DefaultReadonlyIndex {
     pub(super) fn from_segment(segment: Arc<ReadonlyIndexSegment>) -> Self {
-        DefaultReadonlyIndex(segment)
+        DefaultReadonlyIndex {
+            segment,
+            changed_paths: None,
+        }
+    }
+
+    pub(super) fn with_changed_paths(self, changed_paths: Option<Arc<ChangedPathIndex>>) -> Self {
+        DefaultReadonlyIndex {
+            changed_paths,
+            ..self
+        }
     }
 
     pub(super) fn as_segment(&self) -> &Arc<ReadonlyIndexSegment> {
-        &self.0
+        &self.segment
+    }
+
+    /// Returns the changed-path index if it's enabled.
+    pub fn changed_paths(&self) -> Option<&Arc<ChangedPathIndex>> {
+        self.changed_paths.as_ref()
     }
 }
 
 impl AsCompositeIndex for DefaultReadonlyIndex {
     fn as_composite(&self) -> &CompositeIndex {
-        self.0.as_composite()
+        self.segment.as_composite()
     }
 }
 
@@ -649,7 +668,8 @@ impl Index for DefaultReadonlyIndex {
         expression: &ResolvedExpression,
         store: &Arc<Store>,
     ) -> Result<Box<dyn Revset + 'index>, RevsetEvaluationError> {
-        self.as_composite().evaluate_revset(expression, store)
+        self.as_composite()
+            .evaluate_revset(expression, store, self.changed_paths.as_ref())
     }
 }
 
@@ -670,7 +690,7 @@ impl ReadonlyIndex for DefaultReadonlyIndex {
     }
 
     fn start_modification(&self) -> Box<dyn MutableIndex> {
-        Box::new(DefaultMutableIndex::incremental(self.0.clone()))
+        Box::new(DefaultMutableIndex::incremental(self.segment.clone()))
     }
 }
 
@@ -32,6 +32,7 @@ use futures::StreamExt as _;
 use itertools::Itertools as _;
 use pollster::FutureExt as _;
 
+use super::changed_path::ChangedPathIndex;
 use super::rev_walk::EagerRevWalk;
 use super::rev_walk::PeekableRevWalk;
 use super::rev_walk::RevWalk;
@@ -67,6 +68,7 @@ use crate::revset::GENERATION_RANGE_FULL;
 use crate::rewrite;
 use crate::store::Store;
 use crate::str_util::StringPattern;
+use crate::trailer::parse_description_trailers;
 use crate::union_find;
 
 type BoxedPredicateFn<'a> =
@@ -769,10 +771,22 @@ pub fn evaluate<I: AsCompositeIndex + Clone>(
     expression: &ResolvedExpression,
     store: &Arc<Store>,
     index: I,
+) -> Result<RevsetImpl<I>, RevsetEvaluationError> {
+    evaluate_with_changed_paths(expression, store, index, None)
+}
+
+/// Evaluates the `expression` by using the changed-path index to look up the
+/// paths modified by commits, if available.
+pub(super) fn evaluate_with_changed_paths<I: AsCompositeIndex + Clone>(
+    expression: &ResolvedExpression,
+    store: &Arc<Store>,
+    index: I,
+    changed_paths: Option<&Arc<ChangedPathIndex>>,
 ) -> Result<RevsetImpl<I>, RevsetEvaluationError> {
     let context = EvaluationContext {
         store: store.clone(),
         index: index.as_composite(),
+        changed_paths: changed_paths.cloned(),
     };
     let internal_revset = context.evaluate(expression)?;
     Ok(RevsetImpl::new(internal_revset, index))
@@ -781,6 +795,7 @@ pub fn evaluate<I: AsCompositeIndex + Clone>(
 struct EvaluationContext<'index> {
     store: Arc<Store>,
     index: &'index CompositeIndex,
+    changed_paths: Option<Arc<ChangedPathIndex>>,
 }
 
 fn to_u32_generation_range(range: &Range<u64>) -> Result<Range<u32>, RevsetEvaluationError> {
@@ -1016,9 +1031,11 @@ impl EvaluationContext<'_> {
         expression: &ResolvedPredicateExpression,
     ) -> Result<Box<dyn ToPredicateFn>, RevsetEvaluationError> {
         match expression {
-            ResolvedPredicateExpression::Filter(predicate) => {
-                Ok(build_predicate_fn(self.store.clone(), predicate))
-            }
+            ResolvedPredicateExpression::Filter(predicate) => Ok(build_predicate_fn(
+                self.store.clone(),
+                self.changed_paths.clone(),
+                predicate,
+            )),
             ResolvedPredicateExpression::Set(expression) => {
                 Ok(self.evaluate(expression)?.into_predicate())
             }
@@ -1147,6 +1164,7 @@ fn box_pure_predicate_fn<'a>(
 
 fn build_predicate_fn(
     store: Arc<Store>,
+    changed_paths: Option<Arc<ChangedPathIndex>>,
     predicate: &RevsetFilterPredicate,
 ) -> Box<dyn ToPredicateFn> {
     match predicate {
@@ -1173,6 +1191,18 @@ fn build_predicate_fn(
                 Ok(pattern.matches(commit.description().lines().next().unwrap_or_default()))
             })
         }
+        RevsetFilterPredicate::Trailer { key, value } => {
+            let key = key.clone();
+            let value = value.clone();
+            box_pure_predicate_fn(move |index, pos| {
+                let entry = index.entry_by_pos(pos);
+                let commit = store.get_commit(&entry.commit_id())?;
+                let trailers = parse_description_trailers(commit.description());
+                Ok(trailers.iter().any(|trailer| {
+                    trailer.key.eq_ignore_ascii_case(&key) && value.matches(&trailer.value)
+                }))
+            })
+        }
         RevsetFilterPredicate::AuthorName(pattern) => {
             let pattern = pattern.clone();
             box_pure_predicate_fn(move |index, pos| {
@@ -1225,18 +1255,35 @@ fn build_predicate_fn(
         }
         RevsetFilterPredicate::File(expr) => {
             let matcher: Rc<dyn Matcher> = expr.to_matcher().into();
+            // The index only records paths, so it can't evaluate predicates on
+            // file values.
+            let changed_paths = changed_paths.filter(|_| !expr.has_value_predicates());
             box_pure_predicate_fn(move |index, pos| {
                 let entry = index.entry_by_pos(pos);
-                let commit = store.get_commit(&entry.commit_id())?;
+                let commit_id = entry.commit_id();
+                if let Some(mut paths) = lookup_changed_paths(changed_paths.as_deref(), &commit_id)
+                {
+                    return Ok(paths.any(|path| matcher.matches(path)));
+                }
+                let commit = store.get_commit(&commit_id)?;
                 Ok(has_diff_from_parent(&store, index, &commit, &*matcher)?)
             })
         }
         RevsetFilterPredicate::DiffContains { text, files } => {
             let text_pattern = text.clone();
             let files_matcher: Rc<dyn Matcher> = files.to_matcher().into();
+            let changed_paths = changed_paths.filter(|_| !files.has_value_predicates());
             box_pure_predicate_fn(move |index, pos| {
                 let entry = index.entry_by_pos(pos);
-                let commit = store.get_commit(&entry.commit_id())?;
+                let commit_id = entry.commit_id();
+                // Skip commits which don't modify any of the files
+                if let Some(mut paths) = lookup_changed_paths(changed_paths.as_deref(), &commit_id)
+                {
+                    if !paths.any(|path| files_matcher.matches(path)) {
+                        return Ok(false);
+                    }
+                }
+                let commit = store.get_commit(&commit_id)?;
                 Ok(matches_diff_from_parent(
                     &store,
                     index,
@@ -1251,11 +1298,22 @@ fn build_predicate_fn(
             let commit = store.get_commit(&entry.commit_id())?;
             Ok(commit.has_conflict()?)
         }),
-        RevsetFilterPredicate::Signed => box_pure_predicate_fn(move |index, pos| {
+        RevsetFilterPredicate::Signed(None) => box_pure_predicate_fn(move |index, pos| {
             let entry = index.entry_by_pos(pos);
             let commit = store.get_commit(&entry.commit_id())?;
             Ok(commit.is_signed())
         }),
+        RevsetFilterPredicate::Signed(Some(trust)) => {
+            let trust = *trust;
+            box_pure_predicate_fn(move |index, pos| {
+                let entry = index.entry_by_pos(pos);
+                let commit = store.get_commit(&entry.commit_id())?;
+                let commit_trust = commit
+                    .trust()
+                    .map_err(|err| RevsetEvaluationError::Other(err.into()))?;
+                Ok(commit_trust == Some(trust))
+            })
+        }
         RevsetFilterPredicate::Extension(ext) => {
             let ext = ext.clone();
             box_pure_predicate_fn(move |index, pos| {
@@ -1267,6 +1325,13 @@ fn build_predicate_fn(
     }
 }
 
+fn lookup_changed_paths<'a>(
+    changed_paths: Option<&'a ChangedPathIndex>,
+    commit_id: &CommitId,
+) -> Option<impl Iterator<Item = &'a RepoPath>> {
+    changed_paths?.changed_paths(commit_id)
+}
+
 fn has_diff_from_parent(
     store: &Arc<Store>,
     index: &CompositeIndex,
@@ -27,6 +27,12 @@ use itertools::Itertools as _;
 use tempfile::NamedTempFile;
 use thiserror::Error;
 
+use super::changed_path::collect_changed_paths;
+use super::changed_path::ChangedPathIndex;
+use super::changed_path::MutableChangedPathIndex;
+use super::composite::AsCompositeIndex as _;
+use super::composite::CompositeIndex;
+use super::entry::IndexPosition;
 use super::mutable::DefaultMutableIndex;
 use super::readonly::DefaultReadonlyIndex;
 use super::readonly::ReadonlyIndexLoadError;
@@ -84,6 +90,15 @@ pub enum DefaultIndexStoreError {
         op_id: OperationId,
         source: BackendError,
     },
+    #[error("Failed to load changed-path index file")]
+    LoadChangedPathIndex(#[source] io::Error),
+    #[error("Failed to write changed-path index file")]
+    SaveChangedPathIndex(#[source] io::Error),
+    #[error("Failed to index changed paths at operation {op_id}")]
+    IndexChangedPaths {
+        op_id: OperationId,
+        source: BackendError,
+    },
     #[error(transparent)]
     OpStore(#[from] OpStoreError),
 }
@@ -130,6 +145,11 @@ impl DefaultIndexStore {
             }
             fs::remove_file(&path).context(&path)?;
         }
+        // The changed-path index is built on top of the commit index.
+        if self.is_changed_path_index_enabled() {
+            file_util::remove_dir_contents(&self.changed_path_operations_dir())?;
+            file_util::remove_dir_contents(&self.changed_path_segments_dir())?;
+        }
         Ok(())
     }
 
@@ -148,6 +168,23 @@ impl DefaultIndexStore {
         self.dir.join("segments")
     }
 
+    fn changed_paths_dir(&self) -> PathBuf {
+        self.dir.join("changed_paths")
+    }
+
+    fn changed_path_operations_dir(&self) -> PathBuf {
+        self.changed_paths_dir().join("operations")
+    }
+
+    fn changed_path_segments_dir(&self) -> PathBuf {
+        self.changed_paths_dir().join("segments")
+    }
+
+    /// Returns true if the changed-path index should be maintained.
+    pub fn is_changed_path_index_enabled(&self) -> bool {
+        self.changed_paths_dir().is_dir()
+    }
+
     fn load_index_segments_at_operation(
         &self,
         op_id: &OperationId,
@@ -283,6 +320,144 @@ impl DefaultIndexStore {
         Ok(index_file)
     }
 
+    /// Enables the changed-path index, and builds it for all commits in the
+    /// `index` at the given `operation`.
+    pub fn build_changed_path_index_at_operation(
+        &self,
+        operation: &Operation,
+        store: &Arc<Store>,
+        index: &DefaultReadonlyIndex,
+    ) -> Result<Arc<ChangedPathIndex>, DefaultIndexStoreError> {
+        for dir in [
+            self.changed_paths_dir(),
+            self.changed_path_operations_dir(),
+            self.changed_path_segments_dir(),
+        ] {
+            file_util::create_or_reuse_dir(&dir)
+                .map_err(DefaultIndexStoreError::SaveChangedPathIndex)?;
+        }
+        self.build_changed_path_index(operation, store, index.as_composite())
+    }
+
+    fn load_changed_path_index_at_operation(
+        &self,
+        op_id: &OperationId,
+        commit_id_length: usize,
+    ) -> io::Result<Arc<ChangedPathIndex>> {
+        let op_id_file = self.changed_path_operations_dir().join(op_id.hex());
+        let file_id_hex = fs::read_to_string(op_id_file)?;
+        ChangedPathIndex::load(
+            &self.changed_path_segments_dir(),
+            file_id_hex,
+            commit_id_length,
+        )
+    }
+
+    /// Loads the changed-path index at the given `operation`, or builds it
+    /// incrementally if the operation isn't indexed yet.
+    fn load_or_build_changed_path_index(
+        &self,
+        operation: &Operation,
+        store: &Arc<Store>,
+        index: &CompositeIndex,
+    ) -> Result<Arc<ChangedPathIndex>, DefaultIndexStoreError> {
+        match self.load_changed_path_index_at_operation(operation.id(), store.commit_id_length()) {
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                self.build_changed_path_index(operation, store, index)
+            }
+            Err(err) if err.kind() == io::ErrorKind::InvalidData => {
+                eprintln!("Failed to load changed-path index: {err}. Reindexing...");
+                for dir in [
+                    self.changed_path_operations_dir(),
+                    self.changed_path_segments_dir(),
+                ] {
+                    file_util::remove_dir_contents(&dir)
+                        .map_err(|err| DefaultIndexStoreError::SaveChangedPathIndex(err.error))?;
+                }
+                self.build_changed_path_index(operation, store, index)
+            }
+            result => result.map_err(DefaultIndexStoreError::LoadChangedPathIndex),
+        }
+    }
+
+    #[tracing::instrument(skip(self, store, index))]
+    fn build_changed_path_index(
+        &self,
+        operation: &Operation,
+        store: &Arc<Store>,
+        index: &CompositeIndex,
+    ) -> Result<Arc<ChangedPathIndex>, DefaultIndexStoreError> {
+        let operations_dir = self.changed_path_operations_dir();
+        let commit_id_length = store.commit_id_length();
+        let num_commits = index.num_commits();
+        // Pick the latest indexed ancestor operation as the parent segment.
+        let mut parent_op_id: Option<OperationId> = None;
+        for op in dag_walk::dfs_ok(
+            [Ok(operation.clone())],
+            |op: &Operation| op.id().clone(),
+            |op: &Operation| op.parents().collect_vec(),
+        ) {
+            let op = op?;
+            if operations_dir.join(op.id().hex()).is_file() {
+                parent_op_id = Some(op.id().clone());
+                break;
+            }
+        }
+        let mut mutable_index;
+        let start_pos;
+        match parent_op_id {
+            None => {
+                mutable_index = MutableChangedPathIndex::full(commit_id_length, num_commits);
+                start_pos = 0;
+            }
+            Some(parent_op_id) => {
+                let parent_file = self
+                    .load_changed_path_index_at_operation(&parent_op_id, commit_id_length)
+                    .map_err(DefaultIndexStoreError::LoadChangedPathIndex)?;
+                // Commits indexed by the parent segment are supposed to be
+                // placed at lower positions. If they aren't (because the
+                // commit index was merged differently), unindexed commits will
+                // be diffed on lookup.
+                start_pos = parent_file.num_indexed_commits().min(num_commits);
+                mutable_index = MutableChangedPathIndex::incremental(parent_file, num_commits);
+            }
+        }
+
+        tracing::info!(
+            commits_count = num_commits - start_pos,
+            "indexing changed paths of commits"
+        );
+        let to_index_err = |source| DefaultIndexStoreError::IndexChangedPaths {
+            op_id: operation.id().clone(),
+            source,
+        };
+        for pos in start_pos..num_commits {
+            let commit_id = index.entry_by_pos(IndexPosition(pos)).commit_id();
+            if mutable_index.has_id(&commit_id) {
+                continue;
+            }
+            let commit = store.get_commit(&commit_id).map_err(to_index_err)?;
+            let paths = collect_changed_paths(store, index, &commit).map_err(to_index_err)?;
+            mutable_index.add_commit(commit_id, paths);
+        }
+        let num_new_commits = mutable_index.num_local_commits();
+
+        let index_file = mutable_index
+            .squash_and_save_in(&self.changed_path_segments_dir())
+            .map_err(DefaultIndexStoreError::SaveChangedPathIndex)?;
+        self.associate_file_name_with_operation(&operations_dir, index_file.name(), operation.id())
+            .map_err(|source| DefaultIndexStoreError::AssociateIndex {
+                op_id: operation.id().clone(),
+                source,
+            })?;
+        tracing::info!(
+            ?index_file,
+            commits_count = num_new_commits,
+            "saved new changed-path index file"
+        );
+        Ok(index_file)
+    }
+
     fn save_mutable_index(
         &self,
         mutable_index: DefaultMutableIndex,
@@ -305,10 +480,18 @@ impl DefaultIndexStore {
         index: &ReadonlyIndexSegment,
         op_id: &OperationId,
     ) -> io::Result<()> {
-        let dir = self.operations_dir();
-        let mut temp_file = NamedTempFile::new_in(&dir)?;
+        self.associate_file_name_with_operation(&self.operations_dir(), index.name(), op_id)
+    }
+
+    fn associate_file_name_with_operation(
+        &self,
+        dir: &Path,
+        name: &str,
+        op_id: &OperationId,
+    ) -> io::Result<()> {
+        let mut temp_file = NamedTempFile::new_in(dir)?;
         let file = temp_file.as_file_mut();
-        file.write_all(index.name().as_bytes())?;
+        file.write_all(name.as_bytes())?;
         persist_content_addressed_temp_file(temp_file, dir.join(op_id.hex()))?;
         Ok(())
     }
@@ -361,7 +544,17 @@ impl IndexStore for DefaultIndexStore {
             result => result,
         }
         .map_err(|err| IndexReadError(err.into()))?;
-        Ok(Box::new(DefaultReadonlyIndex::from_segment(index_segment)))
+        let changed_paths = if self.is_changed_path_index_enabled() {
+            let index = self
+                .load_or_build_changed_path_index(op, store, index_segment.as_composite())
+                .map_err(|err| IndexReadError(err.into()))?;
+            Some(index)
+        } else {
+            None
+        };
+        Ok(Box::new(
+            DefaultReadonlyIndex::from_segment(index_segment).with_changed_paths(changed_paths),
+        ))
     }
 
     fn write_index(
@@ -16,36 +16,170 @@
 
 use std::path::Path;
 use std::path::PathBuf;
+use std::process::Command;
+use std::process::Output;
+use std::process::Stdio;
 
+use crate::backend::CommitId;
+use crate::object_id::ObjectId as _;
 use crate::submodule_store::SubmoduleStore;
+use crate::submodule_store::SubmoduleStoreError;
 
+/// Stores a bare Git repository per submodule, and checks submodules out as
+/// linked worktrees of these repositories by running the `git` executable.
 #[derive(Debug)]
 pub struct DefaultSubmoduleStore {
-    #[expect(dead_code)]
     path: PathBuf,
+    git_executable_path: PathBuf,
 }
 
 impl DefaultSubmoduleStore {
     /// Load an existing SubmoduleStore
-    pub fn load(store_path: &Path) -> Self {
+    pub fn load(store_path: &Path, git_executable_path: &Path) -> Self {
         DefaultSubmoduleStore {
             path: store_path.to_path_buf(),
+            git_executable_path: git_executable_path.to_path_buf(),
         }
     }
 
-    pub fn init(store_path: &Path) -> Self {
+    pub fn init(store_path: &Path, git_executable_path: &Path) -> Self {
         DefaultSubmoduleStore {
             path: store_path.to_path_buf(),
+            git_executable_path: git_executable_path.to_path_buf(),
         }
     }
 
     pub fn name() -> &'static str {
         "default"
     }
+
+    /// Path to the bare repository backing the submodule `name`.
+    pub fn git_dir(&self, name: &str) -> PathBuf {
+        // Submodule names may contain path separators and "..", so they can't
+        // be used as directory names as is.
+        self.path.join(hex::encode(name))
+    }
+
+    fn git_command(&self) -> Command {
+        let mut cmd = Command::new(&self.git_executable_path);
+        // Hide console window on Windows (https://stackoverflow.com/a/60958956)
+        #[cfg(windows)]
+        {
+            use std::os::windows::process::CommandExt;
+            const CREATE_NO_WINDOW: u32 = 0x08000000;
+            cmd.creation_flags(CREATE_NO_WINDOW);
+        }
+        cmd.env("LC_ALL", "C").stdin(Stdio::null());
+        cmd
+    }
+
+    fn has_commit(&self, git_dir: &Path, commit_id: &CommitId) -> bool {
+        self.git_command()
+            .arg("--git-dir")
+            .arg(git_dir)
+            .args(["cat-file", "-e"])
+            .arg(format!("{}^{{commit}}", commit_id.hex()))
+            .stderr(Stdio::null())
+            .status()
+            .is_ok_and(|status| status.success())
+    }
 }
 
 impl SubmoduleStore for DefaultSubmoduleStore {
     fn name(&self) -> &str {
         Self::name()
     }
+
+    fn fetch(
+        &self,
+        name: &str,
+        url: &str,
+        commit_id: &CommitId,
+    ) -> Result<(), SubmoduleStoreError> {
+        let git_dir = self.git_dir(name);
+        if !git_dir.exists() {
+            let mut cmd = self.git_command();
+            cmd.args(["init", "--quiet", "--bare"]).arg(&git_dir);
+            run_git(name, cmd)?;
+        }
+        if self.has_commit(&git_dir, commit_id) {
+            return Ok(());
+        }
+
+        // Servers may refuse to serve commits that aren't reachable from the
+        // advertised refs, so fetch all branches and tags first.
+        let mut cmd = self.git_command();
+        cmd.arg("--git-dir")
+            .arg(&git_dir)
+            .args(["fetch", "--quiet", "--force", "--tags", url])
+            .arg("+refs/heads/*:refs/remotes/origin/*");
+        run_git(name, cmd)?;
+        if !self.has_commit(&git_dir, commit_id) {
+            let mut cmd = self.git_command();
+            cmd.arg("--git-dir")
+                .arg(&git_dir)
+                .args(["fetch", "--quiet", url])
+                .arg(commit_id.hex());
+            // The commit may still be unreachable, which is reported below.
+            run_git(name, cmd).ok();
+        }
+        if !self.has_commit(&git_dir, commit_id) {
+            return Err(SubmoduleStoreError::CommitNotFound {
+                name: name.to_owned(),
+                url: url.to_owned(),
+                commit_id: commit_id.clone(),
+            });
+        }
+        Ok(())
+    }
+
+    fn check_out(
+        &self,
+        name: &str,
+        commit_id: &CommitId,
+        working_copy_path: &Path,
+    ) -> Result<(), SubmoduleStoreError> {
+        let mut cmd = self.git_command();
+        if working_copy_path.join(".git").exists() {
+            cmd.arg("-C")
+                .arg(working_copy_path)
+                .args(["checkout", "--quiet", "--detach"])
+                .arg(commit_id.hex());
+        } else {
+            // Forget about worktrees that were deleted from disk, which would
+            // otherwise prevent the path from being reused.
+            let git_dir = self.git_dir(name);
+            let mut prune_cmd = self.git_command();
+            prune_cmd
+                .arg("--git-dir")
+                .arg(&git_dir)
+                .args(["worktree", "prune"]);
+            run_git(name, prune_cmd)?;
+            cmd.arg("--git-dir")
+                .arg(&git_dir)
+                .args(["worktree", "add", "--quiet", "--detach"])
+                .arg(working_copy_path)
+                .arg(commit_id.hex());
+        }
+        run_git(name, cmd)?;
+        Ok(())
+    }
+}
+
+fn run_git(name: &str, mut cmd: Command) -> Result<Output, SubmoduleStoreError> {
+    tracing::debug!(?cmd, "spawning a git subprocess");
+    let output = cmd.output().map_err(|err| SubmoduleStoreError::Other {
+        name: name.to_owned(),
+        source: err.into(),
+    })?;
+    if output.status.success() {
+        Ok(output)
+    } else {
+        Err(SubmoduleStoreError::Git {
+            name: name.to_owned(),
+            message: String::from_utf8_lossy(&output.stderr)
+                .trim_end()
+                .to_owned(),
+        })
+    }
 }
@@ -28,6 +28,18 @@ use crate::diff::DiffHunk;
 use crate::diff::DiffHunkKind;
 use crate::merge::Merge;
 
+/// Number of leading bytes that `is_binary_content()` looks at.
+pub const BINARY_PEEK_SIZE: usize = 8000;
+
+/// Returns true if the file `content` is likely binary.
+///
+/// The content is considered binary if the first `BINARY_PEEK_SIZE` bytes
+/// contain a null character. This is the same heuristic used by git as of
+/// writing: https://github.com/git/git/blob/eea0e59ffbed6e33d171ace5be13cde9faa41639/xdiff-interface.c#L192-L198
+pub fn is_binary_content(content: &[u8]) -> bool {
+    content[..BINARY_PEEK_SIZE.min(content.len())].contains(&b'\0')
+}
+
 /// A diff line which may contain small hunks originating from both sides.
 #[derive(PartialEq, Eq, Clone, Debug)]
 pub struct DiffLine<'a> {
@@ -15,15 +15,20 @@
 //! Functional language for selecting a set of paths.
 
 use std::collections::HashMap;
+use std::io::Read as _;
 use std::iter;
+use std::ops::RangeInclusive;
 use std::path;
 use std::slice;
+use std::str::FromStr as _;
 
 use itertools::Itertools as _;
 use once_cell::sync::Lazy;
 use thiserror::Error;
 
+use crate::backend::TreeValue;
 use crate::dsl_util::collect_similar;
+use crate::files;
 use crate::fileset_parser;
 use crate::fileset_parser::BinaryOp;
 use crate::fileset_parser::ExpressionKind;
@@ -37,17 +42,22 @@ use crate::fileset_parser::UnaryOp;
 use crate::matchers::DifferenceMatcher;
 use crate::matchers::EverythingMatcher;
 use crate::matchers::FileGlobsMatcher;
+use crate::matchers::FileValueMatcher;
+use crate::matchers::FileValuePredicate;
 use crate::matchers::FilesMatcher;
 use crate::matchers::IntersectionMatcher;
 use crate::matchers::Matcher;
 use crate::matchers::NothingMatcher;
 use crate::matchers::PrefixMatcher;
 use crate::matchers::UnionMatcher;
+use crate::merge::MergedTreeVal;
 use crate::repo_path::RelativePathParseError;
 use crate::repo_path::RepoPath;
 use crate::repo_path::RepoPathBuf;
 use crate::repo_path::RepoPathUiConverter;
 use crate::repo_path::UiPathParseError;
+use crate::settings::HumanByteSize;
+use crate::store::Store;
 
 /// Error occurred during file pattern parsing.
 #[derive(Debug, Error)]
@@ -66,6 +76,12 @@ pub enum FilePatternParseError {
     GlobPattern(#[from] glob::PatternError),
 }
 
+/// Error occurred when an expression that matches file values is used where
+/// only file paths are known.
+#[derive(Debug, Error)]
+#[error("Functions matching file content or metadata, such as `file_size()`, can't be used here")]
+pub struct FilesetValuePredicateError;
+
 /// Basic pattern to match `RepoPath`.
 #[derive(Clone, Debug)]
 pub enum FilePattern {
@@ -197,6 +213,66 @@ fn split_glob_path(input: &str) -> (&str, &str) {
     input.split_at(prefix_len)
 }
 
+/// Predicate on the value of a file, such as its type or content.
+///
+/// A conflicted file matches if any of the sides of the conflict matches.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum FilePredicate {
+    /// Matches executable files.
+    Executable,
+    /// Matches symbolic links.
+    Symlink,
+    /// Matches conflicted files.
+    Conflict,
+    /// Matches files that look like binary files.
+    Binary,
+    /// Matches files of which size in bytes is in the range.
+    Size(RangeInclusive<u64>),
+}
+
+impl FilePredicate {
+    /// Returns true if a side of the file `value` matches. Files that can't be
+    /// read never match.
+    fn matches_file(&self, store: &Store, path: &RepoPath, value: &TreeValue) -> bool {
+        let TreeValue::File { id, executable } = value else {
+            return matches!(
+                (self, value),
+                (FilePredicate::Symlink, TreeValue::Symlink(_))
+            );
+        };
+        match self {
+            FilePredicate::Executable => *executable,
+            FilePredicate::Symlink | FilePredicate::Conflict => false,
+            FilePredicate::Binary => {
+                let mut buf = Vec::new();
+                store
+                    .read_file(path, id)
+                    .ok()
+                    .and_then(|reader| {
+                        let peek_size = files::BINARY_PEEK_SIZE as u64;
+                        reader.take(peek_size).read_to_end(&mut buf).ok()
+                    })
+                    .is_some_and(|_| files::is_binary_content(&buf))
+            }
+            FilePredicate::Size(range) => store
+                .file_size(path, id)
+                .is_ok_and(|size| range.contains(&size)),
+        }
+    }
+}
+
+impl FileValuePredicate for FilePredicate {
+    fn matches(&self, store: &Store, file: &RepoPath, value: &MergedTreeVal) -> bool {
+        if let FilePredicate::Conflict = self {
+            return !value.is_resolved();
+        }
+        value
+            .adds()
+            .flatten()
+            .any(|value| self.matches_file(store, file, value))
+    }
+}
+
 /// AST-level representation of the fileset expression.
 #[derive(Clone, Debug)]
 pub enum FilesetExpression {
@@ -206,6 +282,8 @@ pub enum FilesetExpression {
     All,
     /// Matches basic pattern.
     Pattern(FilePattern),
+    /// Matches files by their value.
+    Predicate(FilePredicate),
     /// Matches any of the expressions.
     ///
     /// Use `FilesetExpression::union_all()` to construct a union expression.
@@ -233,6 +311,11 @@ impl FilesetExpression {
         FilesetExpression::Pattern(pattern)
     }
 
+    /// Expression that matches files of which value satisfies the `predicate`.
+    pub fn predicate(predicate: FilePredicate) -> Self {
+        FilesetExpression::Predicate(predicate)
+    }
+
     /// Expression that matches file (or exact) path.
     pub fn file_path(path: RepoPathBuf) -> Self {
         FilesetExpression::Pattern(FilePattern::FilePath(path))
@@ -278,7 +361,8 @@ impl FilesetExpression {
             match expr {
                 FilesetExpression::None
                 | FilesetExpression::All
-                | FilesetExpression::Pattern(_) => {}
+                | FilesetExpression::Pattern(_)
+                | FilesetExpression::Predicate(_) => {}
                 FilesetExpression::UnionAll(exprs) => stack.extend(exprs.iter().rev()),
                 FilesetExpression::Intersection(expr1, expr2)
                 | FilesetExpression::Difference(expr1, expr2) => {
@@ -303,10 +387,30 @@ impl FilesetExpression {
         })
     }
 
+    /// Returns true if the expression has predicates on file values, such as
+    /// `file_size()`, which can't be evaluated by path alone.
+    pub fn has_value_predicates(&self) -> bool {
+        self.dfs_pre()
+            .any(|expr| matches!(expr, FilesetExpression::Predicate(_)))
+    }
+
     /// Transforms the expression tree to `Matcher` object.
     pub fn to_matcher(&self) -> Box<dyn Matcher> {
         build_union_matcher(self.as_union_all())
     }
+
+    /// Transforms the expression tree to `Matcher` object that is only
+    /// evaluated against paths, such as the paths of new files in the working
+    /// copy.
+    ///
+    /// Returns an error if the expression has predicates on file values, since
+    /// they would never match by path.
+    pub fn to_path_matcher(&self) -> Result<Box<dyn Matcher>, FilesetValuePredicateError> {
+        if self.has_value_predicates() {
+            return Err(FilesetValuePredicateError);
+        }
+        Ok(self.to_matcher())
+    }
 }
 
 /// Transforms the union `expressions` to `Matcher` object.
@@ -333,6 +437,9 @@ fn build_union_matcher(expressions: &[FilesetExpression]) -> Box<dyn Matcher> {
                 }
                 continue;
             }
+            FilesetExpression::Predicate(predicate) => {
+                Box::new(FileValueMatcher::new(predicate.clone()))
+            }
             // UnionAll is supposed to be flattened by caller.
             FilesetExpression::UnionAll(exprs) => build_union_matcher(exprs),
             FilesetExpression::Intersection(expr1, expr2) => {
@@ -397,9 +504,61 @@ static BUILTIN_FUNCTION_MAP: Lazy<HashMap<&'static str, FilesetFunction>> = Lazy
         function.expect_no_arguments()?;
         Ok(FilesetExpression::all())
     });
+    map.insert("executable", |_diagnostics, _path_converter, function| {
+        function.expect_no_arguments()?;
+        Ok(FilesetExpression::predicate(FilePredicate::Executable))
+    });
+    map.insert("symlink", |_diagnostics, _path_converter, function| {
+        function.expect_no_arguments()?;
+        Ok(FilesetExpression::predicate(FilePredicate::Symlink))
+    });
+    map.insert("conflicts", |_diagnostics, _path_converter, function| {
+        function.expect_no_arguments()?;
+        Ok(FilesetExpression::predicate(FilePredicate::Conflict))
+    });
+    map.insert("binary", |_diagnostics, _path_converter, function| {
+        function.expect_no_arguments()?;
+        Ok(FilesetExpression::predicate(FilePredicate::Binary))
+    });
+    map.insert("file_size", |_diagnostics, _path_converter, function| {
+        let [arg] = function.expect_exact_arguments()?;
+        let text = match &arg.kind {
+            ExpressionKind::Identifier(text) => *text,
+            ExpressionKind::String(text) => text.as_str(),
+            _ => {
+                return Err(FilesetParseError::expression(
+                    "Expected size comparison",
+                    arg.span,
+                ));
+            }
+        };
+        let range = parse_file_size_range(text).map_err(|message| {
+            FilesetParseError::expression(format!("Invalid file size: {message}"), arg.span)
+        })?;
+        Ok(FilesetExpression::predicate(FilePredicate::Size(range)))
+    });
     map
 });
 
+/// Parses size comparison such as `">1MB"` into range of bytes.
+fn parse_file_size_range(text: &str) -> Result<RangeInclusive<u64>, &'static str> {
+    let (op, size) = [">=", "<=", ">", "<", "="]
+        .iter()
+        .find_map(|op| Some((*op, text.strip_prefix(op)?)))
+        .unwrap_or(("=", text));
+    let HumanByteSize(size) = HumanByteSize::from_str(size.trim())?;
+    match op {
+        ">=" => Ok(size..=u64::MAX),
+        "<=" => Ok(0..=size),
+        ">" => Ok(size.saturating_add(1)..=u64::MAX),
+        "<" => size
+            .checked_sub(1)
+            .map(|max| 0..=max)
+            .ok_or("no size is less than 0"),
+        _ => Ok(size..=size),
+    }
+}
+
 fn resolve_function(
     diagnostics: &mut FilesetDiagnostics,
     path_converter: &RepoPathUiConverter,
@@ -765,6 +924,19 @@ mod tests {
             ],
         }
         "#);
+
+        insta::assert_debug_snapshot!(parse("executable()").unwrap(), @"Predicate(Executable)");
+        insta::assert_debug_snapshot!(parse("symlink()").unwrap(), @"Predicate(Symlink)");
+        insta::assert_debug_snapshot!(parse("conflicts()").unwrap(), @"Predicate(Conflict)");
+        insta::assert_debug_snapshot!(parse("binary()").unwrap(), @"Predicate(Binary)");
+        insta::assert_debug_snapshot!(parse("file_size('>1MB')").unwrap(), @"Predicate(Size(1048577..=18446744073709551615))");
+        insta::assert_debug_snapshot!(parse("file_size('>=1K')").unwrap(), @"Predicate(Size(1024..=18446744073709551615))");
+        insta::assert_debug_snapshot!(parse("file_size('<=10')").unwrap(), @"Predicate(Size(0..=10))");
+        insta::assert_debug_snapshot!(parse("file_size('<10')").unwrap(), @"Predicate(Size(0..=9))");
+        insta::assert_debug_snapshot!(parse("file_size('1 KiB')").unwrap(), @"Predicate(Size(1024..=1024))");
+        insta::assert_debug_snapshot!(parse("file_size('<0')").unwrap_err().kind(), @r#"Expression("Invalid file size: no size is less than 0")"#);
+        insta::assert_debug_snapshot!(parse("file_size('>1X')").unwrap_err().kind(), @r#"Expression("Invalid file size: unrecognized unit prefix")"#);
+        insta::assert_debug_snapshot!(parse("file_size(all())").unwrap_err().kind(), @r#"Expression("Expected size comparison")"#);
     }
 
     #[test]
@@ -805,6 +977,21 @@ mod tests {
         "#);
     }
 
+    #[test]
+    fn test_has_value_predicates() {
+        let path_converter = RepoPathUiConverter::Fs {
+            cwd: PathBuf::from("/ws/cur"),
+            base: PathBuf::from("/ws"),
+        };
+        let parse = |text| parse(&mut FilesetDiagnostics::new(), text, &path_converter).unwrap();
+        assert!(!parse("all()").has_value_predicates());
+        assert!(!parse("foo | glob:'*.rs' ~ bar").has_value_predicates());
+        assert!(parse("binary()").has_value_predicates());
+        assert!(parse("foo | ~(bar & file_size('>1K'))").has_value_predicates());
+        assert!(parse("foo ~ executable()").to_path_matcher().is_err());
+        assert!(parse("foo ~ bar").to_path_matcher().is_ok());
+    }
+
     #[test]
     fn test_explicit_paths() {
         let collect = |expr: &FilesetExpression| -> Vec<RepoPathBuf> {
@@ -22,6 +22,7 @@
 
 #![warn(missing_docs)]
 
+use std::ffi::OsString;
 use std::path::PathBuf;
 
 use crate::config::ConfigGetError;
@@ -34,12 +35,24 @@ pub struct WatchmanConfig {
     pub register_trigger: bool,
 }
 
+/// Config for the built-in inotify filesystem monitor.
+#[derive(Eq, PartialEq, Clone, Debug, Default)]
+pub struct InotifyConfig {
+    /// Program and arguments that run the daemon in the foreground. The
+    /// `--working-copy` and `--state-dir` arguments are appended. If empty,
+    /// the daemon isn't started automatically.
+    pub daemon_command: Vec<OsString>,
+}
+
 /// The recognized kinds of filesystem monitors.
 #[derive(Eq, PartialEq, Clone, Debug)]
 pub enum FsmonitorSettings {
     /// The Watchman filesystem monitor (<https://facebook.github.io/watchman/>).
     Watchman(WatchmanConfig),
 
+    /// The built-in filesystem monitor using inotify. Only supported on Linux.
+    Inotify(InotifyConfig),
+
     /// Only used in tests.
     Test {
         /// The set of changed files to pretend that the filesystem monitor is
@@ -67,6 +80,7 @@ impl FsmonitorSettings {
                 error: "Cannot use test fsmonitor in real repository".into(),
                 source_path: None,
             }),
+            "inotify" => Ok(Self::Inotify(InotifyConfig::default())),
             "none" => Ok(Self::None),
             other => Err(ConfigGetError::Type {
                 name: name.to_owned(),
@@ -77,6 +91,9 @@ impl FsmonitorSettings {
     }
 }
 
+#[cfg(target_os = "linux")]
+pub mod inotify;
+
 /// Filesystem monitor integration using Watchman
 /// (<https://facebook.github.io/watchman/>). Requires `watchman` to already be
 /// installed on the system.
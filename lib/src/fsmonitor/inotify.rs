@@ -0,0 +1,638 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Filesystem monitor built on Linux's inotify API.
+//!
+//! A daemon process per workspace watches every directory in the working copy
+//! and records the paths that changed. It serves queries over a Unix socket in
+//! the working-copy state directory. Each answer comes with a clock token, and
+//! passing the token to the next query returns the paths changed since then.
+//!
+//! Before answering a query, the daemon creates a cookie file in the state
+//! directory and waits for its event. Since inotify reports events in order,
+//! this guarantees that changes made before the query are included.
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::collections::VecDeque;
+use std::ffi::OsStr;
+use std::ffi::OsString;
+use std::fs::File;
+use std::io;
+use std::io::Read as _;
+use std::io::Write as _;
+use std::mem::MaybeUninit;
+use std::os::fd::AsRawFd as _;
+use std::os::fd::OwnedFd;
+use std::os::unix::ffi::OsStrExt as _;
+use std::os::unix::net::UnixListener;
+use std::os::unix::net::UnixStream;
+use std::os::unix::process::CommandExt as _;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::Command;
+use std::process::Stdio;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::sync::Condvar;
+use std::sync::Mutex;
+use std::time::Duration;
+use std::time::SystemTime;
+
+use rustix::fs::inotify;
+use rustix::fs::FlockOperation;
+use thiserror::Error;
+use tracing::info;
+use tracing::instrument;
+
+/// Name of the socket file in the working-copy state directory.
+pub const SOCKET_FILE_NAME: &str = "inotify.sock";
+/// Name of the file locked by the running daemon.
+const LOCK_FILE_NAME: &str = "inotify.lock";
+const COOKIE_FILE_PREFIX: &str = "inotify-cookie-";
+
+/// Directories at the root of the working copy that aren't watched.
+const EXCLUDED_DIRS: [&str; 2] = [".git", ".jj"];
+
+const QUERY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Number of recently queried or returned clocks that are assumed to still be
+/// in use by clients. Changes older than all of them are forgotten.
+const RECENT_CLOCKS: usize = 16;
+
+/// Represents an instance in time from the perspective of the daemon.
+///
+/// The clock identifies the daemon process, so the clock of a daemon that has
+/// since exited isn't used to query another one.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Clock(String);
+
+impl Clock {
+    /// Creates a clock from its serialized form.
+    pub fn new(value: String) -> Self {
+        Self(value)
+    }
+
+    /// Returns the serialized form of the clock.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    fn parse(&self) -> Option<(&str, u64)> {
+        let (instance, generation) = self.0.rsplit_once(':')?;
+        Some((instance, generation.parse().ok()?))
+    }
+}
+
+#[expect(missing_docs)]
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("The inotify daemon is not running")]
+    NotRunning(#[source] io::Error),
+
+    #[error("Failed to communicate with the inotify daemon")]
+    Io(#[source] io::Error),
+
+    #[error("Invalid response from the inotify daemon")]
+    InvalidResponse,
+
+    #[error("Failed to start the inotify daemon")]
+    SpawnError(#[source] io::Error),
+
+    #[error("Another inotify daemon is already running for this workspace")]
+    AlreadyRunning,
+
+    #[error("Failed to set up the inotify daemon")]
+    SetupError(#[source] io::Error),
+}
+
+/// Client of the inotify daemon of a workspace.
+pub struct Fsmonitor {
+    state_path: PathBuf,
+}
+
+impl Fsmonitor {
+    /// Creates a client for the daemon serving the working copy whose state is
+    /// stored in `state_path`.
+    pub fn new(state_path: &Path) -> Self {
+        Self {
+            state_path: state_path.to_owned(),
+        }
+    }
+
+    /// Query for changed files since the previous point in time.
+    ///
+    /// The returned list of paths is relative to the working copy root. A path
+    /// may be a directory, in which case anything under it may have changed.
+    /// If the list is `None`, then the caller must crawl the entire working
+    /// copy themselves.
+    #[instrument(skip(self))]
+    pub fn query_changed_files(
+        &self,
+        previous_clock: Option<&Clock>,
+    ) -> Result<(Clock, Option<Vec<PathBuf>>), Error> {
+        info!("Querying inotify daemon for changed files...");
+        let request = format!(
+            "query {}",
+            previous_clock.map_or("", |clock| clock.as_str())
+        );
+        let response = self.send(&request)?;
+        let mut fields = response.split(|&b| b == b'\0');
+        let clock = fields
+            .next()
+            .and_then(|clock| std::str::from_utf8(clock).ok())
+            .ok_or(Error::InvalidResponse)?;
+        let clock = Clock(clock.to_owned());
+        match fields.next() {
+            Some(b"fresh") => Ok((clock, None)),
+            Some(b"changed") => {
+                let paths = fields
+                    .filter(|path| !path.is_empty())
+                    .map(|path| PathBuf::from(OsStr::from_bytes(path)))
+                    .collect();
+                Ok((clock, Some(paths)))
+            }
+            _ => Err(Error::InvalidResponse),
+        }
+    }
+
+    /// Asks the daemon to exit.
+    pub fn stop(&self) -> Result<(), Error> {
+        self.send("stop")?;
+        Ok(())
+    }
+
+    /// Returns whether a daemon holds the lock of the workspace. The daemon may
+    /// still be setting up its watches.
+    pub fn is_daemon_running(&self) -> bool {
+        let Ok(file) = File::open(self.state_path.join(LOCK_FILE_NAME)) else {
+            return false;
+        };
+        rustix::fs::flock(&file, FlockOperation::NonBlockingLockShared).is_err()
+    }
+
+    /// Starts a daemon in the background unless one is already running.
+    ///
+    /// The daemon is run by `daemon_command`, with the `--working-copy` and
+    /// `--state-dir` arguments appended. Nothing is started if the command is
+    /// empty.
+    pub fn spawn_daemon(
+        &self,
+        daemon_command: &[OsString],
+        working_copy_path: &Path,
+    ) -> Result<(), Error> {
+        let Some((program, args)) = daemon_command.split_first() else {
+            return Ok(());
+        };
+        if self.is_daemon_running() {
+            return Ok(());
+        }
+        info!("Starting inotify daemon...");
+        // The daemon isn't waited for. It exits when the working copy is
+        // removed or when asked to stop.
+        let _child = Command::new(program)
+            .args(args)
+            .arg("--working-copy")
+            .arg(working_copy_path)
+            .arg("--state-dir")
+            .arg(&self.state_path)
+            .current_dir("/")
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            // Don't receive signals sent to the terminal's process group.
+            .process_group(0)
+            .spawn()
+            .map_err(Error::SpawnError)?;
+        Ok(())
+    }
+
+    fn send(&self, request: &str) -> Result<Vec<u8>, Error> {
+        let mut stream = UnixStream::connect(self.state_path.join(SOCKET_FILE_NAME))
+            .map_err(Error::NotRunning)?;
+        stream
+            .set_read_timeout(Some(QUERY_TIMEOUT))
+            .map_err(Error::Io)?;
+        stream.write_all(request.as_bytes()).map_err(Error::Io)?;
+        stream
+            .shutdown(std::net::Shutdown::Write)
+            .map_err(Error::Io)?;
+        let mut response = vec![];
+        stream.read_to_end(&mut response).map_err(Error::Io)?;
+        Ok(response)
+    }
+}
+
+/// Runs the daemon for the working copy until the working copy is removed or
+/// the daemon is asked to stop.
+#[instrument]
+pub fn run_daemon(working_copy_path: &Path, state_path: &Path) -> Result<(), Error> {
+    let lock_file = File::create(state_path.join(LOCK_FILE_NAME)).map_err(Error::SetupError)?;
+    rustix::fs::flock(&lock_file, FlockOperation::NonBlockingLockExclusive)
+        .map_err(|_| Error::AlreadyRunning)?;
+    // Replace the socket of a daemon that exited without cleaning up.
+    let socket_path = state_path.join(SOCKET_FILE_NAME);
+    match std::fs::remove_file(&socket_path) {
+        Err(err) if err.kind() != io::ErrorKind::NotFound => {
+            return Err(Error::SetupError(err));
+        }
+        _ => {}
+    }
+
+    // Paths are resolved relative to an open file descriptor, so that they
+    // remain valid if the working copy is moved.
+    let root_dir = File::open(working_copy_path).map_err(Error::SetupError)?;
+    let fd = inotify::init(inotify::CreateFlags::CLOEXEC)
+        .map_err(|err| Error::SetupError(err.into()))?;
+    // The state directory is watched for cookies, and for the removal of the
+    // socket. Since the daemon keeps files open in the state directory, the
+    // kernel doesn't report the removal of the working copy itself until the
+    // daemon exits.
+    let cookie_wd = inotify::add_watch(
+        &fd,
+        state_path,
+        inotify::WatchFlags::CREATE | inotify::WatchFlags::DELETE,
+    )
+    .map_err(|err| Error::SetupError(err.into()))?;
+    let start_time = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default();
+    let mut watcher = Watcher {
+        fd,
+        root_path: PathBuf::from(format!("/proc/self/fd/{}", root_dir.as_raw_fd())),
+        _root_dir: root_dir,
+        root_wd: -1,
+        cookie_wd,
+        shared: Arc::new(Shared {
+            state: Mutex::new(State {
+                instance: format!("{}-{}", std::process::id(), start_time.as_nanos()),
+                generation: 0,
+                fresh_generation: 0,
+                changed_paths: HashMap::new(),
+                recent_clocks: VecDeque::new(),
+                seen_cookies: HashSet::new(),
+                stopping: false,
+            }),
+            cookie_seen: Condvar::new(),
+        }),
+        watches: HashMap::new(),
+    };
+    watcher.root_wd = watcher
+        .add_watch(Path::new(""))
+        .map_err(Error::SetupError)?
+        .ok_or_else(|| Error::SetupError(io::ErrorKind::NotFound.into()))?;
+    watcher.add_watches_recursively(Path::new(""));
+
+    let listener = UnixListener::bind(&socket_path).map_err(Error::SetupError)?;
+    let server = Arc::new(Server {
+        state_path: state_path.to_owned(),
+        shared: watcher.shared.clone(),
+        next_cookie: AtomicU64::new(0),
+    });
+    std::thread::spawn(move || server.serve(listener));
+
+    let result = watcher.watch();
+    std::fs::remove_file(&socket_path).ok();
+    drop(lock_file);
+    result
+}
+
+struct State {
+    /// Identifies this daemon process in clocks.
+    instance: String,
+    /// Incremented for every change.
+    generation: u64,
+    /// Clocks before this generation can't be answered, because events may
+    /// have been lost.
+    fresh_generation: u64,
+    /// Paths relative to the working copy root, and the generation of their
+    /// last change.
+    changed_paths: HashMap<PathBuf, u64>,
+    /// Generations of the clocks most recently queried or returned.
+    recent_clocks: VecDeque<u64>,
+    seen_cookies: HashSet<String>,
+    stopping: bool,
+}
+
+impl State {
+    fn mark_changed(&mut self, path: PathBuf) {
+        self.generation += 1;
+        self.changed_paths.insert(path, self.generation);
+    }
+
+    /// Forgets all changes, so that the next query of every client requires a
+    /// full crawl.
+    fn mark_fresh(&mut self) {
+        self.generation += 1;
+        self.fresh_generation = self.generation;
+        self.changed_paths.clear();
+    }
+
+    /// Records that a client uses the clock of `generation`, and forgets the
+    /// changes that no recent clock needs. Older clocks then require a full
+    /// crawl.
+    fn use_clock(&mut self, generation: u64) {
+        if self.recent_clocks.len() == RECENT_CLOCKS {
+            self.recent_clocks.pop_front();
+        }
+        self.recent_clocks.push_back(generation);
+        let oldest = *self.recent_clocks.iter().min().unwrap();
+        if oldest > self.fresh_generation {
+            self.fresh_generation = oldest;
+            self.changed_paths
+                .retain(|_, generation| *generation > oldest);
+        }
+    }
+
+    fn clock(&self) -> Clock {
+        Clock(format!("{}:{}", self.instance, self.generation))
+    }
+}
+
+struct Shared {
+    state: Mutex<State>,
+    cookie_seen: Condvar,
+}
+
+/// Reads events and records the changed paths.
+struct Watcher {
+    fd: OwnedFd,
+    /// Path to the working copy root through `_root_dir`.
+    root_path: PathBuf,
+    _root_dir: File,
+    root_wd: i32,
+    cookie_wd: i32,
+    shared: Arc<Shared>,
+    /// Watch descriptors and the directories they watch, relative to the
+    /// working copy root.
+    watches: HashMap<i32, PathBuf>,
+}
+
+impl Watcher {
+    fn watch(&mut self) -> Result<(), Error> {
+        let mut buf = vec![MaybeUninit::uninit(); 64 * 1024];
+        let fd = self.fd.try_clone().map_err(Error::SetupError)?;
+        let mut reader = inotify::Reader::new(&fd, &mut buf);
+        loop {
+            let event = match reader.next() {
+                Ok(event) => event,
+                Err(rustix::io::Errno::INTR) => continue,
+                Err(err) => return Err(Error::Io(err.into())),
+            };
+            let name = event
+                .file_name()
+                .map(|name| PathBuf::from(OsStr::from_bytes(name.to_bytes())));
+            if !self.handle_event(event.wd(), event.events(), name) {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Updates the state for an event. Returns false if the daemon should
+    /// exit.
+    fn handle_event(&mut self, wd: i32, flags: inotify::ReadFlags, name: Option<PathBuf>) -> bool {
+        use inotify::ReadFlags;
+
+        if flags.contains(ReadFlags::QUEUE_OVERFLOW) {
+            info!("inotify event queue overflowed");
+            self.shared.state.lock().unwrap().mark_fresh();
+            // Directories created in the meantime may not be watched yet.
+            self.add_watches_recursively(Path::new(""));
+            return true;
+        }
+        if wd == self.cookie_wd {
+            let mut state = self.shared.state.lock().unwrap();
+            let name = name.as_ref().and_then(|name| name.to_str());
+            match name {
+                Some(name)
+                    if name.starts_with(COOKIE_FILE_PREFIX)
+                        && flags.contains(ReadFlags::CREATE) =>
+                {
+                    state.seen_cookies.insert(name.to_owned());
+                    self.shared.cookie_seen.notify_all();
+                }
+                Some(SOCKET_FILE_NAME | LOCK_FILE_NAME) if flags.contains(ReadFlags::DELETE) => {
+                    info!("Working copy state was removed");
+                    return false;
+                }
+                _ => {}
+            }
+            return !state.stopping;
+        }
+        if flags.contains(ReadFlags::IGNORED) {
+            self.watches.remove(&wd);
+            if wd == self.root_wd {
+                info!("Working copy was removed");
+                return false;
+            }
+            return true;
+        }
+        let (Some(dir), Some(name)) = (self.watches.get(&wd), name) else {
+            return true;
+        };
+        if dir.as_os_str().is_empty()
+            && EXCLUDED_DIRS
+                .iter()
+                .any(|excluded| name == Path::new(excluded))
+        {
+            return true;
+        }
+        let path = dir.join(name);
+        if flags.contains(ReadFlags::ISDIR) {
+            if flags.intersects(ReadFlags::CREATE | ReadFlags::MOVED_TO) {
+                // Files may have been added before the directory was watched,
+                // so the whole directory is reported as changed.
+                self.add_watches_recursively(&path);
+            } else if flags.contains(ReadFlags::MOVED_FROM) {
+                self.remove_watches_recursively(&path);
+            } else if !flags.contains(ReadFlags::DELETE) {
+                // Changes to the directory's own metadata don't matter.
+                return true;
+            }
+        }
+        self.shared.state.lock().unwrap().mark_changed(path);
+        true
+    }
+
+    /// Adds a watch for the directory `dir`, relative to the working copy
+    /// root. Returns `None` if the directory doesn't exist anymore.
+    fn add_watch(&mut self, dir: &Path) -> io::Result<Option<i32>> {
+        use inotify::WatchFlags;
+
+        let flags = WatchFlags::ATTRIB
+            | WatchFlags::CREATE
+            | WatchFlags::DELETE
+            | WatchFlags::MODIFY
+            | WatchFlags::MOVED_FROM
+            | WatchFlags::MOVED_TO
+            | WatchFlags::DONT_FOLLOW
+            | WatchFlags::EXCL_UNLINK
+            | WatchFlags::ONLYDIR;
+        match inotify::add_watch(&self.fd, self.root_path.join(dir), flags) {
+            Ok(wd) => {
+                self.watches.insert(wd, dir.to_owned());
+                Ok(Some(wd))
+            }
+            Err(rustix::io::Errno::NOENT | rustix::io::Errno::NOTDIR) => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn add_watches_recursively(&mut self, dir: &Path) {
+        let mut dirs_to_visit = vec![dir.to_owned()];
+        while let Some(dir) = dirs_to_visit.pop() {
+            match self.add_watch(&dir) {
+                Ok(Some(_)) => {}
+                Ok(None) => continue,
+                Err(err) => {
+                    // Most likely the limit of watches was reached. Changes
+                    // in this directory would be missed, so every query needs
+                    // a full crawl.
+                    tracing::warn!(?err, ?dir, "Failed to watch directory");
+                    let mut state = self.shared.state.lock().unwrap();
+                    state.mark_fresh();
+                    state.fresh_generation = u64::MAX;
+                    return;
+                }
+            }
+            let Ok(entries) = std::fs::read_dir(self.root_path.join(&dir)) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let is_dir = entry.file_type().is_ok_and(|file_type| file_type.is_dir());
+                let name = entry.file_name();
+                if is_dir
+                    && !(dir.as_os_str().is_empty()
+                        && EXCLUDED_DIRS.iter().any(|excluded| name == *excluded))
+                {
+                    dirs_to_visit.push(dir.join(name));
+                }
+            }
+        }
+    }
+
+    fn remove_watches_recursively(&mut self, dir: &Path) {
+        let fd = &self.fd;
+        self.watches.retain(|&wd, watched_dir| {
+            if watched_dir.starts_with(dir) {
+                inotify::remove_watch(fd, wd).ok();
+                false
+            } else {
+                true
+            }
+        });
+    }
+}
+
+/// Answers the queries of clients.
+struct Server {
+    state_path: PathBuf,
+    shared: Arc<Shared>,
+    next_cookie: AtomicU64,
+}
+
+impl Server {
+    fn serve(self: Arc<Self>, listener: UnixListener) {
+        for stream in listener.incoming() {
+            let Ok(stream) = stream else {
+                continue;
+            };
+            // A slow client mustn't hold up the others.
+            let server = self.clone();
+            std::thread::spawn(move || server.handle(stream));
+        }
+    }
+
+    fn handle(&self, mut stream: UnixStream) {
+        // Give up on clients that don't finish their request.
+        if stream.set_read_timeout(Some(QUERY_TIMEOUT)).is_err()
+            || stream.set_write_timeout(Some(QUERY_TIMEOUT)).is_err()
+        {
+            return;
+        }
+        let mut request = String::new();
+        if stream.read_to_string(&mut request).is_err() {
+            return;
+        }
+        let cookie_name = format!(
+            "{COOKIE_FILE_PREFIX}{}",
+            self.next_cookie.fetch_add(1, Ordering::Relaxed)
+        );
+        let response = if let Some(clock) = request.strip_prefix("query ") {
+            let clock = (!clock.is_empty()).then(|| Clock(clock.to_owned()));
+            self.query(clock.as_ref(), &cookie_name)
+        } else if request == "stop" {
+            self.shared.state.lock().unwrap().stopping = true;
+            // Wake up the watcher so it notices.
+            self.sync(&cookie_name);
+            vec![]
+        } else {
+            return;
+        };
+        stream.write_all(&response).ok();
+    }
+
+    fn query(&self, previous_clock: Option<&Clock>, cookie_name: &str) -> Vec<u8> {
+        let synced = self.sync(cookie_name);
+        let mut state = self.shared.state.lock().unwrap();
+        let mut response = state.clock().0.into_bytes();
+        let since = previous_clock
+            .and_then(|clock| clock.parse())
+            .filter(|(instance, _)| *instance == state.instance)
+            .map(|(_, generation)| generation)
+            .filter(|&generation| synced && generation >= state.fresh_generation);
+        match since {
+            Some(since) => {
+                response.extend_from_slice(b"\0changed");
+                for (path, _) in state
+                    .changed_paths
+                    .iter()
+                    .filter(|(_, generation)| **generation > since)
+                {
+                    response.push(b'\0');
+                    response.extend_from_slice(path.as_os_str().as_bytes());
+                }
+            }
+            None => response.extend_from_slice(b"\0fresh"),
+        }
+        if let Some(since) = since {
+            state.use_clock(since);
+        }
+        let generation = state.generation;
+        state.use_clock(generation);
+        response
+    }
+
+    /// Waits until the watcher has read all events that happened before the
+    /// call. Returns false if that couldn't be confirmed.
+    fn sync(&self, cookie_name: &str) -> bool {
+        let cookie_path = self.state_path.join(cookie_name);
+        if File::create(&cookie_path).is_err() {
+            return false;
+        }
+        let state = self.shared.state.lock().unwrap();
+        let (mut state, timeout) = self
+            .shared
+            .cookie_seen
+            .wait_timeout_while(state, QUERY_TIMEOUT, |state| {
+                !state.seen_cookies.contains(cookie_name)
+            })
+            .unwrap();
+        state.seen_cookies.remove(cookie_name);
+        drop(state);
+        std::fs::remove_file(&cookie_path).ok();
+        !timeout.timed_out()
+    }
+}
@@ -20,7 +20,9 @@ use std::collections::HashMap;
 use std::collections::HashSet;
 use std::default::Default;
 use std::fs::File;
+use std::io::Read as _;
 use std::num::NonZeroU32;
+use std::path::Path;
 use std::path::PathBuf;
 use std::str;
 use std::sync::Arc;
@@ -35,16 +37,19 @@ use thiserror::Error;
 use crate::backend::BackendError;
 use crate::backend::BackendResult;
 use crate::backend::CommitId;
+use crate::backend::Signature;
 use crate::backend::TreeValue;
 use crate::commit::Commit;
 use crate::file_util::IoResultExt as _;
 use crate::file_util::PathError;
+use crate::git_backend::signature_to_git;
 use crate::git_backend::GitBackend;
 use crate::git_subprocess::GitSubprocessContext;
 use crate::git_subprocess::GitSubprocessError;
 #[cfg(feature = "git2")]
 use crate::index::Index;
 use crate::matchers::EverythingMatcher;
+use crate::matchers::Matcher;
 use crate::merged_tree::MergedTree;
 use crate::merged_tree::TreeDiffEntry;
 use crate::object_id::ObjectId as _;
@@ -52,6 +57,7 @@ use crate::op_store::RefTarget;
 use crate::op_store::RefTargetOptionExt as _;
 use crate::op_store::RemoteRef;
 use crate::op_store::RemoteRefState;
+use crate::op_store::TagAnnotation;
 use crate::ref_name::GitRefName;
 use crate::ref_name::GitRefNameBuf;
 use crate::ref_name::RefName;
@@ -65,11 +71,14 @@ use crate::refs;
 use crate::refs::BookmarkPushUpdate;
 use crate::repo::MutableRepo;
 use crate::repo::Repo;
+use crate::repo_path::InvalidRepoPathError;
 use crate::repo_path::RepoPath;
+use crate::repo_path::RepoPathBuf;
 use crate::revset::RevsetExpression;
 use crate::settings::GitSettings;
 use crate::store::Store;
 use crate::str_util::StringPattern;
+use crate::submodule_store::SubmoduleStoreError;
 use crate::view::View;
 
 /// Reserved remote name for the backing Git repo.
@@ -597,15 +606,17 @@ fn diff_refs_to_import(
         .filter(|&(symbol, _)| git_ref_filter(GitRefKind::Bookmark, symbol))
         .map(|(symbol, remote_ref)| (RemoteRefKey(symbol), (&remote_ref.target, remote_ref.state)))
         .collect();
-    // TODO: compare to tags stored in the "git" remote view. Since tags should
-    // never be moved locally in jj, we can consider local tags as merge base.
+    // TODO: compare to tags stored in the "git" remote view. Since local tags
+    // can be moved in jj, we use the last imported or exported Git refs as
+    // merge base.
     let mut known_remote_tags = view
-        .tags()
+        .git_refs()
         .iter()
-        .map(|(name, target)| {
-            let symbol = name.to_remote_symbol(REMOTE_NAME_FOR_LOCAL_GIT_REPO);
+        .filter_map(|(full_name, target)| {
+            let (kind, symbol) =
+                parse_git_ref(full_name).expect("stored git ref should be parsable");
             let state = RemoteRefState::Tracked;
-            (symbol, (target, state))
+            (kind == GitRefKind::Tag).then_some((symbol, (target, state)))
         })
         .filter(|&(symbol, _)| git_ref_filter(GitRefKind::Tag, symbol))
         .map(|(symbol, remote_ref)| (RemoteRefKey(symbol), remote_ref))
@@ -870,6 +881,8 @@ pub enum FailedRefExportReason {
 pub struct GitExportStats {
     /// Remote bookmarks that couldn't be exported, sorted by `symbol`.
     pub failed_bookmarks: Vec<(RemoteRefSymbolBuf, FailedRefExportReason)>,
+    /// Tags that couldn't be exported, sorted by `symbol`.
+    pub failed_tags: Vec<(RemoteRefSymbolBuf, FailedRefExportReason)>,
 }
 
 #[derive(Debug)]
@@ -884,18 +897,27 @@ struct RefsToExport {
     bookmarks_to_delete: Vec<(RemoteRefSymbolBuf, gix::ObjectId)>,
     /// Remote bookmarks that couldn't be exported, sorted by `symbol`.
     failed_bookmarks: Vec<(RemoteRefSymbolBuf, FailedRefExportReason)>,
+    /// Tag `(symbol, (old_oid, new_oid))`s to update, sorted by `symbol`. The
+    /// oids are of the commits the tags point to.
+    tags_to_update: Vec<(RemoteRefSymbolBuf, (Option<gix::ObjectId>, gix::ObjectId))>,
+    /// Tag `(symbol, old_oid)`s to delete, sorted by `symbol`.
+    tags_to_delete: Vec<(RemoteRefSymbolBuf, gix::ObjectId)>,
+    /// Tags that couldn't be exported, sorted by `symbol`.
+    failed_tags: Vec<(RemoteRefSymbolBuf, FailedRefExportReason)>,
 }
 
-/// Export changes to bookmarks made in the Jujutsu repo compared to our last
-/// seen view of the Git repo in `mut_repo.view().git_refs()`.
+/// Export changes to bookmarks and tags made in the Jujutsu repo compared to
+/// our last seen view of the Git repo in `mut_repo.view().git_refs()`.
 ///
-/// We ignore changed bookmarks that are conflicted (were also changed in the
-/// Git repo compared to our last remembered view of the Git repo). These will
-/// be marked conflicted by the next `jj git import`.
+/// We ignore changed bookmarks and tags that are conflicted (were also changed
+/// in the Git repo compared to our last remembered view of the Git repo).
+/// These will be marked conflicted by the next `jj git import`.
 ///
-/// We do not export tags and other refs at the moment, since these aren't
-/// supposed to be modified by JJ. For them, the Git state is considered
-/// authoritative.
+/// Tags are exported as lightweight tags, unless they have an annotation set
+/// by [`set_annotated_tag()`]. An annotated tag that is moved by JJ is replaced
+/// by a lightweight tag. We do not export other refs at the
+/// moment, since these aren't supposed to be modified by JJ. For them, the Git
+/// state is considered authoritative.
 pub fn export_refs(mut_repo: &mut MutableRepo) -> Result<GitExportStats, GitExportError> {
     export_some_refs(mut_repo, |_, _| true)
 }
@@ -917,11 +939,37 @@ pub fn export_some_refs(
         bookmarks_to_update,
         bookmarks_to_delete,
         mut failed_bookmarks,
+        mut tags_to_update,
+        tags_to_delete,
+        mut failed_tags,
     } = diff_refs_to_export(
         mut_repo.view(),
         mut_repo.store().root_commit_id(),
         &git_ref_filter,
     );
+    // Annotated tags which were set to the target they already had in Git
+    // still have to be replaced by a new tag object.
+    let mut annotated_tags_to_update = vec![];
+    for (name, _) in mut_repo.view().tag_annotations() {
+        let symbol = name.to_remote_symbol(REMOTE_NAME_FOR_LOCAL_GIT_REPO);
+        if get(&tags_to_update, symbol).is_some()
+            || get(&failed_tags, symbol).is_some()
+            || !git_ref_filter(GitRefKind::Tag, symbol)
+        {
+            continue;
+        }
+        let Some(git_ref_name) = to_git_ref_name(GitRefKind::Tag, symbol) else {
+            continue;
+        };
+        let local_target = mut_repo.view().get_tag(name);
+        let git_target = mut_repo.view().get_git_ref(&git_ref_name);
+        if let (Some(id), true) = (local_target.as_normal(), local_target == git_target) {
+            let oid = gix::ObjectId::from_bytes_or_panic(id.as_bytes());
+            annotated_tags_to_update.push((symbol.to_owned(), (Some(oid), oid)));
+        }
+    }
+    tags_to_update.extend(annotated_tags_to_update);
+    tags_to_update.sort_unstable_by(|(name1, _), (name2, _)| name1.cmp(name2));
 
     // TODO: Also check other worktrees' HEAD.
     if let Ok(head_ref) = git_repo.find_reference("HEAD") {
@@ -985,8 +1033,44 @@ pub fn export_some_refs(
         }
     }
 
+    for (symbol, old_oid) in tags_to_delete {
+        let Some(git_ref_name) = to_git_ref_name(GitRefKind::Tag, symbol.as_ref()) else {
+            failed_tags.push((symbol, FailedRefExportReason::InvalidGitName));
+            continue;
+        };
+        let old_oid = resolve_tag_object_id(&git_repo, &git_ref_name, old_oid);
+        if let Err(reason) = delete_git_ref(&git_repo, &git_ref_name, &old_oid) {
+            failed_tags.push((symbol, reason));
+        } else {
+            mut_repo.set_git_ref_target(&git_ref_name, RefTarget::absent());
+        }
+    }
+    for (symbol, (old_oid, new_oid)) in tags_to_update {
+        let Some(git_ref_name) = to_git_ref_name(GitRefKind::Tag, symbol.as_ref()) else {
+            failed_tags.push((symbol, FailedRefExportReason::InvalidGitName));
+            continue;
+        };
+        let old_oid = old_oid.map(|oid| resolve_tag_object_id(&git_repo, &git_ref_name, oid));
+        let annotation = mut_repo.view().get_tag_annotation(&symbol.name).cloned();
+        let new_object_oid = if let Some(annotation) = &annotation {
+            write_annotated_tag(&git_repo, &symbol.name, new_oid, annotation)?
+        } else {
+            new_oid
+        };
+        if let Err(reason) = update_git_ref(&git_repo, &git_ref_name, old_oid, new_object_oid) {
+            failed_tags.push((symbol, reason));
+        } else {
+            let new_target = RefTarget::normal(CommitId::from_bytes(new_oid.as_bytes()));
+            mut_repo.set_git_ref_target(&git_ref_name, new_target);
+            if annotation.is_some() {
+                mut_repo.set_tag_annotation(&symbol.name, None);
+            }
+        }
+    }
+
     // Stabilize output, allow binary search.
     failed_bookmarks.sort_unstable_by(|(name1, _), (name2, _)| name1.cmp(name2));
+    failed_tags.sort_unstable_by(|(name1, _), (name2, _)| name1.cmp(name2));
 
     copy_exportable_local_bookmarks_to_remote_view(
         mut_repo,
@@ -997,7 +1081,95 @@ pub fn export_some_refs(
         },
     );
 
-    Ok(GitExportStats { failed_bookmarks })
+    Ok(GitExportStats {
+        failed_bookmarks,
+        failed_tags,
+    })
+}
+
+/// Points the tag `name` to the `target` commit, and records the `message`
+/// and `tagger` to export it with.
+///
+/// The tag object is written to the Git repo when the tag is exported by
+/// [`export_refs()`]. Until then, the annotation is kept in the view, so it
+/// follows undo and concurrent operations like the tag itself. Moving or
+/// deleting the tag removes the annotation.
+pub fn set_annotated_tag(
+    mut_repo: &mut MutableRepo,
+    name: &RefName,
+    target: &CommitId,
+    message: &str,
+    tagger: &Signature,
+) -> Result<(), GitExportError> {
+    let symbol = name.to_remote_symbol(REMOTE_NAME_FOR_LOCAL_GIT_REPO);
+    if to_git_ref_name(GitRefKind::Tag, symbol).is_none() {
+        return Err(GitExportError::from_git(
+            FailedRefExportReason::InvalidGitName,
+        ));
+    }
+    if target == mut_repo.store().root_commit_id() {
+        return Err(GitExportError::from_git(
+            FailedRefExportReason::OnRootCommit,
+        ));
+    }
+    mut_repo.set_tag_target(name, RefTarget::normal(target.clone()));
+    let annotation = TagAnnotation {
+        message: message.to_owned(),
+        tagger: tagger.clone(),
+    };
+    mut_repo.set_tag_annotation(name, Some(annotation));
+    Ok(())
+}
+
+/// Writes the tag object of the annotated tag `name` of `target_oid` to the
+/// Git repo, and returns its id.
+fn write_annotated_tag(
+    git_repo: &gix::Repository,
+    name: &RefName,
+    target_oid: gix::ObjectId,
+    annotation: &TagAnnotation,
+) -> Result<gix::ObjectId, GitExportError> {
+    let tag = gix::objs::Tag {
+        target: target_oid,
+        target_kind: gix::object::Kind::Commit,
+        name: name.as_str().into(),
+        tagger: Some(signature_to_git(&annotation.tagger).to_owned()),
+        message: annotation.message.as_str().into(),
+        pgp_signature: None,
+    };
+    let tag_oid = git_repo
+        .write_object(&tag)
+        .map_err(GitExportError::from_git)?
+        .detach();
+    Ok(tag_oid)
+}
+
+/// Returns the id of the object the tag ref points to if it is an annotated
+/// tag of `commit_oid`. Otherwise returns `commit_oid`.
+///
+/// The ref of an annotated tag points to a tag object, whereas we record the
+/// id of the tagged commit in `git_refs`.
+fn resolve_tag_object_id(
+    git_repo: &gix::Repository,
+    git_ref_name: &GitRefName,
+    commit_oid: gix::ObjectId,
+) -> gix::ObjectId {
+    let Ok(git_ref) = git_repo.find_reference(git_ref_name.as_str()) else {
+        return commit_oid;
+    };
+    let Some(raw_oid) = git_ref.inner.target.try_id().map(|oid| oid.to_owned()) else {
+        return commit_oid;
+    };
+    let peeled_oid = git_repo
+        .find_object(raw_oid)
+        .ok()
+        .and_then(|object| object.peel_to_commit().ok())
+        .map(|commit| commit.id);
+    if raw_oid != commit_oid && peeled_oid == Some(commit_oid) {
+        raw_oid
+    } else {
+        commit_oid
+    }
 }
 
 fn copy_exportable_local_bookmarks_to_remote_view(
@@ -1027,7 +1199,7 @@ fn copy_exportable_local_bookmarks_to_remote_view(
     }
 }
 
-/// Calculates diff of bookmarks to be exported.
+/// Calculates diff of bookmarks and tags to be exported.
 fn diff_refs_to_export(
     view: &View,
     root_commit_id: &CommitId,
@@ -1048,6 +1220,16 @@ fn diff_refs_to_export(
         .filter(|&(symbol, _)| git_ref_filter(GitRefKind::Bookmark, symbol))
         .map(|(symbol, new_target)| (symbol, (RefTarget::absent_ref(), new_target)))
         .collect();
+    let mut all_tag_targets: HashMap<RemoteRefSymbol, (&RefTarget, &RefTarget)> = view
+        .tags()
+        .iter()
+        .map(|(name, target)| {
+            let symbol = name.to_remote_symbol(REMOTE_NAME_FOR_LOCAL_GIT_REPO);
+            (symbol, target)
+        })
+        .filter(|&(symbol, _)| git_ref_filter(GitRefKind::Tag, symbol))
+        .map(|(symbol, new_target)| (symbol, (RefTarget::absent_ref(), new_target)))
+        .collect();
     let known_git_refs = view
         .git_refs()
         .iter()
@@ -1056,30 +1238,56 @@ fn diff_refs_to_export(
                 parse_git_ref(full_name).expect("stored git ref should be parsable");
             ((kind, symbol), target)
         })
-        .filter(|&((kind, symbol), _)| {
-            // There are two situations where remote bookmarks get out of sync:
-            // 1. `jj bookmark forget --include-remotes`
-            // 2. `jj op undo`/`restore` in colocated repo
-            kind == GitRefKind::Bookmark && git_ref_filter(kind, symbol)
-        });
-    for ((_kind, symbol), target) in known_git_refs {
-        all_bookmark_targets
+        // There are two situations where remote bookmarks get out of sync:
+        // 1. `jj bookmark forget --include-remotes`
+        // 2. `jj op undo`/`restore` in colocated repo
+        .filter(|&((kind, symbol), _)| git_ref_filter(kind, symbol));
+    for ((kind, symbol), target) in known_git_refs {
+        let all_targets = match kind {
+            GitRefKind::Bookmark => &mut all_bookmark_targets,
+            GitRefKind::Tag => &mut all_tag_targets,
+        };
+        all_targets
             .entry(symbol)
             .and_modify(|(old_target, _)| *old_target = target)
             .or_insert((target, RefTarget::absent_ref()));
     }
 
-    let mut bookmarks_to_update = Vec::new();
-    let mut bookmarks_to_delete = Vec::new();
-    let mut failed_bookmarks = Vec::new();
+    let (bookmarks_to_update, bookmarks_to_delete, failed_bookmarks) =
+        classify_refs_to_export(all_bookmark_targets, root_commit_id);
+    let (tags_to_update, tags_to_delete, failed_tags) =
+        classify_refs_to_export(all_tag_targets, root_commit_id);
+    RefsToExport {
+        bookmarks_to_update,
+        bookmarks_to_delete,
+        failed_bookmarks,
+        tags_to_update,
+        tags_to_delete,
+        failed_tags,
+    }
+}
+
+type RefsToUpdate = Vec<(RemoteRefSymbolBuf, (Option<gix::ObjectId>, gix::ObjectId))>;
+type RefsToDelete = Vec<(RemoteRefSymbolBuf, gix::ObjectId)>;
+type FailedRefs = Vec<(RemoteRefSymbolBuf, FailedRefExportReason)>;
+
+/// Sorts `(old_target, new_target)` pairs of refs of the same kind into refs
+/// to be updated, deleted, and that can't be exported.
+fn classify_refs_to_export(
+    all_targets: HashMap<RemoteRefSymbol<'_>, (&RefTarget, &RefTarget)>,
+    root_commit_id: &CommitId,
+) -> (RefsToUpdate, RefsToDelete, FailedRefs) {
+    let mut refs_to_update = Vec::new();
+    let mut refs_to_delete = Vec::new();
+    let mut failed_refs = Vec::new();
     let root_commit_target = RefTarget::normal(root_commit_id.clone());
-    for (symbol, (old_target, new_target)) in all_bookmark_targets {
+    for (symbol, (old_target, new_target)) in all_targets {
         if new_target == old_target {
             continue;
         }
         if *new_target == root_commit_target {
             // Git doesn't have a root commit
-            failed_bookmarks.push((symbol.to_owned(), FailedRefExportReason::OnRootCommit));
+            failed_refs.push((symbol.to_owned(), FailedRefExportReason::OnRootCommit));
             continue;
         }
         let old_oid = if let Some(id) = old_target.as_normal() {
@@ -1087,7 +1295,7 @@ fn diff_refs_to_export(
         } else if old_target.has_conflict() {
             // The old git ref should only be a conflict if there were concurrent import
             // operations while the value changed. Don't overwrite these values.
-            failed_bookmarks.push((symbol.to_owned(), FailedRefExportReason::ConflictedOldState));
+            failed_refs.push((symbol.to_owned(), FailedRefExportReason::ConflictedOldState));
             continue;
         } else {
             assert!(old_target.is_absent());
@@ -1095,25 +1303,21 @@ fn diff_refs_to_export(
         };
         if let Some(id) = new_target.as_normal() {
             let new_oid = gix::ObjectId::from_bytes_or_panic(id.as_bytes());
-            bookmarks_to_update.push((symbol.to_owned(), (old_oid, new_oid)));
+            refs_to_update.push((symbol.to_owned(), (old_oid, new_oid)));
         } else if new_target.has_conflict() {
             // Skip conflicts and leave the old value in git_refs
             continue;
         } else {
             assert!(new_target.is_absent());
-            bookmarks_to_delete.push((symbol.to_owned(), old_oid.unwrap()));
+            refs_to_delete.push((symbol.to_owned(), old_oid.unwrap()));
         }
     }
 
     // Stabilize export order and output, allow binary search.
-    bookmarks_to_update.sort_unstable_by(|(sym1, _), (sym2, _)| sym1.cmp(sym2));
-    bookmarks_to_delete.sort_unstable_by(|(sym1, _), (sym2, _)| sym1.cmp(sym2));
-    failed_bookmarks.sort_unstable_by(|(sym1, _), (sym2, _)| sym1.cmp(sym2));
-    RefsToExport {
-        bookmarks_to_update,
-        bookmarks_to_delete,
-        failed_bookmarks,
-    }
+    refs_to_update.sort_unstable_by(|(sym1, _), (sym2, _)| sym1.cmp(sym2));
+    refs_to_delete.sort_unstable_by(|(sym1, _), (sym2, _)| sym1.cmp(sym2));
+    failed_refs.sort_unstable_by(|(sym1, _), (sym2, _)| sym1.cmp(sym2));
+    (refs_to_update, refs_to_delete, failed_refs)
 }
 
 fn delete_git_ref(
@@ -1574,6 +1778,163 @@ async fn update_intent_to_add_impl(
     Ok(())
 }
 
+#[derive(Debug, Error)]
+pub enum GitSubmoduleError {
+    #[error(transparent)]
+    UnexpectedBackend(#[from] UnexpectedGitBackendError),
+    #[error(transparent)]
+    Backend(#[from] BackendError),
+    #[error("Failed to read .gitmodules")]
+    ReadGitmodules(#[source] Box<dyn std::error::Error + Send + Sync>),
+    #[error("Submodule at {} is not configured in .gitmodules", .0.as_internal_file_string())]
+    NotConfigured(RepoPathBuf),
+    #[error("Submodule {name} has unsupported URL '{url}'")]
+    UnsupportedUrl { name: String, url: String },
+    #[error(
+        "Submodule {name} has URL '{url}' whose scheme isn't allowed by \
+         git.submodules.allowed-url-schemes"
+    )]
+    DisallowedUrl { name: String, url: String },
+    #[error(transparent)]
+    InvalidPath(#[from] InvalidRepoPathError),
+    #[error(transparent)]
+    Store(#[from] SubmoduleStoreError),
+}
+
+/// Diff `old_tree` to `new_tree` and check out the Git submodules that changed
+/// in the workspace at `workspace_root`, fetching them into the submodule
+/// store as needed.
+///
+/// Submodules are looked up by path in the `.gitmodules` file of `new_tree`,
+/// so nothing is done if there's no such file. Since the URLs come from the
+/// tree, only URLs whose scheme is in `submodule_allowed_url_schemes` of the
+/// `git_settings` are fetched. Returns the paths of the updated submodules.
+pub fn update_submodules(
+    repo: &dyn Repo,
+    git_settings: &GitSettings,
+    workspace_root: &Path,
+    old_tree: &MergedTree,
+    new_tree: &MergedTree,
+    matcher: &dyn Matcher,
+) -> Result<Vec<RepoPathBuf>, GitSubmoduleError> {
+    let Some(modules) = read_gitmodules(new_tree)? else {
+        return Ok(vec![]);
+    };
+    let changed_submodules = collect_changed_submodules(old_tree, new_tree, matcher).block_on()?;
+    if changed_submodules.is_empty() {
+        return Ok(vec![]);
+    }
+    let base_url = {
+        let git_repo = get_git_repo(repo.store())?;
+        let remote_url = git_repo
+            .try_find_remote("origin")
+            .and_then(|remote| remote.ok())
+            .and_then(|remote| {
+                remote
+                    .url(gix::remote::Direction::Fetch)
+                    .map(|url| url.to_bstring().to_string())
+            });
+        // Without a remote, the superproject is its own upstream.
+        remote_url.unwrap_or_else(|| workspace_root.to_string_lossy().into_owned())
+    };
+
+    let submodule_store = repo.submodule_store();
+    let mut updated_paths = vec![];
+    for (path, commit_id) in changed_submodules {
+        let name = modules
+            .names()
+            .find(|&name| {
+                modules
+                    .path(name)
+                    .is_ok_and(|module_path| *module_path == path.as_internal_file_string())
+            })
+            .ok_or_else(|| GitSubmoduleError::NotConfigured(path.clone()))?
+            .to_string();
+        let url = modules
+            .config()
+            .string(format!("submodule.{name}.url"))
+            .map(|url| url.to_string())
+            .unwrap_or_default();
+        // Don't let the URL be interpreted as a command-line option.
+        if url.is_empty() || url.starts_with('-') {
+            return Err(GitSubmoduleError::UnsupportedUrl { name, url });
+        }
+        let url = resolve_submodule_url(&base_url, &url);
+        let scheme = gix::url::parse(url.as_str().into()).map(|url| url.scheme);
+        if !scheme.is_ok_and(|scheme| {
+            git_settings
+                .submodule_allowed_url_schemes
+                .iter()
+                .any(|allowed| allowed == scheme.as_str())
+        }) {
+            return Err(GitSubmoduleError::DisallowedUrl { name, url });
+        }
+        let disk_path = path.to_fs_path(workspace_root)?;
+        submodule_store.fetch(&name, &url, &commit_id)?;
+        submodule_store.check_out(&name, &commit_id, &disk_path)?;
+        updated_paths.push(path);
+    }
+    Ok(updated_paths)
+}
+
+async fn collect_changed_submodules(
+    old_tree: &MergedTree,
+    new_tree: &MergedTree,
+    matcher: &dyn Matcher,
+) -> BackendResult<Vec<(RepoPathBuf, CommitId)>> {
+    let mut diff_stream = old_tree.diff_stream(new_tree, matcher);
+    let mut changed_submodules = vec![];
+    while let Some(TreeDiffEntry { path, values }) = diff_stream.next().await {
+        let (_before, after) = values?;
+        if let Some(TreeValue::GitSubmodule(id)) = after.as_normal() {
+            changed_submodules.push((path, id.clone()));
+        }
+    }
+    Ok(changed_submodules)
+}
+
+fn read_gitmodules(tree: &MergedTree) -> Result<Option<gix::submodule::File>, GitSubmoduleError> {
+    let path = RepoPath::from_internal_string(".gitmodules").unwrap();
+    let Some(TreeValue::File { id, .. }) = tree.path_value(path)?.into_resolved().ok().flatten()
+    else {
+        return Ok(None);
+    };
+    let mut content = vec![];
+    tree.store()
+        .read_file(path, &id)?
+        .read_to_end(&mut content)
+        .map_err(|err| GitSubmoduleError::ReadGitmodules(err.into()))?;
+    let modules = gix::submodule::File::from_bytes(&content, None, &Default::default())
+        .map_err(|err| GitSubmoduleError::ReadGitmodules(err.into()))?;
+    Ok(Some(modules))
+}
+
+/// Resolves `url` relative to the superproject's `base_url` if it starts with
+/// "./" or "../", like `git submodule` does.
+fn resolve_submodule_url(base_url: &str, url: &str) -> String {
+    if !url.starts_with("./") && !url.starts_with("../") {
+        return url.to_owned();
+    }
+    let mut base = base_url.trim_end_matches('/');
+    let mut separator = '/';
+    let mut rest = url;
+    loop {
+        if let Some(tail) = rest.strip_prefix("./") {
+            rest = tail;
+        } else if let Some(tail) = rest.strip_prefix("../") {
+            // "host:path" style URLs use ':' as the first separator.
+            if let Some(pos) = base.rfind(['/', ':']) {
+                separator = base.as_bytes()[pos] as char;
+                base = &base[..pos];
+            }
+            rest = tail;
+        } else {
+            break;
+        }
+    }
+    format!("{base}{separator}{rest}")
+}
+
 #[derive(Debug, Error)]
 pub enum GitRemoteManagementError {
     #[error("No git remote named '{}'", .0.as_symbol())]
@@ -660,7 +660,7 @@ fn signature_from_git(signature: gix::actor::SignatureRef) -> Signature {
     }
 }
 
-fn signature_to_git(signature: &Signature) -> gix::actor::SignatureRef<'_> {
+pub(crate) fn signature_to_git(signature: &Signature) -> gix::actor::SignatureRef<'_> {
     // git does not support empty names or emails
     let name = if !signature.name.is_empty() {
         &signature.name
@@ -973,6 +973,15 @@ impl Backend for GitBackend {
         self.read_file_sync(id)
     }
 
+    async fn file_size(&self, _path: &RepoPath, id: &FileId) -> BackendResult<u64> {
+        let git_blob_id = validate_git_object_id(id)?;
+        let locked_repo = self.lock_git_repo();
+        let header = locked_repo
+            .find_header(git_blob_id)
+            .map_err(|err| map_not_found_err(err, id))?;
+        Ok(header.size())
+    }
+
     async fn write_file(
         &self,
         _path: &RepoPath,
@@ -2339,6 +2348,26 @@ mod tests {
         ");
     }
 
+    #[test]
+    fn file_size() {
+        let settings = user_settings();
+        let temp_dir = new_temp_dir();
+        let backend = GitBackend::init_internal(&settings, temp_dir.path()).unwrap();
+        let path = RepoPath::from_internal_string("file").unwrap();
+
+        let id = backend
+            .write_file(path, &mut &b"some content"[..])
+            .block_on()
+            .unwrap();
+        assert_eq!(backend.file_size(path, &id).block_on().unwrap(), 12);
+
+        let missing_id = FileId::new(vec![0; 20]);
+        assert_matches!(
+            backend.file_size(path, &missing_id).block_on(),
+            Err(BackendError::ObjectNotFound { .. })
+        );
+    }
+
     fn git_id(commit_id: &CommitId) -> gix::ObjectId {
         gix::ObjectId::from_bytes_or_panic(commit_id.as_bytes())
     }
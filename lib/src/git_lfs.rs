@@ -0,0 +1,490 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Support for files stored in [Git LFS].
+//!
+//! Git LFS stores small pointer files in Git in place of the file contents.
+//! A pointer names the SHA-256 of the contents, which are kept in the LFS
+//! object store under `.git/lfs/objects`. The working copy replaces pointers
+//! with the contents on checkout ("smudge"), and stores the contents of files
+//! tracked by LFS in `.gitattributes` as pointers on snapshot ("clean").
+//!
+//! [Git LFS]: https://git-lfs.com/
+
+use std::fs;
+use std::fs::File;
+use std::io;
+use std::io::Read;
+use std::io::Seek as _;
+use std::io::Write as _;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use bstr::ByteSlice as _;
+use futures::StreamExt as _;
+use pollster::FutureExt as _;
+use sha2::Digest as _;
+use sha2::Sha256;
+use tempfile::NamedTempFile;
+use thiserror::Error;
+
+use crate::backend::BackendError;
+use crate::backend::CommitId;
+use crate::backend::TreeValue;
+use crate::file_util::persist_content_addressed_temp_file;
+use crate::git::get_git_backend;
+use crate::git::get_git_repo;
+use crate::git::UnexpectedGitBackendError;
+use crate::gitignore::GitIgnoreError;
+use crate::gitignore::GitIgnoreFile;
+use crate::matchers::EverythingMatcher;
+use crate::merged_tree::MergedTree;
+use crate::merged_tree::TreeDiffEntry;
+use crate::ref_name::RemoteName;
+use crate::repo::Repo;
+use crate::repo_path::RepoPath;
+use crate::revset::RevsetEvaluationError;
+use crate::revset::RevsetExpression;
+use crate::store::Store;
+
+/// Version line identifying Git LFS pointer files.
+pub const LFS_POINTER_VERSION: &str = "https://git-lfs.github.com/spec/v1";
+
+/// Pointer files are required to be smaller than this.
+const MAX_POINTER_SIZE: usize = 1024;
+
+/// Error from Git LFS object transfers.
+#[derive(Debug, Error)]
+pub enum GitLfsError {
+    /// The repo isn't backed by Git.
+    #[error(transparent)]
+    UnexpectedBackend(#[from] UnexpectedGitBackendError),
+    /// Failed to read the trees of the transferred commits.
+    #[error(transparent)]
+    Backend(#[from] BackendError),
+    /// Failed to find the transferred commits.
+    #[error(transparent)]
+    RevsetEvaluation(#[from] RevsetEvaluationError),
+    /// Failed to copy an object between the stores.
+    #[error("Failed to copy Git LFS object {oid}")]
+    CopyObject {
+        /// Hex-encoded SHA-256 of the object.
+        oid: String,
+        /// The underlying error.
+        #[source]
+        source: io::Error,
+    },
+}
+
+/// Contents of a Git LFS pointer file.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LfsPointer {
+    /// Hex-encoded SHA-256 of the file contents.
+    pub oid: String,
+    /// Size of the file contents in bytes.
+    pub size: u64,
+}
+
+impl LfsPointer {
+    /// Parses `content` as a pointer file. Returns `None` if it isn't one.
+    pub fn parse(content: &[u8]) -> Option<Self> {
+        if content.len() >= MAX_POINTER_SIZE {
+            return None;
+        }
+        let text = std::str::from_utf8(content).ok()?;
+        let mut lines = text.lines();
+        if lines.next()? != format!("version {LFS_POINTER_VERSION}") {
+            return None;
+        }
+        let mut oid = None;
+        let mut size = None;
+        for line in lines {
+            // Other keys are used by pointer extensions, which we ignore.
+            match line.split_once(' ')? {
+                ("oid", value) => oid = Some(value.strip_prefix("sha256:")?),
+                ("size", value) => size = Some(value.parse().ok()?),
+                _ => {}
+            }
+        }
+        let (oid, size) = (oid?, size?);
+        let is_valid_oid = oid.len() == 64
+            && oid
+                .bytes()
+                .all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b));
+        is_valid_oid.then(|| LfsPointer {
+            oid: oid.to_owned(),
+            size,
+        })
+    }
+
+    /// Serializes the pointer in the canonical pointer file format.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        format!(
+            "version {LFS_POINTER_VERSION}\noid sha256:{}\nsize {}\n",
+            self.oid, self.size
+        )
+        .into_bytes()
+    }
+}
+
+/// Content-addressed store of Git LFS objects.
+#[derive(Clone, Debug)]
+pub struct LfsObjectStore {
+    objects_dir: PathBuf,
+}
+
+impl LfsObjectStore {
+    /// Creates a store of the objects in `objects_dir`.
+    pub fn new(objects_dir: PathBuf) -> Self {
+        LfsObjectStore { objects_dir }
+    }
+
+    /// Returns the object store of the Git repository at `git_dir`.
+    pub fn for_git_dir(git_dir: &Path) -> Self {
+        Self::new(git_dir.join("lfs").join("objects"))
+    }
+
+    /// Returns the object store of the Git repository backing `store`.
+    pub fn for_store(store: &Store) -> Result<Self, UnexpectedGitBackendError> {
+        let git_backend = get_git_backend(store)?;
+        Ok(Self::for_git_dir(git_backend.git_repo_path()))
+    }
+
+    /// Path to the object file.
+    pub fn object_path(&self, oid: &str) -> PathBuf {
+        self.objects_dir.join(&oid[0..2]).join(&oid[2..4]).join(oid)
+    }
+
+    /// Returns true if the object pointed to by `pointer` exists.
+    pub fn contains(&self, pointer: &LfsPointer) -> bool {
+        self.object_path(&pointer.oid).is_file()
+    }
+
+    /// Opens the object pointed to by `pointer`, or returns `None` if the
+    /// object doesn't exist.
+    pub fn open(&self, pointer: &LfsPointer) -> io::Result<Option<File>> {
+        match File::open(self.object_path(&pointer.oid)) {
+            Ok(file) => Ok(Some(file)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Writes `contents` to the store, and returns the pointer to it.
+    pub fn write(&self, contents: &mut dyn Read) -> io::Result<LfsPointer> {
+        fs::create_dir_all(&self.objects_dir)?;
+        let mut temp_file = NamedTempFile::new_in(&self.objects_dir)?;
+        let mut hasher = Sha256::new();
+        let mut size = 0;
+        let mut buf = vec![0; 64 * 1024];
+        loop {
+            let len = contents.read(&mut buf)?;
+            if len == 0 {
+                break;
+            }
+            hasher.update(&buf[..len]);
+            temp_file.write_all(&buf[..len])?;
+            size += len as u64;
+        }
+        let oid = hex::encode(hasher.finalize());
+        let object_path = self.object_path(&oid);
+        fs::create_dir_all(object_path.parent().unwrap())?;
+        persist_content_addressed_temp_file(temp_file, object_path)?;
+        Ok(LfsPointer { oid, size })
+    }
+}
+
+/// Converts between LFS pointers and file contents in the working copy.
+#[derive(Debug)]
+pub struct LfsFilter {
+    objects: LfsObjectStore,
+    tracked_paths: Arc<GitIgnoreFile>,
+}
+
+impl LfsFilter {
+    /// Creates a filter for the paths tracked by LFS according to the
+    /// top-level `.gitattributes` file content. Returns `None` if the store
+    /// isn't backed by Git or if no paths are tracked by LFS.
+    pub fn new(
+        store: &Store,
+        gitattributes_path: &Path,
+        gitattributes: &[u8],
+    ) -> Result<Option<Self>, GitIgnoreError> {
+        let Ok(objects) = LfsObjectStore::for_store(store) else {
+            return Ok(None);
+        };
+        let patterns = lfs_patterns_from_gitattributes(gitattributes);
+        if patterns.is_empty() {
+            return Ok(None);
+        }
+        let tracked_paths =
+            GitIgnoreFile::empty().chain("", gitattributes_path, patterns.as_bytes())?;
+        Ok(Some(LfsFilter {
+            objects,
+            tracked_paths,
+        }))
+    }
+
+    /// Creates a filter from the `.gitattributes` file in the working copy.
+    pub fn load_from_disk(
+        store: &Store,
+        working_copy_path: &Path,
+    ) -> Result<Option<Self>, GitIgnoreError> {
+        let path = working_copy_path.join(".gitattributes");
+        match fs::read(&path) {
+            Ok(content) => Self::new(store, &path, &content),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(GitIgnoreError::ReadFile { path, source: err }),
+        }
+    }
+
+    /// Creates a filter from the `.gitattributes` file in `tree`.
+    pub fn load_from_tree(tree: &MergedTree) -> Result<Option<Self>, BackendError> {
+        let path = RepoPath::from_internal_string(".gitattributes").unwrap();
+        let Some(Some(TreeValue::File { id, .. })) = tree.path_value(path)?.into_resolved().ok()
+        else {
+            return Ok(None);
+        };
+        let mut content = vec![];
+        tree.store()
+            .read_file(path, &id)?
+            .read_to_end(&mut content)
+            .map_err(|err| BackendError::ReadFile {
+                path: path.to_owned(),
+                id: id.clone(),
+                source: err.into(),
+            })?;
+        // Invalid patterns are reported when the working copy is snapshotted.
+        Ok(Self::new(tree.store(), Path::new(".gitattributes"), &content).unwrap_or(None))
+    }
+
+    /// Returns true if the file at `path` is stored as an LFS pointer.
+    pub fn is_tracked(&self, path: &RepoPath) -> bool {
+        self.tracked_paths.matches(path.as_internal_file_string())
+    }
+
+    /// Returns the contents to write to the working copy for the stored file
+    /// `contents`. Pointers are replaced with the objects they point to if
+    /// the objects are present in the store.
+    pub fn smudge<'a>(&self, contents: &'a mut dyn Read) -> io::Result<Box<dyn Read + 'a>> {
+        let mut head = vec![];
+        contents
+            .take(MAX_POINTER_SIZE as u64)
+            .read_to_end(&mut head)?;
+        if let Some(pointer) = LfsPointer::parse(&head) {
+            if let Some(file) = self.objects.open(&pointer)? {
+                return Ok(Box::new(file));
+            }
+        }
+        Ok(Box::new(io::Cursor::new(head).chain(contents)))
+    }
+
+    /// Stores the working-copy `file` in the object store, and returns the
+    /// pointer file contents to store in its place. Files that are pointers
+    /// already are returned as is.
+    pub fn clean(&self, file: &mut File) -> io::Result<Vec<u8>> {
+        let mut head = vec![];
+        file.take(MAX_POINTER_SIZE as u64).read_to_end(&mut head)?;
+        if LfsPointer::parse(&head).is_some() {
+            return Ok(head);
+        }
+        file.rewind()?;
+        Ok(self.objects.write(file)?.to_bytes())
+    }
+}
+
+/// Returns the patterns of `.gitattributes` lines setting or unsetting the
+/// `filter=lfs` attribute, in `.gitignore` syntax.
+fn lfs_patterns_from_gitattributes(content: &[u8]) -> String {
+    let mut patterns = String::new();
+    for line in content.split(|b| *b == b'\n') {
+        let Ok(line) = std::str::from_utf8(line) else {
+            continue;
+        };
+        let mut words = line.split_whitespace();
+        let Some(pattern) = words.next().filter(|word| !word.starts_with('#')) else {
+            continue;
+        };
+        for attribute in words {
+            if attribute == "filter=lfs" {
+                patterns.push_str(pattern);
+                patterns.push('\n');
+            } else if attribute == "-filter" || attribute == "!filter" {
+                patterns.push('!');
+                patterns.push_str(pattern);
+                patterns.push('\n');
+            }
+        }
+    }
+    patterns
+}
+
+/// Returns the LFS object store of the Git remote, if the remote is a local
+/// repository.
+pub fn remote_lfs_store(
+    store: &Store,
+    remote_name: &RemoteName,
+) -> Result<Option<LfsObjectStore>, UnexpectedGitBackendError> {
+    let git_repo = get_git_repo(store)?;
+    let Some(Ok(remote)) = git_repo.try_find_remote(remote_name.as_str()) else {
+        return Ok(None);
+    };
+    let Some(url) = remote.url(gix::remote::Direction::Fetch) else {
+        return Ok(None);
+    };
+    if url.scheme != gix::url::Scheme::File {
+        return Ok(None);
+    }
+    let Ok(path) = gix::path::try_from_bstr(url.path.as_bstr()) else {
+        return Ok(None);
+    };
+    let Ok(remote_repo) = gix::open_opts(path.as_ref(), gix::open::Options::isolated()) else {
+        return Ok(None);
+    };
+    Ok(Some(LfsObjectStore::for_git_dir(remote_repo.git_dir())))
+}
+
+/// Copies the LFS objects pointed to by files changed in commits in
+/// `::heads ~ ::bases` from `source` to `destination`. Returns the number of
+/// copied objects.
+pub fn copy_lfs_objects(
+    repo: &dyn Repo,
+    heads: &[CommitId],
+    bases: &[CommitId],
+    source: &LfsObjectStore,
+    destination: &LfsObjectStore,
+) -> Result<usize, GitLfsError> {
+    let expression = RevsetExpression::commits(heads.to_vec())
+        .ancestors()
+        .minus(&RevsetExpression::commits(bases.to_vec()).ancestors());
+    let mut num_copied = 0;
+    for commit_id in expression.evaluate(repo)?.iter() {
+        let commit = repo.store().get_commit(&commit_id?)?;
+        let parent_tree = commit.parent_tree(repo)?;
+        let entries: Vec<TreeDiffEntry> = parent_tree
+            .diff_stream(&commit.tree()?, &EverythingMatcher)
+            .collect()
+            .block_on();
+        for TreeDiffEntry { path, values } in entries {
+            let (_, after) = values?;
+            let Some(Some(TreeValue::File { id, .. })) = after.as_resolved() else {
+                continue;
+            };
+            let mut head = vec![];
+            repo.store()
+                .read_file(&path, id)?
+                .take(MAX_POINTER_SIZE as u64)
+                .read_to_end(&mut head)
+                .map_err(|err| BackendError::ReadFile {
+                    path: path.clone(),
+                    id: id.clone(),
+                    source: err.into(),
+                })?;
+            let Some(pointer) = LfsPointer::parse(&head) else {
+                continue;
+            };
+            if destination.contains(&pointer) {
+                continue;
+            }
+            let copy_object = || -> io::Result<bool> {
+                let Some(mut file) = source.open(&pointer)? else {
+                    return Ok(false);
+                };
+                destination.write(&mut file)?;
+                Ok(true)
+            };
+            match copy_object() {
+                Ok(true) => num_copied += 1,
+                Ok(false) => {}
+                Err(err) => {
+                    return Err(GitLfsError::CopyObject {
+                        oid: pointer.oid,
+                        source: err,
+                    })
+                }
+            }
+        }
+    }
+    Ok(num_copied)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pointer() {
+        let oid = "4d7a214614ab2935c943f9e0ff69d22eadbb8f32b1258daaa5e2ca24d17e2393";
+        let pointer = LfsPointer {
+            oid: oid.to_owned(),
+            size: 12345,
+        };
+        assert_eq!(
+            LfsPointer::parse(&pointer.to_bytes()),
+            Some(pointer.clone())
+        );
+
+        // Extension keys are ignored
+        let content = format!(
+            "version {LFS_POINTER_VERSION}\next-0-foo sha256:{oid}\noid sha256:{oid}\nsize \
+             12345\n"
+        );
+        assert_eq!(LfsPointer::parse(content.as_bytes()), Some(pointer));
+
+        assert_eq!(LfsPointer::parse(b""), None);
+        assert_eq!(LfsPointer::parse(b"hello\n"), None);
+        let content = format!("version {LFS_POINTER_VERSION}\noid sha256:{oid}\n");
+        assert_eq!(LfsPointer::parse(content.as_bytes()), None);
+        let content = format!("version {LFS_POINTER_VERSION}\noid sha256:abc\nsize 1\n");
+        assert_eq!(LfsPointer::parse(content.as_bytes()), None);
+    }
+
+    #[test]
+    fn test_lfs_patterns_from_gitattributes() {
+        let content = b"\
+# comment
+*.bin filter=lfs diff=lfs merge=lfs -text
+*.txt text
+keep.bin -filter
+";
+        assert_eq!(
+            lfs_patterns_from_gitattributes(content),
+            "*.bin\n!keep.bin\n"
+        );
+    }
+
+    #[test]
+    fn test_object_store_write() {
+        let temp_dir = testutils::new_temp_dir();
+        let store = LfsObjectStore::new(temp_dir.path().to_owned());
+        let pointer = store.write(&mut b"hello\n".as_slice()).unwrap();
+        assert_eq!(
+            pointer,
+            LfsPointer {
+                oid: "5891b5b522d5df086d0ff0b110fbd9d21bb4fc7163af34d08286a2e846f6be03".to_owned(),
+                size: 6,
+            }
+        );
+        assert!(store.contains(&pointer));
+        let mut content = vec![];
+        store
+            .open(&pointer)
+            .unwrap()
+            .unwrap()
+            .read_to_end(&mut content)
+            .unwrap();
+        assert_eq!(content, b"hello\n");
+    }
+}
@@ -31,6 +31,8 @@ pub mod content_hash;
 pub mod absorb;
 pub mod annotate;
 pub mod backend;
+pub mod bisect;
+pub mod cas_op_heads_store;
 pub mod commit;
 pub mod commit_builder;
 pub mod config;
@@ -68,6 +70,8 @@ pub mod git {
 #[cfg(feature = "git")]
 pub mod git_backend;
 #[cfg(feature = "git")]
+pub mod git_lfs;
+#[cfg(feature = "git")]
 mod git_subprocess;
 pub mod gitignore;
 pub mod gpg_signing;
@@ -85,6 +89,8 @@ pub mod op_heads_store;
 pub mod op_store;
 pub mod op_walk;
 pub mod operation;
+pub mod opset;
+pub mod patch;
 #[expect(missing_docs)]
 pub mod protos;
 pub mod ref_name;
@@ -104,6 +110,7 @@ pub mod signing;
 pub mod simple_backend;
 pub mod simple_op_heads_store;
 pub mod simple_op_store;
+pub mod sparse_profile;
 pub mod ssh_signing;
 pub mod stacked_table;
 pub mod store;
@@ -120,6 +127,7 @@ pub mod union_find;
 pub mod view;
 pub mod working_copy;
 pub mod workspace;
+pub mod workspace_store;
 
 #[cfg(test)]
 mod tests {
@@ -17,6 +17,7 @@
 
 use std::any::Any;
 use std::cmp::Ordering;
+use std::collections::BTreeSet;
 use std::collections::HashSet;
 use std::error::Error;
 use std::fs;
@@ -26,6 +27,8 @@ use std::fs::Metadata;
 use std::fs::OpenOptions;
 use std::io;
 use std::io::Read;
+use std::io::Seek as _;
+use std::io::SeekFrom;
 use std::io::Write as _;
 use std::iter;
 use std::mem;
@@ -58,6 +61,7 @@ use tracing::trace_span;
 
 use crate::backend::BackendError;
 use crate::backend::BackendResult;
+use crate::backend::CommitId;
 use crate::backend::FileId;
 use crate::backend::MergedTreeId;
 use crate::backend::MillisSinceEpoch;
@@ -69,16 +73,22 @@ use crate::conflicts;
 use crate::conflicts::choose_materialized_conflict_marker_len;
 use crate::conflicts::materialize_merge_result_to_bytes_with_marker_len;
 use crate::conflicts::materialize_tree_value;
-use crate::conflicts::ConflictMarkerStyle;
+use crate::conflicts::ConflictMarkerStyleMatcher;
 use crate::conflicts::MaterializedTreeValue;
 use crate::conflicts::MIN_CONFLICT_MARKER_LEN;
 use crate::file_util::check_symlink_support;
 use crate::file_util::try_symlink;
+#[cfg(target_os = "linux")]
+use crate::fsmonitor::inotify;
 #[cfg(feature = "watchman")]
 use crate::fsmonitor::watchman;
 use crate::fsmonitor::FsmonitorSettings;
+#[cfg(target_os = "linux")]
+use crate::fsmonitor::InotifyConfig;
 #[cfg(feature = "watchman")]
 use crate::fsmonitor::WatchmanConfig;
+#[cfg(feature = "git")]
+use crate::git_lfs::LfsFilter;
 use crate::gitignore::GitIgnoreFile;
 use crate::lock::FileLock;
 use crate::matchers::DifferenceMatcher;
@@ -100,6 +110,9 @@ use crate::ref_name::WorkspaceNameBuf;
 use crate::repo_path::RepoPath;
 use crate::repo_path::RepoPathBuf;
 use crate::repo_path::RepoPathComponent;
+use crate::sparse_profile::sparse_matcher;
+use crate::sparse_profile::SparseProfile;
+use crate::sparse_profile::SparseProfileError;
 use crate::store::Store;
 use crate::tree::Tree;
 use crate::working_copy::CheckoutError;
@@ -139,6 +152,9 @@ pub struct FileState {
     pub mtime: MillisSinceEpoch,
     pub size: u64,
     pub materialized_conflict_data: Option<MaterializedConflictData>,
+    /// The commit checked out in the Git submodule when the working copy was
+    /// updated, or when a change of it was last snapshotted.
+    pub git_submodule_head: Option<CommitId>,
     /* TODO: What else do we need here? Git stores a lot of fields.
      * TODO: Could possibly handle case-insensitive file systems keeping an
      *       Option<PathBuf> with the actual path here. */
@@ -165,6 +181,7 @@ impl FileState {
             mtime: MillisSinceEpoch(0),
             size: 0,
             materialized_conflict_data: None,
+            git_submodule_head: None,
         }
     }
 
@@ -184,6 +201,7 @@ impl FileState {
             mtime: mtime_from_metadata(metadata),
             size,
             materialized_conflict_data,
+            git_submodule_head: None,
         }
     }
 
@@ -196,15 +214,17 @@ impl FileState {
             mtime: mtime_from_metadata(metadata),
             size: metadata.len(),
             materialized_conflict_data: None,
+            git_submodule_head: None,
         }
     }
 
-    fn for_gitsubmodule() -> Self {
+    fn for_gitsubmodule(head_id: Option<CommitId>) -> Self {
         FileState {
             file_type: FileType::GitSubmodule,
             mtime: MillisSinceEpoch(0),
             size: 0,
             materialized_conflict_data: None,
+            git_submodule_head: head_id,
         }
     }
 }
@@ -425,6 +445,10 @@ pub struct TreeState {
     file_states: FileStatesMap,
     // Currently only path prefixes
     sparse_patterns: Vec<RepoPathBuf>,
+    sparse_profile: Option<SparseProfile>,
+    /// New files that were tracked regardless of the snapshot policy, which
+    /// will be tracked again if they are re-created.
+    force_tracked_paths: BTreeSet<RepoPathBuf>,
     own_mtime: MillisSinceEpoch,
     symlink_support: bool,
 
@@ -432,6 +456,8 @@ pub struct TreeState {
     /// the repo is configured to use the Watchman filesystem monitor and
     /// Watchman has been queried at least once.
     watchman_clock: Option<crate::protos::working_copy::WatchmanClock>,
+    /// The most recent clock value returned by the inotify daemon.
+    inotify_clock: Option<String>,
 }
 
 fn file_state_from_proto(proto: &crate::protos::working_copy::FileState) -> FileState {
@@ -459,6 +485,8 @@ fn file_state_from_proto(proto: &crate::protos::working_copy::FileState) -> File
                 conflict_marker_len: data.conflict_marker_len,
             }
         }),
+        git_submodule_head: (!proto.git_submodule_head.is_empty())
+            .then(|| CommitId::from_bytes(&proto.git_submodule_head)),
     }
 }
 
@@ -482,6 +510,10 @@ fn file_state_to_proto(file_state: &FileState) -> crate::protos::working_copy::F
             conflict_marker_len: data.conflict_marker_len,
         }
     });
+    proto.git_submodule_head = file_state
+        .git_submodule_head
+        .as_ref()
+        .map_or_else(Vec::new, |id| id.to_bytes());
     proto
 }
 
@@ -511,6 +543,16 @@ fn is_file_state_entries_proto_unique_and_sorted(
         .all(|(path1, path2)| path1 < path2)
 }
 
+fn sparse_profile_from_proto(
+    proto: Option<&crate::protos::working_copy::SparsePatterns>,
+) -> Option<SparseProfile> {
+    let profile = proto?.profile.as_ref()?;
+    Some(SparseProfile {
+        name: profile.name.clone(),
+        content: profile.content.clone(),
+    })
+}
+
 fn sparse_patterns_from_proto(
     proto: Option<&crate::protos::working_copy::SparsePatterns>,
 ) -> Vec<RepoPathBuf> {
@@ -607,6 +649,45 @@ fn remove_old_file(disk_path: &Path) -> Result<bool, CheckoutError> {
     }
 }
 
+/// Returns the commit checked out in the Git submodule at `disk_path`, or
+/// `None` if the submodule isn't checked out.
+#[cfg(feature = "git")]
+fn read_git_submodule_head(disk_path: &Path) -> Option<CommitId> {
+    if !disk_path.join(".git").exists() {
+        return None;
+    }
+    let repo = gix::open_opts(disk_path, gix::open::Options::isolated()).ok()?;
+    let head_id = repo.head_id().ok()?;
+    Some(CommitId::from_bytes(head_id.as_bytes()))
+}
+
+#[cfg(not(feature = "git"))]
+fn read_git_submodule_head(_disk_path: &Path) -> Option<CommitId> {
+    None
+}
+
+/// Replaces the Git LFS pointer in `reader` with the object it points to if
+/// the `path` is tracked by LFS.
+#[cfg(feature = "git")]
+fn smudge_lfs_pointer<'a>(
+    lfs_filter: Option<&LfsFilter>,
+    path: &RepoPath,
+    disk_path: &Path,
+    reader: &'a mut dyn Read,
+) -> Result<Box<dyn Read + 'a>, CheckoutError> {
+    match lfs_filter {
+        Some(lfs_filter) if lfs_filter.is_tracked(path) => {
+            lfs_filter
+                .smudge(reader)
+                .map_err(|err| CheckoutError::Other {
+                    message: format!("Failed to read Git LFS object for {}", disk_path.display()),
+                    err: err.into(),
+                })
+        }
+        _ => Ok(Box::new(reader)),
+    }
+}
+
 /// Checks if new file or symlink named `disk_path` can be created.
 ///
 /// If the file already exists, this function return `Ok(false)` to signal
@@ -723,13 +804,72 @@ fn file_state(metadata: &Metadata) -> Option<FileState> {
             mtime,
             size,
             materialized_conflict_data: None,
+            git_submodule_head: None,
         }
     })
 }
 
+/// Magic numbers of binary formats that are typically generated by builds.
+const GENERATED_BINARY_MAGIC_NUMBERS: &[(&[u8], &str)] = &[
+    (b"\x7fELF", "ELF"),
+    (b"\xfe\xed\xfa\xce", "Mach-O"),
+    (b"\xfe\xed\xfa\xcf", "Mach-O"),
+    (b"\xce\xfa\xed\xfe", "Mach-O"),
+    (b"\xcf\xfa\xed\xfe", "Mach-O"),
+    // Also used by Java class files
+    (b"\xca\xfe\xba\xbe", "Mach-O universal or Java class"),
+    (b"\0asm", "WebAssembly"),
+    (b"!<arch>\n", "ar archive"),
+    (b"BC\xc0\xde", "LLVM bitcode"),
+];
+
+/// Returns the name of the binary format if the file at `disk_path` starts
+/// with the magic number of a generated binary.
+fn detect_generated_binary(disk_path: &Path) -> io::Result<Option<&'static str>> {
+    let mut buf = [0; 8];
+    let mut len = 0;
+    let mut file = File::open(disk_path)?;
+    while len < buf.len() {
+        match file.read(&mut buf[len..])? {
+            0 => break,
+            n => len += n,
+        }
+    }
+    let format = GENERATED_BINARY_MAGIC_NUMBERS
+        .iter()
+        .find(|(magic, _)| buf[..len].starts_with(magic))
+        .map(|(_, format)| *format);
+    if format.is_none() && buf[..len].starts_with(b"MZ") && has_pe_signature(&mut file)? {
+        return Ok(Some("PE"));
+    }
+    Ok(format)
+}
+
+/// Returns true if the file starting with the DOS "MZ" magic has the PE
+/// signature at the offset stored in the DOS header. The "MZ" magic alone is
+/// too short to tell PE files from text files.
+fn has_pe_signature(file: &mut File) -> io::Result<bool> {
+    const PE_OFFSET_POSITION: u64 = 0x3c;
+    let mut read_at = |position: u64| -> io::Result<Option<[u8; 4]>> {
+        let mut buf = [0; 4];
+        file.seek(SeekFrom::Start(position))?;
+        match file.read_exact(&mut buf) {
+            Ok(()) => Ok(Some(buf)),
+            Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => Ok(None),
+            Err(err) => Err(err),
+        }
+    };
+    let Some(pe_offset) = read_at(PE_OFFSET_POSITION)? else {
+        return Ok(false);
+    };
+    let signature = read_at(u32::from_le_bytes(pe_offset).into())?;
+    Ok(signature == Some(*b"PE\0\0"))
+}
+
 struct FsmonitorMatcher {
     matcher: Option<Box<dyn Matcher>>,
     watchman_clock: Option<crate::protos::working_copy::WatchmanClock>,
+    inotify_clock: Option<String>,
 }
 
 #[derive(Debug, Error)]
@@ -766,8 +906,16 @@ impl TreeState {
         &self.sparse_patterns
     }
 
-    fn sparse_matcher(&self) -> Box<dyn Matcher> {
-        Box::new(PrefixMatcher::new(&self.sparse_patterns))
+    pub fn sparse_profile(&self) -> Option<&SparseProfile> {
+        self.sparse_profile.as_ref()
+    }
+
+    pub fn force_tracked_paths(&self) -> &BTreeSet<RepoPathBuf> {
+        &self.force_tracked_paths
+    }
+
+    fn sparse_matcher(&self) -> Result<Box<dyn Matcher>, SparseProfileError> {
+        sparse_matcher(&self.sparse_patterns, self.sparse_profile.as_ref())
     }
 
     pub fn init(
@@ -789,9 +937,12 @@ impl TreeState {
             tree_id,
             file_states: FileStatesMap::new(),
             sparse_patterns: vec![RepoPathBuf::root()],
+            sparse_profile: None,
+            force_tracked_paths: BTreeSet::new(),
             own_mtime: MillisSinceEpoch(0),
             symlink_support: check_symlink_support().unwrap_or(false),
             watchman_clock: None,
+            inotify_clock: None,
         }
     }
 
@@ -854,7 +1005,14 @@ impl TreeState {
         self.file_states =
             FileStatesMap::from_proto(proto.file_states, proto.is_file_states_sorted);
         self.sparse_patterns = sparse_patterns_from_proto(proto.sparse_patterns.as_ref());
+        self.sparse_profile = sparse_profile_from_proto(proto.sparse_patterns.as_ref());
+        self.force_tracked_paths = proto
+            .force_tracked_paths
+            .iter()
+            .map(|path| RepoPathBuf::from_internal_string(path).unwrap())
+            .collect();
         self.watchman_clock = proto.watchman_clock;
+        self.inotify_clock = proto.inotify_clock;
         Ok(())
     }
 
@@ -879,8 +1037,20 @@ impl TreeState {
                 .prefixes
                 .push(path.as_internal_file_string().to_owned());
         }
+        sparse_patterns.profile = self.sparse_profile.as_ref().map(|profile| {
+            crate::protos::working_copy::SparseProfile {
+                name: profile.name.clone(),
+                content: profile.content.clone(),
+            }
+        });
         proto.sparse_patterns = Some(sparse_patterns);
+        proto.force_tracked_paths = self
+            .force_tracked_paths
+            .iter()
+            .map(|path| path.as_internal_file_string().to_owned())
+            .collect();
         proto.watchman_clock = self.watchman_clock.clone();
+        proto.inotify_clock = self.inotify_clock.clone();
 
         let mut temp_file = NamedTempFile::new_in(&self.state_path).unwrap();
         temp_file
@@ -915,6 +1085,33 @@ impl TreeState {
         self.watchman_clock.take();
     }
 
+    fn reset_inotify(&mut self) {
+        self.inotify_clock.take();
+    }
+
+    /// Queries the inotify daemon for the files changed since the last
+    /// snapshot. Starts the daemon if it isn't running.
+    #[cfg(target_os = "linux")]
+    #[instrument(skip(self))]
+    pub fn query_inotify(
+        &self,
+        config: &InotifyConfig,
+    ) -> Result<(inotify::Clock, Option<Vec<PathBuf>>), TreeStateError> {
+        let fsmonitor = inotify::Fsmonitor::new(&self.state_path);
+        let previous_clock = self.inotify_clock.clone().map(inotify::Clock::new);
+        match fsmonitor.query_changed_files(previous_clock.as_ref()) {
+            Ok(changed_files) => Ok(changed_files),
+            Err(err @ inotify::Error::NotRunning(_)) => {
+                // The next command will be able to use the daemon.
+                fsmonitor
+                    .spawn_daemon(&config.daemon_command, &self.working_copy_path)
+                    .map_err(|err| TreeStateError::Fsmonitor(Box::new(err)))?;
+                Err(TreeStateError::Fsmonitor(Box::new(err)))
+            }
+            Err(err) => Err(TreeStateError::Fsmonitor(Box::new(err))),
+        }
+    }
+
     #[cfg(feature = "watchman")]
     #[tokio::main(flavor = "current_thread")]
     #[instrument(skip(self))]
@@ -965,16 +1162,25 @@ impl TreeState {
             progress,
             start_tracking_matcher,
             max_new_file_size,
+            new_file_size_limits,
+            ignore_by_default_matcher,
+            untrack_binaries,
+            force_tracking_matcher,
             conflict_marker_style,
+            ref conflict_marker_style_overrides,
         } = options;
 
-        let sparse_matcher = self.sparse_matcher();
+        let sparse_matcher = self.sparse_matcher().map_err(|err| SnapshotError::Other {
+            message: "Failed to load the sparse profile".to_string(),
+            err: err.into(),
+        })?;
 
         let fsmonitor_clock_needs_save = *fsmonitor_settings != FsmonitorSettings::None;
         let mut is_dirty = fsmonitor_clock_needs_save;
         let FsmonitorMatcher {
             matcher: fsmonitor_matcher,
             watchman_clock,
+            inotify_clock,
         } = self.make_fsmonitor_matcher(fsmonitor_settings)?;
         let fsmonitor_matcher = match fsmonitor_matcher.as_ref() {
             None => &EverythingMatcher,
@@ -985,12 +1191,16 @@ impl TreeState {
         if matcher.visit(RepoPath::root()).is_nothing() {
             // No need to load the current tree, set up channels, etc.
             self.watchman_clock = watchman_clock;
+            self.inotify_clock = inotify_clock;
             return Ok((is_dirty, SnapshotStats::default()));
         }
 
+        let conflict_marker_style_matcher =
+            conflict_marker_style_overrides.to_style_matcher(conflict_marker_style);
         let (tree_entries_tx, tree_entries_rx) = channel();
         let (file_states_tx, file_states_rx) = channel();
         let (untracked_paths_tx, untracked_paths_rx) = channel();
+        let (force_tracked_paths_tx, force_tracked_paths_rx) = channel();
         let (deleted_files_tx, deleted_files_rx) = channel();
 
         trace_span!("traverse filesystem").in_scope(|| -> Result<(), SnapshotError> {
@@ -1003,11 +1213,18 @@ impl TreeState {
                 tree_entries_tx,
                 file_states_tx,
                 untracked_paths_tx,
+                force_tracked_paths_tx,
                 deleted_files_tx,
                 error: OnceLock::new(),
                 progress,
                 max_new_file_size,
-                conflict_marker_style,
+                new_file_size_limits,
+                ignore_by_default_matcher,
+                untrack_binaries,
+                force_tracking_matcher,
+                conflict_marker_style: &conflict_marker_style_matcher,
+                #[cfg(feature = "git")]
+                lfs_filter: LfsFilter::load_from_disk(&self.store, &self.working_copy_path)?,
             };
             let directory_to_visit = DirectoryToVisit {
                 dir: RepoPathBuf::root(),
@@ -1027,6 +1244,9 @@ impl TreeState {
         let stats = SnapshotStats {
             untracked_paths: untracked_paths_rx.into_iter().collect(),
         };
+        for path in force_tracked_paths_rx {
+            is_dirty |= self.force_tracked_paths.insert(path);
+        }
         let mut tree_builder = MergedTreeBuilder::new(self.tree_id.clone());
         trace_span!("process tree entries").in_scope(|| {
             for (path, tree_values) in &tree_entries_rx {
@@ -1073,6 +1293,11 @@ impl TreeState {
         } else {
             tracing::info!("not updating watchman clock because there are untracked files");
         }
+        if stats.untracked_paths.is_empty() || inotify_clock.is_none() {
+            self.inotify_clock = inotify_clock;
+        } else {
+            tracing::info!("not updating inotify clock because there are untracked files");
+        }
         Ok((is_dirty, stats))
     }
 
@@ -1092,6 +1317,37 @@ impl TreeState {
                     (None, None)
                 }
             },
+            #[cfg(target_os = "linux")]
+            FsmonitorSettings::Inotify(config) => match self.query_inotify(config) {
+                Ok((clock, changed_files)) => {
+                    // Changed directories are reported as a whole.
+                    let matcher = changed_files.map(|changed_files| -> Box<dyn Matcher> {
+                        let repo_paths = changed_files
+                            .into_iter()
+                            .filter_map(|path| RepoPathBuf::from_relative_path(path).ok())
+                            .collect_vec();
+                        Box::new(PrefixMatcher::new(repo_paths))
+                    });
+                    return Ok(FsmonitorMatcher {
+                        matcher,
+                        watchman_clock: None,
+                        inotify_clock: Some(clock.as_str().to_owned()),
+                    });
+                }
+                Err(err) => {
+                    tracing::warn!(?err, "Failed to query filesystem monitor");
+                    (None, None)
+                }
+            },
+            #[cfg(not(target_os = "linux"))]
+            FsmonitorSettings::Inotify(_) => {
+                return Err(SnapshotError::Other {
+                    message: "Failed to query the filesystem monitor".to_string(),
+                    err: "The inotify filesystem monitor is only supported on Linux (consider \
+                          disabling `core.fsmonitor`)"
+                        .into(),
+                });
+            }
             #[cfg(not(feature = "watchman"))]
             FsmonitorSettings::Watchman(_) => {
                 return Err(SnapshotError::Other {
@@ -1118,6 +1374,7 @@ impl TreeState {
         Ok(FsmonitorMatcher {
             matcher,
             watchman_clock,
+            inotify_clock: None,
         })
     }
 }
@@ -1150,11 +1407,18 @@ struct FileSnapshotter<'a> {
     tree_entries_tx: Sender<(RepoPathBuf, MergedTreeValue)>,
     file_states_tx: Sender<(RepoPathBuf, FileState)>,
     untracked_paths_tx: Sender<(RepoPathBuf, UntrackedReason)>,
+    force_tracked_paths_tx: Sender<RepoPathBuf>,
     deleted_files_tx: Sender<RepoPathBuf>,
     error: OnceLock<SnapshotError>,
     progress: Option<&'a SnapshotProgress<'a>>,
     max_new_file_size: u64,
-    conflict_marker_style: ConflictMarkerStyle,
+    new_file_size_limits: &'a [(Box<dyn Matcher>, u64)],
+    ignore_by_default_matcher: &'a dyn Matcher,
+    untrack_binaries: bool,
+    force_tracking_matcher: &'a dyn Matcher,
+    conflict_marker_style: &'a ConflictMarkerStyleMatcher,
+    #[cfg(feature = "git")]
+    lfs_filter: Option<LfsFilter>,
 }
 
 impl FileSnapshotter<'_> {
@@ -1246,6 +1510,9 @@ impl FileSnapshotter<'_> {
         let maybe_current_file_state = file_states.get_at(dir, name);
         if let Some(file_state) = &maybe_current_file_state {
             if file_state.file_type == FileType::GitSubmodule {
+                if self.matcher.matches(&path) {
+                    self.process_git_submodule(&path, file_state)?;
+                }
                 return Ok(None);
             }
         }
@@ -1296,12 +1563,12 @@ impl FileSnapshotter<'_> {
                     message: format!("Failed to stat file {}", entry.path().display()),
                     err: err.into(),
                 })?;
-                if maybe_current_file_state.is_none() && metadata.len() > self.max_new_file_size {
-                    // Leave the large file untracked
-                    let reason = UntrackedReason::FileTooLarge {
-                        size: metadata.len(),
-                        max_size: self.max_new_file_size,
-                    };
+                let untracked_reason = if maybe_current_file_state.is_none() {
+                    self.check_new_file(&path, &entry.path(), &metadata)?
+                } else {
+                    None
+                };
+                if let Some(reason) = untracked_reason {
                     self.untracked_paths_tx.send((path, reason)).ok();
                     Ok(None)
                 } else if let Some(new_file_state) = file_state(&metadata) {
@@ -1322,13 +1589,60 @@ impl FileSnapshotter<'_> {
         }
     }
 
+    /// Returns the reason to leave the new file untracked, if any. Files that
+    /// match `force_tracking_matcher` are sent to `force_tracked_paths_tx` so
+    /// they're recorded in the tree state, and recorded files are tracked
+    /// regardless of the reason.
+    fn check_new_file(
+        &self,
+        path: &RepoPath,
+        disk_path: &Path,
+        metadata: &Metadata,
+    ) -> Result<Option<UntrackedReason>, SnapshotError> {
+        let reason = if self.ignore_by_default_matcher.matches(path) {
+            Some(UntrackedReason::IgnoredByDefault)
+        } else {
+            let max_size = self
+                .new_file_size_limits
+                .iter()
+                .filter(|(matcher, _)| matcher.matches(path))
+                .map(|(_, max_size)| *max_size)
+                .max()
+                .unwrap_or(self.max_new_file_size);
+            if metadata.len() > max_size {
+                Some(UntrackedReason::FileTooLarge {
+                    size: metadata.len(),
+                    max_size,
+                })
+            } else if self.untrack_binaries && metadata.is_file() {
+                let format =
+                    detect_generated_binary(disk_path).map_err(|err| SnapshotError::Other {
+                        message: format!("Failed to read file {}", disk_path.display()),
+                        err: err.into(),
+                    })?;
+                format.map(|format| UntrackedReason::GeneratedBinary { format })
+            } else {
+                None
+            }
+        };
+        if reason.is_none() || self.tree_state.force_tracked_paths.contains(path) {
+            Ok(None)
+        } else if self.force_tracking_matcher.matches(path) {
+            self.force_tracked_paths_tx.send(path.to_owned()).ok();
+            Ok(None)
+        } else {
+            Ok(reason)
+        }
+    }
+
     /// Visits only paths we're already tracking.
     fn visit_tracked_files(&self, file_states: FileStates<'_>) -> Result<(), SnapshotError> {
         for (tracked_path, current_file_state) in file_states {
-            if current_file_state.file_type == FileType::GitSubmodule {
+            if !self.matcher.matches(tracked_path) {
                 continue;
             }
-            if !self.matcher.matches(tracked_path) {
+            if current_file_state.file_type == FileType::GitSubmodule {
+                self.process_git_submodule(tracked_path, &current_file_state)?;
                 continue;
             }
             let disk_path = tracked_path.to_fs_path(&self.tree_state.working_copy_path)?;
@@ -1385,6 +1699,39 @@ impl FileSnapshotter<'_> {
         Ok(())
     }
 
+    /// Records the commit checked out in the Git submodule at `path` if it
+    /// was moved since the working copy was updated.
+    ///
+    /// The submodule isn't necessarily updated when the working copy is, so
+    /// a commit which is still checked out since then may be outdated and
+    /// isn't recorded.
+    fn process_git_submodule(
+        &self,
+        path: &RepoPath,
+        current_file_state: &FileState,
+    ) -> Result<(), SnapshotError> {
+        let disk_path = path.to_fs_path(&self.tree_state.working_copy_path)?;
+        // The submodule may not have been checked out yet.
+        let Some(head_id) = read_git_submodule_head(&disk_path) else {
+            return Ok(());
+        };
+        if current_file_state.git_submodule_head.as_ref() == Some(&head_id) {
+            return Ok(());
+        }
+        let current_tree_values = self.current_tree.path_value(path)?;
+        let new_tree_values = Merge::normal(TreeValue::GitSubmodule(head_id.clone()));
+        if new_tree_values != current_tree_values {
+            self.tree_entries_tx
+                .send((path.to_owned(), new_tree_values))
+                .ok();
+        }
+        let new_file_state = FileState::for_gitsubmodule(Some(head_id));
+        self.file_states_tx
+            .send((path.to_owned(), new_file_state))
+            .ok();
+        Ok(())
+    }
+
     /// Emits file paths that don't exist in the `present_entries`.
     fn emit_deleted_files(
         &self,
@@ -1518,7 +1865,7 @@ impl FileSnapshotter<'_> {
                 self.store(),
                 repo_path,
                 &content,
-                self.conflict_marker_style,
+                self.conflict_marker_style.style_for(repo_path),
                 materialized_conflict_data.map_or(MIN_CONFLICT_MARKER_LEN, |data| {
                     data.conflict_marker_len as usize
                 }),
@@ -1563,6 +1910,19 @@ impl FileSnapshotter<'_> {
             message: format!("Failed to open file {}", disk_path.display()),
             err: err.into(),
         })?;
+        #[cfg(feature = "git")]
+        if let Some(lfs_filter) = self.lfs_filter.as_ref().filter(|f| f.is_tracked(path)) {
+            let pointer = lfs_filter
+                .clean(&mut file)
+                .map_err(|err| SnapshotError::Other {
+                    message: format!("Failed to store file {} in Git LFS", disk_path.display()),
+                    err: err.into(),
+                })?;
+            return Ok(self
+                .store()
+                .write_file(path, &mut pointer.as_slice())
+                .await?);
+        }
         Ok(self.store().write_file(path, &mut file).await?)
     }
 
@@ -1699,13 +2059,12 @@ impl TreeState {
             },
             other => CheckoutError::InternalBackendError(other),
         })?;
+        let matcher = self.sparse_matcher().map_err(|err| CheckoutError::Other {
+            message: "Failed to load the sparse profile".to_string(),
+            err: err.into(),
+        })?;
         let stats = self
-            .update(
-                &old_tree,
-                new_tree,
-                self.sparse_matcher().as_ref(),
-                options.conflict_marker_style,
-            )
+            .update(&old_tree, new_tree, matcher.as_ref(), options)
             .block_on()?;
         self.tree_id = new_tree.id();
         Ok(stats)
@@ -1715,6 +2074,25 @@ impl TreeState {
         &mut self,
         sparse_patterns: Vec<RepoPathBuf>,
         options: &CheckoutOptions,
+    ) -> Result<CheckoutStats, CheckoutError> {
+        let sparse_profile = self.sparse_profile.clone();
+        self.update_sparsity(sparse_patterns, sparse_profile, options)
+    }
+
+    pub fn set_sparse_profile(
+        &mut self,
+        sparse_profile: Option<SparseProfile>,
+        options: &CheckoutOptions,
+    ) -> Result<CheckoutStats, CheckoutError> {
+        let sparse_patterns = self.sparse_patterns.clone();
+        self.update_sparsity(sparse_patterns, sparse_profile, options)
+    }
+
+    fn update_sparsity(
+        &mut self,
+        sparse_patterns: Vec<RepoPathBuf>,
+        sparse_profile: Option<SparseProfile>,
+        options: &CheckoutOptions,
     ) -> Result<CheckoutStats, CheckoutError> {
         let tree = self.current_tree().map_err(|err| match err {
             err @ BackendError::ObjectNotFound { .. } => CheckoutError::SourceNotFound {
@@ -1722,28 +2100,29 @@ impl TreeState {
             },
             other => CheckoutError::InternalBackendError(other),
         })?;
-        let old_matcher = PrefixMatcher::new(&self.sparse_patterns);
-        let new_matcher = PrefixMatcher::new(&sparse_patterns);
+        // If the current profile can no longer be parsed, the paths that are
+        // checked out are still known, so it can be replaced.
+        let old_matcher = self
+            .sparse_matcher()
+            .unwrap_or_else(|_| Box::new(FilesMatcher::new(self.file_states.all().paths())));
+        let new_matcher =
+            sparse_matcher(&sparse_patterns, sparse_profile.as_ref()).map_err(|err| {
+                CheckoutError::Other {
+                    message: "Failed to load the sparse profile".to_string(),
+                    err: err.into(),
+                }
+            })?;
         let added_matcher = DifferenceMatcher::new(&new_matcher, &old_matcher);
         let removed_matcher = DifferenceMatcher::new(&old_matcher, &new_matcher);
         let empty_tree = MergedTree::resolved(Tree::empty(self.store.clone(), RepoPathBuf::root()));
         let added_stats = self
-            .update(
-                &empty_tree,
-                &tree,
-                &added_matcher,
-                options.conflict_marker_style,
-            )
+            .update(&empty_tree, &tree, &added_matcher, options)
             .block_on()?;
         let removed_stats = self
-            .update(
-                &tree,
-                &empty_tree,
-                &removed_matcher,
-                options.conflict_marker_style,
-            )
+            .update(&tree, &empty_tree, &removed_matcher, options)
             .block_on()?;
         self.sparse_patterns = sparse_patterns;
+        self.sparse_profile = sparse_profile;
         assert_eq!(added_stats.updated_files, 0);
         assert_eq!(added_stats.removed_files, 0);
         assert_eq!(removed_stats.updated_files, 0);
@@ -1762,7 +2141,7 @@ impl TreeState {
         old_tree: &MergedTree,
         new_tree: &MergedTree,
         matcher: &dyn Matcher,
-        conflict_marker_style: ConflictMarkerStyle,
+        options: &CheckoutOptions,
     ) -> Result<CheckoutStats, CheckoutError> {
         // TODO: maybe it's better not include the skipped counts in the "intended"
         // counts
@@ -1774,6 +2153,11 @@ impl TreeState {
         };
         let mut changed_file_states = Vec::new();
         let mut deleted_files = HashSet::new();
+        #[cfg(feature = "git")]
+        let lfs_filter = LfsFilter::load_from_tree(new_tree)?;
+        let conflict_marker_style_matcher = options
+            .conflict_marker_style_overrides
+            .to_style_matcher(options.conflict_marker_style);
         let mut diff_stream = old_tree
             .diff_stream(new_tree, matcher)
             .map(|TreeDiffEntry { path, values }| async {
@@ -1806,9 +2190,18 @@ impl TreeState {
             if matches!(before.as_normal(), Some(TreeValue::GitSubmodule(_)))
                 && matches!(after, MaterializedTreeValue::GitSubmodule(_))
             {
-                eprintln!("ignoring git submodule at {path:?}");
                 // Not updating the file state as if there were no diffs. Leave
                 // the state type as FileType::GitSubmodule if it was before.
+                // The submodule itself is checked out by the SubmoduleStore,
+                // so remember the commit it's at now to tell whether it's
+                // moved later.
+                if let Some(file_state) = self.file_states.all().get(&path) {
+                    if file_state.file_type == FileType::GitSubmodule {
+                        let disk_path = path.to_fs_path_unchecked(&self.working_copy_path);
+                        let head_id = read_git_submodule_head(&disk_path);
+                        changed_file_states.push((path, FileState::for_gitsubmodule(head_id)));
+                    }
+                }
                 continue;
             }
 
@@ -1842,7 +2235,16 @@ impl TreeState {
                     continue;
                 }
                 MaterializedTreeValue::File(mut file) => {
-                    self.write_file(&disk_path, &mut file.reader, file.executable)?
+                    #[cfg(feature = "git")]
+                    let mut reader = smudge_lfs_pointer(
+                        lfs_filter.as_ref(),
+                        &path,
+                        &disk_path,
+                        &mut file.reader,
+                    )?;
+                    #[cfg(not(feature = "git"))]
+                    let mut reader = file.reader;
+                    self.write_file(&disk_path, &mut reader, file.executable)?
                 }
                 MaterializedTreeValue::Symlink { id: _, target } => {
                     if self.symlink_support {
@@ -1852,8 +2254,7 @@ impl TreeState {
                     }
                 }
                 MaterializedTreeValue::GitSubmodule(_) => {
-                    eprintln!("ignoring git submodule at {path:?}");
-                    FileState::for_gitsubmodule()
+                    FileState::for_gitsubmodule(read_git_submodule_head(&disk_path))
                 }
                 MaterializedTreeValue::Tree(_) => {
                     panic!("unexpected tree entry in diff at {path:?}");
@@ -1861,6 +2262,7 @@ impl TreeState {
                 MaterializedTreeValue::FileConflict(file) => {
                     let conflict_marker_len =
                         choose_materialized_conflict_marker_len(&file.contents);
+                    let conflict_marker_style = conflict_marker_style_matcher.style_for(&path);
                     let data = materialize_merge_result_to_bytes_with_marker_len(
                         &file.contents,
                         conflict_marker_style,
@@ -1900,7 +2302,10 @@ impl TreeState {
             other => ResetError::InternalBackendError(other),
         })?;
 
-        let matcher = self.sparse_matcher();
+        let matcher = self.sparse_matcher().map_err(|err| ResetError::Other {
+            message: "Failed to load the sparse profile".to_string(),
+            err: err.into(),
+        })?;
         let mut changed_file_states = Vec::new();
         let mut deleted_files = HashSet::new();
         let mut diff_stream = old_tree.diff_stream(new_tree, matcher.as_ref());
@@ -1919,10 +2324,7 @@ impl TreeState {
                         TreeValue::Conflict(_id) => {
                             panic!("unexpected conflict entry in diff at {path:?}");
                         }
-                        TreeValue::GitSubmodule(_id) => {
-                            eprintln!("ignoring git submodule at {path:?}");
-                            FileType::GitSubmodule
-                        }
+                        TreeValue::GitSubmodule(_id) => FileType::GitSubmodule,
                         TreeValue::Tree(_id) => {
                             panic!("unexpected tree entry in diff at {path:?}");
                         }
@@ -1939,12 +2341,17 @@ impl TreeState {
                     mtime: MillisSinceEpoch(0),
                     size: 0,
                     materialized_conflict_data: None,
+                    git_submodule_head: None,
                 };
                 changed_file_states.push((path, file_state));
             }
         }
         self.file_states
             .merge_in(changed_file_states, &deleted_files);
+        // Forced files that are no longer tracked shouldn't be tracked again
+        // when the working copy is snapshotted.
+        self.force_tracked_paths
+            .retain(|path| !deleted_files.contains(path));
         self.tree_id = new_tree.id();
         Ok(())
     }
@@ -2003,6 +2410,10 @@ impl WorkingCopy for LocalWorkingCopy {
         Ok(self.tree_state()?.sparse_patterns())
     }
 
+    fn sparse_profile(&self) -> Result<Option<&SparseProfile>, WorkingCopyStateError> {
+        Ok(self.tree_state()?.sparse_profile())
+    }
+
     fn start_mutation(&self) -> Result<Box<dyn LockedWorkingCopy>, WorkingCopyStateError> {
         let lock_path = self.state_path.join("working_copy.lock");
         let lock = FileLock::lock(lock_path).map_err(|err| WorkingCopyStateError {
@@ -2150,6 +2561,11 @@ impl LocalWorkingCopy {
         Ok(self.tree_state()?.file_states())
     }
 
+    /// Paths that were tracked with `SnapshotOptions::force_tracking_matcher`.
+    pub fn force_tracked_paths(&self) -> Result<&BTreeSet<RepoPathBuf>, WorkingCopyStateError> {
+        Ok(self.tree_state()?.force_tracked_paths())
+    }
+
     #[instrument(skip_all)]
     fn save(&mut self) {
         self.write_proto(crate::protos::working_copy::Checkout {
@@ -2171,6 +2587,19 @@ impl LocalWorkingCopy {
             })
     }
 
+    #[cfg(target_os = "linux")]
+    pub fn query_inotify(
+        &self,
+        config: &InotifyConfig,
+    ) -> Result<(inotify::Clock, Option<Vec<PathBuf>>), WorkingCopyStateError> {
+        self.tree_state()?
+            .query_inotify(config)
+            .map_err(|err| WorkingCopyStateError {
+                message: "Failed to query the inotify daemon".to_string(),
+                err: err.into(),
+            })
+    }
+
     #[cfg(feature = "watchman")]
     pub fn is_watchman_trigger_registered(
         &self,
@@ -2324,6 +2753,10 @@ impl LockedWorkingCopy for LockedLocalWorkingCopy {
         self.wc.sparse_patterns()
     }
 
+    fn sparse_profile(&self) -> Result<Option<&SparseProfile>, WorkingCopyStateError> {
+        self.wc.sparse_profile()
+    }
+
     fn set_sparse_patterns(
         &mut self,
         new_sparse_patterns: Vec<RepoPathBuf>,
@@ -2343,6 +2776,23 @@ impl LockedWorkingCopy for LockedLocalWorkingCopy {
         Ok(stats)
     }
 
+    fn set_sparse_profile(
+        &mut self,
+        new_sparse_profile: Option<SparseProfile>,
+        options: &CheckoutOptions,
+    ) -> Result<CheckoutStats, CheckoutError> {
+        let stats = self
+            .wc
+            .tree_state_mut()
+            .map_err(|err| CheckoutError::Other {
+                message: "Failed to load the working copy state".to_string(),
+                err: err.into(),
+            })?
+            .set_sparse_profile(new_sparse_profile, options)?;
+        self.tree_state_dirty = true;
+        Ok(stats)
+    }
+
     #[instrument(skip_all)]
     fn finish(
         mut self: Box<Self>,
@@ -2382,6 +2832,18 @@ impl LockedLocalWorkingCopy {
         self.tree_state_dirty = true;
         Ok(())
     }
+
+    pub fn reset_inotify(&mut self) -> Result<(), SnapshotError> {
+        self.wc
+            .tree_state_mut()
+            .map_err(|err| SnapshotError::Other {
+                message: "Failed to read the working copy state".to_string(),
+                err: err.into(),
+            })?
+            .reset_inotify();
+        self.tree_state_dirty = true;
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -2407,6 +2869,7 @@ mod tests {
             mtime: MillisSinceEpoch(0),
             size,
             materialized_conflict_data: None,
+            git_submodule_head: None,
         };
         let new_static_entry = |path: &'static str, size| (repo_path(path), new_state(size));
         let new_owned_entry = |path: &str, size| (repo_path(path).to_owned(), new_state(size));
@@ -2456,6 +2919,7 @@ mod tests {
             mtime: MillisSinceEpoch(0),
             size,
             materialized_conflict_data: None,
+            git_submodule_head: None,
         };
         let new_proto_entry = |path: &str, size| {
             file_state_entry_to_proto(repo_path(path).to_owned(), &new_state(size))
@@ -2521,6 +2985,7 @@ mod tests {
             mtime: MillisSinceEpoch(0),
             size,
             materialized_conflict_data: None,
+            git_submodule_head: None,
         };
         let new_proto_entry = |path: &str, size| {
             file_state_entry_to_proto(repo_path(path).to_owned(), &new_state(size))
@@ -23,8 +23,10 @@ use std::iter;
 use itertools::Itertools as _;
 use tracing::instrument;
 
+use crate::merge::MergedTreeVal;
 use crate::repo_path::RepoPath;
 use crate::repo_path::RepoPathComponentBuf;
+use crate::store::Store;
 
 #[derive(PartialEq, Eq, Debug)]
 pub enum Visit {
@@ -73,8 +75,24 @@ pub enum VisitFiles {
 }
 
 pub trait Matcher: Debug + Sync {
+    /// Returns true if the file at `file` matches by path.
+    ///
+    /// Matchers on file values can't tell by path, and return false. Callers
+    /// that don't know file values must not use such matchers, which is
+    /// checked by `FilesetExpression::to_path_matcher()`.
     fn matches(&self, file: &RepoPath) -> bool;
     fn visit(&self, dir: &RepoPath) -> Visit;
+
+    /// Returns true if the file at `file` with the given `value` matches.
+    ///
+    /// This is the same as `matches()` for matchers that only look at paths.
+    /// Matchers that also look at file values (such as `FileValueMatcher`)
+    /// never match by path alone, so callers that know the value of the file
+    /// should use this instead of `matches()`.
+    fn matches_value(&self, store: &Store, file: &RepoPath, value: &MergedTreeVal) -> bool {
+        let _ = (store, value);
+        self.matches(file)
+    }
 }
 
 impl<T: Matcher + ?Sized> Matcher for &T {
@@ -85,6 +103,10 @@ impl<T: Matcher + ?Sized> Matcher for &T {
     fn visit(&self, dir: &RepoPath) -> Visit {
         <T as Matcher>::visit(self, dir)
     }
+
+    fn matches_value(&self, store: &Store, file: &RepoPath, value: &MergedTreeVal) -> bool {
+        <T as Matcher>::matches_value(self, store, file, value)
+    }
 }
 
 impl<T: Matcher + ?Sized> Matcher for Box<T> {
@@ -95,6 +117,10 @@ impl<T: Matcher + ?Sized> Matcher for Box<T> {
     fn visit(&self, dir: &RepoPath) -> Visit {
         <T as Matcher>::visit(self, dir)
     }
+
+    fn matches_value(&self, store: &Store, file: &RepoPath, value: &MergedTreeVal) -> bool {
+        <T as Matcher>::matches_value(self, store, file, value)
+    }
 }
 
 #[derive(PartialEq, Eq, Debug)]
@@ -297,6 +323,48 @@ impl Matcher for FileGlobsMatcher {
     }
 }
 
+/// Predicate on the value of a file, such as whether it's executable.
+pub trait FileValuePredicate: Debug + Send + Sync {
+    /// Returns true if the `value` at `file` matches. The file content can be
+    /// read from the `store` if needed.
+    fn matches(&self, store: &Store, file: &RepoPath, value: &MergedTreeVal) -> bool;
+}
+
+/// Matches files whose value satisfies the predicate, regardless of the path.
+///
+/// Since the value isn't known from the path alone, `matches()` always returns
+/// false. Use `matches_value()` instead. Consumers that only know paths should
+/// reject such matchers up front (see `FilesetExpression::to_path_matcher()`)
+/// rather than silently matching nothing.
+#[derive(Debug)]
+pub struct FileValueMatcher<P> {
+    predicate: P,
+}
+
+impl<P: FileValuePredicate> FileValueMatcher<P> {
+    pub fn new(predicate: P) -> Self {
+        FileValueMatcher { predicate }
+    }
+}
+
+impl<P: FileValuePredicate> Matcher for FileValueMatcher<P> {
+    fn matches(&self, _file: &RepoPath) -> bool {
+        false
+    }
+
+    fn visit(&self, _dir: &RepoPath) -> Visit {
+        // Any file might match, but don't claim that all of them do.
+        Visit::Specific {
+            dirs: VisitDirs::All,
+            files: VisitFiles::All,
+        }
+    }
+
+    fn matches_value(&self, store: &Store, file: &RepoPath, value: &MergedTreeVal) -> bool {
+        self.predicate.matches(store, file, value)
+    }
+}
+
 /// Matches paths that are matched by any of the input matchers.
 #[derive(Clone, Debug)]
 pub struct UnionMatcher<M1, M2> {
@@ -315,6 +383,11 @@ impl<M1: Matcher, M2: Matcher> Matcher for UnionMatcher<M1, M2> {
         self.input1.matches(file) || self.input2.matches(file)
     }
 
+    fn matches_value(&self, store: &Store, file: &RepoPath, value: &MergedTreeVal) -> bool {
+        self.input1.matches_value(store, file, value)
+            || self.input2.matches_value(store, file, value)
+    }
+
     fn visit(&self, dir: &RepoPath) -> Visit {
         match self.input1.visit(dir) {
             Visit::AllRecursively => Visit::AllRecursively,
@@ -372,6 +445,11 @@ impl<M1: Matcher, M2: Matcher> Matcher for DifferenceMatcher<M1, M2> {
         self.wanted.matches(file) && !self.unwanted.matches(file)
     }
 
+    fn matches_value(&self, store: &Store, file: &RepoPath, value: &MergedTreeVal) -> bool {
+        self.wanted.matches_value(store, file, value)
+            && !self.unwanted.matches_value(store, file, value)
+    }
+
     fn visit(&self, dir: &RepoPath) -> Visit {
         match self.unwanted.visit(dir) {
             Visit::AllRecursively => Visit::Nothing,
@@ -405,6 +483,11 @@ impl<M1: Matcher, M2: Matcher> Matcher for IntersectionMatcher<M1, M2> {
         self.input1.matches(file) && self.input2.matches(file)
     }
 
+    fn matches_value(&self, store: &Store, file: &RepoPath, value: &MergedTreeVal) -> bool {
+        self.input1.matches_value(store, file, value)
+            && self.input2.matches_value(store, file, value)
+    }
+
     fn visit(&self, dir: &RepoPath) -> Visit {
         match self.input1.visit(dir) {
             Visit::AllRecursively => self.input2.visit(dir),
@@ -560,6 +560,7 @@ struct TreeEntriesDirItem {
 impl TreeEntriesDirItem {
     fn new(trees: &Merge<Tree>, matcher: &dyn Matcher) -> Self {
         let mut entries = vec![];
+        let store = trees.first().store();
         let dir = trees.first().dir();
         for (name, value) in all_tree_entries(trees) {
             let path = dir.join(name);
@@ -568,7 +569,7 @@ impl TreeEntriesDirItem {
                 if matcher.visit(&path).is_nothing() {
                     continue;
                 }
-            } else if !matcher.matches(&path) {
+            } else if !matcher.matches_value(store, &path, &value) {
                 continue;
             }
             entries.push((path, value.cloned()));
@@ -731,6 +732,21 @@ impl<'matcher> TreeDiffIterator<'matcher> {
     }
 }
 
+/// Returns true if the file on either side of a diff entry is matched. Sides
+/// that are absent or trees are ignored.
+fn diff_file_matches<'a>(
+    matcher: &dyn Matcher,
+    store: &Store,
+    path: &RepoPath,
+    before: &MergedTreeVal<'a>,
+    after: &MergedTreeVal<'a>,
+) -> bool {
+    [before, after]
+        .into_iter()
+        .filter(|value| value.is_present() && !value.is_tree())
+        .any(|value| matcher.matches_value(store, path, value))
+}
+
 impl TreeDiffDirItem {
     fn from_trees(
         dir: &RepoPath,
@@ -739,6 +755,7 @@ impl TreeDiffDirItem {
         matcher: &dyn Matcher,
     ) -> Self {
         let mut entries = vec![];
+        let store = trees1.first().store();
         for (name, before, after) in merged_tree_entry_diff(trees1, trees2) {
             let path = dir.join(name);
             let tree_before = before.is_tree();
@@ -746,7 +763,8 @@ impl TreeDiffDirItem {
             // Check if trees and files match, but only if either side is a tree or a file
             // (don't query the matcher unnecessarily).
             let tree_matches = (tree_before || tree_after) && !matcher.visit(&path).is_nothing();
-            let file_matches = (!tree_before || !tree_after) && matcher.matches(&path);
+            let file_matches = (!tree_before || !tree_after)
+                && diff_file_matches(matcher, store, &path, &before, &after);
 
             // Replace trees or files that don't match by `Merge::absent()`
             let before = if (tree_before && tree_matches) || (!tree_before && file_matches) {
@@ -975,7 +993,8 @@ impl<'matcher> TreeDiffStreamImpl<'matcher> {
             // (don't query the matcher unnecessarily).
             let tree_matches =
                 (tree_before || tree_after) && !self.matcher.visit(&path).is_nothing();
-            let file_matches = (!tree_before || !tree_after) && self.matcher.matches(&path);
+            let file_matches = (!tree_before || !tree_after)
+                && diff_file_matches(self.matcher, &self.store, &path, &before, &after);
 
             // Replace trees or files that don't match by `Merge::absent()`
             let before = if (tree_before && tree_matches) || (!tree_before && file_matches) {
@@ -28,8 +28,10 @@ use thiserror::Error;
 
 use crate::backend::CommitId;
 use crate::backend::MillisSinceEpoch;
+use crate::backend::Signature;
 use crate::backend::Timestamp;
 use crate::content_hash::ContentHash;
+use crate::content_hash::DigestUpdate;
 use crate::merge::Merge;
 use crate::object_id::id_type;
 use crate::object_id::HexPrefix;
@@ -244,7 +246,7 @@ pub struct BookmarkTarget<'a> {
 
 /// Represents the way the repo looks at a given time, just like how a Tree
 /// object represents how the file system looks at a given time.
-#[derive(ContentHash, PartialEq, Eq, Clone, Debug)]
+#[derive(PartialEq, Eq, Clone, Debug)]
 pub struct View {
     /// All head commits
     pub head_ids: HashSet<CommitId>,
@@ -260,6 +262,56 @@ pub struct View {
     // (.jj/working_copy/) has the source of truth about which commit *is* checked out (to be
     // precise: the commit to which we most recently completed an update to).
     pub wc_commit_ids: BTreeMap<WorkspaceNameBuf, CommitId>,
+    /// State of the in-progress bisection, if any.
+    pub bisect: Option<BisectState>,
+    /// Annotations of tags which haven't been exported to Git yet.
+    pub tag_annotations: BTreeMap<RefNameBuf, TagAnnotation>,
+}
+
+/// Marks the hash of views with tag annotations.
+const TAG_ANNOTATIONS_TAG: [u8; 8] = u64::MAX.to_le_bytes();
+
+// Implemented manually instead of derived. A derived implementation would hash
+// the `bisect` and `tag_annotations` fields even if they're unset, which would
+// change the id of every view, so the same view would be written under
+// different ids by jj versions before and after these fields were added. The
+// fields are therefore only hashed if they're set, and views with tag
+// annotations are marked by `TAG_ANNOTATIONS_TAG` so that their hash can't
+// collide with the hash of a view that only has a bisection state. Fields added
+// in the future need to be hashed the same way. `test_hash_view` in
+// `simple_op_store` pins the resulting hashes.
+impl ContentHash for View {
+    fn hash(&self, state: &mut impl DigestUpdate) {
+        let View {
+            head_ids,
+            local_bookmarks,
+            tags,
+            remote_views,
+            git_refs,
+            git_head,
+            wc_commit_ids,
+            bisect,
+            tag_annotations,
+        } = self;
+        head_ids.hash(state);
+        local_bookmarks.hash(state);
+        tags.hash(state);
+        remote_views.hash(state);
+        git_refs.hash(state);
+        git_head.hash(state);
+        wc_commit_ids.hash(state);
+        if tag_annotations.is_empty() {
+            if let Some(bisect) = bisect {
+                bisect.hash(state);
+            }
+        } else {
+            // A bisection state hashed alone starts with the length of a list,
+            // which can't be as large as this tag.
+            state.update(&TAG_ANNOTATIONS_TAG);
+            bisect.hash(state);
+            tag_annotations.hash(state);
+        }
+    }
 }
 
 impl View {
@@ -276,6 +328,8 @@ impl View {
             git_refs: BTreeMap::new(),
             git_head: RefTarget::absent(),
             wc_commit_ids: BTreeMap::new(),
+            bisect: None,
+            tag_annotations: BTreeMap::new(),
         }
     }
 
@@ -289,10 +343,32 @@ impl View {
             git_refs: BTreeMap::new(),
             git_head: RefTarget::absent(),
             wc_commit_ids: BTreeMap::new(),
+            bisect: None,
+            tag_annotations: BTreeMap::new(),
         }
     }
 }
 
+/// Commits marked while bisecting. Each list is sorted and has no duplicates.
+#[derive(ContentHash, Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct BisectState {
+    /// Commits known to be good.
+    pub good: Vec<CommitId>,
+    /// Commits known to be bad.
+    pub bad: Vec<CommitId>,
+    /// Commits that couldn't be tested.
+    pub skipped: Vec<CommitId>,
+}
+
+/// Message and tagger of a tag which is exported to Git as an annotated tag.
+#[derive(ContentHash, Clone, Debug, Eq, PartialEq)]
+pub struct TagAnnotation {
+    /// The tag message.
+    pub message: String,
+    /// The author of the tag.
+    pub tagger: Signature,
+}
+
 /// Represents the state of the remote repo.
 #[derive(ContentHash, Clone, Debug, Default, Eq, PartialEq)]
 pub struct RemoteView {
@@ -35,9 +35,13 @@ use crate::op_store::OpStoreError;
 use crate::op_store::OpStoreResult;
 use crate::op_store::OperationId;
 use crate::operation::Operation;
+use crate::opset;
+use crate::opset::OpsetEvaluationContext;
 use crate::repo::ReadonlyRepo;
 use crate::repo::Repo as _;
 use crate::repo::RepoLoader;
+use crate::revset::RevsetDiagnostics;
+use crate::revset::RevsetParseError;
 
 /// Error that may occur during evaluation of operation set expression.
 #[derive(Debug, Error)]
@@ -54,6 +58,9 @@ pub enum OpsetEvaluationError {
     /// Failed to access operation object.
     #[error(transparent)]
     OpStore(#[from] OpStoreError),
+    /// Failed to parse operation set expression.
+    #[error(transparent)]
+    Parse(#[from] RevsetParseError),
 }
 
 /// Error that may occur during parsing and resolution of operation set
@@ -86,6 +93,7 @@ pub enum OpsetResolutionError {
 
 /// Resolves operation set expression without loading a repo.
 pub fn resolve_op_for_load(
+    diagnostics: &mut RevsetDiagnostics,
     repo_loader: &RepoLoader,
     op_str: &str,
 ) -> Result<Operation, OpsetEvaluationError> {
@@ -101,21 +109,28 @@ pub fn resolve_op_for_load(
         })
     };
     let get_head_ops = || get_current_head_ops(op_store, op_heads_store);
-    resolve_single_op(op_store, get_current_op, get_head_ops, op_str)
+    resolve_single_op(diagnostics, op_store, get_current_op, get_head_ops, op_str)
 }
 
 /// Resolves operation set expression against the loaded repo.
 ///
 /// The "@" symbol will be resolved to the operation the repo was loaded at.
 pub fn resolve_op_with_repo(
+    diagnostics: &mut RevsetDiagnostics,
     repo: &ReadonlyRepo,
     op_str: &str,
 ) -> Result<Operation, OpsetEvaluationError> {
-    resolve_op_at(repo.op_store(), slice::from_ref(repo.operation()), op_str)
+    resolve_op_at(
+        diagnostics,
+        repo.op_store(),
+        slice::from_ref(repo.operation()),
+        op_str,
+    )
 }
 
 /// Resolves operation set expression at the given head operations.
 pub fn resolve_op_at(
+    diagnostics: &mut RevsetDiagnostics,
     op_store: &Arc<dyn OpStore>,
     head_ops: &[Operation],
     op_str: &str,
@@ -130,43 +145,65 @@ pub fn resolve_op_at(
         .into()),
     };
     let get_head_ops = || Ok(head_ops.to_vec());
-    resolve_single_op(op_store, get_current_op, get_head_ops, op_str)
+    resolve_single_op(diagnostics, op_store, get_current_op, get_head_ops, op_str)
 }
 
 /// Resolves operation set expression with the given "@" symbol resolution
 /// callbacks.
 fn resolve_single_op(
+    diagnostics: &mut RevsetDiagnostics,
     op_store: &Arc<dyn OpStore>,
     get_current_op: impl FnOnce() -> Result<Operation, OpsetEvaluationError>,
     get_head_ops: impl FnOnce() -> Result<Vec<Operation>, OpsetEvaluationError>,
     op_str: &str,
 ) -> Result<Operation, OpsetEvaluationError> {
-    let op_symbol = op_str.trim_end_matches(['-', '+']);
-    let op_postfix = &op_str[op_symbol.len()..];
-    let head_ops = op_postfix.contains('+').then(get_head_ops).transpose()?;
-    let mut operation = match op_symbol {
-        "@" => get_current_op(),
-        s => resolve_single_op_from_store(op_store, s),
-    }?;
-    for c in op_postfix.chars() {
-        let mut neighbor_ops = match c {
-            '-' => operation.parents().try_collect()?,
-            '+' => find_child_ops(head_ops.as_ref().unwrap(), operation.id())?,
-            _ => unreachable!(),
-        };
-        operation = match neighbor_ops.len() {
-            0 => Err(OpsetResolutionError::EmptyOperations(op_str.to_owned()))?,
-            1 => neighbor_ops.pop().unwrap(),
-            _ => Err(OpsetResolutionError::MultipleOperations {
-                expr: op_str.to_owned(),
-                candidates: neighbor_ops.iter().map(|op| op.id().clone()).collect(),
-            })?,
-        };
+    let mut operations = resolve_ops(diagnostics, op_store, get_current_op, get_head_ops, op_str)?;
+    match operations.len() {
+        0 => Err(OpsetResolutionError::EmptyOperations(op_str.to_owned()).into()),
+        1 => Ok(operations.pop().unwrap()),
+        _ => Err(OpsetResolutionError::MultipleOperations {
+            expr: op_str.to_owned(),
+            candidates: operations.iter().map(|op| op.id().clone()).collect(),
+        }
+        .into()),
     }
-    Ok(operation)
 }
 
-fn resolve_single_op_from_store(
+/// Resolves operation set expression to operations at the given head
+/// operations. The returned operations are ordered from the newest.
+pub fn resolve_ops_at(
+    diagnostics: &mut RevsetDiagnostics,
+    op_store: &Arc<dyn OpStore>,
+    head_ops: &[Operation],
+    op_str: &str,
+) -> Result<Vec<Operation>, OpsetEvaluationError> {
+    let get_current_op = || match head_ops {
+        [head_op] => Ok(head_op.clone()),
+        [] => Err(OpsetResolutionError::EmptyOperations("@".to_owned()).into()),
+        _ => Err(OpsetResolutionError::MultipleOperations {
+            expr: "@".to_owned(),
+            candidates: head_ops.iter().map(|op| op.id().clone()).collect(),
+        }
+        .into()),
+    };
+    let get_head_ops = || Ok(head_ops.to_vec());
+    resolve_ops(diagnostics, op_store, get_current_op, get_head_ops, op_str)
+}
+
+fn resolve_ops(
+    diagnostics: &mut RevsetDiagnostics,
+    op_store: &Arc<dyn OpStore>,
+    get_current_op: impl FnOnce() -> Result<Operation, OpsetEvaluationError>,
+    get_head_ops: impl FnOnce() -> Result<Vec<Operation>, OpsetEvaluationError>,
+    op_str: &str,
+) -> Result<Vec<Operation>, OpsetEvaluationError> {
+    let date_pattern_context = chrono::Local::now().into();
+    let expression = opset::parse(diagnostics, op_str, &date_pattern_context)?;
+    let context = OpsetEvaluationContext::new(op_store, get_current_op, get_head_ops);
+    context.evaluate(&expression)
+}
+
+pub(crate) fn resolve_single_op_from_store(
     op_store: &Arc<dyn OpStore>,
     op_str: &str,
 ) -> Result<Operation, OpsetEvaluationError> {
@@ -208,22 +245,8 @@ pub fn get_current_head_ops(
     Ok(head_ops)
 }
 
-/// Looks up children of the `root_op_id` by traversing from the `head_ops`.
-///
-/// This will be slow if the `root_op_id` is far away (or unreachable) from the
-/// `head_ops`.
-fn find_child_ops(
-    head_ops: &[Operation],
-    root_op_id: &OperationId,
-) -> OpStoreResult<Vec<Operation>> {
-    walk_ancestors(head_ops)
-        .take_while(|res| res.as_ref().map_or(true, |op| op.id() != root_op_id))
-        .filter_ok(|op| op.parent_ids().iter().any(|id| id == root_op_id))
-        .try_collect()
-}
-
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
-struct OperationByEndTime(Operation);
+pub(crate) struct OperationByEndTime(pub(crate) Operation);
 
 impl Ord for OperationByEndTime {
     fn cmp(&self, other: &Self) -> Ordering {
@@ -334,3 +357,75 @@ pub fn reparent_range(
         unreachable_count: unreachable_ids.len(),
     })
 }
+
+/// Removes the `abandon_ops` from the history of the `head_ops`.
+///
+/// Descendants of the abandoned operations are rewritten to have the nearest
+/// non-abandoned ancestors as parents. Returns the new head operation ids as
+/// well as some stats. The `unreachable_count` is the number of abandoned
+/// operations reachable from the `head_ops`.
+///
+/// The `head_ops` must not be included in the `abandon_ops`.
+pub fn abandon_operations(
+    op_store: &dyn OpStore,
+    abandon_ops: &[Operation],
+    head_ops: &[Operation],
+) -> OpStoreResult<ReparentStats> {
+    // Collect operations up to the oldest abandoned operation. The remaining
+    // ancestors are unchanged.
+    let mut remaining_ids: HashSet<_> = abandon_ops.iter().map(|op| op.id()).collect();
+    let abandon_ids = remaining_ids.clone();
+    let mut ops_to_visit = vec![];
+    for op in walk_ancestors(head_ops) {
+        if remaining_ids.is_empty() {
+            break;
+        }
+        let op = op?;
+        remaining_ids.remove(op.id());
+        ops_to_visit.push(op);
+    }
+
+    assert!(
+        !abandon_ids.contains(op_store.root_operation_id()),
+        "root operation cannot be abandoned"
+    );
+    // Maps old operation id to the new ids which should be substituted.
+    let mut new_ids_map: HashMap<OperationId, Vec<OperationId>> = HashMap::new();
+    let mut rewritten_count = 0;
+    let mut unreachable_count = 0;
+    for old_op in ops_to_visit.into_iter().rev() {
+        let new_parent_ids = old_op
+            .parent_ids()
+            .iter()
+            .flat_map(|id| new_ids_map.get(id).map_or(slice::from_ref(id), |ids| ids))
+            .unique()
+            .cloned()
+            .collect_vec();
+        let new_ids = if abandon_ids.contains(old_op.id()) {
+            unreachable_count += 1;
+            new_parent_ids
+        } else if new_parent_ids == old_op.parent_ids() {
+            continue;
+        } else {
+            let mut data = old_op.store_operation().clone();
+            data.parents = new_parent_ids;
+            rewritten_count += 1;
+            vec![op_store.write_operation(&data)?]
+        };
+        new_ids_map.insert(old_op.id().clone(), new_ids);
+    }
+
+    let new_head_ids = head_ops
+        .iter()
+        .map(|op| match new_ids_map.get(op.id()).map(Vec::as_slice) {
+            None => op.id().clone(),
+            Some([new_id]) => new_id.clone(),
+            Some(_) => panic!("head operation cannot be abandoned"),
+        })
+        .collect();
+    Ok(ReparentStats {
+        new_head_ids,
+        rewritten_count,
+        unreachable_count,
+    })
+}
@@ -0,0 +1,557 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Operation set expressions.
+//!
+//! Operation sets use the same syntax as revsets, but symbols are resolved to
+//! operations instead of commits. For example, `@-` is the parent of the
+//! current operation, and `description(glob:"snapshot*") & ..@` selects the
+//! snapshot operations.
+
+use std::cell::Cell;
+use std::cell::OnceCell;
+use std::collections::HashSet;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use itertools::Itertools as _;
+
+use crate::dsl_util::collect_similar;
+use crate::op_store::OpStore;
+use crate::op_store::OperationId;
+use crate::op_walk::resolve_single_op_from_store;
+use crate::op_walk::walk_ancestors;
+use crate::op_walk::OperationByEndTime;
+use crate::op_walk::OpsetEvaluationError;
+use crate::operation::Operation;
+use crate::revset::expect_date_pattern;
+use crate::revset::expect_string_pattern;
+use crate::revset::RevsetDiagnostics;
+use crate::revset::RevsetParseError;
+use crate::revset::RevsetParseErrorKind;
+use crate::revset_parser;
+use crate::revset_parser::BinaryOp;
+use crate::revset_parser::ExpressionKind;
+use crate::revset_parser::ExpressionNode;
+use crate::revset_parser::FunctionCallNode;
+use crate::revset_parser::UnaryOp;
+use crate::str_util::StringPattern;
+use crate::str_util::StringPatternParseError;
+use crate::time_util::DatePattern;
+use crate::time_util::DatePatternContext;
+
+/// Predicate to select operations by their metadata.
+#[derive(Clone, Debug)]
+pub enum OpsetFilterPredicate {
+    /// Operations whose description matches the pattern.
+    Description(StringPattern),
+    /// Operations whose `username@hostname` matches the pattern.
+    User(StringPattern),
+    /// Operations which ended within the date range.
+    Time(DatePattern),
+    /// Operations having the tag `key` whose value matches the pattern.
+    Tag {
+        /// Tag name.
+        key: String,
+        /// Pattern to match the tag value.
+        value: StringPattern,
+    },
+    /// Operations which only snapshotted the working copy.
+    Snapshot,
+}
+
+impl OpsetFilterPredicate {
+    fn matches(&self, op: &Operation) -> bool {
+        let metadata = op.metadata();
+        match self {
+            OpsetFilterPredicate::Description(pattern) => pattern.matches(&metadata.description),
+            OpsetFilterPredicate::User(pattern) => {
+                pattern.matches(&format!("{}@{}", metadata.username, metadata.hostname))
+            }
+            OpsetFilterPredicate::Time(pattern) => pattern.matches(&metadata.end_time),
+            OpsetFilterPredicate::Tag { key, value } => metadata
+                .tags
+                .get(key)
+                .is_some_and(|tag_value| value.matches(tag_value)),
+            OpsetFilterPredicate::Snapshot => metadata.is_snapshot,
+        }
+    }
+}
+
+/// Tree of operation set expressions.
+#[derive(Clone, Debug)]
+pub enum OpsetExpression {
+    /// `@`, the current operation.
+    Current,
+    /// The current head operations.
+    Heads,
+    /// `root()`
+    Root,
+    /// Operation ID or its prefix.
+    Id(String),
+    /// All operations reachable from the head operations.
+    All,
+    /// `x-`
+    Parents(Rc<Self>),
+    /// `x+`
+    Children(Rc<Self>),
+    /// `::x`
+    Ancestors(Rc<Self>),
+    /// `x::`
+    Descendants(Rc<Self>),
+    /// `::heads ~ ::roots`
+    Range {
+        /// Operations to exclude along with their ancestors.
+        roots: Rc<Self>,
+        /// Operations to include along with their ancestors.
+        heads: Rc<Self>,
+    },
+    /// `roots:: & ::heads`
+    DagRange {
+        /// Start of the range.
+        roots: Rc<Self>,
+        /// End of the range.
+        heads: Rc<Self>,
+    },
+    /// Operations matching the predicate.
+    Filter(OpsetFilterPredicate),
+    /// `x | y`
+    Union(Rc<Self>, Rc<Self>),
+    /// `x & y`
+    Intersection(Rc<Self>, Rc<Self>),
+    /// `x ~ y`
+    Difference(Rc<Self>, Rc<Self>),
+}
+
+type OpsetFunction = fn(
+    &mut RevsetDiagnostics,
+    &FunctionCallNode,
+    &DatePatternContext,
+) -> Result<Rc<OpsetExpression>, RevsetParseError>;
+
+const FUNCTIONS: &[(&str, OpsetFunction)] = &[
+    ("all", |_diagnostics, function, _context| {
+        function.expect_no_arguments()?;
+        Ok(Rc::new(OpsetExpression::All))
+    }),
+    ("root", |_diagnostics, function, _context| {
+        function.expect_no_arguments()?;
+        Ok(Rc::new(OpsetExpression::Root))
+    }),
+    ("description", |diagnostics, function, _context| {
+        let [arg] = function.expect_exact_arguments()?;
+        let pattern = expect_string_pattern(diagnostics, arg)?;
+        let predicate = OpsetFilterPredicate::Description(pattern);
+        Ok(Rc::new(OpsetExpression::Filter(predicate)))
+    }),
+    ("user", |diagnostics, function, _context| {
+        let [arg] = function.expect_exact_arguments()?;
+        let pattern = expect_string_pattern(diagnostics, arg)?;
+        let predicate = OpsetFilterPredicate::User(pattern);
+        Ok(Rc::new(OpsetExpression::Filter(predicate)))
+    }),
+    ("time", |diagnostics, function, context| {
+        let [arg] = function.expect_exact_arguments()?;
+        let pattern = expect_date_pattern(diagnostics, arg, context)?;
+        let predicate = OpsetFilterPredicate::Time(pattern);
+        Ok(Rc::new(OpsetExpression::Filter(predicate)))
+    }),
+    ("tags", |diagnostics, function, _context| {
+        let [arg] = function.expect_exact_arguments()?;
+        // `key:pattern` matches the tag value, `key` tests the tag existence.
+        let (key, value) = revset_parser::expect_pattern_with(
+            diagnostics,
+            "tag pattern",
+            arg,
+            |_diagnostics, value, kind| -> Result<_, StringPatternParseError> {
+                match kind {
+                    Some(key) => Ok((key.to_owned(), StringPattern::glob(value)?)),
+                    None => Ok((value.to_owned(), StringPattern::glob("*")?)),
+                }
+            },
+        )?;
+        let predicate = OpsetFilterPredicate::Tag { key, value };
+        Ok(Rc::new(OpsetExpression::Filter(predicate)))
+    }),
+    ("snapshot", |_diagnostics, function, _context| {
+        function.expect_no_arguments()?;
+        Ok(Rc::new(OpsetExpression::Filter(
+            OpsetFilterPredicate::Snapshot,
+        )))
+    }),
+];
+
+/// Parses the operation set expression.
+pub fn parse(
+    diagnostics: &mut RevsetDiagnostics,
+    text: &str,
+    date_pattern_context: &DatePatternContext,
+) -> Result<Rc<OpsetExpression>, RevsetParseError> {
+    let node = revset_parser::parse_program(text)?;
+    lower_expression(diagnostics, &node, date_pattern_context)
+}
+
+fn lower_expression(
+    diagnostics: &mut RevsetDiagnostics,
+    node: &ExpressionNode,
+    context: &DatePatternContext,
+) -> Result<Rc<OpsetExpression>, RevsetParseError> {
+    let not_supported = |what: &str| {
+        RevsetParseError::expression(format!("{what} is not supported for operations"), node.span)
+    };
+    match &node.kind {
+        ExpressionKind::Identifier(name) => Ok(Rc::new(OpsetExpression::Id((*name).to_owned()))),
+        ExpressionKind::String(name) => Ok(Rc::new(OpsetExpression::Id(name.clone()))),
+        ExpressionKind::StringPattern { .. } => Err(RevsetParseError::with_span(
+            RevsetParseErrorKind::NotInfixOperator {
+                op: ":".to_owned(),
+                similar_op: "::".to_owned(),
+                description: "DAG range".to_owned(),
+            },
+            node.span,
+        )),
+        ExpressionKind::RemoteSymbol(_) => Err(not_supported("Remote symbol")),
+        ExpressionKind::AtWorkspace(_) => Err(not_supported("Workspace symbol")),
+        ExpressionKind::AtCurrentWorkspace => Ok(Rc::new(OpsetExpression::Current)),
+        ExpressionKind::DagRangeAll => Ok(Rc::new(OpsetExpression::All)),
+        ExpressionKind::RangeAll => Ok(Rc::new(OpsetExpression::Range {
+            roots: Rc::new(OpsetExpression::Root),
+            heads: Rc::new(OpsetExpression::Heads),
+        })),
+        ExpressionKind::Unary(op, arg_node) => {
+            let arg = lower_expression(diagnostics, arg_node, context)?;
+            let expression = match op {
+                UnaryOp::Negate => OpsetExpression::Difference(Rc::new(OpsetExpression::All), arg),
+                UnaryOp::DagRangePre => OpsetExpression::Ancestors(arg),
+                UnaryOp::DagRangePost => OpsetExpression::Descendants(arg),
+                UnaryOp::RangePre => OpsetExpression::Range {
+                    roots: Rc::new(OpsetExpression::Root),
+                    heads: arg,
+                },
+                UnaryOp::RangePost => OpsetExpression::Range {
+                    roots: arg,
+                    heads: Rc::new(OpsetExpression::Heads),
+                },
+                UnaryOp::Parents => OpsetExpression::Parents(arg),
+                UnaryOp::Children => OpsetExpression::Children(arg),
+            };
+            Ok(Rc::new(expression))
+        }
+        ExpressionKind::Binary(op, lhs_node, rhs_node) => {
+            let lhs = lower_expression(diagnostics, lhs_node, context)?;
+            let rhs = lower_expression(diagnostics, rhs_node, context)?;
+            let expression = match op {
+                BinaryOp::Intersection => OpsetExpression::Intersection(lhs, rhs),
+                BinaryOp::Difference => OpsetExpression::Difference(lhs, rhs),
+                BinaryOp::DagRange => OpsetExpression::DagRange {
+                    roots: lhs,
+                    heads: rhs,
+                },
+                BinaryOp::Range => OpsetExpression::Range {
+                    roots: lhs,
+                    heads: rhs,
+                },
+            };
+            Ok(Rc::new(expression))
+        }
+        ExpressionKind::UnionAll(nodes) => {
+            let expressions: Vec<_> = nodes
+                .iter()
+                .map(|node| lower_expression(diagnostics, node, context))
+                .try_collect()?;
+            let expression = expressions
+                .into_iter()
+                .reduce(|lhs, rhs| Rc::new(OpsetExpression::Union(lhs, rhs)))
+                .unwrap();
+            Ok(expression)
+        }
+        ExpressionKind::FunctionCall(function) => {
+            if let Some((_, func)) = FUNCTIONS.iter().find(|(name, _)| *name == function.name) {
+                func(diagnostics, function, context)
+            } else {
+                Err(RevsetParseError::with_span(
+                    RevsetParseErrorKind::NoSuchFunction {
+                        name: function.name.to_owned(),
+                        candidates: collect_similar(
+                            function.name,
+                            FUNCTIONS.iter().map(|(name, _)| name),
+                        ),
+                    },
+                    function.name_span,
+                ))
+            }
+        }
+        ExpressionKind::Modifier(modifier) => Err(RevsetParseError::expression(
+            format!(
+                "Modifier `{}:` is not supported for operations",
+                modifier.name
+            ),
+            modifier.name_span,
+        )),
+        ExpressionKind::AliasExpanded(_, subst) => lower_expression(diagnostics, subst, context),
+    }
+}
+
+type LazyOpsFn<'a> = Box<dyn FnOnce() -> Result<Vec<Operation>, OpsetEvaluationError> + 'a>;
+
+/// Operations to be resolved on first access.
+struct LazyOps<'a> {
+    init: Cell<Option<LazyOpsFn<'a>>>,
+    value: OnceCell<Vec<Operation>>,
+}
+
+impl<'a> LazyOps<'a> {
+    fn new(init: LazyOpsFn<'a>) -> Self {
+        LazyOps {
+            init: Cell::new(Some(init)),
+            value: OnceCell::new(),
+        }
+    }
+
+    fn get(&self) -> Result<&[Operation], OpsetEvaluationError> {
+        if let Some(ops) = self.value.get() {
+            return Ok(ops);
+        }
+        let init = self
+            .init
+            .take()
+            .expect("failed initialization shouldn't be retried");
+        let ops = init()?;
+        Ok(self.value.get_or_init(|| ops))
+    }
+}
+
+/// Context to evaluate operation set expressions.
+pub(crate) struct OpsetEvaluationContext<'a> {
+    op_store: &'a Arc<dyn OpStore>,
+    current_op: LazyOps<'a>,
+    head_ops: LazyOps<'a>,
+}
+
+impl<'a> OpsetEvaluationContext<'a> {
+    /// Creates new context. The `"@"` symbol and the head operations are
+    /// resolved by the given callbacks as needed.
+    pub(crate) fn new(
+        op_store: &'a Arc<dyn OpStore>,
+        get_current_op: impl FnOnce() -> Result<Operation, OpsetEvaluationError> + 'a,
+        get_head_ops: impl FnOnce() -> Result<Vec<Operation>, OpsetEvaluationError> + 'a,
+    ) -> Self {
+        OpsetEvaluationContext {
+            op_store,
+            current_op: LazyOps::new(Box::new(|| Ok(vec![get_current_op()?]))),
+            head_ops: LazyOps::new(Box::new(get_head_ops)),
+        }
+    }
+
+    /// Evaluates the `expression` to operations ordered from the newest.
+    pub(crate) fn evaluate(
+        &self,
+        expression: &OpsetExpression,
+    ) -> Result<Vec<Operation>, OpsetEvaluationError> {
+        match expression {
+            OpsetExpression::Current => Ok(self.current_op.get()?.to_vec()),
+            OpsetExpression::Heads => Ok(sorted_ops(self.head_ops.get()?.to_vec())),
+            OpsetExpression::Root => {
+                let id = self.op_store.root_operation_id();
+                let data = self.op_store.read_operation(id)?;
+                Ok(vec![Operation::new(
+                    self.op_store.clone(),
+                    id.clone(),
+                    data,
+                )])
+            }
+            OpsetExpression::Id(prefix) => {
+                Ok(vec![resolve_single_op_from_store(self.op_store, prefix)?])
+            }
+            OpsetExpression::All => Ok(walk_ancestors(self.head_ops.get()?).try_collect()?),
+            OpsetExpression::Parents(arg) => {
+                let ops = self.evaluate(arg)?;
+                let parents: Vec<_> = ops.iter().flat_map(|op| op.parents()).try_collect()?;
+                Ok(sorted_ops(parents))
+            }
+            OpsetExpression::Children(arg) => {
+                let ids = to_ids(&self.evaluate(arg)?);
+                let walked = self.walk_heads_until_seen(&ids)?;
+                Ok(walked
+                    .into_iter()
+                    .filter(|op| op.parent_ids().iter().any(|id| ids.contains(id)))
+                    .collect())
+            }
+            OpsetExpression::Ancestors(arg) => {
+                Ok(walk_ancestors(&self.evaluate(arg)?).try_collect()?)
+            }
+            OpsetExpression::Descendants(arg) => {
+                let ids = to_ids(&self.evaluate(arg)?);
+                self.descendants(&ids)
+            }
+            OpsetExpression::Range { roots, heads } => {
+                let root_ops = self.evaluate(roots)?;
+                let unwanted_ids: HashSet<_> = walk_ancestors(&root_ops)
+                    .map_ok(|op| op.id().clone())
+                    .try_collect()?;
+                Ok(walk_ancestors(&self.evaluate(heads)?)
+                    .filter_ok(|op| !unwanted_ids.contains(op.id()))
+                    .try_collect()?)
+            }
+            OpsetExpression::DagRange { roots, heads } => {
+                let root_ids = to_ids(&self.evaluate(roots)?);
+                let descendant_ids = to_ids(&self.descendants(&root_ids)?);
+                Ok(walk_ancestors(&self.evaluate(heads)?)
+                    .filter_ok(|op| descendant_ids.contains(op.id()))
+                    .try_collect()?)
+            }
+            OpsetExpression::Filter(predicate) => Ok(walk_ancestors(self.head_ops.get()?)
+                .filter_ok(|op| predicate.matches(op))
+                .try_collect()?),
+            OpsetExpression::Union(lhs, rhs) => {
+                let mut ops = self.evaluate(lhs)?;
+                ops.extend(self.evaluate(rhs)?);
+                Ok(sorted_ops(ops))
+            }
+            OpsetExpression::Intersection(lhs, rhs) => {
+                // Filter the other side instead of walking all operations.
+                match (lhs.as_ref(), rhs.as_ref()) {
+                    (_, OpsetExpression::Filter(predicate)) => {
+                        let mut ops = self.evaluate(lhs)?;
+                        ops.retain(|op| predicate.matches(op));
+                        Ok(ops)
+                    }
+                    (OpsetExpression::Filter(predicate), _) => {
+                        let mut ops = self.evaluate(rhs)?;
+                        ops.retain(|op| predicate.matches(op));
+                        Ok(ops)
+                    }
+                    _ => {
+                        let mut ops = self.evaluate(lhs)?;
+                        let rhs_ids = to_ids(&self.evaluate(rhs)?);
+                        ops.retain(|op| rhs_ids.contains(op.id()));
+                        Ok(ops)
+                    }
+                }
+            }
+            OpsetExpression::Difference(lhs, rhs) => {
+                let mut ops = self.evaluate(lhs)?;
+                let rhs_ids = to_ids(&self.evaluate(rhs)?);
+                ops.retain(|op| !rhs_ids.contains(op.id()));
+                Ok(ops)
+            }
+        }
+    }
+
+    /// Walks the head operations until all of the `ids` are visited.
+    fn walk_heads_until_seen(
+        &self,
+        ids: &HashSet<OperationId>,
+    ) -> Result<Vec<Operation>, OpsetEvaluationError> {
+        let mut remaining = ids.clone();
+        let mut walked = vec![];
+        for op in walk_ancestors(self.head_ops.get()?) {
+            if remaining.is_empty() {
+                break;
+            }
+            let op = op?;
+            remaining.remove(op.id());
+            walked.push(op);
+        }
+        Ok(walked)
+    }
+
+    fn descendants(
+        &self,
+        root_ids: &HashSet<OperationId>,
+    ) -> Result<Vec<Operation>, OpsetEvaluationError> {
+        let walked = self.walk_heads_until_seen(root_ids)?;
+        let mut descendant_ids = root_ids.clone();
+        let mut descendants = vec![];
+        for op in walked.into_iter().rev() {
+            if descendant_ids.contains(op.id())
+                || op.parent_ids().iter().any(|id| descendant_ids.contains(id))
+            {
+                descendant_ids.insert(op.id().clone());
+                descendants.push(op);
+            }
+        }
+        descendants.reverse();
+        Ok(descendants)
+    }
+}
+
+fn to_ids(ops: &[Operation]) -> HashSet<OperationId> {
+    ops.iter().map(|op| op.id().clone()).collect()
+}
+
+/// Sorts operations from the newest, and removes duplicates.
+fn sorted_ops(ops: Vec<Operation>) -> Vec<Operation> {
+    ops.into_iter()
+        .map(OperationByEndTime)
+        .sorted_unstable_by(|op1, op2| op1.cmp(op2).reverse())
+        .dedup()
+        .map(|OperationByEndTime(op)| op)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+
+    use super::*;
+
+    fn parse_normalized(text: &str) -> String {
+        let context = DatePatternContext::from(chrono::Local::now());
+        format!(
+            "{:?}",
+            parse(&mut RevsetDiagnostics::new(), text, &context).unwrap()
+        )
+    }
+
+    #[test]
+    fn test_parse_opset_expression() {
+        insta::assert_snapshot!(parse_normalized("@-"), @"Parents(Current)");
+        insta::assert_snapshot!(parse_normalized("abc+"), @r#"Children(Id("abc"))"#);
+        insta::assert_snapshot!(
+            parse_normalized("..@-"),
+            @"Range { roots: Root, heads: Parents(Current) }");
+        insta::assert_snapshot!(
+            parse_normalized("abc.."),
+            @r#"Range { roots: Id("abc"), heads: Heads }"#);
+        insta::assert_snapshot!(
+            parse_normalized("::@ & snapshot()"),
+            @"Intersection(Ancestors(Current), Filter(Snapshot))");
+        insta::assert_snapshot!(
+            parse_normalized(r#"description(glob:"snapshot*") | user("alice")"#),
+            @r#"Union(Filter(Description(Glob(GlobPattern("snapshot*")))), Filter(User(Substring("alice"))))"#);
+        insta::assert_snapshot!(
+            parse_normalized(r#"tags(args:"*push*") ~ tags(args)"#),
+            @r#"Difference(Filter(Tag { key: "args", value: Glob(GlobPattern("*push*")) }), Filter(Tag { key: "args", value: Glob(GlobPattern("*")) }))"#);
+    }
+
+    #[test]
+    fn test_parse_opset_expression_error() {
+        let context = DatePatternContext::from(chrono::Local::now());
+        let parse_err = |text| parse(&mut RevsetDiagnostics::new(), text, &context).unwrap_err();
+        assert_matches!(
+            parse_err("foo()").kind(),
+            RevsetParseErrorKind::NoSuchFunction { name, .. } if name == "foo"
+        );
+        assert_matches!(
+            parse_err("time(yesterday)").kind(),
+            RevsetParseErrorKind::Expression(_)
+        );
+        assert_matches!(
+            parse_err("foo@").kind(),
+            RevsetParseErrorKind::Expression(_)
+        );
+        assert_matches!(parse_err("@-x").kind(), RevsetParseErrorKind::SyntaxError);
+    }
+}
@@ -0,0 +1,1254 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Parsing of patches in unified diff format, as produced by `git diff` and
+//! `git format-patch`, and application of them to trees.
+
+use std::io::Read as _;
+
+use bstr::BString;
+use bstr::ByteSlice as _;
+use pollster::FutureExt as _;
+use thiserror::Error;
+
+use crate::backend::BackendError;
+use crate::backend::CommitId;
+use crate::backend::FileId;
+use crate::backend::Timestamp;
+use crate::backend::TreeValue;
+use crate::merge::Merge;
+use crate::merged_tree::MergedTree;
+use crate::merged_tree::MergedTreeBuilder;
+use crate::object_id::ObjectId as _;
+use crate::repo_path::RepoPath;
+use crate::repo_path::RepoPathBuf;
+use crate::store::Store;
+
+/// Changes to files, and the metadata of the message they were sent in.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Patch {
+    /// Author from the `From:` and `Date:` headers of the message.
+    pub author: Option<PatchAuthor>,
+    /// Subject and body of the message. Empty if the patch isn't an email.
+    pub description: String,
+    /// Changes to individual files.
+    pub files: Vec<FilePatch>,
+}
+
+/// Author of a patch.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PatchAuthor {
+    /// Name of the author.
+    pub name: String,
+    /// Email address of the author.
+    pub email: String,
+    /// Time the patch was authored, if the message had a valid `Date:` header.
+    pub timestamp: Option<Timestamp>,
+}
+
+/// Mode of a file in a patch.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum FileMode {
+    /// Regular file (`100644`).
+    Normal,
+    /// Executable file (`100755`).
+    Executable,
+    /// Symbolic link (`120000`).
+    Symlink,
+    /// Git submodule (`160000`).
+    GitSubmodule,
+}
+
+impl FileMode {
+    fn parse(mode: &[u8]) -> Option<Self> {
+        match mode.trim() {
+            b"100644" | b"100664" => Some(FileMode::Normal),
+            b"100755" => Some(FileMode::Executable),
+            b"120000" => Some(FileMode::Symlink),
+            b"160000" => Some(FileMode::GitSubmodule),
+            _ => None,
+        }
+    }
+}
+
+/// Changes to a single file.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct FilePatch {
+    /// Path of the file before the change. `None` if the file is added.
+    pub old_path: Option<RepoPathBuf>,
+    /// Path of the file after the change. `None` if the file is deleted.
+    pub new_path: Option<RepoPathBuf>,
+    /// Whether the old file is kept when the paths differ.
+    pub is_copy: bool,
+    /// Mode before the change, if specified.
+    pub old_mode: Option<FileMode>,
+    /// Mode after the change, if specified.
+    pub new_mode: Option<FileMode>,
+    /// Possibly abbreviated object id of the new contents, from the `index`
+    /// line.
+    pub new_id: Option<String>,
+    /// Whether the file is binary. The patch doesn't include hunks then.
+    pub is_binary: bool,
+    /// Changes to the contents.
+    pub hunks: Vec<PatchHunk>,
+}
+
+/// Changed lines of a file, and their context.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PatchHunk {
+    /// 1-based line number of the first old line, or of the line before the
+    /// hunk if there are no old lines.
+    pub old_start: usize,
+    /// Context and removed lines, including their line terminators.
+    pub old_lines: Vec<BString>,
+    /// Context and added lines, including their line terminators.
+    pub new_lines: Vec<BString>,
+}
+
+/// Error parsing a patch.
+#[derive(Debug, Error)]
+pub enum PatchParseError {
+    /// A `@@` line couldn't be parsed.
+    #[error("Invalid hunk header at line {line}")]
+    InvalidHunkHeader {
+        /// 1-based line number in the input.
+        line: usize,
+    },
+    /// A hunk has fewer lines than its header states.
+    #[error("Truncated hunk at line {line}")]
+    TruncatedHunk {
+        /// 1-based line number in the input.
+        line: usize,
+    },
+    /// A hunk doesn't follow a file header.
+    #[error("Hunk without file header at line {line}")]
+    MissingFileHeader {
+        /// 1-based line number in the input.
+        line: usize,
+    },
+    /// A file path isn't valid.
+    #[error("Invalid path {path:?} at line {line}")]
+    InvalidPath {
+        /// 1-based line number in the input.
+        line: usize,
+        /// The path as written in the patch.
+        path: String,
+    },
+}
+
+/// Error applying a patch.
+#[derive(Debug, Error)]
+pub enum PatchApplyError {
+    /// Error reading or writing the tree.
+    #[error(transparent)]
+    Backend(#[from] BackendError),
+    /// The file to change doesn't exist, and the patch has no hunks to
+    /// reconstruct it from.
+    #[error("File {} doesn't exist", .path.as_internal_file_string())]
+    MissingFile {
+        /// Path of the file.
+        path: RepoPathBuf,
+    },
+    /// The file to change has a conflict in the legacy format.
+    #[error("File {} is conflicted", .path.as_internal_file_string())]
+    ConflictedFile {
+        /// Path of the file.
+        path: RepoPathBuf,
+    },
+    /// The path to change is a directory.
+    #[error("Path {} is a directory", .path.as_internal_file_string())]
+    NotAFile {
+        /// Path of the directory.
+        path: RepoPathBuf,
+    },
+    /// The new contents of a binary file aren't in the patch nor in the store.
+    #[error(
+        "The patch doesn't include the contents of binary file {}",
+        .path.as_internal_file_string()
+    )]
+    MissingBinaryData {
+        /// Path of the file.
+        path: RepoPathBuf,
+    },
+    /// The new contents of a symlink or submodule are invalid.
+    #[error("Invalid contents for {}", .path.as_internal_file_string())]
+    InvalidContents {
+        /// Path of the file.
+        path: RepoPathBuf,
+    },
+}
+
+/// Parses the patches in `input`, which is either a mailbox of messages as
+/// produced by `git format-patch`, a single email, or a plain unified diff.
+pub fn parse_patches(input: &[u8]) -> Result<Vec<Patch>, PatchParseError> {
+    let lines: Vec<&[u8]> = input.split_inclusive(|b| *b == b'\n').collect();
+    let starts: Vec<usize> = (0..lines.len())
+        .filter(|&i| is_mbox_separator(&lines, i))
+        .collect();
+    if starts.is_empty() {
+        return Ok(vec![parse_message(&lines, 0)?]);
+    }
+    let ends = starts.iter().skip(1).copied().chain([lines.len()]);
+    starts
+        .iter()
+        .zip(ends)
+        .map(|(&start, end)| parse_message(&lines[start + 1..end], start + 1))
+        .collect()
+}
+
+fn is_mbox_separator(lines: &[&[u8]], index: usize) -> bool {
+    lines[index].starts_with(b"From ")
+        && lines
+            .get(index + 1)
+            .is_some_and(|line| parse_header_line(line).is_some())
+}
+
+fn parse_header_line(line: &[u8]) -> Option<(&[u8], &[u8])> {
+    let (name, value) = line.split_once_str(":")?;
+    let is_name = !name.is_empty() && name.iter().all(|b| b.is_ascii_alphanumeric() || *b == b'-');
+    is_name.then(|| (name, value.trim()))
+}
+
+fn parse_message(lines: &[&[u8]], line_offset: usize) -> Result<Patch, PatchParseError> {
+    if lines
+        .first()
+        .is_none_or(|line| parse_header_line(line).is_none())
+    {
+        let files = parse_diff(lines, line_offset)?;
+        return Ok(Patch {
+            files,
+            ..Patch::default()
+        });
+    }
+
+    let mut headers: Vec<(String, String)> = vec![];
+    let mut index = 0;
+    while let Some(line) = lines.get(index) {
+        index += 1;
+        if line.trim().is_empty() {
+            break;
+        } else if line.starts_with(b" ") || line.starts_with(b"\t") {
+            // Folded header
+            if let Some((_, value)) = headers.last_mut() {
+                value.push(' ');
+                value.push_str(&line.trim().to_str_lossy());
+            }
+        } else if let Some((name, value)) = parse_header_line(line) {
+            headers.push((
+                name.to_str_lossy().into_owned(),
+                value.to_str_lossy().into_owned(),
+            ));
+        }
+    }
+    let header = |name: &str| {
+        headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| decode_header_value(value))
+    };
+    let author = header("From").map(|from| {
+        let (name, email) = parse_address(&from);
+        let timestamp = header("Date")
+            .and_then(|date| chrono::DateTime::parse_from_rfc2822(&date).ok())
+            .map(Timestamp::from_datetime);
+        PatchAuthor {
+            name,
+            email,
+            timestamp,
+        }
+    });
+    let subject = header("Subject").unwrap_or_default();
+
+    let body_start = index;
+    while let Some(line) = lines.get(index) {
+        if line.trim_end() == b"---" || is_diff_start(lines, index) {
+            break;
+        }
+        index += 1;
+    }
+    let body = lines[body_start..index].concat();
+    let mut description = strip_subject_prefix(&subject).to_owned();
+    let body = body.to_str_lossy();
+    if !body.trim().is_empty() {
+        description.push_str("\n\n");
+        description.push_str(body.trim());
+    }
+    if !description.is_empty() {
+        description.push('\n');
+    }
+
+    let files = parse_diff(&lines[index..], line_offset + index)?;
+    Ok(Patch {
+        author,
+        description,
+        files,
+    })
+}
+
+fn is_diff_start(lines: &[&[u8]], index: usize) -> bool {
+    let line = lines[index];
+    line.starts_with(b"diff --git ")
+        || (line.starts_with(b"--- ")
+            && lines
+                .get(index + 1)
+                .is_some_and(|next| next.starts_with(b"+++ ")))
+}
+
+/// Splits `Name <email>` into its parts.
+fn parse_address(address: &str) -> (String, String) {
+    match address.rsplit_once('<') {
+        Some((name, email)) => {
+            let name = name.trim().trim_matches('"').trim();
+            let email = email.trim_end().trim_end_matches('>');
+            (name.to_owned(), email.to_owned())
+        }
+        None => (String::new(), address.trim().to_owned()),
+    }
+}
+
+/// Removes leading `[PATCH n/m]`-like tags from the subject.
+fn strip_subject_prefix(subject: &str) -> &str {
+    let mut subject = subject.trim();
+    while let Some(rest) = subject.strip_prefix('[') {
+        let Some((_, rest)) = rest.split_once(']') else {
+            break;
+        };
+        subject = rest.trim_start();
+    }
+    subject
+}
+
+/// Decodes RFC 2047 encoded words such as `=?UTF-8?q?J=C3=B6rg?=`.
+fn decode_header_value(value: &str) -> String {
+    let mut decoded = String::new();
+    let mut rest = value;
+    let mut after_encoded_word = false;
+    while !rest.is_empty() {
+        let Some(start) = rest.find("=?") else {
+            decoded.push_str(rest);
+            break;
+        };
+        let (text, word) = rest.split_at(start);
+        let decoded_word = word[2..].splitn(4, '?').collect::<Vec<_>>();
+        let (charset, encoding, encoded, remainder) = match decoded_word[..] {
+            [charset, encoding, encoded, remainder] if remainder.starts_with('=') => {
+                (charset, encoding, encoded, &remainder[1..])
+            }
+            _ => {
+                decoded.push_str(text);
+                decoded.push_str("=?");
+                rest = &word[2..];
+                after_encoded_word = false;
+                continue;
+            }
+        };
+        // Whitespace between adjacent encoded words is ignored.
+        if !(after_encoded_word && text.trim().is_empty()) {
+            decoded.push_str(text);
+        }
+        let bytes = if encoding.eq_ignore_ascii_case("b") {
+            decode_base64(encoded)
+        } else {
+            decode_quoted_printable(encoded)
+        };
+        if charset.eq_ignore_ascii_case("iso-8859-1") || charset.eq_ignore_ascii_case("latin1") {
+            decoded.extend(bytes.iter().map(|&b| char::from(b)));
+        } else {
+            decoded.push_str(&bytes.to_str_lossy());
+        }
+        rest = remainder;
+        after_encoded_word = true;
+    }
+    decoded
+}
+
+fn decode_quoted_printable(text: &str) -> Vec<u8> {
+    let mut bytes = vec![];
+    let mut iter = text.bytes();
+    while let Some(b) = iter.next() {
+        match b {
+            b'_' => bytes.push(b' '),
+            b'=' => {
+                let hex = [iter.next().unwrap_or(b'0'), iter.next().unwrap_or(b'0')];
+                let value = std::str::from_utf8(&hex)
+                    .ok()
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+                bytes.push(value.unwrap_or(b'?'));
+            }
+            _ => bytes.push(b),
+        }
+    }
+    bytes
+}
+
+fn decode_base64(text: &str) -> Vec<u8> {
+    fn value(b: u8) -> Option<u32> {
+        match b {
+            b'A'..=b'Z' => Some((b - b'A').into()),
+            b'a'..=b'z' => Some((b - b'a' + 26).into()),
+            b'0'..=b'9' => Some((b - b'0' + 52).into()),
+            b'+' => Some(62),
+            b'/' => Some(63),
+            _ => None,
+        }
+    }
+    let mut bytes = vec![];
+    let mut buffer = 0;
+    let mut bits = 0;
+    for value in text.bytes().map_while(value) {
+        buffer = (buffer << 6) | value;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+    bytes
+}
+
+fn parse_diff(lines: &[&[u8]], line_offset: usize) -> Result<Vec<FilePatch>, PatchParseError> {
+    let mut files: Vec<FilePatch> = vec![];
+    // Whether extended headers of a `diff --git` line are being parsed
+    let mut in_git_header = false;
+    let mut index = 0;
+    while let Some(&line) = lines.get(index) {
+        let line_number = line_offset + index + 1;
+        let to_repo_path = |path: BString| parse_path(&path, line_number);
+        if let Some(rest) = line.strip_prefix(b"diff --git ") {
+            let (old_path, new_path) = parse_git_diff_paths(rest.trim_end());
+            files.push(FilePatch {
+                old_path: old_path.map(to_repo_path).transpose()?,
+                new_path: new_path.map(to_repo_path).transpose()?,
+                ..FilePatch::default()
+            });
+            in_git_header = true;
+        } else if line.starts_with(b"--- ") && is_diff_start(lines, index) {
+            let old_path = parse_patch_path(&line[4..]);
+            let new_path = parse_patch_path(&lines[index + 1][4..]);
+            let old_path = old_path.map(to_repo_path).transpose()?;
+            let new_path = new_path.map(to_repo_path).transpose()?;
+            match files.last_mut() {
+                Some(file) if in_git_header => {
+                    // The paths in the `diff --git` line are ambiguous if they
+                    // contain spaces.
+                    if file.old_path.is_some() {
+                        file.old_path = old_path;
+                    }
+                    if file.new_path.is_some() {
+                        file.new_path = new_path;
+                    }
+                }
+                _ => files.push(FilePatch {
+                    old_path,
+                    new_path,
+                    ..FilePatch::default()
+                }),
+            }
+            in_git_header = false;
+            index += 1;
+        } else if line.starts_with(b"@@ ") {
+            let file = files
+                .last_mut()
+                .ok_or(PatchParseError::MissingFileHeader { line: line_number })?;
+            let (hunk, num_lines) = parse_hunk(&lines[index..], line_number)?;
+            file.hunks.push(hunk);
+            in_git_header = false;
+            index += num_lines;
+            continue;
+        } else if let Some(file) = files.last_mut().filter(|_| in_git_header) {
+            parse_extended_header(file, line.trim_end(), line_number)?;
+        }
+        index += 1;
+    }
+    Ok(files)
+}
+
+fn parse_extended_header(
+    file: &mut FilePatch,
+    line: &[u8],
+    line_number: usize,
+) -> Result<(), PatchParseError> {
+    let to_repo_path = |path: &[u8]| parse_path(&unquote_path(path), line_number);
+    if let Some(mode) = line.strip_prefix(b"old mode ") {
+        file.old_mode = FileMode::parse(mode);
+    } else if let Some(mode) = line.strip_prefix(b"new mode ") {
+        file.new_mode = FileMode::parse(mode);
+    } else if let Some(mode) = line.strip_prefix(b"deleted file mode ") {
+        file.old_mode = FileMode::parse(mode);
+        file.new_path = None;
+    } else if let Some(mode) = line.strip_prefix(b"new file mode ") {
+        file.new_mode = FileMode::parse(mode);
+        file.old_path = None;
+    } else if let Some(path) = line.strip_prefix(b"rename from ") {
+        file.old_path = Some(to_repo_path(path)?);
+    } else if let Some(path) = line.strip_prefix(b"rename to ") {
+        file.new_path = Some(to_repo_path(path)?);
+    } else if let Some(path) = line.strip_prefix(b"copy from ") {
+        file.old_path = Some(to_repo_path(path)?);
+        file.is_copy = true;
+    } else if let Some(path) = line.strip_prefix(b"copy to ") {
+        file.new_path = Some(to_repo_path(path)?);
+        file.is_copy = true;
+    } else if let Some(ids) = line.strip_prefix(b"index ") {
+        let (ids, mode) = ids.split_once_str(" ").unwrap_or((ids, b""));
+        if let Some((_, new_id)) = ids.split_once_str("..") {
+            file.new_id = Some(new_id.to_str_lossy().into_owned());
+        }
+        if let Some(mode) = FileMode::parse(mode) {
+            file.old_mode.get_or_insert(mode);
+            file.new_mode.get_or_insert(mode);
+        }
+    } else if line.starts_with(b"Binary files ") || line == b"GIT binary patch" {
+        file.is_binary = true;
+    }
+    Ok(())
+}
+
+/// Parses the `a/OLD b/NEW` part of a `diff --git` line.
+fn parse_git_diff_paths(paths: &[u8]) -> (Option<BString>, Option<BString>) {
+    if paths.starts_with(b"\"") {
+        let (old, rest) = split_quoted(paths);
+        let new = unquote_path(rest.trim_start());
+        return (strip_path_prefix(&old), strip_path_prefix(&new));
+    }
+    if paths.ends_with(b"\"") {
+        if let Some(pos) = paths.rfind(" \"") {
+            let new = unquote_path(&paths[pos + 1..]);
+            return (strip_path_prefix(&paths[..pos]), strip_path_prefix(&new));
+        }
+    }
+    // The paths are identical unless the file was renamed or copied, in which
+    // case they're overridden by the extended headers.
+    let candidates = paths.find_iter(" b/").collect::<Vec<_>>();
+    let pos = candidates
+        .iter()
+        .copied()
+        .find(|&pos| paths[..pos].strip_prefix(b"a/") == Some(&paths[pos + 3..]))
+        .or(candidates.first().copied());
+    match pos {
+        Some(pos) => (
+            strip_path_prefix(&paths[..pos]),
+            strip_path_prefix(&paths[pos + 1..]),
+        ),
+        None => (None, None),
+    }
+}
+
+/// Parses the path of a `---` or `+++` line. Returns `None` for `/dev/null`.
+fn parse_patch_path(path: &[u8]) -> Option<BString> {
+    let path = path.trim_end();
+    let path = if path.starts_with(b"\"") {
+        unquote_path(path)
+    } else {
+        // `diff -u` appends a timestamp after a tab
+        BString::from(path.split_str("\t").next().unwrap_or_default())
+    };
+    if path == "/dev/null" {
+        None
+    } else {
+        strip_path_prefix(&path)
+    }
+}
+
+/// Strips the `a/` or `b/` prefix from a path. Other paths, such as the ones
+/// of a plain `diff -u`, are kept as is.
+fn strip_path_prefix(path: &[u8]) -> Option<BString> {
+    let path = path
+        .strip_prefix(b"a/")
+        .or_else(|| path.strip_prefix(b"b/"))
+        .unwrap_or(path);
+    Some(path.into())
+}
+
+/// Splits a leading C-style quoted string from the rest.
+fn split_quoted(text: &[u8]) -> (BString, &[u8]) {
+    let mut unquoted = BString::default();
+    let mut iter = text.iter().enumerate().skip(1);
+    while let Some((i, &b)) = iter.next() {
+        match b {
+            b'"' => return (unquoted, &text[i + 1..]),
+            b'\\' => {
+                let Some((_, &escaped)) = iter.next() else {
+                    break;
+                };
+                match escaped {
+                    b'a' => unquoted.push(b'\x07'),
+                    b'b' => unquoted.push(b'\x08'),
+                    b't' => unquoted.push(b'\t'),
+                    b'n' => unquoted.push(b'\n'),
+                    b'v' => unquoted.push(b'\x0b'),
+                    b'f' => unquoted.push(b'\x0c'),
+                    b'r' => unquoted.push(b'\r'),
+                    b'0'..=b'7' => {
+                        let mut value = u32::from(escaped - b'0');
+                        for _ in 0..2 {
+                            if let Some((_, &digit @ b'0'..=b'7')) = iter.clone().next() {
+                                value = value * 8 + u32::from(digit - b'0');
+                                iter.next();
+                            }
+                        }
+                        unquoted.push(value as u8);
+                    }
+                    _ => unquoted.push(escaped),
+                }
+            }
+            _ => unquoted.push(b),
+        }
+    }
+    (unquoted, b"")
+}
+
+fn unquote_path(path: &[u8]) -> BString {
+    if path.starts_with(b"\"") {
+        split_quoted(path).0
+    } else {
+        path.into()
+    }
+}
+
+fn parse_path(path: &[u8], line_number: usize) -> Result<RepoPathBuf, PatchParseError> {
+    let invalid_path = || PatchParseError::InvalidPath {
+        line: line_number,
+        path: path.to_str_lossy().into_owned(),
+    };
+    let path = path.to_str().map_err(|_| invalid_path())?;
+    // Patches shouldn't be able to write outside of the workspace.
+    if path
+        .split('/')
+        .any(|component| matches!(component, "" | "." | ".."))
+    {
+        return Err(invalid_path());
+    }
+    RepoPathBuf::from_internal_string(path).map_err(|_| invalid_path())
+}
+
+/// Parses a hunk starting at its header. Returns the hunk and the number of
+/// lines it spans.
+fn parse_hunk(lines: &[&[u8]], line_number: usize) -> Result<(PatchHunk, usize), PatchParseError> {
+    let (old_start, mut old_remaining, mut new_remaining) = parse_hunk_header(lines[0])
+        .ok_or(PatchParseError::InvalidHunkHeader { line: line_number })?;
+    let mut hunk = PatchHunk {
+        old_start,
+        old_lines: vec![],
+        new_lines: vec![],
+    };
+    let mut index = 1;
+    // Which sides the last line belongs to, for "\ No newline at end of file"
+    let mut last_sides = (false, false);
+    loop {
+        let line = lines.get(index).copied();
+        let truncated = || PatchParseError::TruncatedHunk {
+            line: line_number + index,
+        };
+        if line.is_some_and(|line| line.starts_with(b"\\")) {
+            let (old, new) = last_sides;
+            for (is_side, side_lines) in [(old, &mut hunk.old_lines), (new, &mut hunk.new_lines)] {
+                if let Some(last) = side_lines.last_mut().filter(|_| is_side) {
+                    if last.ends_with(b"\n") {
+                        last.pop();
+                    }
+                }
+            }
+            index += 1;
+            continue;
+        }
+        if old_remaining == 0 && new_remaining == 0 {
+            break;
+        }
+        let line = line.ok_or_else(truncated)?;
+        let (kind, content) = match line.split_first() {
+            // Some tools strip the trailing space of empty context lines.
+            Some((b'\n', _)) | None => (b' ', line),
+            Some((&kind, content)) => (kind, content),
+        };
+        let sides = match kind {
+            b' ' => (true, true),
+            b'-' => (true, false),
+            b'+' => (false, true),
+            _ => return Err(truncated()),
+        };
+        if sides.0 {
+            old_remaining = old_remaining.checked_sub(1).ok_or_else(truncated)?;
+            hunk.old_lines.push(content.into());
+        }
+        if sides.1 {
+            new_remaining = new_remaining.checked_sub(1).ok_or_else(truncated)?;
+            hunk.new_lines.push(content.into());
+        }
+        last_sides = sides;
+        index += 1;
+    }
+    Ok((hunk, index))
+}
+
+/// Parses `@@ -OLD_START[,OLD_COUNT] +NEW_START[,NEW_COUNT] @@`, and returns
+/// the old start and the counts.
+fn parse_hunk_header(line: &[u8]) -> Option<(usize, usize, usize)> {
+    let line = line.to_str().ok()?;
+    let ranges = line.strip_prefix("@@ -")?.split(" @@").next()?;
+    let (old, new) = ranges.split_once(" +")?;
+    let parse_range = |range: &str| -> Option<(usize, usize)> {
+        match range.split_once(',') {
+            Some((start, count)) => Some((start.parse().ok()?, count.parse().ok()?)),
+            None => Some((range.parse().ok()?, 1)),
+        }
+    };
+    let (old_start, old_count) = parse_range(old)?;
+    let (_, new_count) = parse_range(new)?;
+    Some((old_start, old_count, new_count))
+}
+
+/// Applies the `patch` to the `tree`.
+///
+/// Hunks which don't match the contents of the tree are merged into it, so
+/// they result in conflicts instead of errors.
+pub fn apply_patch(tree: &MergedTree, patch: &Patch) -> Result<MergedTree, PatchApplyError> {
+    let store = tree.store();
+    // The patch is applied by merging the changes from a tree which matches
+    // the old side of the hunks to a tree which matches the new side.
+    let mut base_builder = MergedTreeBuilder::new(tree.id());
+    let mut result_builder = MergedTreeBuilder::new(tree.id());
+    for file in &patch.files {
+        apply_file_patch(store, tree, file, &mut base_builder, &mut result_builder)?;
+    }
+    let base_tree = store.get_root_tree(&base_builder.write_tree(store)?)?;
+    let result_tree = store.get_root_tree(&result_builder.write_tree(store)?)?;
+    Ok(tree.merge(&base_tree, &result_tree)?)
+}
+
+fn apply_file_patch(
+    store: &Store,
+    tree: &MergedTree,
+    file: &FilePatch,
+    base_builder: &mut MergedTreeBuilder,
+    result_builder: &mut MergedTreeBuilder,
+) -> Result<(), PatchApplyError> {
+    let current = match &file.old_path {
+        Some(path) => read_file(store, tree, path)?,
+        None => Merge::absent(),
+    };
+    if file.old_path.is_some() && current.is_absent() && file.hunks.is_empty() {
+        let path = file.old_path.clone().unwrap();
+        return Err(PatchApplyError::MissingFile { path });
+    }
+    let binary_contents = match &file.new_path {
+        Some(path) if file.is_binary => Some(read_binary_contents(store, path, file)?),
+        _ => None,
+    };
+
+    // The patch is applied to each side of a conflicted file. Since the sides
+    // of the base match the sides of the tree where the hunks apply, merging
+    // leaves a conflict between the patched sides.
+    let mut base_values = vec![];
+    let mut result_values = vec![];
+    for side in current.iter() {
+        let current_mode = side.as_ref().map(|(_, mode)| *mode);
+        let base_mode = file.old_mode.or(current_mode).unwrap_or(FileMode::Normal);
+        let result_mode = file.new_mode.unwrap_or(base_mode);
+        let current_contents = side
+            .as_ref()
+            .map(|(contents, _)| contents.clone())
+            .unwrap_or_default();
+        let (base_contents, result_contents) = if file.is_binary {
+            (
+                current_contents,
+                binary_contents.clone().unwrap_or_default(),
+            )
+        } else {
+            apply_hunks(&current_contents, &file.hunks)
+        };
+        if let Some(path) = &file.old_path {
+            base_values.push(Some(write_value(store, path, &base_contents, base_mode)?));
+        }
+        if let Some(path) = &file.new_path {
+            result_values.push(Some(write_value(
+                store,
+                path,
+                &result_contents,
+                result_mode,
+            )?));
+        }
+    }
+
+    if let Some(path) = &file.old_path {
+        let value = Merge::from_vec(base_values);
+        base_builder.set_or_remove(path.clone(), value.clone());
+        if file.new_path.as_ref() != Some(path) {
+            if file.is_copy {
+                result_builder.set_or_remove(path.clone(), value);
+            } else {
+                result_builder.set_or_remove(path.clone(), Merge::absent());
+            }
+        }
+    }
+    if let Some(path) = &file.new_path {
+        if file.old_path.as_ref() != Some(path) {
+            base_builder.set_or_remove(path.clone(), Merge::absent());
+        }
+        result_builder.set_or_remove(path.clone(), Merge::from_vec(result_values).simplify());
+    }
+    Ok(())
+}
+
+/// Reads the contents of each side of the file at `path` as they're shown in
+/// a patch.
+fn read_file(
+    store: &Store,
+    tree: &MergedTree,
+    path: &RepoPath,
+) -> Result<Merge<Option<(BString, FileMode)>>, PatchApplyError> {
+    tree.path_value(path)?
+        .try_map(|value| read_value(store, path, value.as_ref()))
+}
+
+fn read_value(
+    store: &Store,
+    path: &RepoPath,
+    value: Option<&TreeValue>,
+) -> Result<Option<(BString, FileMode)>, PatchApplyError> {
+    match value {
+        None => Ok(None),
+        Some(TreeValue::File { id, executable }) => {
+            let contents = read_file_contents(store, path, id)?;
+            let mode = if *executable {
+                FileMode::Executable
+            } else {
+                FileMode::Normal
+            };
+            Ok(Some((contents, mode)))
+        }
+        Some(TreeValue::Symlink(id)) => {
+            let target = store.read_symlink(path, id)?;
+            Ok(Some((target.into(), FileMode::Symlink)))
+        }
+        Some(TreeValue::GitSubmodule(id)) => {
+            let contents = format!("Subproject commit {}\n", id.hex());
+            Ok(Some((contents.into(), FileMode::GitSubmodule)))
+        }
+        Some(TreeValue::Tree(_)) => Err(PatchApplyError::NotAFile {
+            path: path.to_owned(),
+        }),
+        Some(TreeValue::Conflict(_)) => Err(PatchApplyError::ConflictedFile {
+            path: path.to_owned(),
+        }),
+    }
+}
+
+fn read_file_contents(
+    store: &Store,
+    path: &RepoPath,
+    id: &FileId,
+) -> Result<BString, BackendError> {
+    let mut contents = vec![];
+    store
+        .read_file(path, id)?
+        .read_to_end(&mut contents)
+        .map_err(|err| BackendError::ReadFile {
+            path: path.to_owned(),
+            id: id.clone(),
+            source: err.into(),
+        })?;
+    Ok(contents.into())
+}
+
+/// Looks up the new contents of a binary file by the id in the patch, which
+/// only succeeds if the id isn't abbreviated and the object is in the store.
+fn read_binary_contents(
+    store: &Store,
+    path: &RepoPath,
+    file: &FilePatch,
+) -> Result<BString, PatchApplyError> {
+    let missing_data = || PatchApplyError::MissingBinaryData {
+        path: path.to_owned(),
+    };
+    let id = file
+        .new_id
+        .as_deref()
+        .and_then(|hex| FileId::try_from_hex(hex).ok())
+        .ok_or_else(missing_data)?;
+    read_file_contents(store, path, &id).map_err(|_| missing_data())
+}
+
+fn write_value(
+    store: &Store,
+    path: &RepoPath,
+    contents: &[u8],
+    mode: FileMode,
+) -> Result<TreeValue, PatchApplyError> {
+    let invalid_contents = || PatchApplyError::InvalidContents {
+        path: path.to_owned(),
+    };
+    match mode {
+        FileMode::Normal | FileMode::Executable => {
+            let id = store.write_file(path, &mut &contents[..]).block_on()?;
+            let executable = mode == FileMode::Executable;
+            Ok(TreeValue::File { id, executable })
+        }
+        FileMode::Symlink => {
+            let target = contents.to_str().map_err(|_| invalid_contents())?;
+            let id = store.write_symlink(path, target).block_on()?;
+            Ok(TreeValue::Symlink(id))
+        }
+        FileMode::GitSubmodule => {
+            let id = contents
+                .trim_end()
+                .strip_prefix(b"Subproject commit ")
+                .and_then(|hex| CommitId::try_from_hex(hex.to_str().ok()?).ok())
+                .ok_or_else(invalid_contents)?;
+            Ok(TreeValue::GitSubmodule(id))
+        }
+    }
+}
+
+/// Applies the hunks to `contents`. Returns the contents the hunks were
+/// expected to apply to, and the result.
+///
+/// Hunks are located by their old lines, starting at the line number in the
+/// hunk header adjusted by the offset of the previous hunk. If a hunk doesn't
+/// match anywhere, its old lines replace the lines at that position in the
+/// expected contents, so merging the result into `contents` conflicts.
+fn apply_hunks(contents: &[u8], hunks: &[PatchHunk]) -> (BString, BString) {
+    let lines: Vec<&[u8]> = contents.split_inclusive(|b| *b == b'\n').collect();
+    let mut base = BString::default();
+    let mut result = BString::default();
+    let mut pos = 0;
+    let mut offset: isize = 0;
+    for hunk in hunks {
+        let header_start = if hunk.old_lines.is_empty() {
+            hunk.old_start
+        } else {
+            hunk.old_start.saturating_sub(1)
+        };
+        let expected = header_start
+            .saturating_add_signed(offset)
+            .clamp(pos, lines.len());
+        let (start, end) = match find_lines(&lines, &hunk.old_lines, pos, expected) {
+            Some(start) => {
+                offset = start as isize - header_start as isize;
+                (start, start + hunk.old_lines.len())
+            }
+            None => (expected, lines.len().min(expected + hunk.old_lines.len())),
+        };
+        for line in &lines[pos..start] {
+            base.extend_from_slice(line);
+            result.extend_from_slice(line);
+        }
+        for line in &hunk.old_lines {
+            base.extend_from_slice(line);
+        }
+        for line in &hunk.new_lines {
+            result.extend_from_slice(line);
+        }
+        pos = end;
+    }
+    for line in &lines[pos..] {
+        base.extend_from_slice(line);
+        result.extend_from_slice(line);
+    }
+    (base, result)
+}
+
+/// Finds the position of `needle` in `lines` closest to `expected`, not before
+/// `min_start`.
+fn find_lines(
+    lines: &[&[u8]],
+    needle: &[BString],
+    min_start: usize,
+    expected: usize,
+) -> Option<usize> {
+    let max_start = lines.len().checked_sub(needle.len())?;
+    if min_start > max_start {
+        return None;
+    }
+    let matches_at = |start: usize| {
+        lines[start..start + needle.len()]
+            .iter()
+            .zip(needle)
+            .all(|(line, expected)| *line == expected.as_slice())
+    };
+    let expected = expected.clamp(min_start, max_start);
+    (0..=max_start - min_start)
+        .flat_map(|distance| {
+            let before = expected
+                .checked_sub(distance)
+                .filter(|&pos| pos >= min_start);
+            let after = Some(expected + distance).filter(|&pos| pos <= max_start && distance > 0);
+            [before, after]
+        })
+        .flatten()
+        .find(|&pos| matches_at(pos))
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    fn repo_path_buf(value: &str) -> RepoPathBuf {
+        RepoPathBuf::from_internal_string(value).unwrap()
+    }
+
+    fn hunk(old_start: usize, old_lines: &[&str], new_lines: &[&str]) -> PatchHunk {
+        PatchHunk {
+            old_start,
+            old_lines: old_lines.iter().map(|line| (*line).into()).collect(),
+            new_lines: new_lines.iter().map(|line| (*line).into()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_parse_mbox() {
+        let input = indoc! {r#"
+            From 0123456789abcdef0123456789abcdef01234567 Mon Sep 17 00:00:00 2001
+            From: =?UTF-8?q?J=C3=B6rg=20Doe?= <jorg@example.com>
+            Date: Sat, 1 Feb 2025 10:11:12 +0100
+            Subject: [PATCH 1/2] Fix the frobnicator so that it doesn't crash on
+             empty input
+
+            The frobnicator crashed.
+
+            Signed-off-by: Jörg Doe <jorg@example.com>
+            ---
+             file | 2 +-
+             1 file changed, 1 insertion(+), 1 deletion(-)
+
+            diff --git a/file b/file
+            index 257cc56..3bd1f0e 100644
+            --- a/file
+            +++ b/file
+            @@ -1 +1 @@
+            -foo
+            +bar
+            --
+            2.47.0
+
+            From 89abcdef0123456789abcdef0123456789abcdef Mon Sep 17 00:00:00 2001
+            From: Alice <alice@example.com>
+            Subject: [PATCH 2/2] Rename file
+
+            ---
+            diff --git a/file b/renamed
+            similarity index 100%
+            rename from file
+            rename to renamed
+            --
+            2.47.0
+        "#};
+        let patches = parse_patches(input.as_bytes()).unwrap();
+        assert_eq!(patches.len(), 2);
+        assert_eq!(
+            patches[0].author,
+            Some(PatchAuthor {
+                name: "Jörg Doe".to_owned(),
+                email: "jorg@example.com".to_owned(),
+                timestamp: Some(Timestamp {
+                    timestamp: crate::backend::MillisSinceEpoch(1_738_401_072_000),
+                    tz_offset: 60,
+                }),
+            })
+        );
+        insta::assert_snapshot!(patches[0].description, @r"
+        Fix the frobnicator so that it doesn't crash on empty input
+
+        The frobnicator crashed.
+
+        Signed-off-by: Jörg Doe <jorg@example.com>
+        ");
+        assert_eq!(
+            patches[0].files,
+            vec![FilePatch {
+                old_path: Some(repo_path_buf("file")),
+                new_path: Some(repo_path_buf("file")),
+                old_mode: Some(FileMode::Normal),
+                new_mode: Some(FileMode::Normal),
+                new_id: Some("3bd1f0e".to_owned()),
+                hunks: vec![hunk(1, &["foo\n"], &["bar\n"])],
+                ..FilePatch::default()
+            }]
+        );
+
+        assert_eq!(patches[1].author.as_ref().unwrap().timestamp, None);
+        assert_eq!(patches[1].description, "Rename file\n");
+        assert_eq!(
+            patches[1].files,
+            vec![FilePatch {
+                old_path: Some(repo_path_buf("file")),
+                new_path: Some(repo_path_buf("renamed")),
+                ..FilePatch::default()
+            }]
+        );
+    }
+
+    #[test]
+    fn test_parse_git_diff() {
+        let input = indoc! {r#"
+            diff --git a/added b/added
+            new file mode 100755
+            index 0000000..257cc56
+            --- /dev/null
+            +++ b/added
+            @@ -0,0 +1,2 @@
+            +foo
+            +bar
+            \ No newline at end of file
+            diff --git a/deleted b/deleted
+            deleted file mode 100644
+            index 257cc56..0000000
+            Binary files a/deleted and /dev/null differ
+            diff --git a/old b/new
+            similarity index 80%
+            copy from old
+            copy to new
+            index 1111111..2222222
+            --- a/old
+            +++ b/new
+            @@ -1,3 +1,3 @@ fn context()
+             a
+
+            -b
+            +c
+            diff --git a/mode b/mode
+            old mode 100644
+            new mode 100755
+            diff --git "a/with\ttab" "b/with\ttab"
+            index 1111111..2222222 120000
+            --- "a/with\ttab"
+            +++ "b/with\ttab"
+            @@ -1 +1 @@
+            -target
+            \ No newline at end of file
+            +new target
+            \ No newline at end of file
+        "#};
+        let patches = parse_patches(input.as_bytes()).unwrap();
+        assert_eq!(patches.len(), 1);
+        assert_eq!(patches[0].author, None);
+        assert_eq!(patches[0].description, "");
+        assert_eq!(
+            patches[0].files,
+            vec![
+                FilePatch {
+                    old_path: None,
+                    new_path: Some(repo_path_buf("added")),
+                    new_mode: Some(FileMode::Executable),
+                    new_id: Some("257cc56".to_owned()),
+                    hunks: vec![hunk(0, &[], &["foo\n", "bar"])],
+                    ..FilePatch::default()
+                },
+                FilePatch {
+                    old_path: Some(repo_path_buf("deleted")),
+                    new_path: None,
+                    old_mode: Some(FileMode::Normal),
+                    new_id: Some("0000000".to_owned()),
+                    is_binary: true,
+                    ..FilePatch::default()
+                },
+                FilePatch {
+                    old_path: Some(repo_path_buf("old")),
+                    new_path: Some(repo_path_buf("new")),
+                    is_copy: true,
+                    new_id: Some("2222222".to_owned()),
+                    hunks: vec![hunk(1, &["a\n", "\n", "b\n"], &["a\n", "\n", "c\n"])],
+                    ..FilePatch::default()
+                },
+                FilePatch {
+                    old_path: Some(repo_path_buf("mode")),
+                    new_path: Some(repo_path_buf("mode")),
+                    old_mode: Some(FileMode::Normal),
+                    new_mode: Some(FileMode::Executable),
+                    ..FilePatch::default()
+                },
+                FilePatch {
+                    old_path: Some(repo_path_buf("with\ttab")),
+                    new_path: Some(repo_path_buf("with\ttab")),
+                    old_mode: Some(FileMode::Symlink),
+                    new_mode: Some(FileMode::Symlink),
+                    new_id: Some("2222222".to_owned()),
+                    hunks: vec![hunk(1, &["target"], &["new target"])],
+                    ..FilePatch::default()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_unified_diff() {
+        let input = indoc! {"
+            --- dir/file.orig\t2025-01-01 00:00:00.000000000 +0000
+            +++ dir/file\t2025-01-01 00:00:00.000000000 +0000
+            @@ -2,2 +2,3 @@
+             b
+            +c
+             d
+        "};
+        let patches = parse_patches(input.as_bytes()).unwrap();
+        assert_eq!(
+            patches[0].files,
+            vec![FilePatch {
+                old_path: Some(repo_path_buf("dir/file.orig")),
+                new_path: Some(repo_path_buf("dir/file")),
+                hunks: vec![hunk(2, &["b\n", "d\n"], &["b\n", "c\n", "d\n"])],
+                ..FilePatch::default()
+            }]
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let parse = |input: &str| parse_patches(input.as_bytes()).unwrap_err().to_string();
+        insta::assert_snapshot!(parse("@@ -1 +1 @@\n-a\n+b\n"), @"Hunk without file header at line 1");
+        insta::assert_snapshot!(
+            parse("--- a/file\n+++ b/file\n@@ -1 +1 @\n"),
+            @"Invalid hunk header at line 3");
+        insta::assert_snapshot!(
+            parse("--- a/file\n+++ b/file\n@@ -1,2 +1 @@\n-a\n"),
+            @"Truncated hunk at line 5");
+        insta::assert_snapshot!(
+            parse("--- a/file\n+++ b/../file\n"),
+            @r#"Invalid path "../file" at line 1"#);
+    }
+
+    #[test]
+    fn test_apply_hunks() {
+        let contents = b"a\nb\nc\nd\ne\n";
+        // Exact position
+        let (base, result) = apply_hunks(contents, &[hunk(2, &["b\n"], &["B\n"])]);
+        assert_eq!(base, contents.as_slice());
+        assert_eq!(result, "a\nB\nc\nd\ne\n");
+
+        // Shifted hunks, the offset of the first one applies to the next ones
+        let (base, result) = apply_hunks(
+            contents,
+            &[
+                hunk(1, &["c\n"], &["C\n"]),
+                hunk(2, &["d\n"], &[]),
+                hunk(3, &[], &["new\n"]),
+            ],
+        );
+        assert_eq!(base, contents.as_slice());
+        assert_eq!(result, "a\nb\nC\ne\nnew\n");
+
+        // The old lines of a mismatched hunk replace the lines at its position
+        let (base, result) = apply_hunks(contents, &[hunk(2, &["x\n", "y\n"], &["z\n"])]);
+        assert_eq!(base, "a\nx\ny\nd\ne\n");
+        assert_eq!(result, "a\nz\nd\ne\n");
+
+        // Mismatched hunk past the end
+        let (base, result) = apply_hunks(b"a\n", &[hunk(5, &["x\n"], &["y\n"])]);
+        assert_eq!(base, "a\nx\n");
+        assert_eq!(result, "a\ny\n");
+    }
+}
@@ -112,6 +112,34 @@ pub struct View {
     pub git_head_legacy: ::prost::alloc::vec::Vec<u8>,
     #[prost(message, optional, tag = "9")]
     pub git_head: ::core::option::Option<RefTarget>,
+    #[prost(message, optional, tag = "11")]
+    pub bisect: ::core::option::Option<BisectState>,
+    #[prost(message, repeated, tag = "12")]
+    pub tag_annotations: ::prost::alloc::vec::Vec<TagAnnotation>,
+}
+/// State of an in-progress `jj bisect`.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct BisectState {
+    #[prost(bytes = "vec", repeated, tag = "1")]
+    pub good: ::prost::alloc::vec::Vec<::prost::alloc::vec::Vec<u8>>,
+    #[prost(bytes = "vec", repeated, tag = "2")]
+    pub bad: ::prost::alloc::vec::Vec<::prost::alloc::vec::Vec<u8>>,
+    #[prost(bytes = "vec", repeated, tag = "3")]
+    pub skipped: ::prost::alloc::vec::Vec<::prost::alloc::vec::Vec<u8>>,
+}
+/// Annotation of a tag which hasn't been exported to Git yet.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TagAnnotation {
+    #[prost(string, tag = "1")]
+    pub name: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub message: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub tagger_name: ::prost::alloc::string::String,
+    #[prost(string, tag = "4")]
+    pub tagger_email: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "5")]
+    pub tagger_timestamp: ::core::option::Option<Timestamp>,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct Operation {
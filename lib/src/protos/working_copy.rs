@@ -19,6 +19,9 @@ pub struct FileState {
     pub conflict_id: ::prost::alloc::vec::Vec<u8>,
     #[prost(message, optional, tag = "5")]
     pub materialized_conflict_data: ::core::option::Option<MaterializedConflictData>,
+    /// Set only if file_type is GitSubmodule and the submodule was checked out
+    #[prost(bytes = "vec", tag = "6")]
+    pub git_submodule_head: ::prost::alloc::vec::Vec<u8>,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct FileStateEntry {
@@ -28,9 +31,20 @@ pub struct FileStateEntry {
     pub state: ::core::option::Option<FileState>,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SparseProfile {
+    #[prost(string, tag = "1")]
+    pub name: ::prost::alloc::string::String,
+    /// The profile file content at the time it was resolved.
+    #[prost(string, tag = "2")]
+    pub content: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct SparsePatterns {
     #[prost(string, repeated, tag = "1")]
     pub prefixes: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// Paths selected by the profile are checked out in addition to the prefixes.
+    #[prost(message, optional, tag = "2")]
+    pub profile: ::core::option::Option<SparseProfile>,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct TreeState {
@@ -48,6 +62,11 @@ pub struct TreeState {
     pub sparse_patterns: ::core::option::Option<SparsePatterns>,
     #[prost(message, optional, tag = "4")]
     pub watchman_clock: ::core::option::Option<WatchmanClock>,
+    /// New files that were tracked regardless of the snapshot policy.
+    #[prost(string, repeated, tag = "7")]
+    pub force_tracked_paths: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    #[prost(string, optional, tag = "8")]
+    pub inotify_clock: ::core::option::Option<::prost::alloc::string::String>,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct WatchmanClock {
@@ -15,6 +15,7 @@
 #![allow(missing_docs)]
 
 use std::collections::hash_map::Entry;
+use std::collections::BTreeSet;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::fmt::Debug;
@@ -38,6 +39,7 @@ use crate::backend::BackendResult;
 use crate::backend::ChangeId;
 use crate::backend::CommitId;
 use crate::backend::MergedTreeId;
+use crate::cas_op_heads_store::CasOpHeadsStore;
 use crate::commit::Commit;
 use crate::commit::CommitByCommitterTimestamp;
 use crate::commit_builder::CommitBuilder;
@@ -64,6 +66,7 @@ use crate::op_heads_store::OpHeadResolutionError;
 use crate::op_heads_store::OpHeadsStore;
 use crate::op_heads_store::OpHeadsStoreError;
 use crate::op_store;
+use crate::op_store::BisectState;
 use crate::op_store::OpStore;
 use crate::op_store::OpStoreError;
 use crate::op_store::OpStoreResult;
@@ -72,6 +75,7 @@ use crate::op_store::RefTarget;
 use crate::op_store::RemoteRef;
 use crate::op_store::RemoteRefState;
 use crate::op_store::RootOperationData;
+use crate::op_store::TagAnnotation;
 use crate::operation::Operation;
 use crate::ref_name::GitRefName;
 use crate::ref_name::RefName;
@@ -172,7 +176,18 @@ impl ReadonlyRepo {
     }
 
     pub fn default_op_heads_store_initializer() -> &'static OpHeadsStoreInitializer<'static> {
-        &|_settings, store_path| Ok(Box::new(SimpleOpHeadsStore::init(store_path)?))
+        &|settings, store_path| {
+            let store_type = settings
+                .get_string("core.op-heads-store")
+                .map_err(|err| BackendInitError(err.into()))?;
+            match store_type.as_str() {
+                "simple" => Ok(Box::new(SimpleOpHeadsStore::init(store_path)?)),
+                "cas" => Ok(Box::new(CasOpHeadsStore::init(store_path)?)),
+                _ => Err(BackendInitError(
+                    format!("Unknown operation heads store type: {store_type}").into(),
+                )),
+            }
+        }
     }
 
     pub fn default_index_store_initializer() -> &'static IndexStoreInitializer<'static> {
@@ -180,7 +195,15 @@ impl ReadonlyRepo {
     }
 
     pub fn default_submodule_store_initializer() -> &'static SubmoduleStoreInitializer<'static> {
-        &|_settings, store_path| Ok(Box::new(DefaultSubmoduleStore::init(store_path)))
+        &|settings, store_path| {
+            let git_settings = settings
+                .git_settings()
+                .map_err(|err| BackendInitError(err.into()))?;
+            Ok(Box::new(DefaultSubmoduleStore::init(
+                store_path,
+                &git_settings.executable_path,
+            )))
+        }
     }
 
     #[expect(clippy::too_many_arguments)]
@@ -440,6 +463,10 @@ impl Default for StoreFactories {
             SimpleOpHeadsStore::name(),
             Box::new(|_settings, store_path| Ok(Box::new(SimpleOpHeadsStore::load(store_path)))),
         );
+        factories.add_op_heads_store(
+            CasOpHeadsStore::name(),
+            Box::new(|_settings, store_path| Ok(Box::new(CasOpHeadsStore::load(store_path)))),
+        );
 
         // Index
         factories.add_index_store(
@@ -450,7 +477,15 @@ impl Default for StoreFactories {
         // SubmoduleStores
         factories.add_submodule_store(
             DefaultSubmoduleStore::name(),
-            Box::new(|_settings, store_path| Ok(Box::new(DefaultSubmoduleStore::load(store_path)))),
+            Box::new(|settings, store_path| {
+                let git_settings = settings
+                    .git_settings()
+                    .map_err(|err| BackendLoadError(err.into()))?;
+                Ok(Box::new(DefaultSubmoduleStore::load(
+                    store_path,
+                    &git_settings.executable_path,
+                )))
+            }),
         );
 
         factories
@@ -1678,8 +1713,21 @@ impl MutableRepo {
         self.view.with_ref(|v| v.get_tag(name).clone())
     }
 
+    /// Sets tag to point to the given target, and removes the annotation it
+    /// had. If the target is absent, the tag will be removed.
     pub fn set_tag_target(&mut self, name: &RefName, target: RefTarget) {
-        self.view_mut().set_tag_target(name, target);
+        let view = self.view_mut();
+        view.set_tag_target(name, target);
+        view.set_tag_annotation(name, None);
+    }
+
+    pub fn get_tag_annotation(&self, name: &RefName) -> Option<TagAnnotation> {
+        self.view.with_ref(|v| v.get_tag_annotation(name).cloned())
+    }
+
+    /// Sets or removes the annotation to export the tag `name` with.
+    pub fn set_tag_annotation(&mut self, name: &RefName, annotation: Option<TagAnnotation>) {
+        self.view_mut().set_tag_annotation(name, annotation);
     }
 
     pub fn merge_tag(&mut self, name: &RefName, base_target: &RefTarget, other_target: &RefTarget) {
@@ -1719,6 +1767,14 @@ impl MutableRepo {
         self.view_mut().set_git_head_target(target);
     }
 
+    pub fn bisect_state(&self) -> Option<BisectState> {
+        self.view.with_ref(|v| v.bisect_state().cloned())
+    }
+
+    pub fn set_bisect_state(&mut self, state: Option<BisectState>) {
+        self.view_mut().set_bisect_state(state);
+    }
+
     pub fn set_view(&mut self, data: op_store::View) {
         self.view_mut().set_view(data);
         self.view.mark_dirty();
@@ -1806,6 +1862,33 @@ impl MutableRepo {
         );
         self.set_git_head_target(new_git_head_target);
 
+        // Concurrent bisect decisions can't be merged. Keep ours in that case.
+        let new_bisect_state = trivial_merge(&[
+            self.view().bisect_state(),
+            base.bisect_state(),
+            other.bisect_state(),
+        ])
+        .map(|state| state.cloned());
+        if let Some(state) = new_bisect_state {
+            self.set_bisect_state(state);
+        }
+
+        // Same for concurrently changed annotations of a tag
+        let annotated_tag_names: BTreeSet<&RefName> = itertools::chain!(
+            base.tag_annotations().map(|(name, _)| name),
+            other.tag_annotations().map(|(name, _)| name),
+        )
+        .collect();
+        for name in annotated_tag_names {
+            let base_annotation = base.get_tag_annotation(name);
+            let other_annotation = other.get_tag_annotation(name);
+            if other_annotation != base_annotation
+                && self.view().get_tag_annotation(name) == base_annotation
+            {
+                self.set_tag_annotation(name, other_annotation.cloned());
+            }
+        }
+
         Ok(())
     }
 
@@ -66,6 +66,7 @@ pub use crate::revset_parser::RevsetDiagnostics;
 pub use crate::revset_parser::RevsetParseError;
 pub use crate::revset_parser::RevsetParseErrorKind;
 pub use crate::revset_parser::UnaryOp;
+use crate::signing::SigTrust;
 use crate::store::Store;
 use crate::str_util::StringPattern;
 use crate::time_util::DatePattern;
@@ -162,6 +163,9 @@ pub enum RevsetFilterPredicate {
     Description(StringPattern),
     /// Commits with first line of the description matching the pattern.
     Subject(StringPattern),
+    /// Commits with a description trailer of the given key (compared
+    /// case-insensitively) whose value matches the pattern.
+    Trailer { key: String, value: StringPattern },
     /// Commits with author name matching the pattern.
     AuthorName(StringPattern),
     /// Commits with author email matching the pattern.
@@ -183,8 +187,9 @@ pub enum RevsetFilterPredicate {
     },
     /// Commits with conflicts
     HasConflict,
-    /// Commits that are cryptographically signed.
-    Signed,
+    /// Commits that are cryptographically signed, optionally with the given
+    /// trust in the signing key.
+    Signed(Option<SigTrust>),
     /// Custom predicates provided by extensions
     Extension(Rc<dyn RevsetFilterExtension>),
 }
@@ -808,6 +813,17 @@ static BUILTIN_FUNCTION_MAP: Lazy<HashMap<&'static str, RevsetFunction>> = Lazy:
         let predicate = RevsetFilterPredicate::Subject(pattern);
         Ok(RevsetExpression::filter(predicate))
     });
+    map.insert("trailers", |diagnostics, function, _context| {
+        let ([key_arg], [value_opt_arg]) = function.expect_arguments()?;
+        let key = expect_literal(diagnostics, "string", key_arg)?;
+        let value = if let Some(value_arg) = value_opt_arg {
+            expect_string_pattern(diagnostics, value_arg)?
+        } else {
+            StringPattern::everything()
+        };
+        let predicate = RevsetFilterPredicate::Trailer { key, value };
+        Ok(RevsetExpression::filter(predicate))
+    });
     map.insert("author", |diagnostics, function, _context| {
         let [arg] = function.expect_exact_arguments()?;
         let pattern = expect_string_pattern(diagnostics, arg)?;
@@ -835,9 +851,12 @@ static BUILTIN_FUNCTION_MAP: Lazy<HashMap<&'static str, RevsetFunction>> = Lazy:
             pattern,
         )))
     });
-    map.insert("signed", |_diagnostics, function, _context| {
-        function.expect_no_arguments()?;
-        let predicate = RevsetFilterPredicate::Signed;
+    map.insert("signed", |diagnostics, function, _context| {
+        let ([], [trust_opt_arg]) = function.expect_arguments()?;
+        let trust = trust_opt_arg
+            .map(|arg| expect_signature_trust_pattern(diagnostics, arg))
+            .transpose()?;
+        let predicate = RevsetFilterPredicate::Signed(trust);
         Ok(RevsetExpression::filter(predicate))
     });
     map.insert("mine", |_diagnostics, function, context| {
@@ -999,6 +1018,23 @@ pub fn expect_date_pattern(
     )
 }
 
+fn expect_signature_trust_pattern(
+    diagnostics: &mut RevsetDiagnostics,
+    node: &ExpressionNode,
+) -> Result<SigTrust, RevsetParseError> {
+    revset_parser::expect_pattern_with(
+        diagnostics,
+        "signature pattern",
+        node,
+        |_diagnostics, value, kind| -> Result<_, Box<dyn std::error::Error + Send + Sync>> {
+            match kind {
+                Some("trust") => Ok(value.parse()?),
+                _ => Err("Signature pattern must specify 'trust'".into()),
+            }
+        },
+    )
+}
+
 fn parse_remote_bookmarks_arguments(
     diagnostics: &mut RevsetDiagnostics,
     function: &FunctionCallNode,
@@ -1809,7 +1845,8 @@ fn reload_repo_at_operation(
     // of the current operation. If it weren't, there might be commits unknown
     // to the outer repo.
     let base_repo = repo.base_repo();
-    let operation = op_walk::resolve_op_with_repo(base_repo, op_str)
+    // TODO: Report diagnostics if we add proper language support for "opset".
+    let operation = op_walk::resolve_op_with_repo(&mut RevsetDiagnostics::new(), base_repo, op_str)
         .map_err(|err| RevsetResolutionError::Other(err.into()))?;
     base_repo.reload_at(&operation).map_err(|err| match err {
         RepoLoaderError::Backend(err) => RevsetResolutionError::Backend(err),
@@ -3255,7 +3292,47 @@ mod tests {
             ),
         )
         "#);
-        insta::assert_debug_snapshot!(parse("signed()").unwrap(), @"Filter(Signed)");
+        insta::assert_debug_snapshot!(parse("signed()").unwrap(), @"Filter(Signed(None))");
+        insta::assert_debug_snapshot!(parse("signed(trust:verified)").unwrap(), @"Filter(Signed(Some(Verified)))");
+        insta::assert_debug_snapshot!(parse(r#"signed(trust:"expired")"#).unwrap(), @"Filter(Signed(Some(Expired)))");
+        insta::assert_debug_snapshot!(parse("signed(trust:trusted)").unwrap_err().kind(), @r#"Expression("Invalid signature pattern")"#);
+        insta::assert_debug_snapshot!(parse("signed(verified)").unwrap_err().kind(), @r#"Expression("Invalid signature pattern")"#);
+    }
+
+    #[test]
+    fn test_parse_revset_trailers_function() {
+        let settings = insta_settings();
+        let _guard = settings.bind_to_scope();
+
+        insta::assert_debug_snapshot!(parse("trailers(Change-Id)").unwrap(), @r#"
+        Filter(
+            Trailer {
+                key: "Change-Id",
+                value: Substring(""),
+            },
+        )
+        "#);
+        insta::assert_debug_snapshot!(
+            parse("trailers('Reviewed-by', glob:'*@example.com')").unwrap(), @r#"
+        Filter(
+            Trailer {
+                key: "Reviewed-by",
+                value: Glob(GlobPattern("*@example.com")),
+            },
+        )
+        "#);
+        insta::assert_debug_snapshot!(parse("trailers()").unwrap_err().kind(), @r#"
+        InvalidFunctionArguments {
+            name: "trailers",
+            message: "Expected 1 to 2 arguments",
+        }
+        "#);
+        insta::assert_debug_snapshot!(parse("trailers(a, b, c)").unwrap_err().kind(), @r#"
+        InvalidFunctionArguments {
+            name: "trailers",
+            message: "Expected 1 to 2 arguments",
+        }
+        "#);
     }
 
     #[test]
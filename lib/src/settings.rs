@@ -67,6 +67,8 @@ pub struct GitSettings {
     pub subprocess: bool,
     pub executable_path: PathBuf,
     pub write_change_id_header: bool,
+    pub submodule_auto_update: bool,
+    pub submodule_allowed_url_schemes: Vec<String>,
 }
 
 impl GitSettings {
@@ -78,6 +80,8 @@ impl GitSettings {
             subprocess: settings.get_bool("git.subprocess")?,
             executable_path: settings.get("git.executable-path")?,
             write_change_id_header: settings.get("git.write-change-id-header")?,
+            submodule_auto_update: settings.get_bool("git.submodules.auto-update")?,
+            submodule_allowed_url_schemes: settings.get("git.submodules.allowed-url-schemes")?,
         })
     }
 }
@@ -91,6 +95,8 @@ impl Default for GitSettings {
             subprocess: true,
             executable_path: PathBuf::from("git"),
             write_change_id_header: false,
+            submodule_auto_update: true,
+            submodule_allowed_url_schemes: vec!["https".to_owned(), "ssh".to_owned()],
         }
     }
 }
@@ -118,6 +124,10 @@ impl SignSettings {
             }
             SignBehavior::Own => commit.author.email == self.user_email,
             SignBehavior::Force => true,
+            // Unsigned commits are signed by `jj git push` instead.
+            SignBehavior::OnPush => {
+                commit.secure_sig.is_some() && commit.author.email == self.user_email
+            }
         }
     }
 }
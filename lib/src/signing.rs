@@ -17,12 +17,15 @@
 
 use std::fmt::Debug;
 use std::fmt::Display;
+use std::str::FromStr;
 use std::sync::Mutex;
 
 use clru::CLruCache;
 use thiserror::Error;
 
 use crate::backend::CommitId;
+use crate::backend::Signature;
+use crate::backend::Timestamp;
 use crate::config::ConfigGetError;
 use crate::gpg_signing::GpgBackend;
 use crate::gpg_signing::GpgsmBackend;
@@ -54,6 +57,43 @@ impl Display for SigStatus {
     }
 }
 
+/// How much the key which made a signature is trusted for the signer, part of
+/// the [Verification] type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SigTrust {
+    /// Signed by a key that is allowed for the signer's email.
+    Verified,
+    /// Signed by a key that isn't known to be allowed for the signer's email.
+    Unknown,
+    /// Signed by a key that was allowed for the signer's email, but has
+    /// expired.
+    Expired,
+}
+
+impl Display for SigTrust {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            SigTrust::Verified => "verified",
+            SigTrust::Unknown => "unknown",
+            SigTrust::Expired => "expired",
+        };
+        write!(f, "{s}")
+    }
+}
+
+impl FromStr for SigTrust {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "verified" => Ok(SigTrust::Verified),
+            "unknown" => Ok(SigTrust::Unknown),
+            "expired" => Ok(SigTrust::Expired),
+            _ => Err(format!("Invalid signature trust: {s}")),
+        }
+    }
+}
+
 /// The result of a signature verification.
 /// Key and display are optional additional info that backends can or can not
 /// provide to add additional information for the templater to potentially show.
@@ -114,6 +154,24 @@ pub trait SigningBackend: Debug + Send + Sync {
     /// verify(data, sign(data)?)?.status == SigStatus::Good
     /// ```
     fn verify(&self, data: &[u8], signature: &[u8]) -> SignResult<Verification>;
+
+    /// Determine how much the key which made the `signature` is trusted for
+    /// the signer with the given `email`, at the `timestamp` the data was
+    /// signed. This may be slower than [`SigningBackend::verify()`], so it's
+    /// only called when the trust is asked for.
+    ///
+    /// Backends without a trust model don't need to implement this, the
+    /// default returns [`SigTrust::Unknown`].
+    fn trust(
+        &self,
+        data: &[u8],
+        signature: &[u8],
+        email: &str,
+        timestamp: &Timestamp,
+    ) -> SignResult<SigTrust> {
+        let _ = (data, signature, email, timestamp);
+        Ok(SigTrust::Unknown)
+    }
 }
 
 /// An error type for the signing/verifying operations
@@ -159,6 +217,10 @@ pub enum SignBehavior {
     /// Always sign commits, regardless of who authored or signed them before.
     /// This is what jj does on `jj sign -f`.
     Force,
+    /// Don't sign commits when they are created, but sign all unsigned
+    /// commits when they are pushed with `jj git push`. Existing signatures
+    /// of own commits are kept across rewrites.
+    OnPush,
 }
 
 /// Wraps low-level signing backends and adds caching, similar to `Store`.
@@ -172,6 +234,7 @@ pub struct Signer {
     /// for ownership reasons.
     backends: Vec<Box<dyn SigningBackend>>,
     cache: Mutex<CLruCache<CommitId, Verification>>,
+    trust_cache: Mutex<CLruCache<CommitId, SigTrust>>,
 }
 
 impl Signer {
@@ -210,6 +273,7 @@ impl Signer {
             main_backend,
             backends: other_backends,
             cache: Mutex::new(CLruCache::new(COMMIT_CACHE_CAPACITY.try_into().unwrap())),
+            trust_cache: Mutex::new(CLruCache::new(COMMIT_CACHE_CAPACITY.try_into().unwrap())),
         }
     }
 
@@ -277,4 +341,52 @@ impl Signer {
             Ok(Verification::unknown())
         }
     }
+
+    /// Determines how much the key which made the signature is trusted for
+    /// the `signer`'s email at the time of their signature. Bad signatures
+    /// aren't trusted.
+    pub fn trust(
+        &self,
+        commit_id: &CommitId,
+        data: &[u8],
+        signature: &[u8],
+        signer: &Signature,
+    ) -> SignResult<SigTrust> {
+        let cached = self.trust_cache.lock().unwrap().get(commit_id).copied();
+        if let Some(trust) = cached {
+            return Ok(trust);
+        }
+        if self.verify(commit_id, data, signature)?.status == SigStatus::Bad {
+            return Ok(SigTrust::Unknown);
+        }
+
+        // The trust doesn't depend on whether the backend which verified the
+        // signature knows the key, e.g. an SSH key may be valid at the time of
+        // the signature but not anymore. Ask the backends until one knows.
+        let mut trust = SigTrust::Unknown;
+        for backend in self
+            .main_backend
+            .iter()
+            .chain(self.backends.iter())
+            .filter(|b| b.can_read(signature))
+        {
+            match backend.trust(data, signature, &signer.email, &signer.timestamp) {
+                Ok(SigTrust::Unknown) | Err(SignError::InvalidSignatureFormat) => {}
+                result => {
+                    trust = result?;
+                    break;
+                }
+            }
+        }
+
+        // like verifications, unknowns aren't cached since the key might get
+        // allowed before next call
+        if trust != SigTrust::Unknown {
+            self.trust_cache
+                .lock()
+                .unwrap()
+                .put(commit_id.clone(), trust);
+        }
+        Ok(trust)
+    }
 }
@@ -35,6 +35,7 @@ use thiserror::Error;
 use crate::backend::BackendInitError;
 use crate::backend::CommitId;
 use crate::backend::MillisSinceEpoch;
+use crate::backend::Signature;
 use crate::backend::Timestamp;
 use crate::content_hash::blake2b_hash;
 use crate::dag_walk;
@@ -46,6 +47,7 @@ use crate::object_id::HexPrefix;
 use crate::object_id::ObjectId;
 use crate::object_id::PrefixResolution;
 use crate::op_store;
+use crate::op_store::BisectState;
 use crate::op_store::OpStore;
 use crate::op_store::OpStoreError;
 use crate::op_store::OpStoreResult;
@@ -57,6 +59,7 @@ use crate::op_store::RemoteRef;
 use crate::op_store::RemoteRefState;
 use crate::op_store::RemoteView;
 use crate::op_store::RootOperationData;
+use crate::op_store::TagAnnotation;
 use crate::op_store::View;
 use crate::op_store::ViewId;
 use crate::ref_name::GitRefNameBuf;
@@ -520,6 +523,27 @@ fn view_to_proto(view: &View) -> crate::protos::op_store::View {
 
     proto.git_head = ref_target_to_proto(&view.git_head);
 
+    proto.bisect = view
+        .bisect
+        .as_ref()
+        .map(|state| crate::protos::op_store::BisectState {
+            good: state.good.iter().map(|id| id.to_bytes()).collect(),
+            bad: state.bad.iter().map(|id| id.to_bytes()).collect(),
+            skipped: state.skipped.iter().map(|id| id.to_bytes()).collect(),
+        });
+
+    for (name, annotation) in &view.tag_annotations {
+        proto
+            .tag_annotations
+            .push(crate::protos::op_store::TagAnnotation {
+                name: name.into(),
+                message: annotation.message.clone(),
+                tagger_name: annotation.tagger.name.clone(),
+                tagger_email: annotation.tagger.email.clone(),
+                tagger_timestamp: Some(timestamp_to_proto(&annotation.tagger.timestamp)),
+            });
+    }
+
     proto
 }
 
@@ -571,6 +595,28 @@ fn view_from_proto(proto: crate::protos::op_store::View) -> View {
         view.git_head = RefTarget::normal(CommitId::new(proto.git_head_legacy));
     }
 
+    view.bisect = proto.bisect.map(|state| BisectState {
+        good: state.good.into_iter().map(CommitId::new).collect(),
+        bad: state.bad.into_iter().map(CommitId::new).collect(),
+        skipped: state.skipped.into_iter().map(CommitId::new).collect(),
+    });
+
+    for annotation in proto.tag_annotations {
+        let name: RefNameBuf = annotation.name.into();
+        let tagger = Signature {
+            name: annotation.tagger_name,
+            email: annotation.tagger_email,
+            timestamp: timestamp_from_proto(annotation.tagger_timestamp.unwrap_or_default()),
+        };
+        view.tag_annotations.insert(
+            name,
+            TagAnnotation {
+                message: annotation.message,
+                tagger,
+            },
+        );
+    }
+
     view
 }
 
@@ -799,6 +845,24 @@ mod tests {
                 WorkspaceName::DEFAULT.to_owned() => default_wc_commit_id,
                 "test".into() => test_wc_commit_id,
             },
+            bisect: Some(BisectState {
+                good: vec![CommitId::from_hex("eee111")],
+                bad: vec![CommitId::from_hex("eee222")],
+                skipped: vec![CommitId::from_hex("eee333")],
+            }),
+            tag_annotations: btreemap! {
+                "v1.0".into() => TagAnnotation {
+                    message: "Release 1.0\n".to_string(),
+                    tagger: Signature {
+                        name: "Someone".to_string(),
+                        email: "someone@example.com".to_string(),
+                        timestamp: Timestamp {
+                            timestamp: MillisSinceEpoch(123456789),
+                            tz_offset: 3600,
+                        },
+                    },
+                },
+            },
         }
     }
 
@@ -840,8 +904,69 @@ mod tests {
         // Test exact output so we detect regressions in compatibility
         assert_snapshot!(
             ViewId::new(blake2b_hash(&create_view()).to_vec()).hex(),
+            @"820f86f83e2a1f008181f20e9bb2fc5e9c3e3ea1bb38d5d72d65209af82c1e56a81bed7eaed0f122ed7e9cbe546a245251d435a611345db2836942e3c73b0046"
+        );
+        // Views without bisection state and tag annotations keep their hash
+        let view = View {
+            bisect: None,
+            tag_annotations: BTreeMap::new(),
+            ..create_view()
+        };
+        assert_snapshot!(
+            ViewId::new(blake2b_hash(&view).to_vec()).hex(),
+            @"f426676b3a2f7c6b9ec8677cb05ed249d0d244ab7e86a7c51117e2d8a4829db65e55970c761231e2107d303bf3d33a1f2afdd4ed2181f223e99753674b20a35e"
+        );
+        // Views without tag annotations keep their hash
+        let view = View {
+            tag_annotations: BTreeMap::new(),
+            ..create_view()
+        };
+        assert_snapshot!(
+            ViewId::new(blake2b_hash(&view).to_vec()).hex(),
+            @"31707fdf13b69cc9d8519cd707b60ca540050eaeb4b48e9bf498b724cf015f33e4352a41e465913156614b55084162ac78b740a622d18294b255934343c0cf0c"
+        );
+    }
+
+    #[test]
+    fn test_hash_view_tag_annotations() {
+        // The hash of a view without tag annotations is the same as before
+        // they were added
+        let mut view = View {
+            bisect: None,
+            tag_annotations: BTreeMap::new(),
+            ..create_view()
+        };
+        let hash_without_annotations = ViewId::new(blake2b_hash(&view).to_vec());
+        assert_snapshot!(
+            hash_without_annotations.hex(),
             @"f426676b3a2f7c6b9ec8677cb05ed249d0d244ab7e86a7c51117e2d8a4829db65e55970c761231e2107d303bf3d33a1f2afdd4ed2181f223e99753674b20a35e"
         );
+        // Adding an annotation changes the hash
+        view.tag_annotations = create_view().tag_annotations;
+        let hash_with_annotations = ViewId::new(blake2b_hash(&view).to_vec());
+        assert_ne!(hash_with_annotations, hash_without_annotations);
+    }
+
+    #[test]
+    fn test_hash_view_optional_fields() {
+        // Views which only differ in the fields hashed only if set don't have
+        // the same hash
+        let tag_annotations = create_view().tag_annotations;
+        let views = [
+            (None, BTreeMap::new()),
+            (Some(BisectState::default()), BTreeMap::new()),
+            (create_view().bisect, BTreeMap::new()),
+            (None, tag_annotations.clone()),
+            (Some(BisectState::default()), tag_annotations.clone()),
+            (create_view().bisect, tag_annotations),
+        ]
+        .map(|(bisect, tag_annotations)| View {
+            bisect,
+            tag_annotations,
+            ..create_view()
+        });
+        let hashes = views.iter().map(blake2b_hash).collect_vec();
+        assert!(hashes.iter().all_unique());
     }
 
     #[test]
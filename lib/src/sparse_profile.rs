@@ -0,0 +1,255 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Named sparse profiles committed to the repo.
+//!
+//! A profile named `frontend` is stored in the file `.jj-sparse/frontend`.
+//! Each non-empty line of the file is a fileset expression, and lines starting
+//! with `#` are comments. Paths are relative to the repo root. The paths
+//! matched by any of the expressions are checked out in the working copy.
+
+use std::io::Read as _;
+use std::path::PathBuf;
+
+use thiserror::Error;
+
+use crate::backend::BackendError;
+use crate::backend::TreeValue;
+use crate::fileset;
+use crate::fileset::FilesetDiagnostics;
+use crate::fileset::FilesetExpression;
+use crate::fileset::FilesetParseError;
+use crate::fileset::FilesetValuePredicateError;
+use crate::matchers::Matcher;
+use crate::matchers::PrefixMatcher;
+use crate::matchers::UnionMatcher;
+use crate::merged_tree::MergedTree;
+use crate::repo_path::RepoPathBuf;
+use crate::repo_path::RepoPathComponent;
+use crate::repo_path::RepoPathUiConverter;
+
+/// Directory in the repo where sparse profiles are stored.
+pub const SPARSE_PROFILE_DIR: &str = ".jj-sparse";
+
+/// Error that may occur while resolving a sparse profile.
+#[derive(Debug, Error)]
+pub enum SparseProfileError {
+    /// The profile name isn't a valid file name.
+    #[error("Invalid sparse profile name: {0}")]
+    InvalidName(String),
+    /// The profile file doesn't exist in the tree.
+    #[error("No such sparse profile: {0}")]
+    NotFound(String),
+    /// The profile file is conflicted or isn't a regular file.
+    #[error("Sparse profile {0} is not a regular file")]
+    NotAFile(String),
+    /// The profile file content isn't valid UTF-8.
+    #[error("Sparse profile {0} is not valid UTF-8")]
+    InvalidUtf8(String),
+    /// The profile contains an invalid fileset expression.
+    #[error("Failed to parse sparse profile {name}")]
+    Parse {
+        /// Name of the profile.
+        name: String,
+        /// The underlying error.
+        #[source]
+        source: FilesetParseError,
+    },
+    /// The profile matches files by content or metadata, which can't be
+    /// decided by path.
+    #[error("Invalid sparse profile {name}")]
+    ValuePredicate {
+        /// Name of the profile.
+        name: String,
+        /// The underlying error.
+        #[source]
+        source: FilesetValuePredicateError,
+    },
+    /// Reading the profile from the backend failed.
+    #[error(transparent)]
+    Backend(#[from] BackendError),
+}
+
+/// A sparse profile resolved from a tree.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SparseProfile {
+    /// Name of the profile.
+    pub name: String,
+    /// Content of the profile file at the time it was resolved.
+    pub content: String,
+}
+
+impl SparseProfile {
+    /// Returns the path to the file of the profile `name`.
+    pub fn path(name: &str) -> Result<RepoPathBuf, SparseProfileError> {
+        let component = RepoPathComponent::new(name)
+            .ok()
+            .filter(|component| !matches!(component.as_internal_str(), "." | ".."))
+            .ok_or_else(|| SparseProfileError::InvalidName(name.to_owned()))?;
+        let dir = RepoPathBuf::from_internal_string(SPARSE_PROFILE_DIR).unwrap();
+        Ok(dir.join(component))
+    }
+
+    /// Reads the profile `name` from `tree`, and checks that its content is
+    /// valid.
+    pub fn resolve(tree: &MergedTree, name: &str) -> Result<Self, SparseProfileError> {
+        let path = Self::path(name)?;
+        let id = match tree.path_value(&path)?.into_resolved() {
+            Ok(Some(TreeValue::File { id, .. })) => id,
+            Ok(None) => return Err(SparseProfileError::NotFound(name.to_owned())),
+            Ok(Some(_)) | Err(_) => return Err(SparseProfileError::NotAFile(name.to_owned())),
+        };
+        let mut content = vec![];
+        tree.store()
+            .read_file(&path, &id)?
+            .read_to_end(&mut content)
+            .map_err(|err| BackendError::ReadFile {
+                path: path.clone(),
+                id: id.clone(),
+                source: err.into(),
+            })?;
+        let content = String::from_utf8(content)
+            .map_err(|_| SparseProfileError::InvalidUtf8(name.to_owned()))?;
+        let profile = SparseProfile {
+            name: name.to_owned(),
+            content,
+        };
+        profile.to_matcher()?;
+        Ok(profile)
+    }
+
+    /// Iterates over the fileset expressions in the profile, skipping empty
+    /// lines and comments.
+    pub fn patterns(&self) -> impl Iterator<Item = &str> {
+        self.content
+            .lines()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+    }
+
+    /// Parses the profile content into a union of its fileset expressions.
+    pub fn to_expression(&self) -> Result<FilesetExpression, SparseProfileError> {
+        // Paths are relative to the repo root, not to the profile file or the
+        // current directory.
+        let path_converter = RepoPathUiConverter::Fs {
+            cwd: PathBuf::new(),
+            base: PathBuf::new(),
+        };
+        let expressions = self
+            .patterns()
+            .map(|line| {
+                fileset::parse_maybe_bare(&mut FilesetDiagnostics::new(), line, &path_converter)
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|source| SparseProfileError::Parse {
+                name: self.name.clone(),
+                source,
+            })?;
+        Ok(FilesetExpression::union_all(expressions))
+    }
+
+    /// Creates a matcher for the paths selected by the profile.
+    ///
+    /// Since the working copy decides which paths to check out by path alone,
+    /// predicates on file content are rejected.
+    pub fn to_matcher(&self) -> Result<Box<dyn Matcher>, SparseProfileError> {
+        self.to_expression()?.to_path_matcher().map_err(|source| {
+            SparseProfileError::ValuePredicate {
+                name: self.name.clone(),
+                source,
+            }
+        })
+    }
+}
+
+/// Creates a matcher for the paths to check out in a working copy with the
+/// `sparse_patterns` prefixes and the `sparse_profile`.
+///
+/// The profile was validated when it was resolved, so it shouldn't fail to
+/// parse unless the fileset syntax changed since.
+pub fn sparse_matcher(
+    sparse_patterns: &[RepoPathBuf],
+    sparse_profile: Option<&SparseProfile>,
+) -> Result<Box<dyn Matcher>, SparseProfileError> {
+    let prefix_matcher = PrefixMatcher::new(sparse_patterns);
+    match sparse_profile {
+        Some(profile) => {
+            let profile_matcher = profile.to_matcher()?;
+            Ok(Box::new(UnionMatcher::new(prefix_matcher, profile_matcher)))
+        }
+        None => Ok(Box::new(prefix_matcher)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repo_path::RepoPath;
+
+    fn repo_path(value: &str) -> &RepoPath {
+        RepoPath::from_internal_string(value).unwrap()
+    }
+
+    #[test]
+    fn test_path() {
+        assert_eq!(
+            SparseProfile::path("frontend")
+                .unwrap()
+                .as_internal_file_string(),
+            ".jj-sparse/frontend"
+        );
+        assert!(SparseProfile::path("").is_err());
+        assert!(SparseProfile::path("..").is_err());
+        assert!(SparseProfile::path("a/b").is_err());
+    }
+
+    #[test]
+    fn test_to_matcher() {
+        let profile = SparseProfile {
+            name: "frontend".to_owned(),
+            content: "# The web UI\nweb\n\n  glob:'**/*.md'  \nlib ~ lib/gen\n".to_owned(),
+        };
+        let matcher = profile.to_matcher().unwrap();
+        assert!(matcher.matches(repo_path("web/index.html")));
+        assert!(matcher.matches(repo_path("docs/README.md")));
+        assert!(matcher.matches(repo_path("lib/src/main.rs")));
+        assert!(!matcher.matches(repo_path("lib/gen/out.rs")));
+        assert!(!matcher.matches(repo_path("server/main.rs")));
+        assert!(!matcher.matches(repo_path("# The web UI")));
+
+        let profile = SparseProfile {
+            name: "empty".to_owned(),
+            content: "# Nothing\n".to_owned(),
+        };
+        assert!(!profile.to_matcher().unwrap().matches(repo_path("a")));
+
+        let profile = SparseProfile {
+            name: "bad".to_owned(),
+            content: "web\nfoo(\n".to_owned(),
+        };
+        assert!(matches!(
+            profile.to_matcher(),
+            Err(SparseProfileError::Parse { .. })
+        ));
+
+        let profile = SparseProfile {
+            name: "small".to_owned(),
+            content: "web\nfile_size(\"<1MB\")\n".to_owned(),
+        };
+        assert!(matches!(
+            profile.to_matcher(),
+            Err(SparseProfileError::ValuePredicate { .. })
+        ));
+    }
+}
@@ -26,10 +26,12 @@ use std::process::Stdio;
 use either::Either;
 use thiserror::Error;
 
+use crate::backend::Timestamp;
 use crate::config::ConfigGetError;
 use crate::config::ConfigGetResultExt as _;
 use crate::settings::UserSettings;
 use crate::signing::SigStatus;
+use crate::signing::SigTrust;
 use crate::signing::SignError;
 use crate::signing::SigningBackend;
 use crate::signing::Verification;
@@ -182,6 +184,113 @@ impl SshBackend {
         }
         Ok(Some(principal))
     }
+
+    /// Checks if the signature was made by a key that the allowed-signers
+    /// file associates with the given `email` at the signing `timestamp`.
+    fn check_trust(
+        &self,
+        data: &[u8],
+        signature_file_path: &Path,
+        allowed_signers: &OsString,
+        email: &str,
+        timestamp: &Timestamp,
+    ) -> SshResult<SigTrust> {
+        // Like git, check the validity of the key when the data was signed
+        // rather than now. ssh-keygen takes the time in the local time zone.
+        let verify_time = to_local_time(timestamp)
+            .ok_or(SshError::BadResult)?
+            .format("%Y%m%d%H%M%S")
+            .to_string();
+        let verify_at = |verify_time: &str| {
+            let mut command = self.create_command();
+            command
+                .arg("-Y")
+                .arg("verify")
+                .arg("-s")
+                .arg(signature_file_path)
+                .arg("-I")
+                .arg(email)
+                .arg("-f")
+                .arg(allowed_signers)
+                .arg("-n")
+                .arg("git")
+                .arg(format!("-Overify-time={verify_time}"));
+            match run_command(&mut command, data) {
+                Ok(_) => Ok(true),
+                Err(SshError::Command { .. }) => Ok(false),
+                Err(err) => Err(err),
+            }
+        };
+        if verify_at(&verify_time)? {
+            return Ok(SigTrust::Verified);
+        }
+
+        // ssh-keygen fails the same way for expired keys as for unknown ones.
+        // The key has expired if it was allowed for the email at the end of
+        // a validity period which ended before the data was signed.
+        for valid_before in expired_valid_before_times(allowed_signers, timestamp) {
+            if verify_at(&valid_before)? {
+                return Ok(SigTrust::Expired);
+            }
+        }
+        Ok(SigTrust::Unknown)
+    }
+}
+
+fn to_local_time(timestamp: &Timestamp) -> Option<chrono::DateTime<chrono::Local>> {
+    chrono::DateTime::from_timestamp_millis(timestamp.timestamp.0)
+        .map(|time| time.with_timezone(&chrono::Local))
+}
+
+/// Returns the `valid-before` times in the allowed-signers file which are
+/// earlier than `timestamp`, in the format they are written in.
+///
+/// Lines aren't matched against the principal, so the times may belong to
+/// other keys. An unreadable file is treated as having no such times, since
+/// ssh-keygen would fail to verify against it anyway.
+fn expired_valid_before_times(allowed_signers: &OsString, timestamp: &Timestamp) -> Vec<String> {
+    let Ok(content) = std::fs::read_to_string(allowed_signers) else {
+        return vec![];
+    };
+    let Some(signed_at) = to_local_time(timestamp) else {
+        return vec![];
+    };
+    const OPTION: &str = "valid-before=\"";
+    let mut times = vec![];
+    for line in content.lines() {
+        // Options are case-insensitive, and their values can't contain quotes.
+        let Some(start) = line.to_ascii_lowercase().find(OPTION) else {
+            continue;
+        };
+        let Some(value) = line[start + OPTION.len()..].split('"').next() else {
+            continue;
+        };
+        if parse_allowed_signers_time(value).is_some_and(|time| time < signed_at)
+            && !times.iter().any(|time| time == value)
+        {
+            times.push(value.to_owned());
+        }
+    }
+    times
+}
+
+/// Parses a time in the `YYYYMMDD[HHMM[SS]][Z]` format of the allowed-signers
+/// file, which is in the local time zone unless suffixed with `Z`.
+fn parse_allowed_signers_time(value: &str) -> Option<chrono::DateTime<chrono::Local>> {
+    let (value, is_utc) = match value.strip_suffix(['Z', 'z']) {
+        Some(value) => (value, true),
+        None => (value, false),
+    };
+    if !matches!(value.len(), 8 | 12 | 14) {
+        return None;
+    }
+    let padded = format!("{value:0<14}");
+    let time = chrono::NaiveDateTime::parse_from_str(&padded, "%Y%m%d%H%M%S").ok()?;
+    if is_utc {
+        Some(time.and_utc().with_timezone(&chrono::Local))
+    } else {
+        time.and_local_timezone(chrono::Local).earliest()
+    }
 }
 
 impl SigningBackend for SshBackend {
@@ -280,6 +389,34 @@ impl SigningBackend for SshBackend {
             }
         }
     }
+
+    fn trust(
+        &self,
+        data: &[u8],
+        signature: &[u8],
+        email: &str,
+        timestamp: &Timestamp,
+    ) -> Result<SigTrust, SignError> {
+        let Some(allowed_signers) = &self.allowed_signers else {
+            return Ok(SigTrust::Unknown);
+        };
+        let mut signature_file = tempfile::Builder::new()
+            .prefix(".jj-ssh-sig-")
+            .tempfile()
+            .map_err(SshError::Io)?;
+        signature_file.write_all(signature).map_err(SshError::Io)?;
+        signature_file.flush().map_err(SshError::Io)?;
+
+        let signature_file_path = signature_file.into_temp_path();
+
+        Ok(self.check_trust(
+            data,
+            &signature_file_path,
+            allowed_signers,
+            email,
+            timestamp,
+        )?)
+    }
 }
 
 #[cfg(test)]
@@ -321,4 +458,28 @@ mod tests {
             path.left().unwrap().to_str().unwrap()
         );
     }
+
+    #[test]
+    fn test_parse_allowed_signers_time() {
+        let utc = |s: &str| {
+            chrono::NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S")
+                .unwrap()
+                .and_utc()
+        };
+        assert_eq!(
+            parse_allowed_signers_time("20000101Z"),
+            Some(utc("2000-01-01 00:00:00").into())
+        );
+        assert_eq!(
+            parse_allowed_signers_time("200001021234Z"),
+            Some(utc("2000-01-02 12:34:00").into())
+        );
+        assert_eq!(
+            parse_allowed_signers_time("20000102123456z"),
+            Some(utc("2000-01-02 12:34:56").into())
+        );
+        assert!(parse_allowed_signers_time("20000101").is_some());
+        assert_eq!(parse_allowed_signers_time("2000010"), None);
+        assert_eq!(parse_allowed_signers_time("2000x101"), None);
+    }
 }
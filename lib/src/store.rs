@@ -242,6 +242,10 @@ impl Store {
         self.backend.read_file(path, id).await
     }
 
+    pub fn file_size(&self, path: &RepoPath, id: &FileId) -> BackendResult<u64> {
+        self.backend.file_size(path, id).block_on()
+    }
+
     pub async fn write_file(
         &self,
         path: &RepoPath,
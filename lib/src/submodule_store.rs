@@ -15,7 +15,44 @@
 #![allow(missing_docs)]
 
 use std::fmt::Debug;
+use std::path::Path;
+
+use thiserror::Error;
+
+use crate::backend::CommitId;
+
+#[derive(Debug, Error)]
+pub enum SubmoduleStoreError {
+    #[error("Commit {commit_id} of submodule {name} not found at {url}")]
+    CommitNotFound {
+        name: String,
+        url: String,
+        commit_id: CommitId,
+    },
+    #[error("Failed to update submodule {name}: {message}")]
+    Git { name: String, message: String },
+    #[error("Failed to update submodule {name}")]
+    Other {
+        name: String,
+        #[source]
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+}
 
 pub trait SubmoduleStore: Send + Sync + Debug {
     fn name(&self) -> &str;
+
+    /// Makes `commit_id` of the submodule `name` available in the store,
+    /// fetching it from `url` if it isn't already there.
+    fn fetch(&self, name: &str, url: &str, commit_id: &CommitId)
+        -> Result<(), SubmoduleStoreError>;
+
+    /// Checks out `commit_id` of the submodule `name` at `working_copy_path`.
+    /// The commit must have been fetched.
+    fn check_out(
+        &self,
+        name: &str,
+        commit_id: &CommitId,
+        working_copy_path: &Path,
+    ) -> Result<(), SubmoduleStoreError>;
 }
@@ -16,14 +16,18 @@
 //! various backends.
 
 use hex::ToHex as _;
+use jj_lib::backend::Timestamp;
 use jj_lib::content_hash::blake2b_hash;
 use jj_lib::signing::SigStatus;
+use jj_lib::signing::SigTrust;
 use jj_lib::signing::SignError;
 use jj_lib::signing::SignResult;
 use jj_lib::signing::SigningBackend;
 use jj_lib::signing::Verification;
 
 /// A test signing backend that uses a simple hash-based signature format.
+///
+/// A key is trusted for the signer if it is the same as the signer's email.
 #[derive(Debug)]
 pub struct TestSigningBackend;
 
@@ -73,4 +77,19 @@ impl SigningBackend for TestSigningBackend {
             })
         }
     }
+
+    fn trust(
+        &self,
+        data: &[u8],
+        signature: &[u8],
+        email: &str,
+        _timestamp: &Timestamp,
+    ) -> SignResult<SigTrust> {
+        let verification = self.verify(data, signature)?;
+        if verification.status == SigStatus::Good && verification.key.as_deref() == Some(email) {
+            Ok(SigTrust::Verified)
+        } else {
+            Ok(SigTrust::Unknown)
+        }
+    }
 }
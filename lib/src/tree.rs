@@ -35,6 +35,7 @@ use crate::backend::TreeValue;
 use crate::files;
 use crate::matchers::EverythingMatcher;
 use crate::matchers::Matcher;
+use crate::merge::Merge;
 use crate::merge::MergedTreeVal;
 use crate::object_id::ObjectId as _;
 use crate::repo_path::RepoPath;
@@ -241,7 +242,11 @@ impl Iterator for TreeEntriesIterator<'_> {
                         self.stack.push(TreeEntriesDirItem::from(subtree));
                     }
                     value => {
-                        if self.matcher.matches(&path) {
+                        let store = top.tree.store();
+                        if self
+                            .matcher
+                            .matches_value(store, &path, &Merge::resolved(Some(&value)))
+                        {
                             return Some((path, value));
                         }
                     }
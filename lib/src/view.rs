@@ -22,10 +22,12 @@ use thiserror::Error;
 
 use crate::backend::CommitId;
 use crate::op_store;
+use crate::op_store::BisectState;
 use crate::op_store::BookmarkTarget;
 use crate::op_store::RefTarget;
 use crate::op_store::RefTargetOptionExt as _;
 use crate::op_store::RemoteRef;
+use crate::op_store::TagAnnotation;
 use crate::ref_name::GitRefName;
 use crate::ref_name::GitRefNameBuf;
 use crate::ref_name::RefName;
@@ -336,6 +338,32 @@ impl View {
         }
     }
 
+    /// Returns the annotation of the tag `name` if it hasn't been exported to
+    /// Git yet.
+    pub fn get_tag_annotation(&self, name: &RefName) -> Option<&TagAnnotation> {
+        self.data.tag_annotations.get(name)
+    }
+
+    /// Iterates tag annotations which haven't been exported to Git yet.
+    /// Entries are sorted by tag name.
+    pub fn tag_annotations(&self) -> impl Iterator<Item = (&RefName, &TagAnnotation)> {
+        self.data
+            .tag_annotations
+            .iter()
+            .map(|(name, annotation)| (name.as_ref(), annotation))
+    }
+
+    /// Sets or removes the annotation of the tag `name`.
+    pub fn set_tag_annotation(&mut self, name: &RefName, annotation: Option<TagAnnotation>) {
+        if let Some(annotation) = annotation {
+            self.data
+                .tag_annotations
+                .insert(name.to_owned(), annotation);
+        } else {
+            self.data.tag_annotations.remove(name);
+        }
+    }
+
     pub fn get_git_ref(&self, name: &GitRefName) -> &RefTarget {
         self.data.git_refs.get(name).flatten()
     }
@@ -356,6 +384,15 @@ impl View {
         self.data.git_head = target;
     }
 
+    /// Returns the state of the in-progress bisection, if any.
+    pub fn bisect_state(&self) -> Option<&BisectState> {
+        self.data.bisect.as_ref()
+    }
+
+    pub fn set_bisect_state(&mut self, state: Option<BisectState>) {
+        self.data.bisect = state;
+    }
+
     /// Iterates all commit ids referenced by this view.
     ///
     /// This can include hidden commits referenced by remote bookmarks, previous
@@ -381,6 +418,8 @@ impl View {
             git_refs,
             git_head,
             wc_commit_ids,
+            bisect,
+            tag_annotations: _,
         } = &self.data;
         itertools::chain!(
             head_ids,
@@ -394,7 +433,11 @@ impl View {
             }),
             git_refs.values().flat_map(ref_target_ids),
             ref_target_ids(git_head),
-            wc_commit_ids.values()
+            wc_commit_ids.values(),
+            bisect.iter().flat_map(|state| {
+                let BisectState { good, bad, skipped } = state;
+                itertools::chain!(good, bad, skipped)
+            })
         )
     }
 
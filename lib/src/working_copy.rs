@@ -29,12 +29,14 @@ use crate::backend::BackendError;
 use crate::backend::MergedTreeId;
 use crate::commit::Commit;
 use crate::conflicts::ConflictMarkerStyle;
+use crate::conflicts::ConflictMarkerStyleOverrides;
 use crate::dag_walk;
 use crate::fsmonitor::FsmonitorSettings;
 use crate::gitignore::GitIgnoreError;
 use crate::gitignore::GitIgnoreFile;
 use crate::matchers::EverythingMatcher;
 use crate::matchers::Matcher;
+use crate::matchers::NothingMatcher;
 use crate::op_store::OpStoreError;
 use crate::op_store::OperationId;
 use crate::operation::Operation;
@@ -46,6 +48,7 @@ use crate::repo::RewriteRootCommit;
 use crate::repo_path::InvalidRepoPathError;
 use crate::repo_path::RepoPath;
 use crate::repo_path::RepoPathBuf;
+use crate::sparse_profile::SparseProfile;
 use crate::store::Store;
 use crate::transaction::TransactionCommitError;
 
@@ -73,6 +76,10 @@ pub trait WorkingCopy: Send {
     /// that all files should be checked out.
     fn sparse_patterns(&self) -> Result<&[RepoPathBuf], WorkingCopyStateError>;
 
+    /// The sparse profile whose paths are checked out in addition to the paths
+    /// matching `sparse_patterns()`.
+    fn sparse_profile(&self) -> Result<Option<&SparseProfile>, WorkingCopyStateError>;
+
     /// Locks the working copy and returns an instance with methods for updating
     /// the working copy files and state.
     fn start_mutation(&self) -> Result<Box<dyn LockedWorkingCopy>, WorkingCopyStateError>;
@@ -151,6 +158,17 @@ pub trait LockedWorkingCopy {
         options: &CheckoutOptions,
     ) -> Result<CheckoutStats, CheckoutError>;
 
+    /// See `WorkingCopy::sparse_profile()`
+    fn sparse_profile(&self) -> Result<Option<&SparseProfile>, WorkingCopyStateError>;
+
+    /// Updates the sparse profile whose paths should be checked out in
+    /// addition to the sparse patterns.
+    fn set_sparse_profile(
+        &mut self,
+        new_sparse_profile: Option<SparseProfile>,
+        options: &CheckoutOptions,
+    ) -> Result<CheckoutStats, CheckoutError>;
+
     /// Finish the modifications to the working copy by writing the updated
     /// states to disk. Returns the new (unlocked) working copy.
     fn finish(
@@ -220,8 +238,25 @@ pub struct SnapshotOptions<'a> {
     /// (depending on implementation)
     /// return `SnapshotError::NewFileTooLarge`.
     pub max_new_file_size: u64,
+    /// Size limits for new files matching the matchers, which take precedence
+    /// over `max_new_file_size`. If a file matches several of them, the
+    /// largest limit applies.
+    pub new_file_size_limits: &'a [(Box<dyn Matcher>, u64)],
+    /// New files matching this are left untracked.
+    pub ignore_by_default_matcher: &'a dyn Matcher,
+    /// Whether to leave new files untracked if they look like generated
+    /// binaries such as executables or object files.
+    pub untrack_binaries: bool,
+    /// New files matching this are tracked even if they would be left
+    /// untracked because of their size, their content, or
+    /// `ignore_by_default_matcher`. Implementations may remember such files so
+    /// that they are tracked again if they are re-created later.
+    pub force_tracking_matcher: &'a dyn Matcher,
     /// Expected conflict marker style for checking for changed files.
     pub conflict_marker_style: ConflictMarkerStyle,
+    /// Expected conflict marker styles for files matching some patterns, which
+    /// take precedence over `conflict_marker_style`.
+    pub conflict_marker_style_overrides: ConflictMarkerStyleOverrides,
 }
 
 impl SnapshotOptions<'_> {
@@ -233,7 +268,12 @@ impl SnapshotOptions<'_> {
             progress: None,
             start_tracking_matcher: &EverythingMatcher,
             max_new_file_size: u64::MAX,
+            new_file_size_limits: &[],
+            ignore_by_default_matcher: &NothingMatcher,
+            untrack_binaries: false,
+            force_tracking_matcher: &NothingMatcher,
             conflict_marker_style: ConflictMarkerStyle::default(),
+            conflict_marker_style_overrides: ConflictMarkerStyleOverrides::default(),
         }
     }
 }
@@ -260,6 +300,13 @@ pub enum UntrackedReason {
     },
     /// File does not match the fileset specified in snapshot.auto-track.
     FileNotAutoTracked,
+    /// File looked like a generated binary.
+    GeneratedBinary {
+        /// Name of the detected binary format.
+        format: &'static str,
+    },
+    /// File matched the patterns of files that are ignored by default.
+    IgnoredByDefault,
 }
 
 /// Options used when checking out a tree in the working copy.
@@ -267,6 +314,9 @@ pub enum UntrackedReason {
 pub struct CheckoutOptions {
     /// Conflict marker style to use when materializing files
     pub conflict_marker_style: ConflictMarkerStyle,
+    /// Conflict marker styles to use when materializing files matching some
+    /// patterns, which take precedence over `conflict_marker_style`
+    pub conflict_marker_style_overrides: ConflictMarkerStyleOverrides,
 }
 
 impl CheckoutOptions {
@@ -274,6 +324,7 @@ impl CheckoutOptions {
     pub fn empty_for_test() -> Self {
         CheckoutOptions {
             conflict_marker_style: ConflictMarkerStyle::default(),
+            conflict_marker_style_overrides: ConflictMarkerStyleOverrides::default(),
         }
     }
 }
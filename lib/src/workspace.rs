@@ -62,6 +62,9 @@ use crate::working_copy::LockedWorkingCopy;
 use crate::working_copy::WorkingCopy;
 use crate::working_copy::WorkingCopyFactory;
 use crate::working_copy::WorkingCopyStateError;
+use crate::workspace_store::SimpleWorkspaceStore;
+use crate::workspace_store::WorkspaceStore;
+use crate::workspace_store::WorkspaceStoreError;
 
 #[derive(Error, Debug)]
 pub enum WorkspaceInitError {
@@ -83,6 +86,8 @@ pub enum WorkspaceInitError {
     SignInit(#[from] SignInitError),
     #[error(transparent)]
     TransactionCommit(#[from] TransactionCommitError),
+    #[error(transparent)]
+    WorkspaceStore(#[from] WorkspaceStoreError),
 }
 
 #[derive(Error, Debug)]
@@ -127,6 +132,23 @@ fn create_jj_dir(workspace_root: &Path) -> Result<PathBuf, WorkspaceInitError> {
     }
 }
 
+/// Points the `.jj/repo` file in `jj_dir` to the repo at `repo_dir`.
+///
+/// This is used by workspaces other than the one hosting the repo directory.
+pub fn write_repo_path_file(jj_dir: &Path, repo_dir: &Path) -> Result<(), WorkspaceInitError> {
+    let repo_file_path = jj_dir.join("repo");
+    let mut repo_file = File::create(&repo_file_path).context(&repo_file_path)?;
+    repo_file
+        .write_all(
+            repo_dir
+                .to_str()
+                .ok_or(WorkspaceInitError::NonUnicodePath)?
+                .as_bytes(),
+        )
+        .context(&repo_file_path)?;
+    Ok(())
+}
+
 fn init_working_copy(
     repo: &Arc<ReadonlyRepo>,
     workspace_root: &Path,
@@ -321,6 +343,7 @@ impl Workspace {
             )?;
             let repo_loader = repo.loader().clone();
             let workspace = Workspace::new(workspace_root, repo_dir, working_copy, repo_loader)?;
+            workspace.register_in_workspace_store()?;
             Ok((workspace, repo))
         })()
         .inspect_err(|_err| {
@@ -358,16 +381,7 @@ impl Workspace {
         let jj_dir = create_jj_dir(workspace_root)?;
 
         let repo_dir = dunce::canonicalize(repo_path).context(repo_path)?;
-        let repo_file_path = jj_dir.join("repo");
-        let mut repo_file = File::create(&repo_file_path).context(&repo_file_path)?;
-        repo_file
-            .write_all(
-                repo_dir
-                    .to_str()
-                    .ok_or(WorkspaceInitError::NonUnicodePath)?
-                    .as_bytes(),
-            )
-            .context(&repo_file_path)?;
+        write_repo_path_file(&jj_dir, &repo_dir)?;
 
         let (working_copy, repo) = init_working_copy(
             repo,
@@ -382,6 +396,7 @@ impl Workspace {
             working_copy,
             repo.loader().clone(),
         )?;
+        workspace.register_in_workspace_store()?;
         Ok((workspace, repo))
     }
 
@@ -408,6 +423,32 @@ impl Workspace {
         &self.repo_path
     }
 
+    /// Returns the store that records the root paths of the workspaces of the
+    /// repo.
+    pub fn workspace_store(&self) -> Box<dyn WorkspaceStore> {
+        Box::new(SimpleWorkspaceStore::load(&self.repo_path))
+    }
+
+    /// Records the root path of this workspace in the workspace store.
+    pub fn register_in_workspace_store(&self) -> Result<(), WorkspaceStoreError> {
+        self.workspace_store()
+            .add(self.workspace_name(), &self.workspace_root)
+    }
+
+    /// Records the root path of this workspace in the workspace store unless
+    /// it's already recorded. Workspaces created before the store existed
+    /// are recorded this way when they're loaded.
+    pub fn register_in_workspace_store_if_missing(&self) -> Result<(), WorkspaceStoreError> {
+        let workspace_store = self.workspace_store();
+        if workspace_store
+            .get_workspace_path(self.workspace_name())?
+            .is_none()
+        {
+            workspace_store.add(self.workspace_name(), &self.workspace_root)?;
+        }
+        Ok(())
+    }
+
     pub fn repo_loader(&self) -> &RepoLoader {
         &self.repo_loader
     }
@@ -607,6 +648,11 @@ impl WorkspaceLoader for DefaultWorkspaceLoader {
             working_copy,
             repo_loader,
         )?;
+        // Loading shouldn't fail if the repo can't be written to, e.g. for
+        // read-only commands on a read-only repo.
+        if let Err(err) = workspace.register_in_workspace_store_if_missing() {
+            tracing::warn!(?err, "Failed to record workspace in the workspace store");
+        }
         Ok(workspace)
     }
 
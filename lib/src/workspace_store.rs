@@ -0,0 +1,249 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Registry of workspace root paths.
+//!
+//! The view only knows workspaces by name. The workspace store records where
+//! the root of each workspace is, so the workspaces can be found from the repo.
+
+use std::fmt::Debug;
+use std::fs;
+use std::io;
+use std::io::Write as _;
+use std::path::Path;
+use std::path::PathBuf;
+
+use prost::Message as _;
+use tempfile::NamedTempFile;
+use thiserror::Error;
+
+use crate::file_util::create_or_reuse_dir;
+use crate::file_util::IoResultExt as _;
+use crate::file_util::PathError;
+use crate::lock::FileLock;
+use crate::lock::FileLockError;
+use crate::protos::workspace_store as proto;
+use crate::ref_name::WorkspaceName;
+use crate::ref_name::WorkspaceNameBuf;
+
+/// Error that may occur when accessing the workspace store.
+#[derive(Debug, Error)]
+pub enum WorkspaceStoreError {
+    /// Failed to read or write the store files.
+    #[error(transparent)]
+    Path(#[from] PathError),
+    /// Failed to lock the store.
+    #[error(transparent)]
+    Lock(#[from] FileLockError),
+    /// The index file is corrupted.
+    #[error("Failed to decode workspace store index")]
+    Decode(#[from] prost::DecodeError),
+    /// The workspace path cannot be recorded.
+    #[error("Workspace path is not valid UTF-8: {}", .0.display())]
+    NonUnicodePath(PathBuf),
+}
+
+/// Stores the root paths of the workspaces of a repo.
+pub trait WorkspaceStore: Send + Sync + Debug {
+    /// Name of the store implementation.
+    fn name(&self) -> &str;
+
+    /// Records the root path of the workspace, replacing the existing one.
+    fn add(&self, workspace_name: &WorkspaceName, path: &Path) -> Result<(), WorkspaceStoreError>;
+
+    /// Removes the workspaces from the store.
+    fn forget(&self, workspace_names: &[&WorkspaceName]) -> Result<(), WorkspaceStoreError>;
+
+    /// Changes the name of the recorded workspace.
+    fn rename(
+        &self,
+        old_name: &WorkspaceName,
+        new_name: &WorkspaceName,
+    ) -> Result<(), WorkspaceStoreError>;
+
+    /// Looks up the root path of the workspace. Returns `None` if the
+    /// workspace isn't recorded, which is the case for workspaces created
+    /// before the store existed.
+    fn get_workspace_path(
+        &self,
+        workspace_name: &WorkspaceName,
+    ) -> Result<Option<PathBuf>, WorkspaceStoreError>;
+
+    /// Returns all recorded workspaces sorted by name.
+    fn list(&self) -> Result<Vec<(WorkspaceNameBuf, PathBuf)>, WorkspaceStoreError>;
+}
+
+/// Workspace store backed by an index file in the repo directory.
+#[derive(Debug)]
+pub struct SimpleWorkspaceStore {
+    store_dir: PathBuf,
+}
+
+impl SimpleWorkspaceStore {
+    /// Returns the store of the repo at `repo_path`. The store directory is
+    /// created on first write.
+    pub fn load(repo_path: &Path) -> Self {
+        SimpleWorkspaceStore {
+            store_dir: repo_path.join("workspace_store"),
+        }
+    }
+
+    fn index_path(&self) -> PathBuf {
+        self.store_dir.join("index")
+    }
+
+    fn read_index(&self) -> Result<Vec<proto::Workspace>, WorkspaceStoreError> {
+        let index_path = self.index_path();
+        let buf = match fs::read(&index_path) {
+            Ok(buf) => buf,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+            Err(err) => return Err(err).context(&index_path).map_err(Into::into),
+        };
+        Ok(proto::Workspaces::decode(&*buf)?.workspaces)
+    }
+
+    fn write_index(
+        &self,
+        mut workspaces: Vec<proto::Workspace>,
+    ) -> Result<(), WorkspaceStoreError> {
+        workspaces.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+        let buf = proto::Workspaces { workspaces }.encode_to_vec();
+        let mut temp_file = NamedTempFile::new_in(&self.store_dir).context(&self.store_dir)?;
+        temp_file.write_all(&buf).context(temp_file.path())?;
+        let index_path = self.index_path();
+        temp_file
+            .persist(&index_path)
+            .map_err(|err| err.error)
+            .context(&index_path)?;
+        Ok(())
+    }
+
+    /// Applies `f` to the recorded workspaces while holding the store lock.
+    fn update(
+        &self,
+        f: impl FnOnce(&mut Vec<proto::Workspace>) -> Result<(), WorkspaceStoreError>,
+    ) -> Result<(), WorkspaceStoreError> {
+        create_or_reuse_dir(&self.store_dir).context(&self.store_dir)?;
+        let _lock = FileLock::lock(self.store_dir.join("lock"))?;
+        let mut workspaces = self.read_index()?;
+        f(&mut workspaces)?;
+        self.write_index(workspaces)
+    }
+}
+
+impl WorkspaceStore for SimpleWorkspaceStore {
+    fn name(&self) -> &str {
+        "simple"
+    }
+
+    fn add(&self, workspace_name: &WorkspaceName, path: &Path) -> Result<(), WorkspaceStoreError> {
+        let path_str = path
+            .to_str()
+            .ok_or_else(|| WorkspaceStoreError::NonUnicodePath(path.to_owned()))?;
+        self.update(|workspaces| {
+            workspaces.retain(|ws| ws.name != workspace_name.as_str());
+            workspaces.push(proto::Workspace {
+                name: workspace_name.as_str().to_owned(),
+                path: path_str.to_owned(),
+            });
+            Ok(())
+        })
+    }
+
+    fn forget(&self, workspace_names: &[&WorkspaceName]) -> Result<(), WorkspaceStoreError> {
+        self.update(|workspaces| {
+            workspaces.retain(|ws| !workspace_names.iter().any(|name| ws.name == name.as_str()));
+            Ok(())
+        })
+    }
+
+    fn rename(
+        &self,
+        old_name: &WorkspaceName,
+        new_name: &WorkspaceName,
+    ) -> Result<(), WorkspaceStoreError> {
+        self.update(|workspaces| {
+            workspaces.retain(|ws| ws.name != new_name.as_str());
+            for ws in workspaces.iter_mut() {
+                if ws.name == old_name.as_str() {
+                    ws.name = new_name.as_str().to_owned();
+                }
+            }
+            Ok(())
+        })
+    }
+
+    fn get_workspace_path(
+        &self,
+        workspace_name: &WorkspaceName,
+    ) -> Result<Option<PathBuf>, WorkspaceStoreError> {
+        let workspaces = self.read_index()?;
+        Ok(workspaces
+            .into_iter()
+            .find(|ws| ws.name == workspace_name.as_str())
+            .map(|ws| PathBuf::from(ws.path)))
+    }
+
+    fn list(&self) -> Result<Vec<(WorkspaceNameBuf, PathBuf)>, WorkspaceStoreError> {
+        let workspaces = self.read_index()?;
+        Ok(workspaces
+            .into_iter()
+            .map(|ws| (ws.name.into(), PathBuf::from(ws.path)))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_simple_workspace_store() {
+        let temp_dir = testutils::new_temp_dir();
+        let store = SimpleWorkspaceStore::load(temp_dir.path());
+        let name = |s: &str| WorkspaceNameBuf::from(s);
+        assert_eq!(store.list().unwrap(), vec![]);
+        assert_eq!(store.get_workspace_path(&name("default")).unwrap(), None);
+
+        store.add(&name("default"), Path::new("/repo")).unwrap();
+        store.add(&name("second"), Path::new("/second")).unwrap();
+        assert_eq!(
+            store.get_workspace_path(&name("default")).unwrap(),
+            Some(PathBuf::from("/repo"))
+        );
+
+        // Replace the existing path
+        store.add(&name("second"), Path::new("/moved")).unwrap();
+        assert_eq!(
+            store.list().unwrap(),
+            vec![
+                (name("default"), PathBuf::from("/repo")),
+                (name("second"), PathBuf::from("/moved")),
+            ]
+        );
+
+        store.rename(&name("second"), &name("third")).unwrap();
+        assert_eq!(store.get_workspace_path(&name("second")).unwrap(), None);
+        assert_eq!(
+            store.get_workspace_path(&name("third")).unwrap(),
+            Some(PathBuf::from("/moved"))
+        );
+
+        store.forget(&[&name("default")]).unwrap();
+        assert_eq!(
+            store.list().unwrap(),
+            vec![(name("third"), PathBuf::from("/moved"))]
+        );
+    }
+}
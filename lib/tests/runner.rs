@@ -8,6 +8,7 @@ fn test_no_forgotten_test_files() {
 
 mod test_annotate;
 mod test_bad_locking;
+mod test_bisect;
 mod test_commit_builder;
 mod test_commit_concurrent;
 mod test_conflicts;
@@ -22,11 +23,14 @@ mod test_init;
 mod test_load_repo;
 mod test_local_working_copy;
 mod test_local_working_copy_concurrent;
+#[cfg(target_os = "linux")]
+mod test_local_working_copy_inotify;
 mod test_local_working_copy_sparse;
 mod test_merge_trees;
 mod test_merged_tree;
 mod test_mut_repo;
 mod test_operations;
+mod test_patch;
 mod test_refs;
 mod test_revset;
 mod test_rewrite;
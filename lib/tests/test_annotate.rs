@@ -33,6 +33,7 @@ use jj_lib::revset::RevsetExpression;
 use testutils::create_tree;
 use testutils::repo_path;
 use testutils::TestRepo;
+use testutils::TestRepoBackend;
 
 fn create_commit_fn(
     mut_repo: &mut MutableRepo,
@@ -90,13 +91,34 @@ fn annotate_parent_tree(repo: &dyn Repo, commit: &Commit, file_path: &RepoPath)
     format_annotation(repo, &annotator.to_annotation())
 }
 
+fn annotate_following_copies(
+    repo: &dyn Repo,
+    commit: &Commit,
+    domain: &Rc<ResolvedRevsetExpression>,
+    file_path: &RepoPath,
+) -> String {
+    let mut annotator = FileAnnotator::from_commit(commit, file_path).unwrap();
+    annotator.set_follow_copies(true);
+    annotator.compute(repo, domain).unwrap();
+    let mut output = String::new();
+    for (line_origin, line) in annotator.to_annotation().lines() {
+        let origin = line_origin.unwrap_or_else(|origin| origin);
+        let commit = repo.store().get_commit(&origin.commit_id).unwrap();
+        let desc = commit.description().trim_end();
+        let path = origin.path.as_internal_file_string();
+        let sigil = if line_origin.is_err() { '*' } else { ' ' };
+        write!(output, "{desc} {path}{sigil}: {line}").unwrap();
+    }
+    output
+}
+
 fn format_annotation(repo: &dyn Repo, annotation: &FileAnnotation) -> String {
     let mut output = String::new();
-    for (commit_id, line) in annotation.lines() {
-        let id = commit_id.unwrap_or_else(|id| id);
-        let commit = repo.store().get_commit(id).unwrap();
+    for (line_origin, line) in annotation.lines() {
+        let origin = line_origin.unwrap_or_else(|origin| origin);
+        let commit = repo.store().get_commit(&origin.commit_id).unwrap();
         let desc = commit.description().trim_end();
-        let sigil = if commit_id.is_err() { '*' } else { ' ' };
+        let sigil = if line_origin.is_err() { '*' } else { ' ' };
         write!(output, "{desc}{sigil}: {line}").unwrap();
     }
     output
@@ -413,3 +435,206 @@ fn test_annotate_file_directory_transition() {
 
     insta::assert_snapshot!(annotate(tx.repo(), &commit2, file_path2), @"commit2 : 2");
 }
+
+#[test]
+fn test_annotate_rename() {
+    let test_repo = TestRepo::init_with_backend(TestRepoBackend::Git);
+    let repo = &test_repo.repo;
+
+    let root_commit_id = repo.store().root_commit_id();
+    let old_path = repo_path("old");
+    let new_path = repo_path("dir/new");
+
+    let mut tx = repo.start_transaction();
+    let mut create_commit = create_commit_fn(tx.repo_mut());
+    let tree1 = create_tree(repo, &[(old_path, "1\n2\n3\n4\n")]);
+    let tree2 = create_tree(repo, &[(old_path, "1\n2\n3\n4\n5\n")]);
+    let tree3 = create_tree(repo, &[(new_path, "1\n2\n3\n4\n5\n6\n")]);
+    let tree4 = create_tree(repo, &[(new_path, "0\n1\n2\n3\n4\n5\n6\n")]);
+    let commit1 = create_commit("commit1", &[root_commit_id], tree1.id());
+    let commit2 = create_commit("commit2", &[commit1.id()], tree2.id());
+    let commit3 = create_commit("commit3", &[commit2.id()], tree3.id());
+    let commit4 = create_commit("commit4", &[commit3.id()], tree4.id());
+    drop(create_commit);
+
+    // Without copy tracing, the lines before the rename are attributed to the
+    // rename commit
+    insta::assert_snapshot!(annotate(tx.repo(), &commit4, new_path), @r"
+    commit4 : 0
+    commit3 : 1
+    commit3 : 2
+    commit3 : 3
+    commit3 : 4
+    commit3 : 5
+    commit3 : 6
+    ");
+
+    // Lines are attributed to the commits which introduced them at the old path
+    let domain = RevsetExpression::all();
+    insta::assert_snapshot!(
+        annotate_following_copies(tx.repo(), &commit4, &domain, new_path), @r"
+    commit4 dir/new : 0
+    commit1 old : 1
+    commit1 old : 2
+    commit1 old : 3
+    commit1 old : 4
+    commit2 old : 5
+    commit3 dir/new : 6
+    ");
+
+    // The old path is searched only within the domain
+    let domain = RevsetExpression::commits(vec![
+        commit2.id().clone(),
+        commit3.id().clone(),
+        commit4.id().clone(),
+    ]);
+    insta::assert_snapshot!(
+        annotate_following_copies(tx.repo(), &commit4, &domain, new_path), @r"
+    commit4 dir/new : 0
+    commit2 old*: 1
+    commit2 old*: 2
+    commit2 old*: 3
+    commit2 old*: 4
+    commit2 old : 5
+    commit3 dir/new : 6
+    ");
+    let domain = RevsetExpression::commits(vec![commit3.id().clone(), commit4.id().clone()]);
+    insta::assert_snapshot!(
+        annotate_following_copies(tx.repo(), &commit4, &domain, new_path), @r"
+    commit4 dir/new : 0
+    commit3 dir/new*: 1
+    commit3 dir/new*: 2
+    commit3 dir/new*: 3
+    commit3 dir/new*: 4
+    commit3 dir/new*: 5
+    commit3 dir/new : 6
+    ");
+}
+
+#[test]
+fn test_annotate_copy_and_rename_in_merge() {
+    let test_repo = TestRepo::init_with_backend(TestRepoBackend::Git);
+    let repo = &test_repo.repo;
+
+    let root_commit_id = repo.store().root_commit_id();
+    let file_path = repo_path("file");
+    let copy_path = repo_path("copy");
+    let renamed_path = repo_path("renamed");
+
+    let mut tx = repo.start_transaction();
+    let mut create_commit = create_commit_fn(tx.repo_mut());
+    let tree1 = create_tree(repo, &[(file_path, "1\n2\n3\n")]);
+    // The source of a copy must be modified in order to be detected by Git
+    let tree2 = create_tree(
+        repo,
+        &[(file_path, "1\n2\n3\n4\n"), (copy_path, "1\n2\n3\nc\n")],
+    );
+    let tree3 = create_tree(repo, &[(renamed_path, "1\n2\n3\nr\n")]);
+    let tree4 = create_tree(repo, &[(copy_path, "1\n2\n3\nc\nr\n")]);
+    let commit1 = create_commit("commit1", &[root_commit_id], tree1.id());
+    let commit2 = create_commit("commit2", &[commit1.id()], tree2.id());
+    let commit3 = create_commit("commit3", &[commit1.id()], tree3.id());
+    let commit4 = create_commit("commit4", &[commit2.id(), commit3.id()], tree4.id());
+    drop(create_commit);
+
+    let domain = RevsetExpression::all();
+    insta::assert_snapshot!(
+        annotate_following_copies(tx.repo(), &commit4, &domain, copy_path), @r"
+    commit1 file : 1
+    commit1 file : 2
+    commit1 file : 3
+    commit2 copy : c
+    commit3 renamed : r
+    ");
+}
+
+#[test]
+fn test_annotate_ignored_revisions() {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let root_commit_id = repo.store().root_commit_id();
+    let file_path = repo_path("file");
+
+    let mut tx = repo.start_transaction();
+    let mut create_commit = create_commit_fn(tx.repo_mut());
+    let tree1 = create_tree(repo, &[(file_path, "a\nb\nc\n")]);
+    let tree2 = create_tree(repo, &[(file_path, "A\nB\nc\nd\n")]);
+    let tree3 = create_tree(repo, &[(file_path, "A\nB\nC\nd\n")]);
+    let commit1 = create_commit("commit1", &[root_commit_id], tree1.id());
+    let commit2 = create_commit("commit2", &[commit1.id()], tree2.id());
+    let commit3 = create_commit("commit3", &[commit2.id()], tree3.id());
+    drop(create_commit);
+
+    // Changed lines of the ignored commit are attributed to the parent lines at
+    // the same position. The added line has no counterpart in the parent.
+    let mut annotator = FileAnnotator::from_commit(&commit3, file_path).unwrap();
+    annotator.set_ignored_revisions(RevsetExpression::commit(commit2.id().clone()));
+    annotator
+        .compute(tx.repo(), &RevsetExpression::all())
+        .unwrap();
+    insta::assert_snapshot!(format_annotation(tx.repo(), &annotator.to_annotation()), @r"
+    commit1 : A
+    commit1 : B
+    commit3 : C
+    commit2 : d
+    ");
+
+    // Ignoring the starting commit
+    let mut annotator = FileAnnotator::from_commit(&commit3, file_path).unwrap();
+    annotator.set_ignored_revisions(RevsetExpression::commit(commit3.id().clone()));
+    annotator
+        .compute(tx.repo(), &RevsetExpression::all())
+        .unwrap();
+    insta::assert_snapshot!(format_annotation(tx.repo(), &annotator.to_annotation()), @r"
+    commit2 : A
+    commit2 : B
+    commit1 : C
+    commit2 : d
+    ");
+}
+
+#[test]
+fn test_annotate_line_range() {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let root_commit_id = repo.store().root_commit_id();
+    let file_path = repo_path("file");
+
+    let mut tx = repo.start_transaction();
+    let mut create_commit = create_commit_fn(tx.repo_mut());
+    let tree1 = create_tree(repo, &[(file_path, "1\n")]);
+    let tree2 = create_tree(repo, &[(file_path, "1\n2\n")]);
+    let tree3 = create_tree(repo, &[(file_path, "1\n2\n3\n")]);
+    let commit1 = create_commit("commit1", &[root_commit_id], tree1.id());
+    let commit2 = create_commit("commit2", &[commit1.id()], tree2.id());
+    let commit3 = create_commit("commit3", &[commit2.id()], tree3.id());
+    drop(create_commit);
+
+    // Lines out of the range are left unresolved, and the ancestors which
+    // don't contribute to the range aren't visited.
+    let mut annotator = FileAnnotator::from_commit(&commit3, file_path).unwrap();
+    annotator.restrict_to_lines(1..3);
+    annotator
+        .compute(tx.repo(), &RevsetExpression::all())
+        .unwrap();
+    insta::assert_snapshot!(format_annotation(tx.repo(), &annotator.to_annotation()), @r"
+    commit3*: 1
+    commit2 : 2
+    commit3 : 3
+    ");
+    assert_eq!(annotator.pending_commits().count(), 0);
+
+    // Empty range
+    let mut annotator = FileAnnotator::from_commit(&commit3, file_path).unwrap();
+    annotator.restrict_to_lines(3..3);
+    annotator
+        .compute(tx.repo(), &RevsetExpression::all())
+        .unwrap();
+    insta::assert_snapshot!(format_annotation(tx.repo(), &annotator.to_annotation()), @r"
+    commit3*: 1
+    commit3*: 2
+    commit3*: 3
+    ");
+}
@@ -0,0 +1,221 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashSet;
+
+use assert_matches::assert_matches;
+use jj_lib::bisect::mark;
+use jj_lib::bisect::next_step;
+use jj_lib::bisect::BisectError;
+use jj_lib::bisect::BisectionStep;
+use jj_lib::bisect::Evaluation;
+use jj_lib::commit::Commit;
+use jj_lib::op_store::BisectState;
+use jj_lib::repo::Repo;
+use testutils::CommitGraphBuilder;
+use testutils::TestRepo;
+
+fn state_with(marks: &[(&Commit, Evaluation)]) -> BisectState {
+    let mut state = BisectState::default();
+    for (commit, evaluation) in marks {
+        mark(&mut state, commit.id(), *evaluation);
+    }
+    state
+}
+
+/// Bisects with `is_bad` as the test, and returns the first bad commit along
+/// with the number of tested commits.
+fn bisect(
+    repo: &dyn Repo,
+    mut state: BisectState,
+    is_bad: impl Fn(&jj_lib::backend::CommitId) -> bool,
+) -> (BisectionStep, usize) {
+    let mut num_tested = 0;
+    loop {
+        match next_step(repo, &state).unwrap() {
+            BisectionStep::Test(id) => {
+                num_tested += 1;
+                let evaluation = if is_bad(&id) {
+                    Evaluation::Bad
+                } else {
+                    Evaluation::Good
+                };
+                mark(&mut state, &id, evaluation);
+            }
+            step => return (step, num_tested),
+        }
+    }
+}
+
+#[test]
+fn test_bisect_linear() {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let mut tx = repo.start_transaction();
+    let mut graph_builder = CommitGraphBuilder::new(tx.repo_mut());
+    let mut commits = vec![graph_builder.initial_commit()];
+    for _ in 0..15 {
+        let commit = graph_builder.commit_with_parents(&[commits.last().unwrap()]);
+        commits.push(commit);
+    }
+    let repo = tx.commit("test").unwrap();
+
+    // The midpoint of the range is tested first
+    let state = state_with(&[
+        (&commits[0], Evaluation::Good),
+        (&commits[15], Evaluation::Bad),
+    ]);
+    assert_eq!(
+        next_step(repo.as_ref(), &state).unwrap(),
+        BisectionStep::Test(commits[8].id().clone())
+    );
+
+    let first_bad = commits[11].id().clone();
+    let bad_ids: Vec<_> = commits[11..].iter().map(|c| c.id().clone()).collect();
+    let (step, num_tested) = bisect(repo.as_ref(), state, |id| bad_ids.contains(id));
+    assert_eq!(step, BisectionStep::Found(first_bad));
+    assert_eq!(num_tested, 4);
+}
+
+#[test]
+fn test_bisect_long_linear() {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let mut tx = repo.start_transaction();
+    let mut graph_builder = CommitGraphBuilder::new(tx.repo_mut());
+    let mut commits = vec![graph_builder.initial_commit()];
+    for _ in 0..1000 {
+        let commit = graph_builder.commit_with_parents(&[commits.last().unwrap()]);
+        commits.push(commit);
+    }
+    let repo = tx.commit("test").unwrap();
+
+    // A commit close to the midpoint is chosen from the sampled commits
+    let state = state_with(&[
+        (&commits[0], Evaluation::Good),
+        (&commits[1000], Evaluation::Bad),
+    ]);
+    let BisectionStep::Test(id) = next_step(repo.as_ref(), &state).unwrap() else {
+        panic!("expected a commit to test");
+    };
+    let pos = commits.iter().position(|c| c.id() == &id).unwrap();
+    assert!((490..=510).contains(&pos), "chose commit {pos}");
+
+    let first_bad = commits[737].id().clone();
+    let bad_ids: HashSet<_> = commits[737..].iter().map(|c| c.id().clone()).collect();
+    let (step, num_tested) = bisect(repo.as_ref(), state, |id| bad_ids.contains(id));
+    assert_eq!(step, BisectionStep::Found(first_bad));
+    assert!(num_tested <= 11, "tested {num_tested} commits");
+}
+
+#[test]
+fn test_bisect_merge() {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    // F
+    // |\
+    // D E
+    // | |
+    // B C
+    // |/
+    // A
+    let mut tx = repo.start_transaction();
+    let mut graph_builder = CommitGraphBuilder::new(tx.repo_mut());
+    let commit_a = graph_builder.initial_commit();
+    let commit_b = graph_builder.commit_with_parents(&[&commit_a]);
+    let commit_c = graph_builder.commit_with_parents(&[&commit_a]);
+    let commit_d = graph_builder.commit_with_parents(&[&commit_b]);
+    let commit_e = graph_builder.commit_with_parents(&[&commit_c]);
+    let commit_f = graph_builder.commit_with_parents(&[&commit_d, &commit_e]);
+    let repo = tx.commit("test").unwrap();
+
+    // The bug was introduced in the second branch
+    let state = state_with(&[(&commit_a, Evaluation::Good), (&commit_f, Evaluation::Bad)]);
+    let bad_ids = [commit_c.id(), commit_e.id(), commit_f.id()];
+    let (step, _) = bisect(repo.as_ref(), state, |id| bad_ids.contains(&id));
+    assert_eq!(step, BisectionStep::Found(commit_c.id().clone()));
+
+    // The bug was introduced by the merge
+    let state = state_with(&[(&commit_a, Evaluation::Good), (&commit_f, Evaluation::Bad)]);
+    let (step, _) = bisect(repo.as_ref(), state, |id| id == commit_f.id());
+    assert_eq!(step, BisectionStep::Found(commit_f.id().clone()));
+}
+
+#[test]
+fn test_bisect_skip() {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let mut tx = repo.start_transaction();
+    let mut graph_builder = CommitGraphBuilder::new(tx.repo_mut());
+    let commit_a = graph_builder.initial_commit();
+    let commit_b = graph_builder.commit_with_parents(&[&commit_a]);
+    let commit_c = graph_builder.commit_with_parents(&[&commit_b]);
+    let commit_d = graph_builder.commit_with_parents(&[&commit_c]);
+    let repo = tx.commit("test").unwrap();
+
+    // Skipped commits aren't tested again
+    let state = state_with(&[
+        (&commit_a, Evaluation::Good),
+        (&commit_d, Evaluation::Bad),
+        (&commit_b, Evaluation::Skip),
+    ]);
+    assert_eq!(
+        next_step(repo.as_ref(), &state).unwrap(),
+        BisectionStep::Test(commit_c.id().clone())
+    );
+
+    // If the skipped commit can't be ruled out, the result is ambiguous
+    let mut state = state;
+    mark(&mut state, commit_c.id(), Evaluation::Bad);
+    assert_eq!(
+        next_step(repo.as_ref(), &state).unwrap(),
+        BisectionStep::Ambiguous(vec![commit_c.id().clone(), commit_b.id().clone()])
+    );
+
+    // Marking the commit again replaces the previous mark
+    mark(&mut state, commit_b.id(), Evaluation::Good);
+    assert_eq!(state.skipped, vec![]);
+    assert_eq!(
+        next_step(repo.as_ref(), &state).unwrap(),
+        BisectionStep::Found(commit_c.id().clone())
+    );
+}
+
+#[test]
+fn test_bisect_invalid_range() {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let mut tx = repo.start_transaction();
+    let mut graph_builder = CommitGraphBuilder::new(tx.repo_mut());
+    let commit_a = graph_builder.initial_commit();
+    let commit_b = graph_builder.commit_with_parents(&[&commit_a]);
+    let repo = tx.commit("test").unwrap();
+
+    let state = state_with(&[(&commit_a, Evaluation::Good)]);
+    assert_matches!(
+        next_step(repo.as_ref(), &state),
+        Err(BisectError::NoBadCommits)
+    );
+
+    let state = state_with(&[(&commit_b, Evaluation::Good), (&commit_a, Evaluation::Bad)]);
+    assert_matches!(
+        next_step(repo.as_ref(), &state),
+        Err(BisectError::EmptyRange)
+    );
+}
@@ -16,9 +16,13 @@ use std::cmp::max;
 use std::sync::Arc;
 use std::thread;
 
+use jj_lib::cas_op_heads_store::CasOpHeadsStore;
+use jj_lib::config::ConfigLayer;
+use jj_lib::config::ConfigSource;
 use jj_lib::dag_walk;
 use jj_lib::repo::ReadonlyRepo;
 use jj_lib::repo::Repo as _;
+use jj_lib::settings::UserSettings;
 use test_case::test_case;
 use testutils::write_random_commit;
 use testutils::TestRepoBackend;
@@ -101,3 +105,42 @@ fn test_commit_parallel_instances(backend: TestRepoBackend) {
     // initial commit.
     assert_eq!(count_non_merge_operations(&repo), num_threads + 2);
 }
+
+#[test]
+fn test_commit_parallel_cas_op_heads_store() {
+    // Stress test of the lock-free op heads store. Many repo instances commit
+    // several transactions each at the same time. No update should be lost.
+    let mut config = testutils::base_user_config();
+    config.add_layer(
+        ConfigLayer::parse(ConfigSource::User, r#"core.op-heads-store = "cas""#).unwrap(),
+    );
+    let settings = UserSettings::from_config(config).unwrap();
+    let test_workspace =
+        TestWorkspace::init_with_backend_and_settings(TestRepoBackend::Test, &settings);
+    let test_env = &test_workspace.env;
+    let repo_path = test_workspace.repo_path();
+    let repo = &test_workspace.repo;
+    assert_eq!(repo.op_heads_store().name(), CasOpHeadsStore::name());
+
+    let num_threads = max(num_cpus::get(), 4) * 2;
+    let num_commits_per_thread = 5;
+    thread::scope(|s| {
+        for _ in 0..num_threads {
+            let settings = settings.clone();
+            s.spawn(move || {
+                for _ in 0..num_commits_per_thread {
+                    let repo = test_env.load_repo_at_head(&settings, repo_path);
+                    let mut tx = repo.start_transaction();
+                    write_random_commit(tx.repo_mut());
+                    tx.commit("test").unwrap();
+                }
+            });
+        }
+    });
+    let repo = test_env.load_repo_at_head(&settings, repo_path);
+    let num_commits = num_threads * num_commits_per_thread;
+    // One commit per transaction plus the initial working-copy commit
+    assert_eq!(repo.view().heads().len(), num_commits + 1);
+    assert_eq!(count_non_merge_operations(&repo), num_commits + 2);
+    assert_eq!(repo.op_heads_store().get_op_heads().unwrap().len(), 1);
+}
@@ -312,6 +312,207 @@ fn test_materialize_conflict_three_sides() {
     );
 }
 
+#[test]
+fn test_materialize_conflict_zdiff3() {
+    let test_repo = TestRepo::init();
+    let store = test_repo.repo.store();
+
+    let path = repo_path("file");
+    let base_id = testutils::write_file(
+        store,
+        path,
+        indoc! {"
+            line 1
+            line 2
+            line 3
+        "},
+    );
+    let left_id = testutils::write_file(
+        store,
+        path,
+        indoc! {"
+            line 1
+            common 2.1
+            left 2.2
+            common 2.3
+            line 3
+        "},
+    );
+    let right_id = testutils::write_file(
+        store,
+        path,
+        indoc! {"
+            line 1
+            common 2.1
+            right 2.2
+            common 2.3
+            line 3
+        "},
+    );
+
+    let conflict = Merge::from_removes_adds(
+        vec![Some(base_id.clone())],
+        vec![Some(left_id.clone()), Some(right_id.clone())],
+    );
+    // Test materializing "git" conflict markers, which include the lines common
+    // to both sides
+    insta::assert_snapshot!(
+        &materialize_conflict_string(store, path, &conflict, ConflictMarkerStyle::Git),
+        @r"
+    line 1
+    <<<<<<< Side #1 (Conflict 1 of 1)
+    common 2.1
+    left 2.2
+    common 2.3
+    ||||||| Base
+    line 2
+    =======
+    common 2.1
+    right 2.2
+    common 2.3
+    >>>>>>> Side #2 (Conflict 1 of 1 ends)
+    line 3
+    "
+    );
+    // Test materializing "zdiff3" conflict markers, which move the lines common
+    // to both sides out of the conflict
+    let materialized =
+        materialize_conflict_string(store, path, &conflict, ConflictMarkerStyle::Zdiff3);
+    insta::assert_snapshot!(materialized, @r"
+    line 1
+    common 2.1
+    <<<<<<< Side #1 (Conflict 1 of 1)
+    left 2.2
+    ||||||| Base
+    line 2
+    =======
+    right 2.2
+    >>>>>>> Side #2 (Conflict 1 of 1 ends)
+    common 2.3
+    line 3
+    ");
+    // Unchanged content should keep the original conflict
+    assert_eq!(
+        update_from_content(
+            &conflict,
+            store,
+            path,
+            materialized.as_bytes(),
+            ConflictMarkerStyle::Zdiff3,
+            MIN_CONFLICT_MARKER_LEN,
+        )
+        .block_on()
+        .unwrap(),
+        conflict
+    );
+
+    // Editing the content outside the conflict keeps the common lines on the
+    // sides instead of adding them to the base
+    let edited = materialized.replace("line 1", "line one");
+    let new_conflict = update_from_content(
+        &conflict,
+        store,
+        path,
+        edited.as_bytes(),
+        ConflictMarkerStyle::Zdiff3,
+        MIN_CONFLICT_MARKER_LEN,
+    )
+    .block_on()
+    .unwrap();
+    let new_conflict_terms = new_conflict
+        .iter()
+        .map(|id| String::from_utf8(testutils::read_file(store, path, id.as_ref().unwrap())))
+        .try_collect::<_, Vec<_>, _>()
+        .unwrap();
+    let [new_left_side, new_base, new_right_side] = new_conflict_terms.as_slice() else {
+        unreachable!()
+    };
+    insta::assert_snapshot!(new_left_side, @r"
+    line one
+    common 2.1
+    left 2.2
+    common 2.3
+    line 3
+    ");
+    insta::assert_snapshot!(new_base, @r"
+    line one
+    line 2
+    line 3
+    ");
+    insta::assert_snapshot!(new_right_side, @r"
+    line one
+    common 2.1
+    right 2.2
+    common 2.3
+    line 3
+    ");
+    // Edited common lines can't be told apart from resolved content, so they're
+    // added to the base
+    let edited = materialized.replace("common 2.1", "common two");
+    let new_conflict = update_from_content(
+        &conflict,
+        store,
+        path,
+        edited.as_bytes(),
+        ConflictMarkerStyle::Zdiff3,
+        MIN_CONFLICT_MARKER_LEN,
+    )
+    .block_on()
+    .unwrap();
+    let new_conflict_terms = new_conflict
+        .iter()
+        .map(|id| String::from_utf8(testutils::read_file(store, path, id.as_ref().unwrap())))
+        .try_collect::<_, Vec<_>, _>()
+        .unwrap();
+    let [new_left_side, new_base, _] = new_conflict_terms.as_slice() else {
+        unreachable!()
+    };
+    insta::assert_snapshot!(new_left_side, @r"
+    line 1
+    common two
+    left 2.2
+    common 2.3
+    line 3
+    ");
+    insta::assert_snapshot!(new_base, @r"
+    line 1
+    common two
+    line 2
+    line 3
+    ");
+
+    // If one side only adds lines to the other, the common lines are moved out
+    // of the conflict region
+    let left_id = testutils::write_file(
+        store,
+        path,
+        indoc! {"
+            line 1
+            common 2.1
+            line 3
+        "},
+    );
+    let conflict = Merge::from_removes_adds(
+        vec![Some(base_id.clone())],
+        vec![Some(left_id.clone()), Some(right_id.clone())],
+    );
+    insta::assert_snapshot!(
+        &materialize_conflict_string(store, path, &conflict, ConflictMarkerStyle::Zdiff3),
+        @r"
+    line 1
+    common 2.1
+    <<<<<<< Side #1 (Conflict 1 of 1)
+    ||||||| Base
+    line 2
+    =======
+    right 2.2
+    common 2.3
+    >>>>>>> Side #2 (Conflict 1 of 1 ends)
+    line 3
+    "
+    );
+}
+
 #[test]
 fn test_materialize_conflict_multi_rebase_conflicts() {
     let test_repo = TestRepo::init();
@@ -2013,6 +2013,193 @@ fn test_export_bookmark_on_root_commit() {
     );
 }
 
+#[test]
+fn test_export_tags() {
+    // Tags created, moved, and deleted in jj are exported
+    let test_data = GitRepoData::create();
+    let git_settings = GitSettings::default();
+    let git_repo = test_data.git_repo;
+    let commit1 = empty_git_commit(&git_repo, "refs/heads/main", &[]);
+    let commit2 = empty_git_commit(&git_repo, "refs/heads/main", &[commit1]);
+    git_repo
+        .tag_reference(
+            "imported",
+            commit1,
+            gix::refs::transaction::PreviousValue::MustNotExist,
+        )
+        .unwrap();
+    git_repo
+        .tag(
+            "annotated",
+            commit1,
+            gix::object::Kind::Commit,
+            None,
+            "message",
+            gix::refs::transaction::PreviousValue::MustNotExist,
+        )
+        .unwrap();
+
+    let mut tx = test_data.repo.start_transaction();
+    let mut_repo = tx.repo_mut();
+    git::import_refs(mut_repo, &git_settings).unwrap();
+    let stats = git::export_refs(mut_repo).unwrap();
+    assert!(stats.failed_tags.is_empty());
+
+    mut_repo.set_tag_target("new".as_ref(), RefTarget::normal(jj_id(commit2)));
+    mut_repo.set_tag_target("imported".as_ref(), RefTarget::absent());
+    mut_repo.set_tag_target("annotated".as_ref(), RefTarget::normal(jj_id(commit2)));
+    mut_repo.set_tag_target(
+        "on_root".as_ref(),
+        RefTarget::normal(mut_repo.store().root_commit_id().clone()),
+    );
+    let stats = git::export_refs(mut_repo).unwrap();
+    assert_eq!(stats.failed_tags.len(), 1);
+    assert_eq!(
+        stats.failed_tags[0].0.as_ref(),
+        remote_symbol("on_root", "git")
+    );
+    assert_matches!(stats.failed_tags[0].1, FailedRefExportReason::OnRootCommit);
+
+    assert!(git_repo.find_reference("refs/tags/imported").is_err());
+    for name in ["refs/tags/new", "refs/tags/annotated"] {
+        let git_ref = git_repo.find_reference(name).unwrap();
+        assert_eq!(git_ref.target().id(), commit2);
+        assert_eq!(
+            mut_repo.get_git_ref(name.as_ref()),
+            RefTarget::normal(jj_id(commit2))
+        );
+    }
+
+    // The exported state is the base of the next import
+    git::import_refs(mut_repo, &git_settings).unwrap();
+    assert_eq!(
+        mut_repo.get_tag("new".as_ref()),
+        RefTarget::normal(jj_id(commit2))
+    );
+    assert!(mut_repo.get_tag("imported".as_ref()).is_absent());
+}
+
+#[test]
+fn test_set_annotated_tag() {
+    let test_data = GitRepoData::create();
+    let git_repo = test_data.git_repo;
+    let commit1 = empty_git_commit(&git_repo, "refs/heads/main", &[]);
+    let commit2 = empty_git_commit(&git_repo, "refs/heads/main", &[commit1]);
+
+    let mut tx = test_data.repo.start_transaction();
+    let mut_repo = tx.repo_mut();
+    git::import_refs(mut_repo, &GitSettings::default()).unwrap();
+    let tagger = test_data.repo.settings().signature();
+    for target in [commit1, commit2] {
+        git::set_annotated_tag(mut_repo, "v1".as_ref(), &jj_id(target), "message", &tagger)
+            .unwrap();
+        assert_eq!(
+            mut_repo.get_tag("v1".as_ref()),
+            RefTarget::normal(jj_id(target))
+        );
+    }
+    // The Git ref isn't written until the tag is exported
+    assert!(git_repo
+        .try_find_reference("refs/tags/v1")
+        .unwrap()
+        .is_none());
+    assert!(mut_repo.get_tag_annotation("v1".as_ref()).is_some());
+
+    let stats = git::export_refs(mut_repo).unwrap();
+    assert!(stats.failed_tags.is_empty());
+    let tag = git_repo
+        .find_reference("refs/tags/v1")
+        .unwrap()
+        .id()
+        .object()
+        .unwrap()
+        .into_tag();
+    assert_eq!(tag.target_id().unwrap(), commit2);
+    assert_eq!(tag.decode().unwrap().message, "message");
+    assert_eq!(
+        mut_repo.get_git_ref("refs/tags/v1".as_ref()),
+        RefTarget::normal(jj_id(commit2))
+    );
+    assert!(mut_repo.get_tag_annotation("v1".as_ref()).is_none());
+
+    // Nothing left to export
+    let stats = git::export_refs(mut_repo).unwrap();
+    assert!(stats.failed_tags.is_empty());
+    assert_eq!(
+        git_repo
+            .find_reference("refs/tags/v1")
+            .unwrap()
+            .peel_to_commit()
+            .unwrap()
+            .id(),
+        commit2
+    );
+
+    // Annotating the exported tag again replaces the tag object
+    git::set_annotated_tag(mut_repo, "v1".as_ref(), &jj_id(commit2), "new", &tagger).unwrap();
+    let stats = git::export_refs(mut_repo).unwrap();
+    assert!(stats.failed_tags.is_empty());
+    let tag = git_repo
+        .find_reference("refs/tags/v1")
+        .unwrap()
+        .id()
+        .object()
+        .unwrap()
+        .into_tag();
+    assert_eq!(tag.target_id().unwrap(), commit2);
+    assert_eq!(tag.decode().unwrap().message, "new");
+}
+
+#[test]
+fn test_set_annotated_tag_then_lightweight() {
+    let test_data = GitRepoData::create();
+    let git_repo = test_data.git_repo;
+    let commit1 = empty_git_commit(&git_repo, "refs/heads/main", &[]);
+
+    // Setting the tag without an annotation removes the pending annotation
+    let mut tx = test_data.repo.start_transaction();
+    let mut_repo = tx.repo_mut();
+    git::import_refs(mut_repo, &GitSettings::default()).unwrap();
+    let tagger = test_data.repo.settings().signature();
+    git::set_annotated_tag(mut_repo, "v1".as_ref(), &jj_id(commit1), "message", &tagger).unwrap();
+    mut_repo.set_tag_target("v1".as_ref(), RefTarget::normal(jj_id(commit1)));
+    assert!(mut_repo.get_tag_annotation("v1".as_ref()).is_none());
+    let stats = git::export_refs(mut_repo).unwrap();
+    assert!(stats.failed_tags.is_empty());
+    let git_ref = git_repo.find_reference("refs/tags/v1").unwrap();
+    assert_eq!(git_ref.id(), commit1);
+}
+
+#[test]
+fn test_set_annotated_tag_not_exported() {
+    let test_data = GitRepoData::create();
+    let git_repo = test_data.git_repo;
+    let commit1 = empty_git_commit(&git_repo, "refs/heads/main", &[]);
+    let commit2 = empty_git_commit(&git_repo, "refs/heads/main", &[commit1]);
+
+    // The transaction is abandoned, so the annotated tag isn't exported
+    let mut tx = test_data.repo.start_transaction();
+    let mut_repo = tx.repo_mut();
+    git::import_refs(mut_repo, &GitSettings::default()).unwrap();
+    let tagger = test_data.repo.settings().signature();
+    git::set_annotated_tag(mut_repo, "v1".as_ref(), &jj_id(commit1), "message", &tagger).unwrap();
+    drop(tx);
+    assert!(git_repo
+        .try_find_reference("refs/tags/v1")
+        .unwrap()
+        .is_none());
+
+    // The stale annotated tag isn't used for another target
+    let mut tx = test_data.repo.start_transaction();
+    let mut_repo = tx.repo_mut();
+    git::import_refs(mut_repo, &GitSettings::default()).unwrap();
+    mut_repo.set_tag_target("v1".as_ref(), RefTarget::normal(jj_id(commit2)));
+    let stats = git::export_refs(mut_repo).unwrap();
+    assert!(stats.failed_tags.is_empty());
+    let git_ref = git_repo.find_reference("refs/tags/v1").unwrap();
+    assert_eq!(git_ref.id(), commit2);
+}
+
 #[test]
 fn test_export_partial_failure() {
     // Check that we skip bookmarks that fail to export
@@ -22,11 +22,14 @@ use jj_lib::backend::CommitId;
 use jj_lib::commit::Commit;
 use jj_lib::commit_builder::CommitBuilder;
 use jj_lib::default_index::AsCompositeIndex as _;
+use jj_lib::default_index::ChangedPathIndex;
 use jj_lib::default_index::CompositeIndex;
 use jj_lib::default_index::DefaultIndexStore;
 use jj_lib::default_index::DefaultIndexStoreError;
 use jj_lib::default_index::DefaultMutableIndex;
 use jj_lib::default_index::DefaultReadonlyIndex;
+use jj_lib::fileset::FilePredicate;
+use jj_lib::fileset::FilesetExpression;
 use jj_lib::index::Index as _;
 use jj_lib::object_id::HexPrefix;
 use jj_lib::object_id::ObjectId as _;
@@ -39,11 +42,19 @@ use jj_lib::ref_name::RemoteRefSymbol;
 use jj_lib::repo::MutableRepo;
 use jj_lib::repo::ReadonlyRepo;
 use jj_lib::repo::Repo as _;
+use jj_lib::repo_path::RepoPath;
+use jj_lib::repo_path::RepoPathBuf;
 use jj_lib::revset::ResolvedExpression;
+use jj_lib::revset::RevsetExpression;
+use jj_lib::revset::RevsetFilterPredicate;
 use jj_lib::revset::GENERATION_RANGE_FULL;
+use jj_lib::str_util::StringPattern;
 use maplit::hashset;
 use testutils::commit_transactions;
 use testutils::create_random_commit;
+use testutils::create_tree;
+use testutils::repo_path;
+use testutils::repo_path_buf;
 use testutils::test_backend::TestBackend;
 use testutils::write_random_commit;
 use testutils::CommitGraphBuilder;
@@ -732,6 +743,178 @@ fn test_reindex_missing_commit() {
     assert_matches!(err, DefaultIndexStoreError::IndexCommits { op_id, .. } if op_id == *bad_op_id);
 }
 
+#[test]
+fn test_changed_path_index() {
+    let settings = testutils::user_settings();
+    let test_repo = TestRepo::init();
+    let test_env = &test_repo.env;
+    let repo = &test_repo.repo;
+
+    let root_commit = repo.store().root_commit();
+    let tree_a = create_tree(repo, &[(repo_path("a"), "a")]);
+    let tree_ab = create_tree(repo, &[(repo_path("a"), "a"), (repo_path("b"), "b")]);
+    let mut tx = repo.start_transaction();
+    let commit_a = tx
+        .repo_mut()
+        .new_commit(vec![root_commit.id().clone()], tree_a.id())
+        .write()
+        .unwrap();
+    let commit_b = tx
+        .repo_mut()
+        .new_commit(vec![commit_a.id().clone()], tree_ab.id())
+        .write()
+        .unwrap();
+    let repo = tx.commit("test").unwrap();
+
+    let changed_paths = |index: &ChangedPathIndex, commit: &Commit| -> Option<Vec<RepoPathBuf>> {
+        let paths = index.changed_paths(commit.id())?;
+        Some(paths.map(ToOwned::to_owned).collect())
+    };
+    let resolve_files = |repo: &ReadonlyRepo, file_path: &RepoPath| -> Vec<CommitId> {
+        let expression = RevsetExpression::filter(RevsetFilterPredicate::File(
+            FilesetExpression::prefix_path(file_path.to_owned()),
+        ));
+        let revset = expression.evaluate(repo).unwrap();
+        revset.iter().map(Result::unwrap).collect()
+    };
+
+    // The changed-path index is disabled by default
+    let default_index_store: &DefaultIndexStore =
+        repo.index_store().as_any().downcast_ref().unwrap();
+    assert!(!default_index_store.is_changed_path_index_enabled());
+    let default_index: &DefaultReadonlyIndex =
+        repo.readonly_index().as_any().downcast_ref().unwrap();
+    assert!(default_index.changed_paths().is_none());
+
+    let index = default_index_store
+        .build_changed_path_index_at_operation(repo.operation(), repo.store(), default_index)
+        .unwrap();
+    assert!(default_index_store.is_changed_path_index_enabled());
+    assert_eq!(
+        index.num_commits(),
+        as_readonly_composite(&repo).num_commits() as usize
+    );
+    assert_eq!(changed_paths(&index, &root_commit), Some(vec![]));
+    assert_eq!(
+        changed_paths(&index, &commit_a),
+        Some(vec![repo_path_buf("a")])
+    );
+    assert_eq!(
+        changed_paths(&index, &commit_b),
+        Some(vec![repo_path_buf("b")])
+    );
+
+    // New commits are indexed when the repo is loaded
+    let mut tx = repo.start_transaction();
+    let commit_c = tx
+        .repo_mut()
+        .new_commit(vec![commit_b.id().clone()], tree_a.id())
+        .write()
+        .unwrap();
+    tx.commit("test").unwrap();
+    let repo = test_env.load_repo_at_head(&settings, test_repo.repo_path());
+    let default_index: &DefaultReadonlyIndex =
+        repo.readonly_index().as_any().downcast_ref().unwrap();
+    let index = default_index.changed_paths().unwrap();
+    assert_eq!(
+        index.num_commits(),
+        as_readonly_composite(&repo).num_commits() as usize
+    );
+    assert_eq!(
+        changed_paths(index, &commit_c),
+        Some(vec![repo_path_buf("b")])
+    );
+
+    assert_eq!(
+        resolve_files(&repo, repo_path("a")),
+        vec![commit_a.id().clone()]
+    );
+    assert_eq!(
+        resolve_files(&repo, repo_path("b")),
+        vec![commit_c.id().clone(), commit_b.id().clone()]
+    );
+
+    // The changed-path index is rebuilt along with the commit index
+    default_index_store.reinit().unwrap();
+    let repo = test_env.load_repo_at_head(&settings, test_repo.repo_path());
+    let default_index: &DefaultReadonlyIndex =
+        repo.readonly_index().as_any().downcast_ref().unwrap();
+    let index = default_index.changed_paths().unwrap();
+    assert_eq!(
+        changed_paths(index, &commit_c),
+        Some(vec![repo_path_buf("b")])
+    );
+}
+
+#[test]
+fn test_changed_path_index_value_predicates() {
+    let settings = testutils::user_settings();
+    let test_repo = TestRepo::init();
+    let test_env = &test_repo.env;
+    let repo = &test_repo.repo;
+
+    let root_commit = repo.store().root_commit();
+    let large_content = "large\n".repeat(200);
+    let tree_a = create_tree(repo, &[(repo_path("a"), "small\n")]);
+    let tree_ab = create_tree(
+        repo,
+        &[
+            (repo_path("a"), "small\n"),
+            (repo_path("b"), &large_content),
+        ],
+    );
+    let mut tx = repo.start_transaction();
+    let commit_a = tx
+        .repo_mut()
+        .new_commit(vec![root_commit.id().clone()], tree_a.id())
+        .write()
+        .unwrap();
+    let commit_b = tx
+        .repo_mut()
+        .new_commit(vec![commit_a.id().clone()], tree_ab.id())
+        .write()
+        .unwrap();
+    let repo = tx.commit("test").unwrap();
+
+    let large_files = FilesetExpression::predicate(FilePredicate::Size(1000..=u64::MAX));
+    let resolve = |repo: &ReadonlyRepo, predicate: RevsetFilterPredicate| -> Vec<CommitId> {
+        let expression = RevsetExpression::filter(predicate);
+        let revset = expression.evaluate(repo).unwrap();
+        revset.iter().map(Result::unwrap).collect()
+    };
+    let resolve_all = |repo: &ReadonlyRepo| {
+        [
+            resolve(repo, RevsetFilterPredicate::File(large_files.clone())),
+            resolve(
+                repo,
+                RevsetFilterPredicate::DiffContains {
+                    text: StringPattern::substring("large"),
+                    files: large_files.clone(),
+                },
+            ),
+        ]
+    };
+
+    // Without the changed-path index
+    let default_index: &DefaultReadonlyIndex =
+        repo.readonly_index().as_any().downcast_ref().unwrap();
+    assert!(default_index.changed_paths().is_none());
+    let expected = vec![commit_b.id().clone()];
+    assert_eq!(resolve_all(&repo), [expected.clone(), expected.clone()]);
+
+    // The index only records paths, so value predicates fall back to diffing
+    let default_index_store: &DefaultIndexStore =
+        repo.index_store().as_any().downcast_ref().unwrap();
+    default_index_store
+        .build_changed_path_index_at_operation(repo.operation(), repo.store(), default_index)
+        .unwrap();
+    let repo = test_env.load_repo_at_head(&settings, test_repo.repo_path());
+    let default_index: &DefaultReadonlyIndex =
+        repo.readonly_index().as_any().downcast_ref().unwrap();
+    assert!(default_index.changed_paths().is_some());
+    assert_eq!(resolve_all(&repo), [expected.clone(), expected]);
+}
+
 /// Test that .jj/repo/index/type is created when the repo is created.
 #[test]
 fn test_index_store_type() {
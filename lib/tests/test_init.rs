@@ -15,10 +15,15 @@
 use std::path::Path;
 use std::path::PathBuf;
 
+use jj_lib::cas_op_heads_store::CasOpHeadsStore;
+use jj_lib::config::ConfigLayer;
+use jj_lib::config::ConfigSource;
 use jj_lib::config::StackedConfig;
 use jj_lib::git_backend::GitBackend;
 use jj_lib::ref_name::WorkspaceName;
 use jj_lib::repo::Repo as _;
+use jj_lib::repo::RepoLoader;
+use jj_lib::repo::StoreFactories;
 use jj_lib::settings::UserSettings;
 use jj_lib::workspace::Workspace;
 use test_case::test_case;
@@ -156,6 +161,41 @@ fn test_init_with_default_config(backend: TestRepoBackend) {
     assert_eq!(wc_commit.committer().email, "".to_string());
 }
 
+#[test]
+fn test_init_op_heads_store() {
+    let mut config = testutils::base_user_config();
+    config.add_layer(
+        ConfigLayer::parse(ConfigSource::User, r#"core.op-heads-store = "cas""#).unwrap(),
+    );
+    let settings = UserSettings::from_config(config).unwrap();
+    let temp_dir = testutils::new_temp_dir();
+    let (workspace, repo) = Workspace::init_simple(&settings, temp_dir.path()).unwrap();
+    assert_eq!(repo.op_heads_store().name(), CasOpHeadsStore::name());
+    let mut tx = repo.start_transaction();
+    write_random_commit(tx.repo_mut());
+    tx.commit("test").unwrap();
+
+    // The store type is recorded, so it is used regardless of the settings
+    let repo = RepoLoader::init_from_file_system(
+        &testutils::user_settings(),
+        workspace.repo_path(),
+        &StoreFactories::default(),
+    )
+    .unwrap()
+    .load_at_head()
+    .unwrap();
+    assert_eq!(repo.op_heads_store().name(), CasOpHeadsStore::name());
+    assert_eq!(repo.op_heads_store().get_op_heads().unwrap().len(), 1);
+
+    let mut config = testutils::base_user_config();
+    config.add_layer(
+        ConfigLayer::parse(ConfigSource::User, r#"core.op-heads-store = "bad""#).unwrap(),
+    );
+    let settings = UserSettings::from_config(config).unwrap();
+    let temp_dir = testutils::new_temp_dir();
+    assert!(Workspace::init_simple(&settings, temp_dir.path()).is_err());
+}
+
 #[test_case(TestRepoBackend::Simple ; "simple backend")]
 #[test_case(TestRepoBackend::Git ; "git backend")]
 fn test_init_checkout(backend: TestRepoBackend) {
@@ -23,14 +23,20 @@ use std::sync::Arc;
 use assert_matches::assert_matches;
 use indoc::indoc;
 use itertools::Itertools as _;
+use jj_lib::backend::CommitId;
 use jj_lib::backend::MergedTreeId;
 use jj_lib::backend::TreeId;
 use jj_lib::backend::TreeValue;
 use jj_lib::file_util::check_symlink_support;
 use jj_lib::file_util::try_symlink;
 use jj_lib::fsmonitor::FsmonitorSettings;
+use jj_lib::git_lfs::LfsObjectStore;
+use jj_lib::git_lfs::LfsPointer;
 use jj_lib::gitignore::GitIgnoreFile;
 use jj_lib::local_working_copy::LocalWorkingCopy;
+use jj_lib::matchers::FilesMatcher;
+use jj_lib::matchers::Matcher;
+use jj_lib::matchers::PrefixMatcher;
 use jj_lib::merge::Merge;
 use jj_lib::merge::MergedTreeValue;
 use jj_lib::merged_tree::MergedTree;
@@ -1441,6 +1447,149 @@ fn test_git_submodule(gitignore_content: &str) {
     assert_eq!(stats.skipped_files, 1);
 }
 
+#[test]
+fn test_git_submodule_head_moved() {
+    // Tests that the commit checked out in a git submodule is snapshotted.
+
+    let mut test_workspace = TestWorkspace::init_with_backend(TestRepoBackend::Git);
+    let repo = test_workspace.repo.clone();
+    let store = repo.store().clone();
+    let workspace_root = test_workspace.workspace.workspace_root().to_owned();
+
+    let submodule_path = repo_path("module");
+    let mut tree_builder = MergedTreeBuilder::new(store.empty_merged_tree_id());
+    tree_builder.set_or_remove(
+        submodule_path.to_owned(),
+        Merge::normal(TreeValue::GitSubmodule(CommitId::from_hex(
+            "1111111111111111111111111111111111111111",
+        ))),
+    );
+    let tree_id1 = tree_builder.write_tree(&store).unwrap();
+    let commit1 = commit_with_tree(repo.store(), tree_id1.clone());
+    let ws = &mut test_workspace.workspace;
+    ws.check_out(
+        repo.op_id().clone(),
+        None,
+        &commit1,
+        &CheckoutOptions::empty_for_test(),
+    )
+    .unwrap();
+
+    // The submodule isn't checked out yet
+    let new_tree = test_workspace.snapshot().unwrap();
+    assert_eq!(new_tree.id(), tree_id1);
+
+    let submodule_repo = testutils::git::init(submodule_path.to_fs_path_unchecked(&workspace_root));
+    let submodule_commit =
+        testutils::git::add_commit(&submodule_repo, "refs/heads/main", "file", b"", "", &[]);
+    testutils::git::set_head_to_id(&submodule_repo, submodule_commit.commit_id);
+
+    // The new HEAD of the submodule is recorded, but not the files in it
+    let new_tree = test_workspace.snapshot().unwrap();
+    assert_eq!(
+        new_tree.path_value(submodule_path).unwrap(),
+        Merge::normal(TreeValue::GitSubmodule(CommitId::from_bytes(
+            submodule_commit.commit_id.as_bytes()
+        )))
+    );
+    assert_eq!(
+        new_tree.entries().map(|(path, _)| path).collect_vec(),
+        [submodule_path.to_owned()]
+    );
+}
+
+#[test]
+fn test_git_lfs() {
+    // Tests that LFS pointers are replaced with the objects on checkout, and
+    // that files tracked by LFS are stored as pointers on snapshot.
+
+    let mut test_workspace = TestWorkspace::init_with_backend(TestRepoBackend::Git);
+    let repo = test_workspace.repo.clone();
+    let workspace_root = test_workspace.workspace.workspace_root().to_owned();
+    let lfs_store = LfsObjectStore::for_store(repo.store()).unwrap();
+    let pointer = lfs_store.write(&mut b"large\n".as_slice()).unwrap();
+    let pointer_text = String::from_utf8(pointer.to_bytes()).unwrap();
+    let missing_pointer = LfsPointer {
+        oid: "0".repeat(64),
+        size: 1,
+    };
+    let missing_pointer_text = String::from_utf8(missing_pointer.to_bytes()).unwrap();
+
+    let gitattributes_path = repo_path(".gitattributes");
+    let present_path = repo_path("present.bin");
+    let missing_path = repo_path("missing.bin");
+    let untracked_path = repo_path("pointer.txt");
+    let new_path = repo_path("new.bin");
+    let tree = create_tree(
+        &repo,
+        &[
+            (
+                gitattributes_path,
+                "*.bin filter=lfs diff=lfs merge=lfs -text\n",
+            ),
+            (present_path, &pointer_text),
+            (missing_path, &missing_pointer_text),
+            (untracked_path, &pointer_text),
+        ],
+    );
+    let commit = commit_with_tree(repo.store(), tree.id());
+    test_workspace
+        .workspace
+        .check_out(
+            repo.op_id().clone(),
+            None,
+            &commit,
+            &CheckoutOptions::empty_for_test(),
+        )
+        .unwrap();
+
+    // Only pointers to available objects in paths tracked by LFS are replaced
+    let read_disk_file =
+        |path: &RepoPath| std::fs::read(path.to_fs_path_unchecked(&workspace_root)).unwrap();
+    assert_eq!(read_disk_file(present_path), b"large\n");
+    assert_eq!(read_disk_file(missing_path), missing_pointer.to_bytes());
+    assert_eq!(read_disk_file(untracked_path), pointer.to_bytes());
+    let new_tree = test_workspace.snapshot().unwrap();
+    assert_eq!(new_tree.id(), tree.id());
+
+    // New and modified files tracked by LFS are stored as pointers
+    let write_disk_file = |path: &RepoPath, contents: &str| {
+        std::fs::write(path.to_fs_path_unchecked(&workspace_root), contents).unwrap();
+    };
+    write_disk_file(present_path, "modified large\n");
+    write_disk_file(new_path, "new large\n");
+    write_disk_file(untracked_path, "small\n");
+    let new_tree = test_workspace.snapshot().unwrap();
+    let scratch_dir = testutils::new_temp_dir();
+    let scratch_store = LfsObjectStore::new(scratch_dir.path().to_owned());
+    let modified_pointer = scratch_store
+        .write(&mut b"modified large\n".as_slice())
+        .unwrap();
+    let new_pointer = scratch_store.write(&mut b"new large\n".as_slice()).unwrap();
+    assert!(lfs_store.contains(&modified_pointer));
+    assert!(lfs_store.contains(&new_pointer));
+    let expected_tree = create_tree(
+        &repo,
+        &[
+            (
+                gitattributes_path,
+                "*.bin filter=lfs diff=lfs merge=lfs -text\n",
+            ),
+            (
+                present_path,
+                &String::from_utf8(modified_pointer.to_bytes()).unwrap(),
+            ),
+            (missing_path, &missing_pointer_text),
+            (untracked_path, "small\n"),
+            (
+                new_path,
+                &String::from_utf8(new_pointer.to_bytes()).unwrap(),
+            ),
+        ],
+    );
+    assert_eq!(new_tree.id(), expected_tree.id());
+}
+
 #[test]
 fn test_check_out_existing_file_cannot_be_removed() {
     let mut test_workspace = TestWorkspace::init();
@@ -2222,3 +2371,198 @@ fn test_snapshot_max_new_file_size() {
         UntrackedReason::FileTooLarge { .. }
     );
 }
+
+#[test]
+fn test_snapshot_new_file_size_limits() {
+    let mut test_workspace = TestWorkspace::init();
+    let workspace_root = test_workspace.workspace.workspace_root().to_owned();
+    let asset_path = repo_path("assets/large");
+    let other_path = repo_path("other/large");
+    for path in [asset_path, other_path] {
+        let disk_path = path.to_fs_path_unchecked(&workspace_root);
+        std::fs::create_dir_all(disk_path.parent().unwrap()).unwrap();
+        std::fs::write(disk_path, vec![0; 2048]).unwrap();
+    }
+    let size_limits: Vec<(Box<dyn Matcher>, u64)> = vec![
+        (Box::new(PrefixMatcher::new([repo_path("assets")])), 1024),
+        (Box::new(PrefixMatcher::new([repo_path("assets")])), 4096),
+        (Box::new(PrefixMatcher::new([repo_path("other")])), 1024),
+    ];
+    let options = SnapshotOptions {
+        max_new_file_size: u64::MAX,
+        new_file_size_limits: &size_limits,
+        ..SnapshotOptions::empty_for_test()
+    };
+    let (tree, stats) = test_workspace.snapshot_with_options(&options).unwrap();
+    // The largest matching limit applies
+    assert!(tree.path_value(asset_path).unwrap().is_present());
+    assert!(tree.path_value(other_path).unwrap().is_absent());
+    assert_eq!(
+        stats
+            .untracked_paths
+            .keys()
+            .map(AsRef::as_ref)
+            .collect_vec(),
+        [other_path]
+    );
+    assert_matches!(
+        stats.untracked_paths.values().next().unwrap(),
+        UntrackedReason::FileTooLarge {
+            size: 2048,
+            max_size: 1024
+        }
+    );
+}
+
+#[test]
+fn test_snapshot_untrack_binaries() {
+    let mut test_workspace = TestWorkspace::init();
+    let workspace_root = test_workspace.workspace.workspace_root().to_owned();
+    let elf_path = repo_path("app");
+    let wasm_path = repo_path("module.wasm");
+    let text_path = repo_path("script");
+    let short_path = repo_path("short");
+    std::fs::write(
+        elf_path.to_fs_path_unchecked(&workspace_root),
+        b"\x7fELF\x02\x01\x01\x00rest",
+    )
+    .unwrap();
+    std::fs::write(
+        wasm_path.to_fs_path_unchecked(&workspace_root),
+        b"\x00asm\x01\x00\x00\x00",
+    )
+    .unwrap();
+    std::fs::write(
+        text_path.to_fs_path_unchecked(&workspace_root),
+        "#!/bin/sh\necho hello\n",
+    )
+    .unwrap();
+    std::fs::write(short_path.to_fs_path_unchecked(&workspace_root), "MZ").unwrap();
+
+    // Binaries are tracked unless requested
+    let (tree, stats) = test_workspace
+        .snapshot_with_options(&SnapshotOptions::empty_for_test())
+        .unwrap();
+    assert!(tree.path_value(elf_path).unwrap().is_present());
+    assert!(stats.untracked_paths.is_empty());
+
+    // Binaries already tracked stay tracked
+    let new_elf_path = repo_path("app2");
+    std::fs::copy(
+        elf_path.to_fs_path_unchecked(&workspace_root),
+        new_elf_path.to_fs_path_unchecked(&workspace_root),
+    )
+    .unwrap();
+    std::fs::remove_file(wasm_path.to_fs_path_unchecked(&workspace_root)).unwrap();
+    let new_wasm_path = repo_path("module2.wasm");
+    std::fs::write(
+        new_wasm_path.to_fs_path_unchecked(&workspace_root),
+        b"\x00asm\x01\x00\x00\x00",
+    )
+    .unwrap();
+    // PE files are recognized by the signature that the DOS header points to
+    let pe_path = repo_path("app.exe");
+    let mut pe_content = b"MZ".to_vec();
+    pe_content.resize(0x3c, 0);
+    pe_content.extend(0x40u32.to_le_bytes());
+    pe_content.extend(b"PE\0\0rest");
+    std::fs::write(pe_path.to_fs_path_unchecked(&workspace_root), pe_content).unwrap();
+    let mz_text_path = repo_path("notes");
+    std::fs::write(
+        mz_text_path.to_fs_path_unchecked(&workspace_root),
+        "MZ is the magic number of DOS executables.\nIt is not enough to detect PE files.\n",
+    )
+    .unwrap();
+    let options = SnapshotOptions {
+        untrack_binaries: true,
+        ..SnapshotOptions::empty_for_test()
+    };
+    let (tree, stats) = test_workspace.snapshot_with_options(&options).unwrap();
+    assert!(tree.path_value(elf_path).unwrap().is_present());
+    assert!(tree.path_value(new_elf_path).unwrap().is_absent());
+    assert!(tree.path_value(new_wasm_path).unwrap().is_absent());
+    assert!(tree.path_value(text_path).unwrap().is_present());
+    assert!(tree.path_value(short_path).unwrap().is_present());
+    assert!(tree.path_value(pe_path).unwrap().is_absent());
+    assert!(tree.path_value(mz_text_path).unwrap().is_present());
+    assert_eq!(
+        stats
+            .untracked_paths
+            .keys()
+            .map(AsRef::as_ref)
+            .collect_vec(),
+        [pe_path, new_elf_path, new_wasm_path]
+    );
+    assert_matches!(
+        stats.untracked_paths.get(new_elf_path),
+        Some(UntrackedReason::GeneratedBinary { format: "ELF" })
+    );
+    assert_matches!(
+        stats.untracked_paths.get(new_wasm_path),
+        Some(UntrackedReason::GeneratedBinary {
+            format: "WebAssembly"
+        })
+    );
+    assert_matches!(
+        stats.untracked_paths.get(pe_path),
+        Some(UntrackedReason::GeneratedBinary { format: "PE" })
+    );
+}
+
+#[test]
+fn test_snapshot_ignore_by_default_and_force_tracking() {
+    let mut test_workspace = TestWorkspace::init();
+    let workspace_root = test_workspace.workspace.workspace_root().to_owned();
+    let log_path = repo_path("debug.log");
+    let large_path = repo_path("large");
+    std::fs::write(log_path.to_fs_path_unchecked(&workspace_root), "log\n").unwrap();
+    std::fs::write(
+        large_path.to_fs_path_unchecked(&workspace_root),
+        vec![0; 2048],
+    )
+    .unwrap();
+    let ignore_by_default_matcher = FilesMatcher::new([log_path]);
+    let options = SnapshotOptions {
+        max_new_file_size: 1024,
+        ignore_by_default_matcher: &ignore_by_default_matcher,
+        ..SnapshotOptions::empty_for_test()
+    };
+    let (tree, stats) = test_workspace.snapshot_with_options(&options).unwrap();
+    assert!(tree.path_value(log_path).unwrap().is_absent());
+    assert!(tree.path_value(large_path).unwrap().is_absent());
+    assert_matches!(
+        stats.untracked_paths.get(log_path),
+        Some(UntrackedReason::IgnoredByDefault)
+    );
+
+    // Forcing tracking records the paths in the working-copy state
+    let force_tracking_matcher = FilesMatcher::new([log_path, large_path]);
+    let (tree, stats) = test_workspace
+        .snapshot_with_options(&SnapshotOptions {
+            force_tracking_matcher: &force_tracking_matcher,
+            ..options.clone()
+        })
+        .unwrap();
+    assert!(tree.path_value(log_path).unwrap().is_present());
+    assert!(tree.path_value(large_path).unwrap().is_present());
+    assert!(stats.untracked_paths.is_empty());
+    let ws = &test_workspace.workspace;
+    let wc: &LocalWorkingCopy = ws.working_copy().as_any().downcast_ref().unwrap();
+    assert_eq!(
+        wc.force_tracked_paths()
+            .unwrap()
+            .iter()
+            .map(AsRef::as_ref)
+            .collect_vec(),
+        [log_path, large_path]
+    );
+
+    // Re-created files are tracked again without forcing
+    std::fs::remove_file(log_path.to_fs_path_unchecked(&workspace_root)).unwrap();
+    let (tree, _stats) = test_workspace.snapshot_with_options(&options).unwrap();
+    assert!(tree.path_value(log_path).unwrap().is_absent());
+    std::fs::write(log_path.to_fs_path_unchecked(&workspace_root), "log\n").unwrap();
+    let (tree, stats) = test_workspace.snapshot_with_options(&options).unwrap();
+    assert!(tree.path_value(log_path).unwrap().is_present());
+    assert!(stats.untracked_paths.is_empty());
+}
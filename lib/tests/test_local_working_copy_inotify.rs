@@ -0,0 +1,272 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::os::unix::net::UnixStream;
+use std::path::Path;
+use std::path::PathBuf;
+use std::thread;
+use std::thread::JoinHandle;
+use std::time::Duration;
+use std::time::Instant;
+
+use assert_matches::assert_matches;
+use jj_lib::fsmonitor::inotify;
+use jj_lib::fsmonitor::FsmonitorSettings;
+use jj_lib::fsmonitor::InotifyConfig;
+use jj_lib::local_working_copy::LocalWorkingCopy;
+use jj_lib::repo::Repo as _;
+use jj_lib::working_copy::SnapshotOptions;
+use testutils::repo_path;
+use testutils::TestWorkspace;
+
+/// Runs the daemon in a thread of the test process, and waits until it
+/// answers queries.
+fn start_daemon(working_copy_path: &Path, state_path: &Path) -> JoinHandle<()> {
+    let working_copy_path = working_copy_path.to_owned();
+    let daemon_state_path = state_path.to_owned();
+    let handle = thread::spawn(move || {
+        inotify::run_daemon(&working_copy_path, &daemon_state_path).unwrap();
+    });
+    let fsmonitor = inotify::Fsmonitor::new(state_path);
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while fsmonitor.query_changed_files(None).is_err() {
+        assert!(Instant::now() < deadline, "the inotify daemon didn't start");
+        thread::sleep(Duration::from_millis(10));
+    }
+    handle
+}
+
+fn sorted(mut paths: Vec<PathBuf>) -> Vec<PathBuf> {
+    paths.sort();
+    paths
+}
+
+#[test]
+fn test_inotify_query_changed_files() {
+    let test_workspace = TestWorkspace::init();
+    let workspace_root = test_workspace.workspace.workspace_root().to_owned();
+    let wc: &LocalWorkingCopy = test_workspace
+        .workspace
+        .working_copy()
+        .as_any()
+        .downcast_ref()
+        .unwrap();
+    let state_path = wc.state_path().to_owned();
+    std::fs::create_dir(workspace_root.join("dir")).unwrap();
+
+    let daemon = start_daemon(&workspace_root, &state_path);
+    let fsmonitor = inotify::Fsmonitor::new(&state_path);
+
+    // Without a previous clock, the whole working copy has to be scanned.
+    let (clock, changed_files) = fsmonitor.query_changed_files(None).unwrap();
+    assert_eq!(changed_files, None);
+
+    // Nothing has changed since
+    let (clock, changed_files) = fsmonitor.query_changed_files(Some(&clock)).unwrap();
+    assert_eq!(changed_files, Some(vec![]));
+
+    // New files, and files in new directories, are reported
+    std::fs::write(workspace_root.join("file"), "contents").unwrap();
+    std::fs::write(workspace_root.join("dir").join("file"), "contents").unwrap();
+    std::fs::create_dir(workspace_root.join("new-dir")).unwrap();
+    std::fs::write(workspace_root.join("new-dir").join("file"), "contents").unwrap();
+    let (clock, changed_files) = fsmonitor.query_changed_files(Some(&clock)).unwrap();
+    // The file in the new directory is reported as well if the directory was
+    // watched before the file was written.
+    let changed_files = changed_files
+        .unwrap()
+        .into_iter()
+        .filter(|path| path != Path::new("new-dir/file"))
+        .collect();
+    assert_eq!(
+        sorted(changed_files),
+        vec![
+            PathBuf::from("dir/file"),
+            PathBuf::from("file"),
+            PathBuf::from("new-dir"),
+        ]
+    );
+
+    // Files in the new directory are watched from now on
+    std::fs::write(workspace_root.join("new-dir").join("file"), "updated").unwrap();
+    let (clock, changed_files) = fsmonitor.query_changed_files(Some(&clock)).unwrap();
+    assert_eq!(changed_files, Some(vec![PathBuf::from("new-dir/file")]));
+
+    // Removed files are reported
+    std::fs::remove_file(workspace_root.join("file")).unwrap();
+    let (clock, changed_files) = fsmonitor.query_changed_files(Some(&clock)).unwrap();
+    assert_eq!(changed_files, Some(vec![PathBuf::from("file")]));
+
+    // Changes in .jj aren't reported
+    std::fs::write(state_path.join("unrelated"), "contents").unwrap();
+    let (clock, changed_files) = fsmonitor.query_changed_files(Some(&clock)).unwrap();
+    assert_eq!(changed_files, Some(vec![]));
+
+    // A clock from another daemon can't be used
+    let (_clock, changed_files) = fsmonitor
+        .query_changed_files(Some(&inotify::Clock::new("1-2:3".to_owned())))
+        .unwrap();
+    assert_eq!(changed_files, None);
+
+    fsmonitor.stop().unwrap();
+    daemon.join().unwrap();
+    assert!(!fsmonitor.is_daemon_running());
+    assert_matches!(
+        fsmonitor.query_changed_files(Some(&clock)),
+        Err(inotify::Error::NotRunning(_))
+    );
+}
+
+#[test]
+fn test_inotify_daemon_already_running() {
+    let test_workspace = TestWorkspace::init();
+    let workspace_root = test_workspace.workspace.workspace_root().to_owned();
+    let wc: &LocalWorkingCopy = test_workspace
+        .workspace
+        .working_copy()
+        .as_any()
+        .downcast_ref()
+        .unwrap();
+    let state_path = wc.state_path().to_owned();
+
+    let daemon = start_daemon(&workspace_root, &state_path);
+    let fsmonitor = inotify::Fsmonitor::new(&state_path);
+    assert!(fsmonitor.is_daemon_running());
+    assert_matches!(
+        inotify::run_daemon(&workspace_root, &state_path),
+        Err(inotify::Error::AlreadyRunning)
+    );
+
+    fsmonitor.stop().unwrap();
+    daemon.join().unwrap();
+}
+
+#[test]
+fn test_inotify_idle_client() {
+    let test_workspace = TestWorkspace::init();
+    let workspace_root = test_workspace.workspace.workspace_root().to_owned();
+    let wc: &LocalWorkingCopy = test_workspace
+        .workspace
+        .working_copy()
+        .as_any()
+        .downcast_ref()
+        .unwrap();
+    let state_path = wc.state_path().to_owned();
+
+    let daemon = start_daemon(&workspace_root, &state_path);
+    let fsmonitor = inotify::Fsmonitor::new(&state_path);
+
+    // A client that never finishes its request doesn't block the others
+    let _idle_client = UnixStream::connect(state_path.join(inotify::SOCKET_FILE_NAME)).unwrap();
+    let (clock, _changed_files) = fsmonitor.query_changed_files(None).unwrap();
+    let (_clock, changed_files) = fsmonitor.query_changed_files(Some(&clock)).unwrap();
+    assert_eq!(changed_files, Some(vec![]));
+
+    fsmonitor.stop().unwrap();
+    daemon.join().unwrap();
+}
+
+#[test]
+fn test_inotify_forget_unused_clocks() {
+    let test_workspace = TestWorkspace::init();
+    let workspace_root = test_workspace.workspace.workspace_root().to_owned();
+    let wc: &LocalWorkingCopy = test_workspace
+        .workspace
+        .working_copy()
+        .as_any()
+        .downcast_ref()
+        .unwrap();
+    let state_path = wc.state_path().to_owned();
+
+    let daemon = start_daemon(&workspace_root, &state_path);
+    let fsmonitor = inotify::Fsmonitor::new(&state_path);
+
+    let (old_clock, _changed_files) = fsmonitor.query_changed_files(None).unwrap();
+    std::fs::write(workspace_root.join("file"), "contents").unwrap();
+    let (mut clock, changed_files) = fsmonitor.query_changed_files(Some(&old_clock)).unwrap();
+    assert_eq!(changed_files, Some(vec![PathBuf::from("file")]));
+
+    // The old clock is still in use until newer clocks replace it
+    for i in 0..20 {
+        std::fs::write(workspace_root.join("file"), format!("contents {i}")).unwrap();
+        let (new_clock, changed_files) = fsmonitor.query_changed_files(Some(&clock)).unwrap();
+        assert_eq!(changed_files, Some(vec![PathBuf::from("file")]));
+        clock = new_clock;
+    }
+    let (_clock, changed_files) = fsmonitor.query_changed_files(Some(&old_clock)).unwrap();
+    assert_eq!(changed_files, None);
+
+    // Recent clocks can still be used
+    let (_clock, changed_files) = fsmonitor.query_changed_files(Some(&clock)).unwrap();
+    assert_eq!(changed_files, Some(vec![]));
+
+    fsmonitor.stop().unwrap();
+    daemon.join().unwrap();
+}
+
+#[test]
+fn test_inotify_snapshot() {
+    let mut test_workspace = TestWorkspace::init();
+    let repo = test_workspace.repo.clone();
+    let workspace_root = test_workspace.workspace.workspace_root().to_owned();
+    let wc: &LocalWorkingCopy = test_workspace
+        .workspace
+        .working_copy()
+        .as_any()
+        .downcast_ref()
+        .unwrap();
+    let state_path = wc.state_path().to_owned();
+
+    let foo_path = repo_path("foo");
+    let nested_path = repo_path("path/to/nested");
+    testutils::write_working_copy_file(&workspace_root, foo_path, "foo\n");
+    testutils::write_working_copy_file(&workspace_root, nested_path, "nested\n");
+
+    // The daemon is already running, so snapshotting doesn't start another
+    // one.
+    let daemon = start_daemon(&workspace_root, &state_path);
+    let options = SnapshotOptions {
+        fsmonitor_settings: FsmonitorSettings::Inotify(InotifyConfig::default()),
+        ..SnapshotOptions::empty_for_test()
+    };
+    let ws = &mut test_workspace.workspace;
+
+    {
+        let mut locked_ws = ws.start_working_copy_mutation().unwrap();
+        let (tree_id, _stats) = locked_ws.locked_wc().snapshot(&options).unwrap();
+        insta::assert_snapshot!(testutils::dump_tree(repo.store(), &tree_id), @r#"
+        tree 1ea648d9caaac2c33693
+          file "foo" (e99c2057c15160add351): "foo\n"
+          file "path/to/nested" (6209060941cd770c8d46): "nested\n"
+        "#);
+        locked_ws.finish(repo.op_id().clone()).unwrap();
+    }
+
+    {
+        testutils::write_working_copy_file(&workspace_root, foo_path, "updated foo\n");
+        std::fs::remove_file(nested_path.to_fs_path_unchecked(&workspace_root)).unwrap();
+        testutils::write_working_copy_file(&workspace_root, repo_path("new/file"), "new\n");
+        let mut locked_ws = ws.start_working_copy_mutation().unwrap();
+        let (tree_id, _stats) = locked_ws.locked_wc().snapshot(&options).unwrap();
+        insta::assert_snapshot!(testutils::dump_tree(repo.store(), &tree_id), @r#"
+        tree 410f6e18d893568fa8ab
+          file "foo" (e0fbd106147cc04ccd05): "updated foo\n"
+          file "new/file" (c2bcc6eb158b9fe4f027): "new\n"
+        "#);
+        locked_ws.finish(repo.op_id().clone()).unwrap();
+    }
+
+    inotify::Fsmonitor::new(&state_path).stop().unwrap();
+    daemon.join().unwrap();
+}
@@ -19,6 +19,7 @@ use jj_lib::matchers::EverythingMatcher;
 use jj_lib::repo::Repo as _;
 use jj_lib::repo_path::RepoPath;
 use jj_lib::repo_path::RepoPathBuf;
+use jj_lib::sparse_profile::SparseProfile;
 use jj_lib::working_copy::CheckoutOptions;
 use jj_lib::working_copy::CheckoutStats;
 use jj_lib::working_copy::WorkingCopy as _;
@@ -174,6 +175,112 @@ fn test_sparse_checkout() {
     );
 }
 
+#[test]
+fn test_sparse_checkout_profile() {
+    let mut test_workspace = TestWorkspace::init();
+    let repo = &test_workspace.repo;
+    let working_copy_path = test_workspace.workspace.workspace_root().to_owned();
+
+    let profile_path = repo_path(".jj-sparse/docs");
+    let readme_path = repo_path("README.md");
+    let doc_path = repo_path("doc/guide.md");
+    let doc_image_path = repo_path("doc/image.png");
+    let src_path = repo_path("src/main.rs");
+    let tree = create_tree(
+        repo,
+        &[
+            (profile_path, "# Documentation\nglob:'**/*.md'\n"),
+            (readme_path, "contents"),
+            (doc_path, "contents"),
+            (doc_image_path, "contents"),
+            (src_path, "contents"),
+        ],
+    );
+    let commit = commit_with_tree(repo.store(), tree.id());
+    test_workspace
+        .workspace
+        .check_out(
+            repo.op_id().clone(),
+            None,
+            &commit,
+            &CheckoutOptions::empty_for_test(),
+        )
+        .unwrap();
+    let ws = &mut test_workspace.workspace;
+
+    // The profile selects files by fileset in addition to the prefixes
+    let profile = SparseProfile::resolve(&tree, "docs").unwrap();
+    assert_eq!(profile.patterns().collect_vec(), vec!["glob:'**/*.md'"]);
+    let mut locked_ws = ws.start_working_copy_mutation().unwrap();
+    locked_ws
+        .locked_wc()
+        .set_sparse_profile(Some(profile.clone()), &CheckoutOptions::empty_for_test())
+        .unwrap();
+    let stats = locked_ws
+        .locked_wc()
+        .set_sparse_patterns(
+            to_owned_path_vec(&[src_path]),
+            &CheckoutOptions::empty_for_test(),
+        )
+        .unwrap();
+    assert_eq!(
+        stats,
+        CheckoutStats {
+            updated_files: 0,
+            added_files: 0,
+            removed_files: 2,
+            skipped_files: 0,
+        }
+    );
+    assert_eq!(
+        locked_ws.locked_wc().sparse_profile().unwrap(),
+        Some(&profile)
+    );
+    locked_ws.finish(repo.op_id().clone()).unwrap();
+    assert!(readme_path
+        .to_fs_path_unchecked(&working_copy_path)
+        .exists());
+    assert!(doc_path.to_fs_path_unchecked(&working_copy_path).exists());
+    assert!(!doc_image_path
+        .to_fs_path_unchecked(&working_copy_path)
+        .exists());
+    assert!(src_path.to_fs_path_unchecked(&working_copy_path).exists());
+    assert!(!profile_path
+        .to_fs_path_unchecked(&working_copy_path)
+        .exists());
+
+    // Reload the state to check that it was persisted
+    let wc: &LocalWorkingCopy = ws.working_copy().as_any().downcast_ref().unwrap();
+    let wc = LocalWorkingCopy::load(
+        repo.store().clone(),
+        ws.workspace_root().to_path_buf(),
+        wc.state_path().to_path_buf(),
+    );
+    assert_eq!(wc.sparse_profile().unwrap(), Some(&profile));
+    assert_eq!(
+        wc.file_states().unwrap().paths().collect_vec(),
+        vec![readme_path, doc_path, src_path]
+    );
+
+    // Dropping the profile removes the files it selected
+    let mut locked_wc = wc.start_mutation().unwrap();
+    let stats = locked_wc
+        .set_sparse_profile(None, &CheckoutOptions::empty_for_test())
+        .unwrap();
+    assert_eq!(stats.removed_files, 2);
+    assert_eq!(locked_wc.sparse_profile().unwrap(), None);
+
+    // A profile that can't be parsed isn't silently ignored
+    let bad_profile = SparseProfile {
+        name: "bad".to_owned(),
+        content: "doc\nfile_size('>1K')\n".to_owned(),
+    };
+    assert!(locked_wc
+        .set_sparse_profile(Some(bad_profile), &CheckoutOptions::empty_for_test())
+        .is_err());
+    assert_eq!(locked_wc.sparse_profile().unwrap(), None);
+}
+
 /// Test that sparse patterns are respected on commit
 #[test]
 fn test_sparse_commit() {
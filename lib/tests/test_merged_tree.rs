@@ -23,6 +23,8 @@ use jj_lib::copies::CopiesTreeDiffEntryPath;
 use jj_lib::copies::CopyOperation;
 use jj_lib::copies::CopyRecords;
 use jj_lib::files;
+use jj_lib::fileset::FilePredicate;
+use jj_lib::fileset::FilesetExpression;
 use jj_lib::matchers::EverythingMatcher;
 use jj_lib::matchers::FilesMatcher;
 use jj_lib::matchers::Matcher;
@@ -45,7 +47,10 @@ use testutils::create_tree;
 use testutils::repo_path;
 use testutils::repo_path_buf;
 use testutils::repo_path_component;
+use testutils::write_executable_file;
 use testutils::write_file;
+use testutils::write_normal_file;
+use testutils::write_symlink;
 use testutils::TestRepo;
 
 fn file_value(file_id: &FileId) -> TreeValue {
@@ -1368,6 +1373,105 @@ fn test_diff_dir_file() {
 }
 
 /// Merge 3 resolved trees that can be resolved
+#[test]
+fn test_file_value_predicates() {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+    let store = repo.store();
+
+    let normal_path = repo_path("normal");
+    let executable_path = repo_path("dir/executable");
+    let symlink_path = repo_path("symlink");
+    let binary_path = repo_path("dir/binary");
+    let conflict_path = repo_path("conflict");
+    let create_side = |conflict_contents: &str| {
+        let mut tree_builder = store.tree_builder(store.empty_tree_id().clone());
+        write_normal_file(&mut tree_builder, normal_path, "normal\n");
+        write_executable_file(&mut tree_builder, executable_path, "#!/bin/sh\n");
+        write_symlink(&mut tree_builder, symlink_path, "normal");
+        write_normal_file(&mut tree_builder, binary_path, "bin\0ary");
+        write_normal_file(&mut tree_builder, conflict_path, conflict_contents);
+        let id = tree_builder.write_tree().unwrap();
+        store.get_tree(RepoPathBuf::root(), &id).unwrap()
+    };
+    let tree = MergedTree::new(Merge::from_vec(vec![
+        create_side("side 1"),
+        create_side("base"),
+        create_side("side 2"),
+    ]));
+    let empty_tree = MergedTree::resolved(create_single_tree(repo, &[]));
+
+    let entries_matching = |expression: FilesetExpression| {
+        let matcher = expression.to_matcher();
+        let entry_paths = tree
+            .entries_matching(matcher.as_ref())
+            .map(|(path, _)| path)
+            .collect_vec();
+        let diff_paths = empty_tree
+            .diff_stream(&tree, matcher.as_ref())
+            .map(|diff| diff.path)
+            .collect::<Vec<_>>()
+            .block_on();
+        assert_eq!(diff_paths, entry_paths);
+        diff_stream_equals_iter(&empty_tree, &tree, matcher.as_ref());
+        entry_paths
+    };
+    let predicate = FilesetExpression::predicate;
+
+    assert_eq!(
+        entries_matching(predicate(FilePredicate::Executable)),
+        vec![executable_path.to_owned()]
+    );
+    assert_eq!(
+        entries_matching(predicate(FilePredicate::Symlink)),
+        vec![symlink_path.to_owned()]
+    );
+    assert_eq!(
+        entries_matching(predicate(FilePredicate::Conflict)),
+        vec![conflict_path.to_owned()]
+    );
+    assert_eq!(
+        entries_matching(predicate(FilePredicate::Binary)),
+        vec![binary_path.to_owned()]
+    );
+    // "#!/bin/sh\n" is the only file of 8 bytes or more, but any side of the
+    // conflict can match
+    assert_eq!(
+        entries_matching(predicate(FilePredicate::Size(8..=u64::MAX))),
+        vec![executable_path.to_owned()]
+    );
+    assert_eq!(
+        entries_matching(predicate(FilePredicate::Size(6..=6))),
+        vec![conflict_path.to_owned()]
+    );
+
+    // Predicates can be combined with paths and negated
+    assert_eq!(
+        entries_matching(FilesetExpression::all().difference(predicate(FilePredicate::Binary))),
+        vec![
+            conflict_path.to_owned(),
+            executable_path.to_owned(),
+            normal_path.to_owned(),
+            symlink_path.to_owned(),
+        ]
+    );
+    assert_eq!(
+        entries_matching(
+            FilesetExpression::prefix_path(repo_path_buf("dir")).intersection(
+                FilesetExpression::all().difference(predicate(FilePredicate::Executable))
+            )
+        ),
+        vec![binary_path.to_owned()]
+    );
+    assert_eq!(
+        entries_matching(FilesetExpression::union_all(vec![
+            FilesetExpression::file_path(normal_path.to_owned()),
+            predicate(FilePredicate::Symlink),
+        ])),
+        vec![normal_path.to_owned(), symlink_path.to_owned()]
+    );
+}
+
 #[test]
 fn test_merge_simple() {
     let test_repo = TestRepo::init();
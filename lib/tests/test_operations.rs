@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashSet;
 use std::path::Path;
 use std::slice;
 use std::sync::Arc;
@@ -23,12 +24,14 @@ use jj_lib::backend::CommitId;
 use jj_lib::config::ConfigLayer;
 use jj_lib::config::ConfigSource;
 use jj_lib::object_id::ObjectId as _;
+use jj_lib::op_store::OperationId;
 use jj_lib::op_walk;
 use jj_lib::op_walk::OpsetEvaluationError;
 use jj_lib::op_walk::OpsetResolutionError;
 use jj_lib::operation::Operation;
 use jj_lib::repo::ReadonlyRepo;
 use jj_lib::repo::Repo;
+use jj_lib::revset::RevsetDiagnostics;
 use jj_lib::settings::UserSettings;
 use testutils::create_random_commit;
 use testutils::write_random_commit;
@@ -416,6 +419,99 @@ fn test_reparent_range_branchy() {
     assert_eq!(new_op_f.parent_ids(), slice::from_ref(repo_d.op_id()));
 }
 
+#[test]
+fn test_abandon_operations() {
+    let test_repo = TestRepo::init();
+    let repo_0 = test_repo.repo;
+    let loader = repo_0.loader();
+    let op_store = repo_0.op_store();
+
+    let read_op = |id| loader.load_operation(id).unwrap();
+
+    fn op_parents<const N: usize>(op: &Operation) -> [Operation; N] {
+        let parents: Vec<_> = op.parents().try_collect().unwrap();
+        parents.try_into().unwrap()
+    }
+
+    // Set up branchy operation graph:
+    // F
+    // |\
+    // | E
+    // D |
+    // C |
+    // |/
+    // B
+    // A
+    // 0 (initial)
+    let random_tx = |repo: &Arc<ReadonlyRepo>| {
+        let mut tx = repo.start_transaction();
+        write_random_commit(tx.repo_mut());
+        tx
+    };
+    let repo_a = random_tx(&repo_0).commit("op A").unwrap();
+    let repo_b = random_tx(&repo_a).commit("op B").unwrap();
+    let repo_c = random_tx(&repo_b).commit("op C").unwrap();
+    let tx_d = random_tx(&repo_c);
+    let tx_e = random_tx(&repo_b);
+    let repo_f = testutils::commit_transactions(vec![tx_d, tx_e]);
+    let [op_d, op_e] = op_parents(repo_f.operation());
+
+    // Abandon A|C:
+    // F'
+    // |\
+    // | E'
+    // D'|
+    // |/
+    // B'
+    // 0 (initial)
+    let stats = op_walk::abandon_operations(
+        op_store.as_ref(),
+        &[repo_a.operation().clone(), repo_c.operation().clone()],
+        slice::from_ref(repo_f.operation()),
+    )
+    .unwrap();
+    assert_eq!(stats.new_head_ids.len(), 1);
+    assert_eq!(stats.rewritten_count, 4);
+    assert_eq!(stats.unreachable_count, 2);
+    let new_op_f = read_op(&stats.new_head_ids[0]);
+    assert_eq!(new_op_f.metadata(), repo_f.operation().metadata());
+    let [new_op_d, new_op_e] = op_parents(&new_op_f);
+    assert_eq!(new_op_d.metadata(), op_d.metadata());
+    assert_eq!(new_op_e.metadata(), op_e.metadata());
+    assert_eq!(new_op_d.parent_ids(), new_op_e.parent_ids());
+    let [new_op_b] = op_parents(&new_op_d);
+    assert_eq!(new_op_b.metadata(), repo_b.operation().metadata());
+    assert_eq!(new_op_b.parent_ids(), slice::from_ref(repo_0.op_id()));
+
+    // Abandon D|E, which merges the parents:
+    // F'
+    // C
+    // B
+    // A
+    // 0 (initial)
+    let stats = op_walk::abandon_operations(
+        op_store.as_ref(),
+        &[op_d.clone(), op_e.clone()],
+        slice::from_ref(repo_f.operation()),
+    )
+    .unwrap();
+    assert_eq!(stats.rewritten_count, 1);
+    assert_eq!(stats.unreachable_count, 2);
+    let new_op_f = read_op(&stats.new_head_ids[0]);
+    assert_eq!(
+        new_op_f.parent_ids(),
+        [repo_c.op_id().clone(), repo_b.op_id().clone()]
+    );
+
+    // Abandon nothing
+    let stats =
+        op_walk::abandon_operations(op_store.as_ref(), &[], slice::from_ref(repo_f.operation()))
+            .unwrap();
+    assert_eq!(stats.new_head_ids, [repo_f.op_id().clone()]);
+    assert_eq!(stats.rewritten_count, 0);
+    assert_eq!(stats.unreachable_count, 0);
+}
+
 fn stable_op_id_settings() -> UserSettings {
     let mut config = testutils::base_user_config();
     config.add_layer(
@@ -456,7 +552,9 @@ fn test_resolve_op_id() {
     "#);
 
     let repo_loader = repo.loader();
-    let resolve = |op_str: &str| op_walk::resolve_op_for_load(repo_loader, op_str);
+    let resolve = |op_str: &str| {
+        op_walk::resolve_op_for_load(&mut RevsetDiagnostics::new(), repo_loader, op_str)
+    };
 
     // Full id
     assert_eq!(resolve(&operations[0].id().hex()).unwrap(), operations[0]);
@@ -477,13 +575,8 @@ fn test_resolve_op_id() {
             OpsetResolutionError::AmbiguousIdPrefix(_)
         ))
     );
-    // Empty id
-    assert_matches!(
-        resolve(""),
-        Err(OpsetEvaluationError::OpsetResolution(
-            OpsetResolutionError::InvalidIdPrefix(_)
-        ))
-    );
+    // Empty expression
+    assert_matches!(resolve(""), Err(OpsetEvaluationError::Parse(_)));
     // Unknown id
     assert_matches!(
         resolve("deadbee"),
@@ -511,7 +604,7 @@ fn test_resolve_current_op() {
     let repo = test_repo.repo;
 
     assert_eq!(
-        op_walk::resolve_op_with_repo(&repo, "@").unwrap(),
+        op_walk::resolve_op_with_repo(&mut RevsetDiagnostics::new(), &repo, "@").unwrap(),
         *repo.operation()
     );
 }
@@ -534,16 +627,30 @@ fn test_resolve_op_parents_children() {
     // Parent
     let op2_id_hex = operations[2].id().hex();
     assert_eq!(
-        op_walk::resolve_op_with_repo(repo, &format!("{op2_id_hex}-")).unwrap(),
+        op_walk::resolve_op_with_repo(
+            &mut RevsetDiagnostics::new(),
+            repo,
+            &format!("{op2_id_hex}-")
+        )
+        .unwrap(),
         *operations[1]
     );
     assert_eq!(
-        op_walk::resolve_op_with_repo(repo, &format!("{op2_id_hex}--")).unwrap(),
+        op_walk::resolve_op_with_repo(
+            &mut RevsetDiagnostics::new(),
+            repo,
+            &format!("{op2_id_hex}--")
+        )
+        .unwrap(),
         *operations[0]
     );
     // "{op2_id_hex}----" is the root operation
     assert_matches!(
-        op_walk::resolve_op_with_repo(repo, &format!("{op2_id_hex}-----")),
+        op_walk::resolve_op_with_repo(
+            &mut RevsetDiagnostics::new(),
+            repo,
+            &format!("{op2_id_hex}-----")
+        ),
         Err(OpsetEvaluationError::OpsetResolution(
             OpsetResolutionError::EmptyOperations(_)
         ))
@@ -552,15 +659,29 @@ fn test_resolve_op_parents_children() {
     // Child
     let op0_id_hex = operations[0].id().hex();
     assert_eq!(
-        op_walk::resolve_op_with_repo(repo, &format!("{op0_id_hex}+")).unwrap(),
+        op_walk::resolve_op_with_repo(
+            &mut RevsetDiagnostics::new(),
+            repo,
+            &format!("{op0_id_hex}+")
+        )
+        .unwrap(),
         *operations[1]
     );
     assert_eq!(
-        op_walk::resolve_op_with_repo(repo, &format!("{op0_id_hex}++")).unwrap(),
+        op_walk::resolve_op_with_repo(
+            &mut RevsetDiagnostics::new(),
+            repo,
+            &format!("{op0_id_hex}++")
+        )
+        .unwrap(),
         *operations[2]
     );
     assert_matches!(
-        op_walk::resolve_op_with_repo(repo, &format!("{op0_id_hex}+++")),
+        op_walk::resolve_op_with_repo(
+            &mut RevsetDiagnostics::new(),
+            repo,
+            &format!("{op0_id_hex}+++")
+        ),
         Err(OpsetEvaluationError::OpsetResolution(
             OpsetResolutionError::EmptyOperations(_)
         ))
@@ -568,17 +689,31 @@ fn test_resolve_op_parents_children() {
 
     // Child of parent
     assert_eq!(
-        op_walk::resolve_op_with_repo(repo, &format!("{op2_id_hex}--+")).unwrap(),
+        op_walk::resolve_op_with_repo(
+            &mut RevsetDiagnostics::new(),
+            repo,
+            &format!("{op2_id_hex}--+")
+        )
+        .unwrap(),
         *operations[1]
     );
 
     // Child at old repo: new operations shouldn't be visible
     assert_eq!(
-        op_walk::resolve_op_with_repo(&repos[1], &format!("{op0_id_hex}+")).unwrap(),
+        op_walk::resolve_op_with_repo(
+            &mut RevsetDiagnostics::new(),
+            &repos[1],
+            &format!("{op0_id_hex}+")
+        )
+        .unwrap(),
         *operations[1]
     );
     assert_matches!(
-        op_walk::resolve_op_with_repo(&repos[0], &format!("{op0_id_hex}+")),
+        op_walk::resolve_op_with_repo(
+            &mut RevsetDiagnostics::new(),
+            &repos[0],
+            &format!("{op0_id_hex}+")
+        ),
         Err(OpsetEvaluationError::OpsetResolution(
             OpsetResolutionError::EmptyOperations(_)
         ))
@@ -590,20 +725,126 @@ fn test_resolve_op_parents_children() {
     let repo = testutils::commit_transactions(vec![tx1, tx2]);
     let op5_id_hex = repo.operation().id().hex();
     assert_matches!(
-        op_walk::resolve_op_with_repo(&repo, &format!("{op5_id_hex}-")),
+        op_walk::resolve_op_with_repo(
+            &mut RevsetDiagnostics::new(),
+            &repo,
+            &format!("{op5_id_hex}-")
+        ),
         Err(OpsetEvaluationError::OpsetResolution(
             OpsetResolutionError::MultipleOperations { .. }
         ))
     );
     let op2_id_hex = operations[2].id().hex();
     assert_matches!(
-        op_walk::resolve_op_with_repo(&repo, &format!("{op2_id_hex}+")),
+        op_walk::resolve_op_with_repo(
+            &mut RevsetDiagnostics::new(),
+            &repo,
+            &format!("{op2_id_hex}+")
+        ),
         Err(OpsetEvaluationError::OpsetResolution(
             OpsetResolutionError::MultipleOperations { .. }
         ))
     );
 }
 
+#[test]
+fn test_resolve_ops_at() {
+    // Use monotonic timestamp to stabilize merge order of transactions
+    let settings = testutils::user_settings();
+    let test_repo = TestRepo::init_with_settings(&settings);
+    let repo_0 = test_repo.repo;
+    let op_store = repo_0.op_store();
+
+    // Set up operation graph:
+    //
+    // D
+    // |\
+    // B C (snapshot)
+    // |/
+    // A
+    // 0 (root)
+    let repo_a = repo_0.start_transaction().commit("op A").unwrap();
+    let repo_b = repo_a.start_transaction().commit("op B").unwrap();
+    let mut tx = repo_a.start_transaction();
+    tx.set_is_snapshot(true);
+    tx.set_tag("args".to_owned(), "jj debug snapshot".to_owned());
+    let repo_c = tx.commit("op C").unwrap();
+    let repo_d = repo_0.loader().load_at_head().unwrap();
+    let op_0 = repo_0.operation();
+    let op_a = repo_a.operation();
+    let op_b = repo_b.operation();
+    let op_c = repo_c.operation();
+    let op_d = repo_d.operation();
+
+    // The operations may have the same timestamp, so the order isn't tested.
+    let resolve = |op_str: &str| -> HashSet<OperationId> {
+        op_walk::resolve_ops_at(
+            &mut RevsetDiagnostics::new(),
+            op_store,
+            slice::from_ref(op_d),
+            op_str,
+        )
+        .unwrap()
+        .iter()
+        .map(|op| op.id().clone())
+        .collect()
+    };
+    let ids = |ops: &[&Operation]| -> HashSet<OperationId> {
+        ops.iter().map(|op| op.id().clone()).collect()
+    };
+
+    assert_eq!(resolve("@"), ids(&[op_d]));
+    assert_eq!(resolve("@-"), ids(&[op_c, op_b]));
+    assert_eq!(resolve("@--"), ids(&[op_a]));
+    assert_eq!(resolve("root()"), ids(&[op_0]));
+    assert_eq!(resolve("root()+"), ids(&[op_a]));
+    assert_eq!(resolve("all()"), ids(&[op_d, op_c, op_b, op_a, op_0]));
+    assert_eq!(resolve("::@-"), ids(&[op_c, op_b, op_a, op_0]));
+    assert_eq!(resolve("@--::"), ids(&[op_d, op_c, op_b, op_a]));
+    assert_eq!(resolve("root()..@-"), ids(&[op_c, op_b, op_a]));
+    assert_eq!(
+        resolve(&format!("{}..", op_b.id().hex())),
+        ids(&[op_d, op_c])
+    );
+    assert_eq!(
+        resolve(&format!("{}::@", op_b.id().hex())),
+        ids(&[op_d, op_b])
+    );
+    assert_eq!(resolve("~::@-"), ids(&[op_d]));
+    assert_eq!(resolve("snapshot()"), ids(&[op_c]));
+    assert_eq!(resolve("tags(args)"), ids(&[op_c]));
+    assert_eq!(resolve(r#"tags(args:"*debug*")"#), ids(&[op_c]));
+    assert_eq!(resolve(r#"tags(args:"*log*")"#), ids(&[]));
+    assert_eq!(
+        resolve(r#"description(exact:"op B") | description(exact:"op C")"#),
+        ids(&[op_c, op_b])
+    );
+    assert_eq!(
+        resolve(r#"description(glob:"op ?") & ~snapshot()"#),
+        ids(&[op_b, op_a])
+    );
+    assert_eq!(
+        resolve("user(test-username)"),
+        ids(&[op_d, op_c, op_b, op_a])
+    );
+    assert_eq!(resolve(r#"time(before:"1970-01-02")"#), ids(&[op_0]));
+
+    // Operations unreachable from the heads
+    assert_eq!(
+        op_walk::resolve_ops_at(
+            &mut RevsetDiagnostics::new(),
+            op_store,
+            slice::from_ref(op_b),
+            "all()"
+        )
+        .unwrap()
+        .iter()
+        .map(|op| op.id().clone())
+        .collect::<HashSet<_>>(),
+        ids(&[op_b, op_a, op_0])
+    );
+}
+
 #[test]
 fn test_gc() {
     let settings = stable_op_id_settings();
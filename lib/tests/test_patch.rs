@@ -0,0 +1,278 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use assert_matches::assert_matches;
+use indoc::indoc;
+use jj_lib::backend::TreeValue;
+use jj_lib::conflicts::materialize_merge_result_to_bytes;
+use jj_lib::conflicts::materialize_tree_value;
+use jj_lib::conflicts::ConflictMarkerStyle;
+use jj_lib::conflicts::MaterializedTreeValue;
+use jj_lib::merge::Merge;
+use jj_lib::merged_tree::MergedTree;
+use jj_lib::merged_tree::MergedTreeBuilder;
+use jj_lib::object_id::ObjectId as _;
+use jj_lib::patch::apply_patch;
+use jj_lib::patch::parse_patches;
+use jj_lib::patch::PatchApplyError;
+use jj_lib::repo::Repo as _;
+use jj_lib::repo_path::RepoPath;
+use pollster::FutureExt as _;
+use testutils::create_tree;
+use testutils::repo_path;
+use testutils::TestRepo;
+
+fn apply(tree: &MergedTree, patch: &str) -> Result<MergedTree, PatchApplyError> {
+    let patches = parse_patches(patch.as_bytes()).unwrap();
+    assert_eq!(patches.len(), 1);
+    apply_patch(tree, &patches[0])
+}
+
+fn materialize(tree: &MergedTree, path: &RepoPath) -> String {
+    let value = tree.path_value(path).unwrap();
+    match materialize_tree_value(tree.store(), path, value)
+        .block_on()
+        .unwrap()
+    {
+        MaterializedTreeValue::FileConflict(file) => {
+            let contents =
+                materialize_merge_result_to_bytes(&file.contents, ConflictMarkerStyle::Diff);
+            String::from_utf8(contents.into()).unwrap()
+        }
+        value => panic!("unexpected value at {path:?}: {}", value.is_present()),
+    }
+}
+
+#[test]
+fn test_apply_patch_clean() {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+    let store = repo.store();
+
+    let tree = create_tree(
+        repo,
+        &[
+            (repo_path("modified"), "a\nb\nc\nd\ne\nf\n"),
+            (repo_path("deleted"), "foo\n"),
+            (repo_path("renamed"), "1\n2\n3\n"),
+            (repo_path("copied"), "x\n"),
+            (repo_path("chmod"), "mode\n"),
+        ],
+    );
+    // The hunk of "modified" is offset by one line
+    let new_tree = apply(
+        &tree,
+        indoc! {"
+            diff --git a/modified b/modified
+            --- a/modified
+            +++ b/modified
+            @@ -1,3 +1,3 @@
+             c
+            -d
+            +D
+             e
+            diff --git a/deleted b/deleted
+            deleted file mode 100644
+            --- a/deleted
+            +++ /dev/null
+            @@ -1 +0,0 @@
+            -foo
+            diff --git a/renamed b/dir/new-name
+            similarity index 80%
+            rename from renamed
+            rename to dir/new-name
+            --- a/renamed
+            +++ b/dir/new-name
+            @@ -3 +3 @@
+            -3
+            +three
+            diff --git a/copied b/copy
+            similarity index 100%
+            copy from copied
+            copy to copy
+            diff --git a/chmod b/chmod
+            old mode 100644
+            new mode 100755
+            diff --git a/added b/added
+            new file mode 100644
+            --- /dev/null
+            +++ b/added
+            @@ -0,0 +1 @@
+            +new
+        "},
+    )
+    .unwrap();
+    insta::assert_snapshot!(testutils::dump_tree(store, &new_tree.id()), @r#"
+    tree a35c3e2cd5935afa5eab
+      file "added" (c2bcc6eb158b9fe4f027): "new\n"
+      file "chmod" (26f273e15671aca7d828): "mode\n"
+      file "copied" (0c1b04f4a0ed676c8242): "x\n"
+      file "copy" (0c1b04f4a0ed676c8242): "x\n"
+      file "dir/new-name" (0d95dc00241f3f19f43c): "1\n2\nthree\n"
+      file "modified" (baa57c623b3afaa2f1e4): "a\nb\nc\nD\ne\nf\n"
+    "#);
+    assert_matches!(
+        new_tree
+            .path_value(repo_path("chmod"))
+            .unwrap()
+            .into_resolved(),
+        Ok(Some(TreeValue::File {
+            executable: true,
+            ..
+        }))
+    );
+}
+
+#[test]
+fn test_apply_patch_conflicts() {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let tree = create_tree(
+        repo,
+        &[
+            (repo_path("file"), "a\nb\nc\nd\ne\n"),
+            (repo_path("existing"), "existing\n"),
+        ],
+    );
+    // The first hunk applies, the second one doesn't
+    let new_tree = apply(
+        &tree,
+        indoc! {"
+            --- a/file
+            +++ b/file
+            @@ -1,2 +1,2 @@
+            -a
+            +A
+             b
+            @@ -4,2 +4,2 @@
+             x
+            -e
+            +E
+            --- a/missing
+            +++ b/missing
+            @@ -1 +1 @@
+            -old
+            +new
+            --- /dev/null
+            +++ b/existing
+            @@ -0,0 +1 @@
+            +added
+        "},
+    )
+    .unwrap();
+    insta::assert_snapshot!(materialize(&new_tree, repo_path("file")), @r"
+    A
+    b
+    c
+    <<<<<<< Conflict 1 of 1
+    %%%%%%% Changes from base to side #1
+    -x
+    +d
+     e
+    +++++++ Contents of side #2
+    x
+    E
+    >>>>>>> Conflict 1 of 1 ends
+    ");
+    // Modified in the patch, but absent in the tree
+    let value = new_tree.path_value(repo_path("missing")).unwrap();
+    assert_eq!(value.num_sides(), 2);
+    assert!(value.adds().next().unwrap().is_none());
+    // Added in the patch, but already present in the tree
+    insta::assert_snapshot!(materialize(&new_tree, repo_path("existing")), @r"
+    <<<<<<< Conflict 1 of 1
+    +++++++ Contents of side #1
+    existing
+    %%%%%%% Changes from base to side #2
+    +added
+    >>>>>>> Conflict 1 of 1 ends
+    ");
+}
+
+#[test]
+fn test_apply_patch_conflicted_file() {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let base = create_tree(repo, &[(repo_path("file"), "1\nbase\n3\n")]);
+    let left = create_tree(repo, &[(repo_path("file"), "1\nleft\n3\n")]);
+    let right = create_tree(repo, &[(repo_path("file"), "1\nright\n3\n")]);
+    let tree = left.merge(&base, &right).unwrap();
+    // The hunk is applied to each side of the conflict
+    let new_tree = apply(
+        &tree,
+        indoc! {"
+            --- a/file
+            +++ b/file
+            @@ -1 +1 @@
+            -1
+            +one
+        "},
+    )
+    .unwrap();
+    insta::assert_snapshot!(materialize(&new_tree, repo_path("file")), @r"
+    one
+    <<<<<<< Conflict 1 of 1
+    %%%%%%% Changes from base to side #1
+    -base
+    +left
+    +++++++ Contents of side #2
+    right
+    >>>>>>> Conflict 1 of 1 ends
+    3
+    ");
+}
+
+#[test]
+fn test_apply_patch_errors() {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+    let store = repo.store();
+
+    let tree = create_tree(repo, &[(repo_path("file"), "a\n")]);
+    assert_matches!(
+        apply(&tree, "diff --git a/missing b/renamed\nrename from missing\nrename to renamed\n"),
+        Err(PatchApplyError::MissingFile { path }) if path.as_ref() == repo_path("missing")
+    );
+    assert_matches!(
+        apply(
+            &tree,
+            indoc! {"
+                diff --git a/file b/file
+                index 7898192..6178079 100644
+                Binary files a/file and b/file differ
+            "}
+        ),
+        Err(PatchApplyError::MissingBinaryData { path }) if path.as_ref() == repo_path("file")
+    );
+
+    // The contents of binary files are looked up by full object id
+    let binary_id = testutils::write_file(store, repo_path("file"), "\0binary\0");
+    let patch = format!(
+        "diff --git a/file b/file\nindex 7898192..{} 100644\nBinary files a/file and b/file \
+         differ\n",
+        binary_id.hex()
+    );
+    let new_tree = apply(&tree, &patch).unwrap();
+    let mut expected_builder = MergedTreeBuilder::new(tree.id());
+    expected_builder.set_or_remove(
+        repo_path("file").to_owned(),
+        Merge::normal(TreeValue::File {
+            id: binary_id,
+            executable: false,
+        }),
+    );
+    assert_eq!(new_tree.id(), expected_builder.write_tree(store).unwrap());
+}
@@ -2750,6 +2750,59 @@ fn test_evaluate_expression_description() {
     );
 }
 
+#[test]
+fn test_evaluate_expression_trailers() {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let mut tx = repo.start_transaction();
+    let mut_repo = tx.repo_mut();
+
+    let commit1 = create_random_commit(mut_repo)
+        .set_description("commit 1\n\nReviewed-by: Alice <alice@corp.com>\n")
+        .write()
+        .unwrap();
+    let commit2 = create_random_commit(mut_repo)
+        .set_parents(vec![commit1.id().clone()])
+        .set_description("commit 2\n\nChange-Id: I1234\nReviewed-by: Bob <bob@example.com>\n")
+        .write()
+        .unwrap();
+    create_random_commit(mut_repo)
+        .set_parents(vec![commit2.id().clone()])
+        .set_description("Reviewed-by: Bob <bob@corp.com>\n")
+        .write()
+        .unwrap();
+
+    // Matches commits having the trailer
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "trailers('Reviewed-by')"),
+        vec![commit2.id().clone(), commit1.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "trailers(Change-Id)"),
+        vec![commit2.id().clone()]
+    );
+    // Keys are compared case-insensitively
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "trailers('change-id')"),
+        vec![commit2.id().clone()]
+    );
+    // Matches the value against the pattern
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "trailers('Reviewed-by', glob:'*@corp.com>')"),
+        vec![commit1.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "trailers('Reviewed-by', 'Bob')"),
+        vec![commit2.id().clone()]
+    );
+    // A description consisting of a single paragraph has no trailers
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "trailers('Reviewed-by', 'bob@corp.com')"),
+        vec![]
+    );
+}
+
 #[test]
 fn test_evaluate_expression_author() {
     let test_repo = TestRepo::init();
@@ -6,6 +6,7 @@ use jj_lib::config::ConfigSource;
 use jj_lib::repo::Repo as _;
 use jj_lib::settings::UserSettings;
 use jj_lib::signing::SigStatus;
+use jj_lib::signing::SigTrust;
 use jj_lib::signing::SignBehavior;
 use jj_lib::signing::Signer;
 use jj_lib::signing::Verification;
@@ -31,6 +32,7 @@ fn user_settings(behavior: SignBehavior) -> UserSettings {
                     SignBehavior::Keep => "keep",
                     SignBehavior::Own => "own",
                     SignBehavior::Force => "force",
+                    SignBehavior::OnPush => "on-push",
                 }
             ),
         )
@@ -181,6 +183,41 @@ fn configured(backend: TestRepoBackend) {
     assert_eq!(commit.verification().unwrap(), good_verification());
 }
 
+#[test_case(TestRepoBackend::Git ; "git backend")]
+fn trust(backend: TestRepoBackend) {
+    let settings = user_settings(SignBehavior::Own);
+
+    let signer = Signer::new(Some(Box::new(TestSigningBackend)), vec![]);
+    let test_workspace = TestWorkspace::init_with_backend_and_signer(backend, signer, &settings);
+
+    let repo = &test_workspace.repo;
+
+    let repo = repo.clone();
+    let mut tx = repo.start_transaction();
+    let unsigned = create_random_commit(tx.repo_mut())
+        .set_sign_behavior(SignBehavior::Drop)
+        .write()
+        .unwrap();
+    let untrusted = write_random_commit(tx.repo_mut());
+    // The test backend trusts a key if it is the committer's email
+    let trusted = create_random_commit(tx.repo_mut())
+        .set_committer(Signature {
+            email: "impeccable".to_string(),
+            ..someone_else()
+        })
+        .write()
+        .unwrap();
+    tx.commit("test").unwrap();
+
+    let unsigned = repo.store().get_commit(unsigned.id()).unwrap();
+    assert_eq!(unsigned.trust().unwrap(), None);
+    let untrusted = repo.store().get_commit(untrusted.id()).unwrap();
+    assert_eq!(untrusted.trust().unwrap(), Some(SigTrust::Unknown));
+    let trusted = repo.store().get_commit(trusted.id()).unwrap();
+    assert_eq!(trusted.trust().unwrap(), Some(SigTrust::Verified));
+    assert_eq!(trusted.verification().unwrap(), good_verification());
+}
+
 #[test_case(TestRepoBackend::Git ; "git backend")]
 fn drop_behavior(backend: TestRepoBackend) {
     let settings = user_settings(SignBehavior::Drop);
@@ -208,3 +245,49 @@ fn drop_behavior(backend: TestRepoBackend) {
     let rewritten_commit = repo.store().get_commit(rewritten.id()).unwrap();
     assert_eq!(rewritten_commit.verification().unwrap(), None);
 }
+
+#[test_case(TestRepoBackend::Git ; "git backend")]
+fn on_push_behavior(backend: TestRepoBackend) {
+    let settings = user_settings(SignBehavior::OnPush);
+
+    let signer = Signer::new(Some(Box::new(TestSigningBackend)), vec![]);
+    let test_workspace = TestWorkspace::init_with_backend_and_signer(backend, signer, &settings);
+
+    let repo = &test_workspace.repo;
+
+    let repo = repo.clone();
+    let mut tx = repo.start_transaction();
+    let unsigned = write_random_commit(tx.repo_mut());
+    let signed = create_random_commit(tx.repo_mut())
+        .set_sign_behavior(SignBehavior::Own)
+        .write()
+        .unwrap();
+    tx.commit("test").unwrap();
+
+    // Commits aren't signed when created...
+    let unsigned = repo.store().get_commit(unsigned.id()).unwrap();
+    assert_eq!(unsigned.verification().unwrap(), None);
+
+    // ...nor when rewritten
+    let mut tx = repo.start_transaction();
+    let rewritten = tx.repo_mut().rewrite_commit(&unsigned).write().unwrap();
+    let rewritten = repo.store().get_commit(rewritten.id()).unwrap();
+    assert_eq!(rewritten.verification().unwrap(), None);
+
+    // Existing signatures are kept when rewritten
+    let signed = repo.store().get_commit(signed.id()).unwrap();
+    assert_eq!(signed.verification().unwrap(), good_verification());
+    let rewritten = tx.repo_mut().rewrite_commit(&signed).write().unwrap();
+    let rewritten = repo.store().get_commit(rewritten.id()).unwrap();
+    assert_eq!(rewritten.verification().unwrap(), good_verification());
+
+    // Signatures of someone else's commits aren't replaced by own ones
+    let rewritten = tx
+        .repo_mut()
+        .rewrite_commit(&signed)
+        .set_author(someone_else())
+        .write()
+        .unwrap();
+    let rewritten = repo.store().get_commit(rewritten.id()).unwrap();
+    assert_eq!(rewritten.verification().unwrap(), None);
+}
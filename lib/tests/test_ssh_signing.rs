@@ -21,7 +21,10 @@ use std::os::unix::prelude::PermissionsExt as _;
 use std::path::Path;
 use std::path::PathBuf;
 
+use jj_lib::backend::MillisSinceEpoch;
+use jj_lib::backend::Timestamp;
 use jj_lib::signing::SigStatus;
+use jj_lib::signing::SigTrust;
 use jj_lib::signing::SigningBackend as _;
 use jj_lib::ssh_signing::SshBackend;
 
@@ -85,6 +88,23 @@ impl SshEnvironment {
         self.allowed_signers = Some(allowed_signers_path);
     }
 
+    fn with_expired_public_key(&mut self) {
+        let mut allowed_signers = tempfile::Builder::new()
+            .prefix("jj-test-allowed-signers-")
+            .tempfile()
+            .unwrap();
+
+        allowed_signers
+            .write_all("test@example.com valid-before=\"20000101\" ".as_bytes())
+            .unwrap();
+        allowed_signers.write_all(PUBLIC_KEY.as_bytes()).unwrap();
+        allowed_signers.flush().unwrap();
+
+        let allowed_signers_path = allowed_signers.into_temp_path();
+
+        self.allowed_signers = Some(allowed_signers_path);
+    }
+
     fn with_bad_public_key(&mut self) {
         let mut allowed_signers = tempfile::Builder::new()
             .prefix("jj-test-allowed-signers-")
@@ -167,3 +187,82 @@ fn ssh_signing_missing_allowed_signers() {
     assert_eq!(check.status, SigStatus::Unknown);
     assert_eq!(check.display.unwrap(), "Signature OK. Unknown principal");
 }
+
+#[test]
+fn ssh_signing_trust() {
+    let env = SshEnvironment::new().unwrap();
+    let backend = backend(&env);
+    let data = b"hello world";
+    let now = Timestamp::now();
+
+    let signature = backend
+        .sign(data, Some(env.private_key_path.to_str().unwrap()))
+        .unwrap();
+
+    let trust = backend
+        .trust(data, &signature, "test@example.com", &now)
+        .unwrap();
+    assert_eq!(trust, SigTrust::Verified);
+
+    // The key is allowed, but for another email
+    let trust = backend
+        .trust(data, &signature, "someone-else@example.com", &now)
+        .unwrap();
+    assert_eq!(trust, SigTrust::Unknown);
+
+    let trust = backend
+        .trust(b"invalid-commit-data", &signature, "test@example.com", &now)
+        .unwrap();
+    assert_eq!(trust, SigTrust::Unknown);
+}
+
+#[test]
+fn ssh_signing_trust_expired_key() {
+    let mut env = SshEnvironment::new().unwrap();
+    env.with_expired_public_key();
+
+    let backend = backend(&env);
+    let data = b"hello world";
+
+    let signature = backend
+        .sign(data, Some(env.private_key_path.to_str().unwrap()))
+        .unwrap();
+
+    let trust = backend
+        .trust(data, &signature, "test@example.com", &Timestamp::now())
+        .unwrap();
+    assert_eq!(trust, SigTrust::Expired);
+
+    // The key was valid at the time of the signature
+    let signed_at = Timestamp {
+        timestamp: MillisSinceEpoch(915_148_800_000), // 1999-01-01
+        tz_offset: 0,
+    };
+    let trust = backend
+        .trust(data, &signature, "test@example.com", &signed_at)
+        .unwrap();
+    assert_eq!(trust, SigTrust::Verified);
+
+    let trust = backend
+        .trust(data, &signature, "someone-else@example.com", &signed_at)
+        .unwrap();
+    assert_eq!(trust, SigTrust::Unknown);
+}
+
+#[test]
+fn ssh_signing_trust_missing_allowed_signers() {
+    let mut env = SshEnvironment::new().unwrap();
+    env.allowed_signers = None;
+
+    let backend = backend(&env);
+    let data = b"hello world";
+
+    let signature = backend
+        .sign(data, Some(env.private_key_path.to_str().unwrap()))
+        .unwrap();
+
+    let trust = backend
+        .trust(data, &signature, "test@example.com", &Timestamp::now())
+        .unwrap();
+    assert_eq!(trust, SigTrust::Unknown);
+}
@@ -92,6 +92,27 @@ fn test_init_additional_workspace() {
     assert_eq!(same_workspace.workspace_root(), ws2.workspace_root());
 }
 
+#[test]
+fn test_load_with_broken_workspace_store() {
+    let settings = testutils::user_settings();
+    let test_workspace = TestWorkspace::init_with_settings(&settings);
+    let workspace_root = test_workspace.workspace.workspace_root().to_owned();
+
+    // The workspace can't be recorded in the store, as if the repo were
+    // read-only, but it can still be loaded.
+    let store_dir = test_workspace.repo_path().join("workspace_store");
+    std::fs::remove_dir_all(&store_dir).unwrap();
+    std::fs::write(&store_dir, "").unwrap();
+    let workspace = Workspace::load(
+        &settings,
+        &workspace_root,
+        &test_workspace.env.default_store_factories(),
+        &default_working_copy_factories(),
+    )
+    .unwrap();
+    assert!(workspace.workspace_store().list().is_err());
+}
+
 /// Test cross-thread access to a workspace, which requires it to be Send
 #[test]
 fn test_sendable() {